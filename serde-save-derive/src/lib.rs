@@ -0,0 +1,355 @@
+//! Derive macros for [`serde-save`](https://docs.rs/serde-save).
+//!
+//! Use them through `serde_save`'s `derive` feature.
+
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::{
+    parse_macro_input, punctuated::Punctuated, spanned::Spanned as _, Attribute, Data, DeriveInput,
+    Expr, ExprLit, Fields, Ident, Lit, Meta, MetaNameValue, Token,
+};
+
+/// Generate functions which build expected [`Save`] trees for a type,
+/// with the names filled in and the values supplied by the caller.
+///
+/// See `serde_save::SaveTemplate` for more.
+///
+/// [`Save`]: https://docs.rs/serde-save/latest/serde_save/enum.Save.html
+#[proc_macro_derive(SaveTemplate, attributes(serde))]
+pub fn save_template(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    expand(parse_macro_input!(input as DeriveInput))
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+    let container = Attrs::parse(&input.attrs)?;
+    let ident = &input.ident;
+    let name = container.rename.unwrap_or_else(|| ident.to_string());
+    let fns = match &input.data {
+        Data::Struct(it) => {
+            if container.rename_all_fields.is_some() {
+                return Err(syn::Error::new(
+                    Span::call_site(),
+                    "`#[serde(rename_all_fields)]` can only be used on enums",
+                ));
+            }
+            let (params, body) = fields(&it.fields, container.rename_all, |fields| match fields {
+                Shaped::Unit => quote!(::serde_save::Save::UnitStruct(#name)),
+                Shaped::NewType(value) => quote! {
+                    ::serde_save::Save::NewTypeStruct { name: #name, value: ::std::boxed::Box::new(#value) }
+                },
+                Shaped::Tuple(values) => quote! {
+                    ::serde_save::Save::TupleStruct { name: #name, values: ::std::vec![#(#values),*] }
+                },
+                Shaped::Struct(fields) => quote! {
+                    ::serde_save::Save::Struct { name: #name, fields: ::std::vec![#(#fields),*] }
+                },
+            })?;
+            let doc = format!("An expected tree for a `{}`.", ident);
+            vec![template(&doc, format_ident!("save_template"), params, body)]
+        }
+        Data::Enum(it) => {
+            let mut fns = Vec::new();
+            for (variant_index, variant) in it.variants.iter().enumerate() {
+                let attrs = Attrs::parse(&variant.attrs)?;
+                if attrs.skip {
+                    continue;
+                }
+                let variant_name = attrs
+                    .rename
+                    .unwrap_or_else(|| rename(&unraw(&variant.ident), container.rename_all, true));
+                let variant_index = variant_index as u32;
+                let tag = quote! {
+                    ::serde_save::Variant {
+                        name: #name,
+                        variant_index: #variant_index,
+                        variant: #variant_name,
+                    }
+                };
+                let (params, body) = fields(
+                    &variant.fields,
+                    attrs.rename_all.or(container.rename_all_fields),
+                    |fields| match fields {
+                        Shaped::Unit => quote!(::serde_save::Save::UnitVariant(#tag)),
+                        Shaped::NewType(value) => quote! {
+                            ::serde_save::Save::NewTypeVariant { variant: #tag, value: ::std::boxed::Box::new(#value) }
+                        },
+                        Shaped::Tuple(values) => quote! {
+                            ::serde_save::Save::TupleVariant { variant: #tag, values: ::std::vec![#(#values),*] }
+                        },
+                        Shaped::Struct(fields) => quote! {
+                            ::serde_save::Save::StructVariant { variant: #tag, fields: ::std::vec![#(#fields),*] }
+                        },
+                    },
+                )?;
+                let doc = format!("An expected tree for a `{}::{}`.", ident, variant.ident);
+                let fn_name =
+                    format_ident!("save_template_{}", snake_ident(&unraw(&variant.ident)));
+                fns.push(template(&doc, fn_name, params, body))
+            }
+            fns
+        }
+        Data::Union(it) => {
+            return Err(syn::Error::new(
+                it.union_token.span(),
+                "SaveTemplate cannot be derived for unions",
+            ))
+        }
+    };
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        #[automatically_derived]
+        impl #impl_generics #ident #ty_generics #where_clause {
+            #(#fns)*
+        }
+    })
+}
+
+/// A single generated function, with a parameter per field.
+fn template(doc: &str, name: Ident, params: Vec<Ident>, body: TokenStream) -> TokenStream {
+    quote! {
+        #[doc = #doc]
+        #[allow(clippy::too_many_arguments)]
+        pub fn #name<'__save, __E>(
+            #(#params: ::serde_save::Save<'__save, __E>),*
+        ) -> ::serde_save::Save<'__save, __E> {
+            #body
+        }
+    }
+}
+
+/// The generated children of a struct or variant.
+enum Shaped {
+    Unit,
+    NewType(Ident),
+    Tuple(Vec<Ident>),
+    /// `("name", Some(param))` pairs.
+    Struct(Vec<TokenStream>),
+}
+
+/// Return the parameters for `fields`, and the body built by `build`.
+fn fields(
+    fields: &Fields,
+    rename_all: Option<Case>,
+    build: impl FnOnce(Shaped) -> TokenStream,
+) -> syn::Result<(Vec<Ident>, TokenStream)> {
+    let mut params = Vec::new();
+    let shaped = match fields {
+        Fields::Unit => Shaped::Unit,
+        Fields::Unnamed(it) => {
+            for (ix, field) in it.unnamed.iter().enumerate() {
+                if !Attrs::parse(&field.attrs)?.skip {
+                    params.push(Ident::new(&format!("_{}", ix), Span::call_site()))
+                }
+            }
+            match params.len() {
+                1 if it.unnamed.len() == 1 => Shaped::NewType(params[0].clone()),
+                _ => Shaped::Tuple(params.clone()),
+            }
+        }
+        Fields::Named(it) => {
+            let mut pairs = Vec::new();
+            for field in &it.named {
+                let attrs = Attrs::parse(&field.attrs)?;
+                if attrs.skip {
+                    continue;
+                }
+                let ident = field.ident.clone().expect("named fields have idents");
+                let name = attrs
+                    .rename
+                    .unwrap_or_else(|| rename(&unraw(&ident), rename_all, false));
+                pairs.push(quote!((#name, ::core::option::Option::Some(#ident))));
+                params.push(ident);
+            }
+            Shaped::Struct(pairs)
+        }
+    };
+    Ok((params, build(shaped)))
+}
+
+/// The `#[serde(...)]` attributes which affect the shape of the tree.
+#[derive(Default)]
+struct Attrs {
+    rename: Option<String>,
+    rename_all: Option<Case>,
+    rename_all_fields: Option<Case>,
+    skip: bool,
+}
+
+impl Attrs {
+    fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut out = Self::default();
+        for attr in attrs.iter().filter(|it| it.path().is_ident("serde")) {
+            for meta in attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)? {
+                let path = meta.path();
+                let ident = path.get_ident().map(Ident::to_string).unwrap_or_default();
+                match (ident.as_str(), &meta) {
+                    ("rename", Meta::NameValue(it)) => out.rename = Some(string(&it.value)?),
+                    ("rename_all", Meta::NameValue(it)) => out.rename_all = Some(case(it, &ident)?),
+                    ("rename_all_fields", Meta::NameValue(it)) => {
+                        out.rename_all_fields = Some(case(it, &ident)?)
+                    }
+                    ("skip" | "skip_serializing", Meta::Path(_)) => out.skip = true,
+                    (
+                        "tag" | "content" | "untagged" | "transparent" | "flatten"
+                        | "serialize_with" | "with" | "into" | "remote",
+                        _,
+                    )
+                    | ("rename" | "rename_all" | "rename_all_fields", Meta::List(_)) => {
+                        return Err(syn::Error::new(
+                            path.span(),
+                            format!("`#[serde({})]` is not supported by SaveTemplate", ident),
+                        ))
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Ok(out)
+    }
+}
+
+fn case(it: &MetaNameValue, attr: &str) -> syn::Result<Case> {
+    Case::parse(&string(&it.value)?)
+        .ok_or_else(|| syn::Error::new(it.value.span(), format!("unknown case for `{}`", attr)))
+}
+
+fn string(expr: &Expr) -> syn::Result<String> {
+    match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Str(it), ..
+        }) => Ok(it.value()),
+        // e.g. a `$lit:literal` from a `macro_rules!`.
+        Expr::Group(it) => string(&it.expr),
+        _ => Err(syn::Error::new(expr.span(), "expected a string literal")),
+    }
+}
+
+/// The identifier without any `r#` prefix, as serde names it.
+fn unraw(ident: &Ident) -> String {
+    let s = ident.to_string();
+    s.strip_prefix("r#").map(String::from).unwrap_or(s)
+}
+
+/// A case for `#[serde(rename_all = "...")]`.
+#[derive(Clone, Copy)]
+enum Case {
+    Lower,
+    Upper,
+    Pascal,
+    Camel,
+    Snake,
+    ScreamingSnake,
+    Kebab,
+    ScreamingKebab,
+}
+
+impl Case {
+    fn parse(s: &str) -> Option<Self> {
+        Some(match s {
+            "lowercase" => Case::Lower,
+            "UPPERCASE" => Case::Upper,
+            "PascalCase" => Case::Pascal,
+            "camelCase" => Case::Camel,
+            "snake_case" => Case::Snake,
+            "SCREAMING_SNAKE_CASE" => Case::ScreamingSnake,
+            "kebab-case" => Case::Kebab,
+            "SCREAMING-KEBAB-CASE" => Case::ScreamingKebab,
+            _ => return None,
+        })
+    }
+}
+
+/// Rename `s` like serde does, which treats variants as `PascalCase`
+/// and fields as `snake_case`.
+fn rename(s: &str, case: Option<Case>, is_variant: bool) -> String {
+    match (case, is_variant) {
+        (None, _) => String::from(s),
+        (Some(case), true) => case.apply_to_variant(s),
+        (Some(case), false) => case.apply_to_field(s),
+    }
+}
+
+impl Case {
+    /// See serde's `RenameRule::apply_to_variant`.
+    fn apply_to_variant(self, variant: &str) -> String {
+        match self {
+            Case::Pascal => String::from(variant),
+            Case::Lower => variant.to_ascii_lowercase(),
+            Case::Upper => variant.to_ascii_uppercase(),
+            Case::Camel => lower_first(variant),
+            Case::Snake => {
+                let mut snake = String::new();
+                for (ix, c) in variant.char_indices() {
+                    if ix > 0 && c.is_uppercase() {
+                        snake.push('_');
+                    }
+                    snake.push(c.to_ascii_lowercase());
+                }
+                snake
+            }
+            Case::ScreamingSnake => Case::Snake.apply_to_variant(variant).to_ascii_uppercase(),
+            Case::Kebab => Case::Snake.apply_to_variant(variant).replace('_', "-"),
+            Case::ScreamingKebab => Case::ScreamingSnake
+                .apply_to_variant(variant)
+                .replace('_', "-"),
+        }
+    }
+    /// See serde's `RenameRule::apply_to_field`.
+    fn apply_to_field(self, field: &str) -> String {
+        match self {
+            Case::Lower | Case::Snake => String::from(field),
+            Case::Upper | Case::ScreamingSnake => field.to_ascii_uppercase(),
+            Case::Pascal => {
+                let mut pascal = String::new();
+                let mut capitalize = true;
+                for c in field.chars() {
+                    if c == '_' {
+                        capitalize = true;
+                    } else if capitalize {
+                        pascal.push(c.to_ascii_uppercase());
+                        capitalize = false;
+                    } else {
+                        pascal.push(c);
+                    }
+                }
+                pascal
+            }
+            Case::Camel => lower_first(&Case::Pascal.apply_to_field(field)),
+            Case::Kebab => field.replace('_', "-"),
+            Case::ScreamingKebab => field.to_ascii_uppercase().replace('_', "-"),
+        }
+    }
+}
+
+/// `snake_case` a `PascalCase` identifier for use in a function name,
+/// keeping runs of capitals together, so `HTTPRequest` becomes `http_request`.
+///
+/// Serialized names still follow serde's rule, see [`Case::apply_to_variant`].
+fn snake_ident(s: &str) -> String {
+    let chars = s.chars().collect::<Vec<_>>();
+    let mut snake = String::new();
+    for (ix, c) in chars.iter().enumerate() {
+        if ix > 0 && c.is_uppercase() {
+            let prev = chars[ix - 1];
+            let next = chars.get(ix + 1);
+            if prev.is_lowercase()
+                || prev.is_ascii_digit()
+                || (prev.is_uppercase() && next.is_some_and(|it| it.is_lowercase()))
+            {
+                snake.push('_');
+            }
+        }
+        snake.extend(c.to_lowercase());
+    }
+    snake
+}
+
+fn lower_first(s: &str) -> String {
+    let mut chars = s.chars();
+    chars
+        .next()
+        .map(|it| it.to_ascii_lowercase().to_string() + chars.as_str())
+        .unwrap_or_default()
+}
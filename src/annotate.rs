@@ -0,0 +1,85 @@
+use crate::{Path, Save};
+use core::convert::Infallible;
+use std::collections::BTreeMap;
+
+/// A [`Save`] with user-defined annotations attached to its nodes.
+///
+/// Annotations are attached to [`Path`]s, so can be used for post-processing
+/// metadata like source offsets, timestamps or provenance.
+///
+/// ```
+/// # use serde_save::{Annotated, Path, Save};
+/// let save = Save::<std::convert::Infallible>::strukt("Point", [("x", 1), ("y", 2)]);
+/// let mut annotated = Annotated::new(save);
+/// annotated.annotate(Path::parse("y").unwrap(), "line 2");
+///
+/// let annotated = annotated.map_annotations(|_path, it| it.len());
+///
+/// let mut seen = vec![];
+/// annotated.walk(|path, _node, annotation| seen.push((path.to_string(), annotation.copied())));
+/// assert_eq!(
+///     seen,
+///     [
+///         (String::from(""), None),
+///         (String::from("x"), None),
+///         (String::from("y"), Some(6)),
+///     ]
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Annotated<'a, M, E = Infallible> {
+    /// The serialization tree.
+    pub save: Save<'a, E>,
+    /// Annotations, by location in [`Self::save`].
+    ///
+    /// Annotations for [`Path`]s which don't exist in the tree are retained,
+    /// but never [visited](Self::walk).
+    pub annotations: BTreeMap<Path<'a>, M>,
+}
+
+impl<'a, M, E> Annotated<'a, M, E> {
+    /// Wrap a tree with no annotations.
+    pub fn new(save: Save<'a, E>) -> Self {
+        Self {
+            save,
+            annotations: BTreeMap::new(),
+        }
+    }
+    /// Attach an annotation to the node(s) at `path`, returning the previous
+    /// annotation, if any.
+    pub fn annotate(&mut self, path: Path<'a>, annotation: M) -> Option<M> {
+        self.annotations.insert(path, annotation)
+    }
+    /// Get the annotation for the node(s) at `path`, if any.
+    pub fn annotation(&self, path: &Path<'a>) -> Option<&M> {
+        self.annotations.get(path)
+    }
+    /// Transform each annotation, retaining the tree.
+    pub fn map_annotations<N>(self, mut f: impl FnMut(&Path<'a>, M) -> N) -> Annotated<'a, N, E> {
+        Annotated {
+            save: self.save,
+            annotations: self
+                .annotations
+                .into_iter()
+                .map(|(path, it)| {
+                    let it = f(&path, it);
+                    (path, it)
+                })
+                .collect(),
+        }
+    }
+    /// Like [`Save::walk`], but also yields the annotation for each node.
+    ///
+    /// Note that the contents of wrapper nodes share their wrapper's [`Path`],
+    /// and therefore its annotation.
+    pub fn walk(&self, mut f: impl FnMut(&Path<'a>, &Save<'a, E>, Option<&M>)) {
+        self.save
+            .walk(|path, node| f(path, node, self.annotations.get(path)))
+    }
+}
+
+impl<'a, M, E> From<Save<'a, E>> for Annotated<'a, M, E> {
+    fn from(save: Save<'a, E>) -> Self {
+        Self::new(save)
+    }
+}
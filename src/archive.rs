@@ -0,0 +1,627 @@
+//! A compact, self-describing binary encoding for [`Save`] trees.
+//!
+//! The encoding starts with a magic number, a format version byte and a
+//! [schema version](Migrations) (a little-endian [`u32`]), followed by a
+//! LEB128 length and a single node.
+//! The archive ends with a checksum (a little-endian 64-bit FNV-1a hash) of
+//! every 64 KiB chunk of the bytes before it, so that corruption can be located.
+//!
+//! Each node is a one byte tag (the index of its variant in [`Save`]),
+//! followed by its contents:
+//! - Numbers are fixed-width little-endian, [`char`]s are [`u32`]s.
+//! - Strings, bytes and collection lengths are prefixed with a LEB128 length.
+//! - Optional items are prefixed with a `0` or `1` byte.
+//! - [`Save::Error`]s are stored as their [`Display`](fmt::Display) message.
+//!
+//! Nodes may be nested at most 128 deep,
+//! so that hostile archives cannot overflow the stack when decoded.
+
+use crate::{Error, Save, Variant};
+use core::fmt;
+use serde::ser::Error as _;
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+const MAGIC: &[u8; 4] = b"SSAV";
+const VERSION: u8 = 1;
+const SCHEMA_OFFSET: usize = MAGIC.len() + 1;
+/// Archives are checksummed in chunks of this many bytes.
+const CHUNK_LEN: usize = 64 * 1024;
+/// The deepest nesting of nodes which will be decoded.
+const MAX_DEPTH: usize = 128;
+
+/// An archive failed to decode.
+///
+/// See [`Save::from_bytes`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArchiveError {
+    offset: usize,
+    msg: &'static str,
+    corrupt: bool,
+}
+
+impl ArchiveError {
+    /// The byte offset into the archive at which decoding failed.
+    ///
+    /// For [corrupt](Self::is_corrupt) archives, this is the start of the
+    /// first chunk which failed its checksum.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+    /// Whether the archive failed a checksum, rather than being malformed.
+    ///
+    /// ```
+    /// # use serde_save::{save, Save};
+    /// let mut bytes = save("hello").unwrap().to_bytes();
+    /// let ix = bytes.iter().position(|it| *it == b'h').unwrap();
+    /// bytes[ix] = b'j';
+    ///
+    /// let e = Save::from_bytes(&bytes).unwrap_err();
+    /// assert!(e.is_corrupt());
+    /// assert_eq!(e.offset(), 0);
+    /// ```
+    pub fn is_corrupt(&self) -> bool {
+        self.corrupt
+    }
+}
+
+impl fmt::Display for ArchiveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid archive at offset {}: {}", self.offset, self.msg)
+    }
+}
+
+impl std::error::Error for ArchiveError {}
+
+impl<'a, E: fmt::Display> Save<'a, E> {
+    /// Encode this tree in the binary archive format.
+    ///
+    /// Names borrow from the archive when decoded, see [`Save::from_bytes`].
+    ///
+    /// ```
+    /// # use serde_save::{save, Save};
+    /// let before = save(("hello", [1u8, 2], Some('c'))).unwrap();
+    /// let bytes = before.to_bytes();
+    /// assert_eq!(Save::from_bytes(&bytes).unwrap(), before);
+    /// ```
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.to_bytes_versioned(0)
+    }
+
+    /// Encode this tree in the binary archive format, stamped with a
+    /// user-defined `schema` version, so that it may be [migrated](Migrations)
+    /// when loaded by later versions of a program.
+    pub fn to_bytes_versioned(&self, schema: u32) -> Vec<u8> {
+        let mut out = MAGIC.to_vec();
+        out.push(VERSION);
+        out.extend(schema.to_le_bytes());
+        let mut node = Vec::new();
+        encode(&mut node, self);
+        len(&mut out, node.len());
+        out.extend(node);
+        let checksums = out.chunks(CHUNK_LEN).map(fnv1a).collect::<Vec<_>>();
+        for it in checksums {
+            out.extend(it.to_le_bytes())
+        }
+        out
+    }
+
+    /// Write each tree to its own file in `dir`,
+    /// for use as a seed corpus for fuzzing.
+    ///
+    /// Files are named by a hash of their contents, so writing the same tree
+    /// twice only produces one file.
+    ///
+    /// Returns the paths of the written files.
+    /// See [`Save::read_corpus`] for loading them again.
+    pub fn write_corpus<'s>(
+        dir: impl AsRef<Path>,
+        trees: impl IntoIterator<Item = &'s Self>,
+    ) -> io::Result<Vec<PathBuf>>
+    where
+        'a: 's,
+        E: 's,
+    {
+        let dir = dir.as_ref();
+        fs::create_dir_all(dir)?;
+        let mut paths = Vec::new();
+        for tree in trees {
+            let bytes = tree.to_bytes();
+            let path = dir.join(format!("{:016x}.bin", fnv1a(&bytes)));
+            fs::write(&path, bytes)?;
+            paths.push(path);
+        }
+        Ok(paths)
+    }
+}
+
+impl<'a> Save<'a> {
+    /// Decode a tree written by [`Save::to_bytes`],
+    /// failing if the archive contains a [`Save::Error`].
+    ///
+    /// Archives nested more than 128 nodes deep are rejected.
+    ///
+    /// ```
+    /// # use serde_save::Save;
+    /// let mut save = Save::<std::convert::Infallible>::Unit;
+    /// for _ in 0..200 {
+    ///     save = Save::Option(Some(Box::new(save)))
+    /// }
+    /// assert_eq!(
+    ///     Save::from_bytes(&save.to_bytes()).unwrap_err().to_string(),
+    ///     "invalid archive at offset 267: nesting too deep"
+    /// );
+    /// ```
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, ArchiveError> {
+        decode(bytes, |_| None).map(|(_, it)| it)
+    }
+
+    /// Like [`Save::from_bytes`], but also return the schema version written
+    /// by [`Save::to_bytes_versioned`].
+    pub fn from_bytes_versioned(bytes: &'a [u8]) -> Result<(u32, Self), ArchiveError> {
+        decode(bytes, |_| None)
+    }
+
+    /// Read every file in a corpus written by [`Save::write_corpus`],
+    /// in order of file name.
+    ///
+    /// Decode each with [`Save::from_bytes`] or [`Save::from_bytes_errors`].
+    ///
+    /// ```
+    /// # use serde_save::{save, Save};
+    /// # let dir = std::env::temp_dir().join("serde-save-corpus-doctest");
+    /// let trees = [save(1).unwrap(), save("two").unwrap()];
+    /// Save::write_corpus(&dir, &trees).unwrap();
+    ///
+    /// let files = Save::read_corpus(&dir).unwrap();
+    /// let mut loaded = files
+    ///     .iter()
+    ///     .map(|it| Save::from_bytes(it))
+    ///     .collect::<Result<Vec<_>, _>>()
+    ///     .unwrap();
+    /// loaded.sort_by(Save::cmp_canonical);
+    /// assert_eq!(loaded, trees);
+    /// # std::fs::remove_dir_all(&dir).unwrap();
+    /// ```
+    pub fn read_corpus(dir: impl AsRef<Path>) -> io::Result<Vec<Vec<u8>>> {
+        let mut paths = fs::read_dir(dir)?
+            .map(|it| it.map(|it| it.path()))
+            .collect::<io::Result<Vec<_>>>()?;
+        paths.retain(|it| it.is_file());
+        paths.sort();
+        paths.into_iter().map(fs::read).collect()
+    }
+}
+
+impl<'a> Save<'a, Error> {
+    /// Decode a tree written by [`Save::to_bytes`],
+    /// restoring any [`Save::Error`]s from their messages.
+    pub fn from_bytes_errors(bytes: &'a [u8]) -> Result<Self, ArchiveError> {
+        decode(bytes, |msg| Some(Error::custom(msg))).map(|(_, it)| it)
+    }
+}
+
+/// A step in a chain of [`Migrations`], which rewrites a tree from one schema
+/// version to the next.
+pub type Migration = for<'a> fn(Save<'a>) -> Save<'a>;
+
+/// Upgrade archived trees written with older [schema versions](Save::to_bytes_versioned)
+/// as they are loaded, so that long-lived archives survive changes to user types.
+///
+/// ```
+/// # use serde_save::{save, Migrations, Save};
+/// // Version 0 stored a bare number, version 1 wraps it in a struct.
+/// let archived = save(42).unwrap().to_bytes_versioned(0);
+///
+/// let migrations = Migrations::new(1).register(0, |it| Save::strukt("Answer", [("value", it)]));
+/// assert_eq!(
+///     migrations.load(&archived).unwrap(),
+///     Save::strukt("Answer", [("value", 42)]),
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct Migrations {
+    current: u32,
+    /// Indexed by the version migrated from.
+    steps: Vec<Option<Migration>>,
+}
+
+impl Migrations {
+    /// Create an empty chain, for a program which reads and writes
+    /// schema version `current`.
+    pub fn new(current: u32) -> Self {
+        Self {
+            current,
+            steps: Vec::new(),
+        }
+    }
+    /// The schema version that trees are migrated to.
+    pub fn current(&self) -> u32 {
+        self.current
+    }
+    /// Register the migration from schema version `from` to `from + 1`,
+    /// replacing any previous registration.
+    pub fn register(mut self, from: u32, migration: Migration) -> Self {
+        let ix = from as usize;
+        if self.steps.len() <= ix {
+            self.steps.resize(ix + 1, None)
+        }
+        self.steps[ix] = Some(migration);
+        self
+    }
+    /// Decode an archive with [`Save::from_bytes_versioned`], and migrate it
+    /// to the [current](Self::current) schema version.
+    ///
+    /// Fails if the archive is newer than the current version,
+    /// or if any migration is missing.
+    pub fn load<'a>(&self, bytes: &'a [u8]) -> Result<Save<'a>, ArchiveError> {
+        let (schema, mut save) = Save::from_bytes_versioned(bytes)?;
+        let fail = |msg| ArchiveError {
+            offset: SCHEMA_OFFSET,
+            msg,
+            corrupt: false,
+        };
+        if schema > self.current {
+            return Err(fail("schema version is newer than supported"));
+        }
+        for from in schema..self.current {
+            match self.steps.get(from as usize) {
+                Some(Some(migration)) => save = migration(save),
+                _ => return Err(fail("no migration registered for schema version")),
+            }
+        }
+        Ok(save)
+    }
+}
+
+/// A stable 64-bit FNV-1a hash.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x100000001b3)
+    })
+}
+
+fn encode<E: fmt::Display>(out: &mut Vec<u8>, save: &Save<'_, E>) {
+    out.push(save.kind_index());
+    match save {
+        Save::Bool(it) => out.push(u8::from(*it)),
+        Save::I8(it) => out.extend(it.to_le_bytes()),
+        Save::I16(it) => out.extend(it.to_le_bytes()),
+        Save::I32(it) => out.extend(it.to_le_bytes()),
+        Save::I64(it) => out.extend(it.to_le_bytes()),
+        Save::I128(it) => out.extend(it.to_le_bytes()),
+        Save::U8(it) => out.push(*it),
+        Save::U16(it) => out.extend(it.to_le_bytes()),
+        Save::U32(it) => out.extend(it.to_le_bytes()),
+        Save::U64(it) => out.extend(it.to_le_bytes()),
+        Save::U128(it) => out.extend(it.to_le_bytes()),
+        Save::F32(it) => out.extend(it.to_le_bytes()),
+        Save::F64(it) => out.extend(it.to_le_bytes()),
+        Save::Char(it) => out.extend(u32::from(*it).to_le_bytes()),
+        Save::String(it) => bytes(out, it.as_bytes()),
+        Save::ByteArray(it) => bytes(out, it),
+        Save::Option(None) => out.push(0),
+        Save::Option(Some(it)) => {
+            out.push(1);
+            encode(out, it)
+        }
+        Save::Unit => {}
+        Save::UnitStruct(name) => bytes(out, name.as_bytes()),
+        Save::UnitVariant(variant) => self::variant(out, variant),
+        Save::NewTypeStruct { name, value } => {
+            bytes(out, name.as_bytes());
+            encode(out, value)
+        }
+        Save::NewTypeVariant { variant, value } => {
+            self::variant(out, variant);
+            encode(out, value)
+        }
+        Save::Seq(it) | Save::Tuple(it) => elements(out, it),
+        Save::Map(it) => {
+            len(out, it.len());
+            for (k, v) in it {
+                encode(out, k);
+                encode(out, v)
+            }
+        }
+        Save::TupleStruct { name, values } => {
+            bytes(out, name.as_bytes());
+            elements(out, values)
+        }
+        Save::TupleVariant { variant, values } => {
+            self::variant(out, variant);
+            elements(out, values)
+        }
+        Save::Struct { name, fields } => {
+            bytes(out, name.as_bytes());
+            self::fields(out, fields)
+        }
+        Save::StructVariant { variant, fields } => {
+            self::variant(out, variant);
+            self::fields(out, fields)
+        }
+        Save::Error(e) => bytes(out, e.to_string().as_bytes()),
+    }
+}
+
+fn len(out: &mut Vec<u8>, len: usize) {
+    let mut len = len as u64;
+    loop {
+        let byte = (len & 0x7f) as u8;
+        len >>= 7;
+        match len {
+            0 => break out.push(byte),
+            _ => out.push(byte | 0x80),
+        }
+    }
+}
+
+fn bytes(out: &mut Vec<u8>, bytes: &[u8]) {
+    len(out, bytes.len());
+    out.extend_from_slice(bytes)
+}
+
+fn variant(out: &mut Vec<u8>, variant: &Variant<'_>) {
+    let Variant {
+        name,
+        variant_index,
+        variant,
+    } = variant;
+    bytes(out, name.as_bytes());
+    out.extend(variant_index.to_le_bytes());
+    bytes(out, variant.as_bytes());
+}
+
+fn elements<E: fmt::Display>(out: &mut Vec<u8>, elements: &[Save<'_, E>]) {
+    len(out, elements.len());
+    for it in elements {
+        encode(out, it)
+    }
+}
+
+fn fields<E: fmt::Display>(out: &mut Vec<u8>, fields: &[(&str, Option<Save<'_, E>>)]) {
+    len(out, fields.len());
+    for (name, value) in fields {
+        bytes(out, name.as_bytes());
+        match value {
+            None => out.push(0),
+            Some(it) => {
+                out.push(1);
+                encode(out, it)
+            }
+        }
+    }
+}
+
+/// Returns the schema version and the tree.
+fn decode<'a, E>(
+    bytes: &'a [u8],
+    error: fn(&str) -> Option<E>,
+) -> Result<(u32, Save<'a, E>), ArchiveError> {
+    let mut reader = Reader {
+        bytes,
+        offset: 0,
+        depth: 0,
+        error,
+    };
+    if reader.take(MAGIC.len())? != MAGIC {
+        return Err(reader.fail(0, "bad magic"));
+    }
+    let version = reader.byte()?;
+    if version != VERSION {
+        return Err(reader.fail(MAGIC.len(), "unsupported version"));
+    }
+    let schema = u32::from_le_bytes(reader.array()?);
+    let offset = reader.offset;
+    let end = reader
+        .len()?
+        .checked_add(reader.offset)
+        .filter(|it| *it <= bytes.len())
+        .ok_or_else(|| reader.fail(offset, "unexpected end of input"))?;
+    verify(bytes, end)?;
+    reader.bytes = &bytes[..end];
+    let save = reader.node()?;
+    match reader.offset == end {
+        true => Ok((schema, save)),
+        false => Err(reader.fail(reader.offset, "trailing bytes")),
+    }
+}
+
+/// Check the checksums which follow the first `end` bytes of an archive.
+fn verify(bytes: &[u8], end: usize) -> Result<(), ArchiveError> {
+    let (covered, checksums) = bytes.split_at(end);
+    let chunks = covered.chunks(CHUNK_LEN);
+    if checksums.len() != chunks.len() * 8 {
+        return Err(ArchiveError {
+            offset: end,
+            msg: "missing checksums",
+            corrupt: false,
+        });
+    }
+    for (ix, (chunk, checksum)) in chunks.zip(checksums.chunks(8)).enumerate() {
+        if fnv1a(chunk).to_le_bytes() != checksum {
+            return Err(ArchiveError {
+                offset: ix * CHUNK_LEN,
+                msg: "checksum mismatch",
+                corrupt: true,
+            });
+        }
+    }
+    Ok(())
+}
+
+struct Reader<'a, E> {
+    bytes: &'a [u8],
+    offset: usize,
+    /// The number of nodes currently being decoded, see [`MAX_DEPTH`].
+    depth: usize,
+    error: fn(&str) -> Option<E>,
+}
+
+impl<'a, E> Reader<'a, E> {
+    fn fail(&self, offset: usize, msg: &'static str) -> ArchiveError {
+        ArchiveError {
+            offset,
+            msg,
+            corrupt: false,
+        }
+    }
+    fn take(&mut self, n: usize) -> Result<&'a [u8], ArchiveError> {
+        let bytes = self.bytes;
+        match bytes.get(self.offset..).and_then(|it| it.get(..n)) {
+            Some(it) => {
+                self.offset += n;
+                Ok(it)
+            }
+            None => Err(self.fail(self.offset, "unexpected end of input")),
+        }
+    }
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], ArchiveError> {
+        let mut array = [0; N];
+        array.copy_from_slice(self.take(N)?);
+        Ok(array)
+    }
+    fn byte(&mut self) -> Result<u8, ArchiveError> {
+        self.array::<1>().map(|[it]| it)
+    }
+    fn bool(&mut self) -> Result<bool, ArchiveError> {
+        let offset = self.offset;
+        match self.byte()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(self.fail(offset, "invalid bool")),
+        }
+    }
+    fn len(&mut self) -> Result<usize, ArchiveError> {
+        let offset = self.offset;
+        let mut len = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.byte()?;
+            len |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return usize::try_from(len).map_err(|_| self.fail(offset, "length overflow"));
+            }
+        }
+        Err(self.fail(offset, "length overflow"))
+    }
+    fn bytes(&mut self) -> Result<&'a [u8], ArchiveError> {
+        let len = self.len()?;
+        self.take(len)
+    }
+    fn str(&mut self) -> Result<&'a str, ArchiveError> {
+        let offset = self.offset;
+        let bytes = self.bytes()?;
+        core::str::from_utf8(bytes).map_err(|_| self.fail(offset, "invalid utf-8"))
+    }
+    fn variant(&mut self) -> Result<Variant<'a>, ArchiveError> {
+        Ok(Variant {
+            name: self.str()?,
+            variant_index: u32::from_le_bytes(self.array()?),
+            variant: self.str()?,
+        })
+    }
+    /// Read a length-prefixed sequence of items.
+    ///
+    /// The length is not trusted for preallocation.
+    fn many<T>(
+        &mut self,
+        mut f: impl FnMut(&mut Self) -> Result<T, ArchiveError>,
+    ) -> Result<Vec<T>, ArchiveError> {
+        let len = self.len()?;
+        let mut items = Vec::new();
+        for _ in 0..len {
+            items.push(f(self)?)
+        }
+        Ok(items)
+    }
+    fn elements(&mut self) -> Result<Vec<Save<'a, E>>, ArchiveError> {
+        self.many(Self::node)
+    }
+    #[allow(clippy::type_complexity)]
+    fn fields(&mut self) -> Result<Vec<(&'a str, Option<Save<'a, E>>)>, ArchiveError> {
+        self.many(|this| {
+            let name = this.str()?;
+            let value = match this.bool()? {
+                true => Some(this.node()?),
+                false => None,
+            };
+            Ok((name, value))
+        })
+    }
+    fn node(&mut self) -> Result<Save<'a, E>, ArchiveError> {
+        if self.depth == MAX_DEPTH {
+            return Err(self.fail(self.offset, "nesting too deep"));
+        }
+        self.depth += 1;
+        let node = self.contents();
+        self.depth -= 1;
+        node
+    }
+    /// Read a node, after its depth has been checked.
+    fn contents(&mut self) -> Result<Save<'a, E>, ArchiveError> {
+        let offset = self.offset;
+        Ok(match self.byte()? {
+            0 => Save::Bool(self.bool()?),
+            1 => Save::I8(i8::from_le_bytes(self.array()?)),
+            2 => Save::I16(i16::from_le_bytes(self.array()?)),
+            3 => Save::I32(i32::from_le_bytes(self.array()?)),
+            4 => Save::I64(i64::from_le_bytes(self.array()?)),
+            5 => Save::I128(i128::from_le_bytes(self.array()?)),
+            6 => Save::U8(self.byte()?),
+            7 => Save::U16(u16::from_le_bytes(self.array()?)),
+            8 => Save::U32(u32::from_le_bytes(self.array()?)),
+            9 => Save::U64(u64::from_le_bytes(self.array()?)),
+            10 => Save::U128(u128::from_le_bytes(self.array()?)),
+            11 => Save::F32(f32::from_le_bytes(self.array()?)),
+            12 => Save::F64(f64::from_le_bytes(self.array()?)),
+            13 => {
+                let offset = self.offset;
+                let it = u32::from_le_bytes(self.array()?);
+                Save::Char(char::from_u32(it).ok_or_else(|| self.fail(offset, "invalid char"))?)
+            }
+            14 => Save::String(self.str()?.into()),
+            15 => Save::ByteArray(self.bytes()?.into()),
+            16 => Save::Option(match self.bool()? {
+                true => Some(Box::new(self.node()?)),
+                false => None,
+            }),
+            17 => Save::Unit,
+            18 => Save::UnitStruct(self.str()?),
+            19 => Save::UnitVariant(self.variant()?),
+            20 => Save::NewTypeStruct {
+                name: self.str()?,
+                value: Box::new(self.node()?),
+            },
+            21 => Save::NewTypeVariant {
+                variant: self.variant()?,
+                value: Box::new(self.node()?),
+            },
+            22 => Save::Seq(self.elements()?),
+            23 => Save::Map(self.many(|this| Ok((this.node()?, this.node()?)))?),
+            24 => Save::Tuple(self.elements()?),
+            25 => Save::TupleStruct {
+                name: self.str()?,
+                values: self.elements()?,
+            },
+            26 => Save::TupleVariant {
+                variant: self.variant()?,
+                values: self.elements()?,
+            },
+            27 => Save::Struct {
+                name: self.str()?,
+                fields: self.fields()?,
+            },
+            28 => Save::StructVariant {
+                variant: self.variant()?,
+                fields: self.fields()?,
+            },
+            29 => {
+                let msg = self.str()?;
+                Save::Error((self.error)(msg).ok_or_else(|| self.fail(offset, "unexpected error"))?)
+            }
+            _ => return Err(self.fail(offset, "unknown tag")),
+        })
+    }
+}
@@ -0,0 +1,346 @@
+use crate::Save;
+use core::cmp::Ordering;
+
+impl<'a, E> Save<'a, E> {
+    /// A total ordering over trees, which is consistent across runs.
+    ///
+    /// Nodes of different kinds are ordered by the declaration order of
+    /// [`Save`]'s variants, and floats are ordered by [`f64::total_cmp`].
+    ///
+    /// ```
+    /// # use serde_save::Save;
+    /// # use std::convert::Infallible;
+    /// let mut items: Vec<Save<Infallible>> = vec![Save::F64(f64::NAN), Save::F64(1.0), Save::Bool(true)];
+    /// items.sort_by(Save::cmp_canonical);
+    /// assert_eq!(format!("{:?}", items), "[Bool(true), F64(1.0), F64(NaN)]");
+    /// ```
+    pub fn cmp_canonical(&self, other: &Self) -> Ordering
+    where
+        E: Ord,
+    {
+        self.cmp_canonical_by(other, &E::cmp)
+    }
+    /// [`Save::cmp_canonical`], ordering [`Save::Error`]s with `errors`.
+    pub(crate) fn cmp_canonical_by(
+        &self,
+        other: &Self,
+        errors: &impl Fn(&E, &E) -> Ordering,
+    ) -> Ordering {
+        match (self, other) {
+            (Save::Bool(l), Save::Bool(r)) => l.cmp(r),
+            (Save::I8(l), Save::I8(r)) => l.cmp(r),
+            (Save::I16(l), Save::I16(r)) => l.cmp(r),
+            (Save::I32(l), Save::I32(r)) => l.cmp(r),
+            (Save::I64(l), Save::I64(r)) => l.cmp(r),
+            (Save::I128(l), Save::I128(r)) => l.cmp(r),
+            (Save::U8(l), Save::U8(r)) => l.cmp(r),
+            (Save::U16(l), Save::U16(r)) => l.cmp(r),
+            (Save::U32(l), Save::U32(r)) => l.cmp(r),
+            (Save::U64(l), Save::U64(r)) => l.cmp(r),
+            (Save::U128(l), Save::U128(r)) => l.cmp(r),
+            (Save::F32(l), Save::F32(r)) => l.total_cmp(r),
+            (Save::F64(l), Save::F64(r)) => l.total_cmp(r),
+            (Save::Char(l), Save::Char(r)) => l.cmp(r),
+            (Save::String(l), Save::String(r)) => l.cmp(r),
+            (Save::ByteArray(l), Save::ByteArray(r)) => l.cmp(r),
+            (Save::Option(l), Save::Option(r)) => match (l, r) {
+                (None, None) => Ordering::Equal,
+                (None, Some(_)) => Ordering::Less,
+                (Some(_), None) => Ordering::Greater,
+                (Some(l), Some(r)) => l.cmp_canonical_by(r, errors),
+            },
+            (Save::Unit, Save::Unit) => Ordering::Equal,
+            (Save::UnitStruct(l), Save::UnitStruct(r)) => l.cmp(r),
+            (Save::UnitVariant(l), Save::UnitVariant(r)) => l.cmp(r),
+            (
+                Save::NewTypeStruct { name: ln, value: l },
+                Save::NewTypeStruct { name: rn, value: r },
+            ) => ln.cmp(rn).then_with(|| l.cmp_canonical_by(r, errors)),
+            (
+                Save::NewTypeVariant {
+                    variant: lv,
+                    value: l,
+                },
+                Save::NewTypeVariant {
+                    variant: rv,
+                    value: r,
+                },
+            ) => lv.cmp(rv).then_with(|| l.cmp_canonical_by(r, errors)),
+            (Save::Seq(l), Save::Seq(r)) | (Save::Tuple(l), Save::Tuple(r)) => {
+                elements(l, r, errors)
+            }
+            (
+                Save::TupleStruct {
+                    name: ln,
+                    values: l,
+                },
+                Save::TupleStruct {
+                    name: rn,
+                    values: r,
+                },
+            ) => ln.cmp(rn).then_with(|| elements(l, r, errors)),
+            (
+                Save::TupleVariant {
+                    variant: lv,
+                    values: l,
+                },
+                Save::TupleVariant {
+                    variant: rv,
+                    values: r,
+                },
+            ) => lv.cmp(rv).then_with(|| elements(l, r, errors)),
+            (Save::Map(l), Save::Map(r)) => {
+                let mut l = l.iter();
+                let mut r = r.iter();
+                loop {
+                    match (l.next(), r.next()) {
+                        (None, None) => break Ordering::Equal,
+                        (None, Some(_)) => break Ordering::Less,
+                        (Some(_), None) => break Ordering::Greater,
+                        (Some((lk, lv)), Some((rk, rv))) => {
+                            match lk
+                                .cmp_canonical_by(rk, errors)
+                                .then_with(|| lv.cmp_canonical_by(rv, errors))
+                            {
+                                Ordering::Equal => continue,
+                                it => break it,
+                            }
+                        }
+                    }
+                }
+            }
+            (
+                Save::Struct {
+                    name: ln,
+                    fields: l,
+                },
+                Save::Struct {
+                    name: rn,
+                    fields: r,
+                },
+            ) => ln.cmp(rn).then_with(|| fields(l, r, errors)),
+            (
+                Save::StructVariant {
+                    variant: lv,
+                    fields: l,
+                },
+                Save::StructVariant {
+                    variant: rv,
+                    fields: r,
+                },
+            ) => lv.cmp(rv).then_with(|| fields(l, r, errors)),
+            (Save::Error(l), Save::Error(r)) => errors(l, r),
+            (l, r) => l.kind_index().cmp(&r.kind_index()),
+        }
+    }
+
+    /// If this is a [`Save::Seq`], sort its elements by [`Save::cmp_canonical`].
+    ///
+    /// This allows e.g captures of a [`HashSet`](std::collections::HashSet)
+    /// to be compared regardless of iteration order.
+    ///
+    /// ```
+    /// # use serde_save::{save, Path, Save};
+    /// # use std::collections::HashSet;
+    /// #[derive(serde::Serialize)]
+    /// struct User {
+    ///     roles: HashSet<&'static str>,
+    /// }
+    ///
+    /// let mut saved = save(User { roles: HashSet::from(["b", "a", "c"]) }).unwrap();
+    /// saved.get_mut(&Path::parse("roles").unwrap()).unwrap().sort_seq();
+    /// assert_eq!(
+    ///     saved,
+    ///     Save::strukt("User", [("roles", save(["a", "b", "c"].to_vec()).unwrap())]),
+    /// );
+    /// ```
+    pub fn sort_seq(&mut self)
+    where
+        E: Ord,
+    {
+        if let Save::Seq(it) = self {
+            it.sort_by(Save::cmp_canonical)
+        }
+    }
+    /// If this is a [`Save::Seq`], remove consecutive elements which are equal
+    /// according to [`Save::cmp_canonical`].
+    ///
+    /// Call [`Save::sort_seq`] first to remove all duplicates.
+    ///
+    /// ```
+    /// # use serde_save::save;
+    /// let mut saved = save(vec![f64::NAN, 1.0, f64::NAN, 1.0]).unwrap();
+    /// saved.sort_seq();
+    /// saved.dedup_seq();
+    /// assert_eq!(format!("{:?}", saved), "Seq([F64(1.0), F64(NaN)])");
+    /// ```
+    pub fn dedup_seq(&mut self)
+    where
+        E: Ord,
+    {
+        if let Save::Seq(it) = self {
+            it.dedup_by(|l, r| l.cmp_canonical(r).is_eq())
+        }
+    }
+
+    /// The index of this node's variant in [`Save`]'s declaration.
+    pub(crate) fn kind_index(&self) -> u8 {
+        match self {
+            Save::Bool(_) => 0,
+            Save::I8(_) => 1,
+            Save::I16(_) => 2,
+            Save::I32(_) => 3,
+            Save::I64(_) => 4,
+            Save::I128(_) => 5,
+            Save::U8(_) => 6,
+            Save::U16(_) => 7,
+            Save::U32(_) => 8,
+            Save::U64(_) => 9,
+            Save::U128(_) => 10,
+            Save::F32(_) => 11,
+            Save::F64(_) => 12,
+            Save::Char(_) => 13,
+            Save::String(_) => 14,
+            Save::ByteArray(_) => 15,
+            Save::Option(_) => 16,
+            Save::Unit => 17,
+            Save::UnitStruct(_) => 18,
+            Save::UnitVariant(_) => 19,
+            Save::NewTypeStruct { .. } => 20,
+            Save::NewTypeVariant { .. } => 21,
+            Save::Seq(_) => 22,
+            Save::Map(_) => 23,
+            Save::Tuple(_) => 24,
+            Save::TupleStruct { .. } => 25,
+            Save::TupleVariant { .. } => 26,
+            Save::Struct { .. } => 27,
+            Save::StructVariant { .. } => 28,
+            Save::Error(_) => 29,
+        }
+    }
+    /// The name of this node's kind, see [`KINDS`].
+    pub(crate) fn kind_name(&self) -> &'static str {
+        KINDS[usize::from(self.kind_index())]
+    }
+    /// The name of this node's variant of [`Save`], see [`VARIANTS`].
+    pub(crate) fn variant_name(&self) -> &'static str {
+        VARIANTS[usize::from(self.kind_index())]
+    }
+}
+
+fn elements<E>(
+    l: &[Save<'_, E>],
+    r: &[Save<'_, E>],
+    errors: &impl Fn(&E, &E) -> Ordering,
+) -> Ordering {
+    let mut l = l.iter();
+    let mut r = r.iter();
+    loop {
+        match (l.next(), r.next()) {
+            (None, None) => break Ordering::Equal,
+            (None, Some(_)) => break Ordering::Less,
+            (Some(_), None) => break Ordering::Greater,
+            (Some(l), Some(r)) => match l.cmp_canonical_by(r, errors) {
+                Ordering::Equal => continue,
+                it => break it,
+            },
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn fields<E>(
+    l: &[(&str, Option<Save<'_, E>>)],
+    r: &[(&str, Option<Save<'_, E>>)],
+    errors: &impl Fn(&E, &E) -> Ordering,
+) -> Ordering {
+    let mut l = l.iter();
+    let mut r = r.iter();
+    loop {
+        match (l.next(), r.next()) {
+            (None, None) => break Ordering::Equal,
+            (None, Some(_)) => break Ordering::Less,
+            (Some(_), None) => break Ordering::Greater,
+            (Some((ln, lv)), Some((rn, rv))) => {
+                let it = ln.cmp(rn).then_with(|| match (lv, rv) {
+                    (None, None) => Ordering::Equal,
+                    (None, Some(_)) => Ordering::Less,
+                    (Some(_), None) => Ordering::Greater,
+                    (Some(l), Some(r)) => l.cmp_canonical_by(r, errors),
+                });
+                match it {
+                    Ordering::Equal => continue,
+                    it => break it,
+                }
+            }
+        }
+    }
+}
+
+/// The names of each kind of node, indexed by [`Save::kind_index`].
+pub(crate) const KINDS: [&str; 30] = [
+    "bool",
+    "i8",
+    "i16",
+    "i32",
+    "i64",
+    "i128",
+    "u8",
+    "u16",
+    "u32",
+    "u64",
+    "u128",
+    "f32",
+    "f64",
+    "char",
+    "string",
+    "bytes",
+    "option",
+    "unit",
+    "unit_struct",
+    "unit_variant",
+    "newtype_struct",
+    "newtype_variant",
+    "seq",
+    "map",
+    "tuple",
+    "tuple_struct",
+    "tuple_variant",
+    "struct",
+    "struct_variant",
+    "error",
+];
+
+/// The names of [`Save`]'s variants, indexed by [`Save::kind_index`].
+pub(crate) const VARIANTS: [&str; 30] = [
+    "Bool",
+    "I8",
+    "I16",
+    "I32",
+    "I64",
+    "I128",
+    "U8",
+    "U16",
+    "U32",
+    "U64",
+    "U128",
+    "F32",
+    "F64",
+    "Char",
+    "String",
+    "ByteArray",
+    "Option",
+    "Unit",
+    "UnitStruct",
+    "UnitVariant",
+    "NewTypeStruct",
+    "NewTypeVariant",
+    "Seq",
+    "Map",
+    "Tuple",
+    "TupleStruct",
+    "TupleVariant",
+    "Struct",
+    "StructVariant",
+    "Error",
+];
@@ -0,0 +1,243 @@
+use crate::Save;
+use core::{
+    iter::{Enumerate, Map},
+    slice,
+};
+use std::vec;
+
+/// How a child is reached from its parent, see [`Save::children`].
+#[derive(Debug, PartialEq)]
+pub enum Edge<'s, 'a, E> {
+    /// A named field of a [`Save::Struct`] or [`Save::StructVariant`].
+    Field(&'a str),
+    /// An element of a [`Save::Seq`], [`Save::Tuple`], [`Save::TupleStruct`]
+    /// or [`Save::TupleVariant`].
+    Index(usize),
+    /// The value of a [`Save::Map`] entry with the given key.
+    Key(&'s Save<'a, E>),
+    /// The contents of a [`Save::Option`], [`Save::NewTypeStruct`] or
+    /// [`Save::NewTypeVariant`].
+    Value,
+}
+
+impl<E> Clone for Edge<'_, '_, E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<E> Copy for Edge<'_, '_, E> {}
+
+/// An iterator over the immediate children of a node, see [`Save::children`].
+#[derive(Debug)]
+pub struct Children<'s, 'a, E> {
+    inner: Inner<'s, 'a, E>,
+}
+
+#[derive(Debug)]
+enum Inner<'s, 'a, E> {
+    Value(Option<&'s Save<'a, E>>),
+    Elements(Enumerate<slice::Iter<'s, Save<'a, E>>>),
+    Entries(slice::Iter<'s, (Save<'a, E>, Save<'a, E>)>),
+    Fields(slice::Iter<'s, (&'a str, Option<Save<'a, E>>)>),
+}
+
+impl<'s, 'a, E> Iterator for Children<'s, 'a, E> {
+    type Item = (Edge<'s, 'a, E>, &'s Save<'a, E>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.inner {
+            Inner::Value(it) => it.take().map(|it| (Edge::Value, it)),
+            Inner::Elements(it) => it.next().map(|(ix, it)| (Edge::Index(ix), it)),
+            Inner::Entries(it) => it.next().map(|(k, v)| (Edge::Key(k), v)),
+            Inner::Fields(it) => it.find_map(|(name, it)| Some((Edge::Field(name), it.as_ref()?))),
+        }
+    }
+}
+
+impl<'a, E> Save<'a, E> {
+    /// Iterate over the immediate children of this node.
+    ///
+    /// [Skipped](serde::ser::SerializeStruct::skip_field) fields are not
+    /// yielded, and map keys are yielded as part of each value's [`Edge`].
+    ///
+    /// ```
+    /// # use serde_save::{Edge, Save};
+    /// let save = Save::<std::convert::Infallible>::strukt("Point", [("x", 1), ("y", 2)]);
+    /// assert_eq!(
+    ///     save.children().collect::<Vec<_>>(),
+    ///     [
+    ///         (Edge::Field("x"), &Save::I32(1)),
+    ///         (Edge::Field("y"), &Save::I32(2)),
+    ///     ]
+    /// );
+    /// ```
+    pub fn children(&self) -> Children<'_, 'a, E> {
+        let inner = match self {
+            Save::Option(Some(it))
+            | Save::NewTypeStruct { value: it, .. }
+            | Save::NewTypeVariant { value: it, .. } => Inner::Value(Some(it)),
+            Save::Seq(it)
+            | Save::Tuple(it)
+            | Save::TupleStruct { values: it, .. }
+            | Save::TupleVariant { values: it, .. } => Inner::Elements(it.iter().enumerate()),
+            Save::Map(it) => Inner::Entries(it.iter()),
+            Save::Struct { fields, .. } | Save::StructVariant { fields, .. } => {
+                Inner::Fields(fields.iter())
+            }
+            _ => Inner::Value(None),
+        };
+        Children { inner }
+    }
+}
+
+impl<'a, E> Save<'a, E> {
+    /// The elements of a [`Save::Seq`], [`Save::Tuple`], [`Save::TupleStruct`]
+    /// or [`Save::TupleVariant`].
+    ///
+    /// Empty for any other kind of node.
+    pub fn elements(&self) -> slice::Iter<'_, Save<'a, E>> {
+        match self {
+            Save::Seq(it)
+            | Save::Tuple(it)
+            | Save::TupleStruct { values: it, .. }
+            | Save::TupleVariant { values: it, .. } => it.iter(),
+            _ => [].iter(),
+        }
+    }
+    /// Consuming version of [`Self::elements`].
+    ///
+    /// ```
+    /// # use serde_save::{save, Save};
+    /// let odd = save(vec![1, 2, 3])
+    ///     .unwrap()
+    ///     .into_elements()
+    ///     .filter(|it| !matches!(it, Save::I32(2)))
+    ///     .collect::<Save>();
+    /// assert_eq!(odd, Save::from_iter([1, 3]));
+    /// ```
+    pub fn into_elements(self) -> vec::IntoIter<Save<'a, E>> {
+        match self {
+            Save::Seq(it)
+            | Save::Tuple(it)
+            | Save::TupleStruct { values: it, .. }
+            | Save::TupleVariant { values: it, .. } => it.into_iter(),
+            _ => Vec::new().into_iter(),
+        }
+    }
+    /// The entries of a [`Save::Map`].
+    ///
+    /// Empty for any other kind of node.
+    ///
+    /// ```
+    /// # use serde_save::{save, Save};
+    /// # use std::collections::BTreeMap;
+    /// let saved = save(BTreeMap::from([("a", 1), ("b", 2)])).unwrap();
+    /// let values = saved.entries().map(|(_, v)| v).collect::<Vec<_>>();
+    /// assert_eq!(values, [&Save::I32(1), &Save::I32(2)]);
+    /// ```
+    pub fn entries(&self) -> slice::Iter<'_, (Save<'a, E>, Save<'a, E>)> {
+        match self {
+            Save::Map(it) => it.iter(),
+            _ => [].iter(),
+        }
+    }
+    /// Consuming version of [`Self::entries`].
+    pub fn into_entries(self) -> vec::IntoIter<(Save<'a, E>, Save<'a, E>)> {
+        match self {
+            Save::Map(it) => it.into_iter(),
+            _ => Vec::new().into_iter(),
+        }
+    }
+}
+
+/// Iterate over the values of the immediate children of a node,
+/// in the same order as [`Save::children`].
+///
+/// - The elements of sequences and tuples.
+/// - The values of map entries, but not their keys,
+///   which are available from [`Save::entries`].
+/// - The values of struct fields, except [skipped](serde::ser::SerializeStruct::skip_field) fields.
+/// - The contents of [`Save::Option`], [`Save::NewTypeStruct`] and [`Save::NewTypeVariant`].
+///
+/// ```
+/// # use serde_save::{save, Save};
+/// # use std::collections::BTreeMap;
+/// let saved = save(BTreeMap::from([("a", 1), ("b", 2)])).unwrap();
+/// let mut sum = 0;
+/// for value in &saved {
+///     if let Save::I32(it) = value {
+///         sum += it
+///     }
+/// }
+/// assert_eq!(sum, 3);
+/// ```
+impl<'s, 'a, E> IntoIterator for &'s Save<'a, E> {
+    type Item = &'s Save<'a, E>;
+    type IntoIter =
+        Map<Children<'s, 'a, E>, fn((Edge<'s, 'a, E>, &'s Save<'a, E>)) -> &'s Save<'a, E>>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.children().map(|(_, it)| it)
+    }
+}
+
+/// Consuming version of the implementation for `&Save`,
+/// which drops map keys.
+///
+/// ```
+/// # use serde_save::{save, Save};
+/// let saved = save((1, "two")).unwrap();
+/// assert_eq!(
+///     saved.into_iter().collect::<Vec<_>>(),
+///     [Save::I32(1), Save::string("two")]
+/// );
+/// ```
+impl<'a, E> IntoIterator for Save<'a, E> {
+    type Item = Save<'a, E>;
+    type IntoIter = IntoChildren<'a, E>;
+    fn into_iter(self) -> Self::IntoIter {
+        let inner = match self {
+            Save::Option(Some(it))
+            | Save::NewTypeStruct { value: it, .. }
+            | Save::NewTypeVariant { value: it, .. } => IntoInner::Value(Some(*it)),
+            Save::Seq(it)
+            | Save::Tuple(it)
+            | Save::TupleStruct { values: it, .. }
+            | Save::TupleVariant { values: it, .. } => IntoInner::Elements(it.into_iter()),
+            Save::Map(it) => IntoInner::Entries(it.into_iter()),
+            Save::Struct { fields, .. } | Save::StructVariant { fields, .. } => {
+                IntoInner::Fields(fields.into_iter())
+            }
+            _ => IntoInner::Value(None),
+        };
+        IntoChildren { inner }
+    }
+}
+
+/// A consuming iterator over the values of the immediate children of a node,
+/// see the [`IntoIterator`] implementation for [`Save`].
+#[derive(Debug)]
+pub struct IntoChildren<'a, E> {
+    inner: IntoInner<'a, E>,
+}
+
+#[derive(Debug)]
+enum IntoInner<'a, E> {
+    Value(Option<Save<'a, E>>),
+    Elements(vec::IntoIter<Save<'a, E>>),
+    Entries(vec::IntoIter<(Save<'a, E>, Save<'a, E>)>),
+    Fields(vec::IntoIter<(&'a str, Option<Save<'a, E>>)>),
+}
+
+impl<'a, E> Iterator for IntoChildren<'a, E> {
+    type Item = Save<'a, E>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.inner {
+            IntoInner::Value(it) => it.take(),
+            IntoInner::Elements(it) => it.next(),
+            IntoInner::Entries(it) => it.next().map(|(_, v)| v),
+            IntoInner::Fields(it) => it.find_map(|(_, it)| it),
+        }
+    }
+}
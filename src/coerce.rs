@@ -0,0 +1,114 @@
+use crate::Save;
+use std::borrow::Cow;
+
+impl<'a, E> Save<'a, E> {
+    /// View a [`Save::Struct`], [`Save::StructVariant`], or [`Save::Map`] with
+    /// string keys as a list of key/value entries,
+    /// for consumers which don't care which of the three the producer chose.
+    ///
+    /// [Skipped](serde::ser::SerializeStruct::skip_field) fields are omitted.
+    ///
+    /// Returns [`None`] for any other node,
+    /// or if a map has a key which isn't a [`Save::String`].
+    ///
+    /// ```
+    /// # use serde_save::{save, Save};
+    /// # use std::collections::BTreeMap;
+    /// #[derive(serde::Serialize)]
+    /// struct Point {
+    ///     x: i32,
+    ///     y: i32,
+    /// }
+    ///
+    /// let strukt = save(Point { x: 1, y: 2 }).unwrap();
+    /// let map = save(BTreeMap::from([("x", 1), ("y", 2)])).unwrap();
+    /// assert_eq!(strukt.as_map_like(), map.as_map_like());
+    /// assert_eq!(
+    ///     strukt.as_map_like().unwrap(),
+    ///     [("x", &Save::I32(1)), ("y", &Save::I32(2))],
+    /// );
+    ///
+    /// assert_eq!(save(BTreeMap::from([(1, 1)])).unwrap().as_map_like(), None);
+    /// ```
+    pub fn as_map_like(&self) -> Option<Vec<(&str, &Self)>> {
+        match self {
+            Save::Struct { fields, .. } | Save::StructVariant { fields, .. } => Some(
+                fields
+                    .iter()
+                    .filter_map(|(k, v)| Some((*k, v.as_ref()?)))
+                    .collect(),
+            ),
+            Save::Map(it) => it
+                .iter()
+                .map(|(k, v)| match k {
+                    Save::String(k) => Some((k.as_str(), v)),
+                    _ => None,
+                })
+                .collect(),
+            _ => None,
+        }
+    }
+    /// Take the key/value entries of a [`Save::Struct`], [`Save::StructVariant`],
+    /// or [`Save::Map`] with string keys, see [`Save::as_map_like`].
+    ///
+    /// Returns the original node if it is not map-like.
+    ///
+    /// ```
+    /// # use serde_save::Save;
+    /// # use std::borrow::Cow;
+    /// let strukt = Save::<std::convert::Infallible>::strukt("Point", [("x", 1)]);
+    /// assert_eq!(strukt.try_into_map().unwrap(), [(Cow::Borrowed("x"), Save::I32(1))]);
+    ///
+    /// let seq = Save::<std::convert::Infallible>::from_iter([1]);
+    /// assert_eq!(seq.clone().try_into_map().unwrap_err(), seq);
+    /// ```
+    #[allow(clippy::type_complexity)]
+    pub fn try_into_map(self) -> Result<Vec<(Cow<'a, str>, Self)>, Self> {
+        match self {
+            Save::Struct { fields, .. } | Save::StructVariant { fields, .. } => Ok(fields
+                .into_iter()
+                .filter_map(|(k, v)| Some((Cow::Borrowed(k), v?)))
+                .collect()),
+            Save::Map(it) if it.iter().all(|(k, _)| matches!(k, Save::String(_))) => Ok(it
+                .into_iter()
+                .map(|(k, v)| match k {
+                    Save::String(k) => (Cow::Owned(k), v),
+                    _ => unreachable!("checked above"),
+                })
+                .collect()),
+            other => Err(other),
+        }
+    }
+    /// Present a map-like node as a [`Save::Struct`] called `name`,
+    /// with a field per entry, see [`Save::as_map_like`].
+    ///
+    /// Field names are borrowed from this tree,
+    /// so the values are cloned.
+    ///
+    /// ```
+    /// # use serde_save::{save, Save};
+    /// # use std::collections::BTreeMap;
+    /// #[derive(serde::Serialize)]
+    /// struct Point {
+    ///     x: i32,
+    ///     y: i32,
+    /// }
+    ///
+    /// let map = save(BTreeMap::from([("x", 1), ("y", 2)])).unwrap();
+    /// assert_eq!(
+    ///     map.map_to_struct("Point").unwrap(),
+    ///     save(Point { x: 1, y: 2 }).unwrap(),
+    /// );
+    /// ```
+    pub fn map_to_struct<'s>(&'s self, name: &'s str) -> Option<Save<'s, E>>
+    where
+        E: Clone,
+    {
+        let fields = self
+            .as_map_like()?
+            .into_iter()
+            .map(|(k, v)| (k, Some(v.clone())))
+            .collect();
+        Some(Save::Struct { name, fields })
+    }
+}
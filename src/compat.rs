@@ -0,0 +1,283 @@
+use crate::{Segment, Shape, ShapeChange, ShapeDiff};
+use core::fmt;
+use serde::ser::{SerializeSeq as _, SerializeStruct as _};
+
+/// Whether data can be exchanged between the old and new versions of a type,
+/// see [`ShapeDiff::compatibility`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Compatibility {
+    /// Old and new readers can read both old and new data.
+    Full,
+    /// New readers can read old data.
+    Backward,
+    /// Old readers can read new data.
+    Forward,
+    /// Neither reader can read the other's data.
+    Breaking,
+}
+
+impl Compatibility {
+    fn new(backward: bool, forward: bool) -> Self {
+        match (backward, forward) {
+            (true, true) => Self::Full,
+            (true, false) => Self::Backward,
+            (false, true) => Self::Forward,
+            (false, false) => Self::Breaking,
+        }
+    }
+    /// New readers can read old data.
+    pub fn is_backward(self) -> bool {
+        matches!(self, Self::Full | Self::Backward)
+    }
+    /// Old readers can read new data.
+    pub fn is_forward(self) -> bool {
+        matches!(self, Self::Full | Self::Forward)
+    }
+    /// The compatibility of two changes together.
+    pub fn and(self, other: Self) -> Self {
+        Self::new(
+            self.is_backward() && other.is_backward(),
+            self.is_forward() && other.is_forward(),
+        )
+    }
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Full => "full",
+            Self::Backward => "backward",
+            Self::Forward => "forward",
+            Self::Breaking => "breaking",
+        }
+    }
+}
+
+impl fmt::Display for Compatibility {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Serialized as a lowercase string.
+impl serde::Serialize for Compatibility {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+/// Assumptions about readers, for [`ShapeDiff::compatibility`].
+///
+/// The defaults match the behaviour of `#[derive(Deserialize)]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct CompatRules {
+    /// See [`CompatRules::ignore_unknown_fields`].
+    pub ignore_unknown_fields: bool,
+    /// See [`CompatRules::default_missing_options`].
+    pub default_missing_options: bool,
+    /// See [`CompatRules::default_missing_fields`].
+    pub default_missing_fields: bool,
+    /// See [`CompatRules::integer_widening`].
+    pub integer_widening: bool,
+}
+
+impl CompatRules {
+    /// Unknown fields are ignored and missing [`Option`] fields are [`None`],
+    /// but other changes are breaking.
+    pub fn new() -> Self {
+        Self {
+            ignore_unknown_fields: true,
+            default_missing_options: true,
+            default_missing_fields: false,
+            integer_widening: false,
+        }
+    }
+    /// Readers ignore fields they don't know about,
+    /// so adding a field is forward compatible and removing one is backward compatible.
+    ///
+    /// Disable this for types with `#[serde(deny_unknown_fields)]`.
+    pub fn ignore_unknown_fields(mut self, ignore: bool) -> Self {
+        self.ignore_unknown_fields = ignore;
+        self
+    }
+    /// Readers treat missing [`Option`] fields as [`None`],
+    /// so adding an optional field is backward compatible and removing one is forward compatible.
+    pub fn default_missing_options(mut self, default: bool) -> Self {
+        self.default_missing_options = default;
+        self
+    }
+    /// Readers fill in any missing field, as with `#[serde(default)]`.
+    pub fn default_missing_fields(mut self, default: bool) -> Self {
+        self.default_missing_fields = default;
+        self
+    }
+    /// Readers accept integers of a narrower type,
+    /// so widening an integer is backward compatible and narrowing one is forward compatible.
+    pub fn integer_widening(mut self, widening: bool) -> Self {
+        self.integer_widening = widening;
+        self
+    }
+}
+
+impl Default for CompatRules {
+    /// See [`Self::new`].
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The [`Compatibility`] of each change in a [`ShapeDiff`],
+/// see [`ShapeDiff::compatibility`].
+///
+/// Serializes as a struct with the overall `verdict`, and a list of `changes`
+/// with their `path`, `kind` and `verdict`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompatReport {
+    changes: Vec<(ShapeChange, Compatibility)>,
+}
+
+impl CompatReport {
+    /// The compatibility of all changes together.
+    pub fn verdict(&self) -> Compatibility {
+        self.changes
+            .iter()
+            .fold(Compatibility::Full, |acc, (_, it)| acc.and(*it))
+    }
+    /// Each change, with its compatibility.
+    pub fn iter(&self) -> core::slice::Iter<'_, (ShapeChange, Compatibility)> {
+        self.changes.iter()
+    }
+    /// Only the changes which are not [fully](Compatibility::Full) compatible.
+    pub fn incompatible(&self) -> impl Iterator<Item = &(ShapeChange, Compatibility)> {
+        self.changes
+            .iter()
+            .filter(|(_, it)| *it != Compatibility::Full)
+    }
+}
+
+impl serde::Serialize for CompatReport {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        struct Changes<'r>(&'r [(ShapeChange, Compatibility)]);
+        struct Entry<'r>(&'r ShapeChange, Compatibility);
+        impl serde::Serialize for Changes<'_> {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                let mut seq = serializer.serialize_seq(Some(self.0.len()))?;
+                for (change, verdict) in self.0 {
+                    seq.serialize_element(&Entry(change, *verdict))?
+                }
+                seq.end()
+            }
+        }
+        impl serde::Serialize for Entry<'_> {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                let kind = match self.0 {
+                    ShapeChange::Added { .. } => "added",
+                    ShapeChange::Removed { .. } => "removed",
+                    ShapeChange::Renamed { .. } => "renamed",
+                    ShapeChange::Changed { .. } => "changed",
+                };
+                let mut strukt = serializer.serialize_struct("Change", 3)?;
+                strukt.serialize_field("path", &self.0.path().to_string())?;
+                strukt.serialize_field("kind", kind)?;
+                strukt.serialize_field("verdict", &self.1)?;
+                strukt.end()
+            }
+        }
+        let mut strukt = serializer.serialize_struct("CompatReport", 2)?;
+        strukt.serialize_field("verdict", &self.verdict())?;
+        strukt.serialize_field("changes", &Changes(&self.changes))?;
+        strukt.end()
+    }
+}
+
+impl ShapeDiff {
+    /// Classify each change from the old shape to the new shape,
+    /// according to `rules`.
+    ///
+    /// Renames, added or removed elements of tuples, and changes of kind
+    /// (other than [integer widening](CompatRules::integer_widening)) are
+    /// [breaking](Compatibility::Breaking).
+    ///
+    /// ```
+    /// # use serde_save::{CompatRules, Compatibility, Shape, save};
+    /// mod v1 {
+    ///     #[derive(serde::Serialize)]
+    ///     pub struct User { pub id: u32, pub email: String }
+    /// }
+    /// mod v2 {
+    ///     #[derive(serde::Serialize)]
+    ///     pub struct User { pub id: u32, pub email: String, pub nickname: Option<String> }
+    /// }
+    /// mod v3 {
+    ///     #[derive(serde::Serialize)]
+    ///     pub struct User { pub id: u32, pub nickname: Option<String> }
+    /// }
+    ///
+    /// let v1 = Shape::of(&save(v1::User { id: 1, email: "".into() }).unwrap());
+    /// let v2 = Shape::of(&save(v2::User { id: 1, email: "".into(), nickname: None }).unwrap());
+    /// let v3 = Shape::of(&save(v3::User { id: 1, nickname: None }).unwrap());
+    ///
+    /// let rules = CompatRules::new();
+    /// assert_eq!(v1.diff(&v2).compatibility(rules).verdict(), Compatibility::Full);
+    /// // Old readers require `email`.
+    /// assert_eq!(v2.diff(&v3).compatibility(rules).verdict(), Compatibility::Backward);
+    /// ```
+    pub fn compatibility(&self, rules: CompatRules) -> CompatReport {
+        CompatReport {
+            changes: self
+                .iter()
+                .map(|change| (change.clone(), classify(change, rules)))
+                .collect(),
+        }
+    }
+}
+
+fn classify(change: &ShapeChange, rules: CompatRules) -> Compatibility {
+    let is_field = matches!(change.path().last(), Some(Segment::Field(_)));
+    // Whether a reader which expects a field of this shape accepts its absence.
+    let defaulted = |shape: &Shape| {
+        rules.default_missing_fields
+            || (rules.default_missing_options && matches!(shape, Shape::Option(_)))
+    };
+    match change {
+        ShapeChange::Added { shape, .. } if is_field => {
+            Compatibility::new(defaulted(shape), rules.ignore_unknown_fields)
+        }
+        ShapeChange::Removed { shape, .. } if is_field => {
+            Compatibility::new(rules.ignore_unknown_fields, defaulted(shape))
+        }
+        ShapeChange::Changed { before, after, .. } if rules.integer_widening => {
+            match (width(before), width(after)) {
+                (Some(before), Some(after)) => {
+                    Compatibility::new(fits(before, after), fits(after, before))
+                }
+                _ => Compatibility::Breaking,
+            }
+        }
+        _ => Compatibility::Breaking,
+    }
+}
+
+/// The signedness and bit width of an integer shape.
+fn width(shape: &Shape) -> Option<(bool, u32)> {
+    Some(match shape {
+        Shape::I8 => (true, 8),
+        Shape::I16 => (true, 16),
+        Shape::I32 => (true, 32),
+        Shape::I64 => (true, 64),
+        Shape::I128 => (true, 128),
+        Shape::U8 => (false, 8),
+        Shape::U16 => (false, 16),
+        Shape::U32 => (false, 32),
+        Shape::U64 => (false, 64),
+        Shape::U128 => (false, 128),
+        _ => return None,
+    })
+}
+
+/// Whether every value of integer type `from` can be represented by `to`.
+fn fits((from_signed, from_bits): (bool, u32), (to_signed, to_bits): (bool, u32)) -> bool {
+    match (from_signed, to_signed) {
+        (false, true) => from_bits < to_bits,
+        (true, false) => false,
+        _ => from_bits <= to_bits,
+    }
+}
@@ -0,0 +1,97 @@
+use crate::{Error, Save};
+use core::{cmp::Ordering, convert::Infallible};
+use serde::ser::Error as _;
+use std::sync::Mutex;
+
+/// Assemble a [`Save::Seq`] from elements captured on multiple threads.
+///
+/// Elements are stamped with their index, and ordered when [finished](Self::finish).
+///
+/// ```
+/// # use serde_save::{save, Save, SeqBuilder};
+/// let data = (0..100).collect::<Vec<u32>>();
+/// let builder = SeqBuilder::new();
+/// std::thread::scope(|scope| {
+///     for (ix, chunk) in data.chunks(30).enumerate() {
+///         let builder = &builder;
+///         scope.spawn(move || {
+///             let elements = chunk.iter().map(|it| save(it).unwrap()).collect();
+///             builder.insert_partition(ix * 30, elements);
+///         });
+///     }
+/// });
+/// assert_eq!(builder.finish().unwrap(), save(&data).unwrap());
+/// ```
+#[derive(Debug)]
+pub struct SeqBuilder<E = Infallible> {
+    /// Contiguous runs of elements, by starting index.
+    partitions: Mutex<Vec<(usize, Vec<Save<'static, E>>)>>,
+}
+
+impl<E> Default for SeqBuilder<E> {
+    /// See [`Self::new`].
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E> SeqBuilder<E> {
+    /// Create an empty builder.
+    pub fn new() -> Self {
+        Self {
+            partitions: Mutex::new(Vec::new()),
+        }
+    }
+    /// Insert the element at `index`.
+    pub fn insert(&self, index: usize, element: Save<'static, E>) {
+        self.insert_partition(index, vec![element])
+    }
+    /// Insert a contiguous run of elements, starting at `start`.
+    pub fn insert_partition(&self, start: usize, elements: Vec<Save<'static, E>>) {
+        self.partitions
+            .lock()
+            .unwrap_or_else(|it| it.into_inner())
+            .push((start, elements))
+    }
+    /// Merge the inserted elements into a [`Save::Seq`].
+    ///
+    /// Fails if any index was inserted more than once, or if any index
+    /// before the last was never inserted.
+    ///
+    /// ```
+    /// # use serde_save::{Save, SeqBuilder};
+    /// let builder = SeqBuilder::<std::convert::Infallible>::new();
+    /// builder.insert(0, Save::U8(0));
+    /// builder.insert(2, Save::U8(2));
+    /// assert_eq!(
+    ///     builder.finish().unwrap_err().to_string(),
+    ///     "element at index 1 was never inserted"
+    /// );
+    /// ```
+    pub fn finish(self) -> Result<Save<'static, E>, Error> {
+        let mut partitions = self
+            .partitions
+            .into_inner()
+            .unwrap_or_else(|it| it.into_inner());
+        partitions.sort_by_key(|(start, _)| *start);
+        let mut elements = Vec::with_capacity(partitions.iter().map(|(_, it)| it.len()).sum());
+        for (start, partition) in partitions {
+            match start.cmp(&elements.len()) {
+                Ordering::Less if !partition.is_empty() => {
+                    return Err(Error::custom(format_args!(
+                        "element at index {} was inserted more than once",
+                        start
+                    )))
+                }
+                Ordering::Greater => {
+                    return Err(Error::custom(format_args!(
+                        "element at index {} was never inserted",
+                        elements.len()
+                    )))
+                }
+                _ => elements.extend(partition),
+            }
+        }
+        Ok(Save::Seq(elements))
+    }
+}
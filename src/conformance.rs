@@ -0,0 +1,218 @@
+//! A conformance kit for authors of [`serde::Serializer`]s.
+//!
+//! Each tree in the [`corpus`] exercises a corner of the serde data model,
+//! and replaying a tree drives a serializer through exactly the calls that
+//! the tree represents.
+//!
+//! ```
+//! # use serde_save::{conformance, Serializer};
+//! // Our own serializer accepts everything.
+//! conformance::assert_conforms(Serializer::new);
+//!
+//! // Replaying a tree reproduces it exactly.
+//! for (_, save) in conformance::corpus() {
+//!     assert_eq!(conformance::check(&save, Serializer::new()).unwrap(), save);
+//! }
+//! ```
+
+use crate::{Save, Variant};
+use core::fmt;
+
+/// A case in the [`corpus`] which a serializer failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Failure {
+    /// The name of the case.
+    pub case: &'static str,
+    /// The error returned by the serializer.
+    pub error: String,
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.case, self.error)
+    }
+}
+
+/// Drive `serializer` through the calls represented by `save`.
+pub fn check<S: serde::Serializer>(save: &Save<'static>, serializer: S) -> Result<S::Ok, S::Error> {
+    serde::Serialize::serialize(save, serializer)
+}
+
+/// Run every case in the [`corpus`], with a fresh serializer from `make`
+/// for each, returning the cases which failed.
+pub fn run<S: serde::Serializer>(mut make: impl FnMut() -> S) -> Vec<Failure> {
+    corpus()
+        .into_iter()
+        .filter_map(|(case, save)| {
+            let error = check(&save, make()).err()?;
+            Some(Failure {
+                case,
+                error: error.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Like [`run`], but panic with a list of failures, if there were any.
+#[track_caller]
+pub fn assert_conforms<S: serde::Serializer>(make: impl FnMut() -> S) {
+    let failures = run(make);
+    if !failures.is_empty() {
+        let mut msg = format!("{} conformance case(s) failed:", failures.len());
+        for it in &failures {
+            msg.push_str("\n- ");
+            msg.push_str(&it.to_string())
+        }
+        panic!("{}", msg)
+    }
+}
+
+/// Canonical trees covering every node in the serde data model,
+/// including boundary values, empty containers and nesting.
+///
+/// Each tree is paired with a descriptive name.
+pub fn corpus() -> Vec<(&'static str, Save<'static>)> {
+    let variant = |variant_index, variant| Variant {
+        name: "Enum",
+        variant_index,
+        variant,
+    };
+    vec![
+        ("bool false", Save::Bool(false)),
+        ("bool true", Save::Bool(true)),
+        ("i8 min", Save::I8(i8::MIN)),
+        ("i8 max", Save::I8(i8::MAX)),
+        ("i16 min", Save::I16(i16::MIN)),
+        ("i16 max", Save::I16(i16::MAX)),
+        ("i32 min", Save::I32(i32::MIN)),
+        ("i32 max", Save::I32(i32::MAX)),
+        ("i64 min", Save::I64(i64::MIN)),
+        ("i64 max", Save::I64(i64::MAX)),
+        ("i128 min", Save::I128(i128::MIN)),
+        ("i128 max", Save::I128(i128::MAX)),
+        ("u8 max", Save::U8(u8::MAX)),
+        ("u16 max", Save::U16(u16::MAX)),
+        ("u32 max", Save::U32(u32::MAX)),
+        ("u64 max", Save::U64(u64::MAX)),
+        ("u128 max", Save::U128(u128::MAX)),
+        ("u64 zero", Save::U64(0)),
+        ("f32 negative zero", Save::F32(-0.0)),
+        ("f32 min positive", Save::F32(f32::MIN_POSITIVE)),
+        ("f32 max", Save::F32(f32::MAX)),
+        ("f64 negative zero", Save::F64(-0.0)),
+        ("f64 epsilon", Save::F64(f64::EPSILON)),
+        ("f64 min", Save::F64(f64::MIN)),
+        ("char nul", Save::Char('\0')),
+        ("char max", Save::Char(char::MAX)),
+        ("char multibyte", Save::Char('é')),
+        ("string empty", Save::string("")),
+        ("string escapes", Save::string("\"\\\n\t\r\u{8}\u{c}/")),
+        ("string control", Save::string("\u{0}\u{1f}\u{7f}")),
+        ("string unicode", Save::string("héllo, 世界 🦀")),
+        ("bytes empty", Save::ByteArray(Vec::new())),
+        ("bytes all", Save::ByteArray((0..=u8::MAX).collect())),
+        ("none", Save::Option(None)),
+        ("some", Save::Option(Some(Box::new(Save::U8(1))))),
+        (
+            "some none",
+            Save::Option(Some(Box::new(Save::Option(None)))),
+        ),
+        ("some unit", Save::Option(Some(Box::new(Save::Unit)))),
+        ("unit", Save::Unit),
+        ("unit struct", Save::UnitStruct("Unit")),
+        ("unit variant", Save::UnitVariant(variant(0, "Unit"))),
+        (
+            "newtype struct",
+            Save::NewTypeStruct {
+                name: "NewType",
+                value: Box::new(Save::U8(1)),
+            },
+        ),
+        (
+            "newtype variant",
+            Save::NewTypeVariant {
+                variant: variant(1, "NewType"),
+                value: Box::new(Save::U8(1)),
+            },
+        ),
+        ("seq empty", Save::Seq(Vec::new())),
+        (
+            "seq heterogeneous",
+            Save::Seq(vec![Save::U8(1), Save::string("two"), Save::Unit]),
+        ),
+        (
+            "seq nested",
+            Save::Seq(vec![Save::Seq(vec![Save::Seq(Vec::new())])]),
+        ),
+        ("map empty", Save::Map(Vec::new())),
+        (
+            "map string keys",
+            Save::Map(vec![
+                (Save::string("a"), Save::U8(1)),
+                (Save::string("b"), Save::U8(2)),
+            ]),
+        ),
+        (
+            "map integer keys",
+            Save::Map(vec![
+                (Save::I32(-1), Save::Unit),
+                (Save::I32(1), Save::Unit),
+            ]),
+        ),
+        (
+            "map compound keys",
+            Save::Map(vec![(
+                Save::Tuple(vec![Save::U8(1), Save::U8(2)]),
+                Save::Unit,
+            )]),
+        ),
+        ("tuple empty", Save::Tuple(Vec::new())),
+        ("tuple", Save::Tuple(vec![Save::U8(1), Save::Bool(true)])),
+        (
+            "tuple struct",
+            Save::TupleStruct {
+                name: "TupleStruct",
+                values: vec![Save::U8(1), Save::U8(2)],
+            },
+        ),
+        (
+            "tuple variant",
+            Save::TupleVariant {
+                variant: variant(2, "Tuple"),
+                values: vec![Save::U8(1), Save::U8(2)],
+            },
+        ),
+        (
+            "struct empty",
+            Save::Struct {
+                name: "Empty",
+                fields: Vec::new(),
+            },
+        ),
+        (
+            "struct",
+            Save::Struct {
+                name: "Struct",
+                fields: vec![("a", Some(Save::U8(1))), ("b", Some(Save::string("b")))],
+            },
+        ),
+        (
+            "struct skipped field",
+            Save::Struct {
+                name: "Struct",
+                fields: vec![("a", Some(Save::U8(1))), ("b", None)],
+            },
+        ),
+        (
+            "struct variant",
+            Save::StructVariant {
+                variant: variant(3, "Struct"),
+                fields: vec![("a", Some(Save::U8(1)))],
+            },
+        ),
+        (
+            "deeply nested",
+            (0..64).fold(Save::Unit, |acc, _| Save::Seq(vec![acc])),
+        ),
+    ]
+}
@@ -0,0 +1,116 @@
+use crate::Save;
+use core::convert::Infallible;
+
+impl<'a, E> Save<'a, E> {
+    /// Convert the error type of this tree, by rewriting every [`Save::Error`] with `f`.
+    ///
+    /// This allows embedding a capture in application data structures,
+    /// or persisting it with a serializable error type.
+    ///
+    /// ```
+    /// # use serde_save::{save_errors, Save};
+    /// # use std::{ffi::OsString, os::unix::ffi::OsStringExt as _, path::PathBuf};
+    /// let non_utf8_path = PathBuf::from(OsString::from_vec(vec![u8::MAX]));
+    /// let saved: Save<String> = save_errors(("fine", non_utf8_path)).map_error(|it| it.to_string());
+    /// assert_eq!(
+    ///     saved,
+    ///     Save::Tuple(vec![
+    ///         Save::string("fine"),
+    ///         Save::Error(String::from("path contains invalid UTF-8 characters")),
+    ///     ]),
+    /// );
+    /// ```
+    pub fn map_error<E2>(self, mut f: impl FnMut(E) -> E2) -> Save<'a, E2> {
+        map_error(self, &mut f)
+    }
+}
+
+impl<'a> Save<'a, Infallible> {
+    /// Convert a tree which cannot contain errors to any error type.
+    ///
+    /// Expected trees are naturally written as `Save<Infallible>`,
+    /// so this allows comparing or [merging](Save::merge3) them with captures
+    /// from [`save_errors`](crate::save_errors).
+    ///
+    /// ```
+    /// # use serde_save::{save_errors, Save};
+    /// let expected = Save::<std::convert::Infallible>::Seq(vec![Save::U8(1), Save::U8(2)]);
+    /// assert_eq!(expected.widen(), save_errors(vec![1u8, 2]));
+    /// ```
+    pub fn widen<E>(self) -> Save<'a, E> {
+        self.map_error(|never| match never {})
+    }
+}
+
+fn map_error<'a, E, E2>(save: Save<'a, E>, f: &mut impl FnMut(E) -> E2) -> Save<'a, E2> {
+    let mut elements = |it: Vec<Save<'a, E>>| {
+        it.into_iter()
+            .map(|it| map_error(it, f))
+            .collect::<Vec<_>>()
+    };
+    match save {
+        Save::Bool(it) => Save::Bool(it),
+        Save::I8(it) => Save::I8(it),
+        Save::I16(it) => Save::I16(it),
+        Save::I32(it) => Save::I32(it),
+        Save::I64(it) => Save::I64(it),
+        Save::I128(it) => Save::I128(it),
+        Save::U8(it) => Save::U8(it),
+        Save::U16(it) => Save::U16(it),
+        Save::U32(it) => Save::U32(it),
+        Save::U64(it) => Save::U64(it),
+        Save::U128(it) => Save::U128(it),
+        Save::F32(it) => Save::F32(it),
+        Save::F64(it) => Save::F64(it),
+        Save::Char(it) => Save::Char(it),
+        Save::String(it) => Save::String(it),
+        Save::ByteArray(it) => Save::ByteArray(it),
+        Save::Option(it) => Save::Option(it.map(|it| Box::new(map_error(*it, f)))),
+        Save::Unit => Save::Unit,
+        Save::UnitStruct(it) => Save::UnitStruct(it),
+        Save::UnitVariant(it) => Save::UnitVariant(it),
+        Save::NewTypeStruct { name, value } => Save::NewTypeStruct {
+            name,
+            value: Box::new(map_error(*value, f)),
+        },
+        Save::NewTypeVariant { variant, value } => Save::NewTypeVariant {
+            variant,
+            value: Box::new(map_error(*value, f)),
+        },
+        Save::Seq(it) => Save::Seq(elements(it)),
+        Save::Tuple(it) => Save::Tuple(elements(it)),
+        Save::TupleStruct { name, values } => Save::TupleStruct {
+            name,
+            values: elements(values),
+        },
+        Save::TupleVariant { variant, values } => Save::TupleVariant {
+            variant,
+            values: elements(values),
+        },
+        Save::Map(it) => Save::Map(
+            it.into_iter()
+                .map(|(k, v)| (map_error(k, f), map_error(v, f)))
+                .collect(),
+        ),
+        Save::Struct { name, fields } => Save::Struct {
+            name,
+            fields: map_fields(fields, f),
+        },
+        Save::StructVariant { variant, fields } => Save::StructVariant {
+            variant,
+            fields: map_fields(fields, f),
+        },
+        Save::Error(e) => Save::Error(f(e)),
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn map_fields<'a, E, E2>(
+    fields: Vec<(&'a str, Option<Save<'a, E>>)>,
+    f: &mut impl FnMut(E) -> E2,
+) -> Vec<(&'a str, Option<Save<'a, E2>>)> {
+    fields
+        .into_iter()
+        .map(|(k, v)| (k, v.map(|v| map_error(v, f))))
+        .collect()
+}
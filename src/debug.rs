@@ -0,0 +1,135 @@
+use crate::Save;
+use core::fmt;
+
+/// Renders a [`Save`] like the derived [`Debug`] implementation of the
+/// type that was serialized, see [`Save::derived_debug`].
+pub struct DerivedDebug<'s, 'a, E>(&'s Save<'a, E>);
+
+impl<'a, E> Save<'a, E> {
+    /// Render this tree as `#[derive(Debug)]` would have rendered the original
+    /// value, so that captures can be compared with [`dbg!`] output.
+    ///
+    /// Type names are dropped from primitives and enum names are dropped from
+    /// variants.
+    /// Structs with [skipped](Save::Struct::fields) fields end with `..`,
+    /// and [`Save::Error`]s are written as `<error: ..>`.
+    ///
+    /// The alternate flag, `{:#?}`, is respected.
+    ///
+    /// ```
+    /// # use serde_save::save;
+    /// #[derive(Debug, serde::Serialize)]
+    /// struct MyStruct {
+    ///     a: u8,
+    ///     b: Vec<MyEnum>,
+    ///     c: (char, Option<&'static str>),
+    /// }
+    ///
+    /// #[derive(Debug, serde::Serialize)]
+    /// enum MyEnum {
+    ///     Unit,
+    ///     Tuple(f32, i64),
+    ///     Struct { x: bool },
+    /// }
+    ///
+    /// let my_struct = MyStruct {
+    ///     a: 1,
+    ///     b: vec![MyEnum::Unit, MyEnum::Tuple(1.0, -1), MyEnum::Struct { x: true }],
+    ///     c: ('c', Some("hello")),
+    /// };
+    /// let saved = save(&my_struct).unwrap();
+    /// assert_eq!(
+    ///     format!("{:?}", saved.derived_debug()),
+    ///     format!("{:?}", my_struct),
+    /// );
+    /// assert_eq!(
+    ///     format!("{:#?}", saved.derived_debug()),
+    ///     format!("{:#?}", my_struct),
+    /// );
+    /// ```
+    pub fn derived_debug(&self) -> DerivedDebug<'_, 'a, E> {
+        DerivedDebug(self)
+    }
+}
+
+impl<E: fmt::Display> fmt::Debug for DerivedDebug<'_, '_, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Save::Bool(it) => fmt::Debug::fmt(it, f),
+            Save::I8(it) => fmt::Debug::fmt(it, f),
+            Save::I16(it) => fmt::Debug::fmt(it, f),
+            Save::I32(it) => fmt::Debug::fmt(it, f),
+            Save::I64(it) => fmt::Debug::fmt(it, f),
+            Save::I128(it) => fmt::Debug::fmt(it, f),
+            Save::U8(it) => fmt::Debug::fmt(it, f),
+            Save::U16(it) => fmt::Debug::fmt(it, f),
+            Save::U32(it) => fmt::Debug::fmt(it, f),
+            Save::U64(it) => fmt::Debug::fmt(it, f),
+            Save::U128(it) => fmt::Debug::fmt(it, f),
+            Save::F32(it) => fmt::Debug::fmt(it, f),
+            Save::F64(it) => fmt::Debug::fmt(it, f),
+            Save::Char(it) => fmt::Debug::fmt(it, f),
+            Save::String(it) => fmt::Debug::fmt(it, f),
+            Save::ByteArray(it) => fmt::Debug::fmt(it, f),
+            Save::Option(None) => f.write_str("None"),
+            Save::Option(Some(it)) => f.debug_tuple("Some").field(&it.derived_debug()).finish(),
+            Save::Unit => f.write_str("()"),
+            Save::UnitStruct(name) => f.write_str(name),
+            Save::UnitVariant(variant) => f.write_str(variant.variant),
+            Save::NewTypeStruct { name, value } => {
+                f.debug_tuple(name).field(&value.derived_debug()).finish()
+            }
+            Save::NewTypeVariant { variant, value } => f
+                .debug_tuple(variant.variant)
+                .field(&value.derived_debug())
+                .finish(),
+            Save::Seq(it) => f
+                .debug_list()
+                .entries(it.iter().map(Save::derived_debug))
+                .finish(),
+            Save::Map(it) => f
+                .debug_map()
+                .entries(
+                    it.iter()
+                        .map(|(k, v)| (k.derived_debug(), v.derived_debug())),
+                )
+                .finish(),
+            Save::Tuple(it) => tuple(f, "", it),
+            Save::TupleStruct { name, values } => tuple(f, name, values),
+            Save::TupleVariant { variant, values } => tuple(f, variant.variant, values),
+            Save::Struct { name, fields } => strukt(f, name, fields),
+            Save::StructVariant { variant, fields } => strukt(f, variant.variant, fields),
+            Save::Error(e) => write!(f, "<error: {}>", e),
+        }
+    }
+}
+
+fn tuple<E: fmt::Display>(
+    f: &mut fmt::Formatter<'_>,
+    name: &str,
+    values: &[Save<'_, E>],
+) -> fmt::Result {
+    let mut tuple = f.debug_tuple(name);
+    for it in values {
+        tuple.field(&it.derived_debug());
+    }
+    tuple.finish()
+}
+
+#[allow(clippy::type_complexity)]
+fn strukt<E: fmt::Display>(
+    f: &mut fmt::Formatter<'_>,
+    name: &str,
+    fields: &[(&str, Option<Save<'_, E>>)],
+) -> fmt::Result {
+    let mut strukt = f.debug_struct(name);
+    for (name, value) in fields {
+        if let Some(value) = value {
+            strukt.field(name, &value.derived_debug());
+        }
+    }
+    match fields.iter().any(|(_, it)| it.is_none()) {
+        true => strukt.finish_non_exhaustive(),
+        false => strukt.finish(),
+    }
+}
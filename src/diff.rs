@@ -0,0 +1,736 @@
+use crate::{
+    pretty::{paint, style},
+    Path, Save, Segment,
+};
+use core::{convert::Infallible, fmt};
+use std::borrow::Cow;
+
+/// The structural differences between two [`Save`] trees.
+///
+/// See [`Save::diff`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diff<'a, E = Infallible> {
+    changes: Vec<Change<'a, E>>,
+    /// Parallel to `changes`.
+    classes: Vec<ChangeClass>,
+}
+
+/// Whether a [`Change`] affects the structure of a tree, or only its values.
+///
+/// See [`Diff::classified`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ChangeClass {
+    /// A node changed kind, name or enum variant, a struct field was added,
+    /// removed or renamed, or a tuple changed length.
+    Structure,
+    /// A leaf changed value, an [`Option`] was filled or emptied,
+    /// or an element or entry of a sequence or map was added, removed or moved.
+    Value,
+}
+
+/// A single difference between two [`Save`] trees.
+///
+/// See [`Save::diff`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change<'a, E = Infallible> {
+    /// A node is only present in the right hand side.
+    Added {
+        /// Location of the node in the right hand side.
+        path: Path<'a>,
+        /// The node that was added.
+        ///
+        /// For map entries, this is a [`Save::Tuple`] of the key and value.
+        value: Save<'a, E>,
+    },
+    /// A node is only present in the left hand side.
+    Removed {
+        /// Location of the node in the left hand side.
+        path: Path<'a>,
+        /// The node that was removed.
+        ///
+        /// For map entries, this is a [`Save::Tuple`] of the key and value.
+        value: Save<'a, E>,
+    },
+    /// A node is present on both sides, but differs.
+    Changed {
+        /// Location of the node in the left hand side.
+        path: Path<'a>,
+        /// The left hand side.
+        before: Save<'a, E>,
+        /// The right hand side.
+        after: Save<'a, E>,
+    },
+    /// A struct field has the same value under a different name.
+    ///
+    /// See [`DiffOptions::detect_renames`].
+    Renamed {
+        /// Location of the field in the left hand side.
+        path: Path<'a>,
+        /// The new name of the field.
+        to: Cow<'a, str>,
+    },
+    /// An element of a sequence or tuple has moved to a different index.
+    ///
+    /// See [`DiffOptions::detect_moves`].
+    Moved {
+        /// Location of the element in the left hand side.
+        path: Path<'a>,
+        /// Location of the element in the right hand side.
+        to: Path<'a>,
+    },
+}
+
+impl<'a, E> Change<'a, E> {
+    /// The location of this change.
+    pub fn path(&self) -> &Path<'a> {
+        match self {
+            Change::Added { path, .. }
+            | Change::Removed { path, .. }
+            | Change::Changed { path, .. }
+            | Change::Renamed { path, .. }
+            | Change::Moved { path, .. } => path,
+        }
+    }
+}
+
+impl<'a, E> Diff<'a, E> {
+    /// A diff from nothing to `value`, as a single [`Change::Added`] at the root.
+    pub(crate) fn added(value: Save<'a, E>) -> Self {
+        Self {
+            changes: vec![Change::Added {
+                path: Path::new(),
+                value,
+            }],
+            classes: vec![ChangeClass::Structure],
+        }
+    }
+    /// Returns `true` if the trees were identical.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+    /// The number of [`Change`]s.
+    pub fn len(&self) -> usize {
+        self.changes.len()
+    }
+    /// The [`Change`]s, in pre-order.
+    pub fn iter(&self) -> core::slice::Iter<'_, Change<'a, E>> {
+        self.changes.iter()
+    }
+    /// The [`Change`]s, in pre-order, along with their [`ChangeClass`].
+    pub fn classified(&self) -> impl Iterator<Item = (&Change<'a, E>, ChangeClass)> {
+        self.changes.iter().zip(self.classes.iter().copied())
+    }
+    /// Keep only the changes of the given `class`.
+    ///
+    /// ```
+    /// # use serde_save::{ChangeClass, Save};
+    /// let before = Save::<std::convert::Infallible>::strukt("Point", [("x", 1), ("y", 2)]);
+    /// let after = Save::strukt("Point", [("x", Save::I32(5)), ("z", Save::I64(2))]);
+    /// let diff = before.diff(&after);
+    /// assert_eq!(diff.clone().only(ChangeClass::Value).to_string(), "~ x: I32(1) -> I32(5)\n");
+    /// assert_eq!(
+    ///     diff.only(ChangeClass::Structure).to_string(),
+    ///     "- y: I32(2)\n+ z: I64(2)\n"
+    /// );
+    /// ```
+    pub fn only(self, class: ChangeClass) -> Self {
+        let (changes, classes) = self
+            .changes
+            .into_iter()
+            .zip(self.classes)
+            .filter(|(_, it)| *it == class)
+            .unzip();
+        Self { changes, classes }
+    }
+}
+
+impl<'a, E> IntoIterator for Diff<'a, E> {
+    type Item = Change<'a, E>;
+    type IntoIter = std::vec::IntoIter<Change<'a, E>>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.changes.into_iter()
+    }
+}
+
+impl<'s, 'a, E> IntoIterator for &'s Diff<'a, E> {
+    type Item = &'s Change<'a, E>;
+    type IntoIter = core::slice::Iter<'s, Change<'a, E>>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.changes.iter()
+    }
+}
+
+pub(crate) struct DisplayPath<'s, 'a>(pub &'s Path<'a>);
+
+impl fmt::Display for DisplayPath<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0.is_empty() {
+            true => f.write_str("<root>"),
+            false => fmt::Display::fmt(self.0, f),
+        }
+    }
+}
+
+/// One line per change, prefixed with `+`, `-` or `~`.
+impl<E: fmt::Debug> fmt::Display for Diff<'_, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write(f, false)
+    }
+}
+
+impl<E: fmt::Debug> Diff<'_, E> {
+    /// [`Display`](fmt::Display) this diff, highlighting additions in green,
+    /// removals in red and changes in yellow.
+    ///
+    /// ```
+    /// # use serde_save::{ColorChoice, Save};
+    /// let before = Save::<std::convert::Infallible>::from_iter([1]);
+    /// let after = Save::from_iter([2]);
+    /// assert_eq!(
+    ///     before.diff(&after).color(ColorChoice::Always).to_string(),
+    ///     "\x1b[33m~ [0]: \x1b[0m\x1b[31mI32(1)\x1b[0m -> \x1b[32mI32(2)\x1b[0m\n",
+    /// );
+    /// ```
+    #[cfg(feature = "color")]
+    pub fn color(&self, choice: crate::ColorChoice) -> impl fmt::Display + '_ {
+        struct Colored<'d, 'a, E>(&'d Diff<'a, E>, bool);
+        impl<E: fmt::Debug> fmt::Display for Colored<'_, '_, E> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                self.0.write(f, self.1)
+            }
+        }
+        Colored(self, choice.enabled())
+    }
+
+    fn write(&self, f: &mut fmt::Formatter<'_>, color: bool) -> fmt::Result {
+        let style = |it| color.then_some(it);
+        for change in &self.changes {
+            match change {
+                Change::Added { path, value } => paint(
+                    f,
+                    style(style::ADDED),
+                    format_args!("+ {}: {:?}", DisplayPath(path), value),
+                )?,
+                Change::Removed { path, value } => paint(
+                    f,
+                    style(style::REMOVED),
+                    format_args!("- {}: {:?}", DisplayPath(path), value),
+                )?,
+                Change::Changed {
+                    path,
+                    before,
+                    after,
+                } => {
+                    paint(
+                        f,
+                        style(style::CHANGED),
+                        format_args!("~ {}: ", DisplayPath(path)),
+                    )?;
+                    paint(f, style(style::REMOVED), format_args!("{:?}", before))?;
+                    f.write_str(" -> ")?;
+                    paint(f, style(style::ADDED), format_args!("{:?}", after))?;
+                }
+                Change::Renamed { path, to } => paint(
+                    f,
+                    style(style::CHANGED),
+                    format_args!("~ {}: renamed to {:?}", DisplayPath(path), to),
+                )?,
+                Change::Moved { path, to } => paint(
+                    f,
+                    style(style::CHANGED),
+                    format_args!("~ {}: moved to {}", DisplayPath(path), DisplayPath(to)),
+                )?,
+            }
+            writeln!(f)?
+        }
+        Ok(())
+    }
+}
+
+impl<'a, E> Save<'a, E>
+where
+    E: Clone + PartialEq,
+{
+    /// Compute the structural differences between `self` (the left hand side)
+    /// and `other` (the right hand side).
+    ///
+    /// - Sequences are compared element-wise.
+    /// - Struct fields are matched by name, and [skipped](serde::ser::SerializeStruct::skip_field)
+    ///   fields are treated as absent.
+    /// - Map entries are matched by key.
+    ///
+    /// ```
+    /// # use serde_save::{Change, Path, Save};
+    /// let before = Save::<std::convert::Infallible>::strukt("Point", [("x", 1), ("y", 2)]);
+    /// let after = Save::strukt("Point", [("x", 1), ("y", 3)]);
+    /// let diff = before.diff(&after);
+    /// assert_eq!(
+    ///     diff.iter().collect::<Vec<_>>(),
+    ///     [&Change::Changed {
+    ///         path: Path::parse("y").unwrap(),
+    ///         before: Save::I32(2),
+    ///         after: Save::I32(3),
+    ///     }]
+    /// );
+    /// assert_eq!(diff.to_string(), "~ y: I32(2) -> I32(3)\n");
+    /// ```
+    pub fn diff(&self, other: &Self) -> Diff<'a, E> {
+        self.diff_with(other, DiffOptions::new())
+    }
+    /// Like [`Save::diff`], but with the given [`DiffOptions`].
+    pub fn diff_with(&self, other: &Self, options: DiffOptions) -> Diff<'a, E> {
+        let mut changes = Vec::new();
+        diff(options, &mut Path::new(), self, other, &mut changes);
+        let classes = changes.iter().map(|it| classify(self, other, it)).collect();
+        Diff { changes, classes }
+    }
+}
+
+/// See [`ChangeClass`].
+fn classify<E>(left: &Save<'_, E>, right: &Save<'_, E>, change: &Change<'_, E>) -> ChangeClass {
+    // The container a child was added to or removed from.
+    let parent = |tree: &Save<'_, E>, path: &Path<'_>| {
+        let mut node = tree.get(&path[..path.len().saturating_sub(1)])?;
+        while let Save::Option(Some(it))
+        | Save::NewTypeStruct { value: it, .. }
+        | Save::NewTypeVariant { value: it, .. } = node
+        {
+            node = it
+        }
+        Some(matches!(node, Save::Seq(_) | Save::Map(_)))
+    };
+    let value = match change {
+        Change::Added { path, .. } => parent(right, path).unwrap_or_default(),
+        Change::Removed { path, .. } => parent(left, path).unwrap_or_default(),
+        Change::Changed { before, after, .. } => {
+            before.kind_index() == after.kind_index()
+                && matches!(
+                    before,
+                    Save::Bool(_)
+                        | Save::I8(_)
+                        | Save::I16(_)
+                        | Save::I32(_)
+                        | Save::I64(_)
+                        | Save::I128(_)
+                        | Save::U8(_)
+                        | Save::U16(_)
+                        | Save::U32(_)
+                        | Save::U64(_)
+                        | Save::U128(_)
+                        | Save::F32(_)
+                        | Save::F64(_)
+                        | Save::Char(_)
+                        | Save::String(_)
+                        | Save::ByteArray(_)
+                        | Save::Option(_)
+                        | Save::Unit
+                        | Save::Error(_)
+                )
+        }
+        Change::Renamed { .. } => false,
+        Change::Moved { .. } => true,
+    };
+    match value {
+        true => ChangeClass::Value,
+        false => ChangeClass::Structure,
+    }
+}
+
+/// Options for [`Save::diff_with`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[non_exhaustive]
+pub struct DiffOptions {
+    /// See [`DiffOptions::align_sequences`].
+    pub align_sequences: bool,
+    /// See [`DiffOptions::detect_renames`].
+    pub detect_renames: bool,
+    /// See [`DiffOptions::detect_moves`].
+    pub detect_moves: bool,
+}
+
+impl DiffOptions {
+    /// All options are disabled by default, matching [`Save::diff`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Align the elements of sequences and tuples by their longest common
+    /// subsequence, rather than by index, producing a minimal script of
+    /// insertions, deletions and updates.
+    ///
+    /// Elements which only differ in place are reported as updates.
+    /// Note that alignment takes time and memory proportional to the product
+    /// of the lengths of the (differing middles of the) sequences.
+    ///
+    /// ```
+    /// # use serde_save::{DiffOptions, Save};
+    /// let before = Save::<std::convert::Infallible>::from_iter([1, 2, 3]);
+    /// let after = Save::from_iter([0, 1, 2, 4]);
+    /// assert_eq!(before.diff(&after).len(), 4);
+    ///
+    /// let aligned = before.diff_with(&after, DiffOptions::new().align_sequences(true));
+    /// assert_eq!(aligned.to_string(), "+ [0]: I32(0)\n~ [2]: I32(3) -> I32(4)\n");
+    /// ```
+    pub fn align_sequences(mut self, align: bool) -> Self {
+        self.align_sequences = align;
+        self
+    }
+    /// Report a field which was removed and a field with an equal value
+    /// which was added as a single [`Change::Renamed`].
+    ///
+    /// ```
+    /// # use serde_save::{DiffOptions, Save};
+    /// let before = Save::<std::convert::Infallible>::strukt("User", [("id", 1)]);
+    /// let after = Save::strukt("User", [("user_id", 1)]);
+    /// let diff = before.diff_with(&after, DiffOptions::new().detect_renames(true));
+    /// assert_eq!(diff.to_string(), "~ id: renamed to \"user_id\"\n");
+    /// ```
+    pub fn detect_renames(mut self, detect: bool) -> Self {
+        self.detect_renames = detect;
+        self
+    }
+    /// Report an element which was removed from a sequence or tuple and an
+    /// equal element which was added to it as a single [`Change::Moved`].
+    ///
+    /// This is most useful with [`Self::align_sequences`].
+    ///
+    /// ```
+    /// # use serde_save::{DiffOptions, Save};
+    /// let before = Save::<std::convert::Infallible>::from_iter([1, 2, 3]);
+    /// let after = Save::from_iter([3, 1, 2]);
+    /// let options = DiffOptions::new().align_sequences(true).detect_moves(true);
+    /// assert_eq!(before.diff_with(&after, options).to_string(), "~ [2]: moved to [0]\n");
+    /// ```
+    pub fn detect_moves(mut self, detect: bool) -> Self {
+        self.detect_moves = detect;
+        self
+    }
+}
+
+fn diff<'a, E: Clone + PartialEq>(
+    options: DiffOptions,
+    path: &mut Path<'a>,
+    left: &Save<'a, E>,
+    right: &Save<'a, E>,
+    changes: &mut Vec<Change<'a, E>>,
+) {
+    match (left, right) {
+        (Save::Option(Some(l)), Save::Option(Some(r))) => diff(options, path, l, r, changes),
+        (
+            Save::NewTypeStruct { name: ln, value: l },
+            Save::NewTypeStruct { name: rn, value: r },
+        ) if ln == rn => diff(options, path, l, r, changes),
+        (
+            Save::NewTypeVariant {
+                variant: lv,
+                value: l,
+            },
+            Save::NewTypeVariant {
+                variant: rv,
+                value: r,
+            },
+        ) if lv == rv => diff(options, path, l, r, changes),
+        (Save::Seq(l), Save::Seq(r)) | (Save::Tuple(l), Save::Tuple(r)) => {
+            elements(options, path, l, r, changes)
+        }
+        (
+            Save::TupleStruct {
+                name: ln,
+                values: l,
+            },
+            Save::TupleStruct {
+                name: rn,
+                values: r,
+            },
+        ) if ln == rn => elements(options, path, l, r, changes),
+        (
+            Save::TupleVariant {
+                variant: lv,
+                values: l,
+            },
+            Save::TupleVariant {
+                variant: rv,
+                values: r,
+            },
+        ) if lv == rv => elements(options, path, l, r, changes),
+        (
+            Save::Struct {
+                name: ln,
+                fields: l,
+            },
+            Save::Struct {
+                name: rn,
+                fields: r,
+            },
+        ) if ln == rn => fields(options, path, l, r, changes),
+        (
+            Save::StructVariant {
+                variant: lv,
+                fields: l,
+            },
+            Save::StructVariant {
+                variant: rv,
+                fields: r,
+            },
+        ) if lv == rv => fields(options, path, l, r, changes),
+        (Save::Map(l), Save::Map(r)) => entries(options, path, l, r, changes),
+        (l, r) => {
+            if l != r {
+                changes.push(Change::Changed {
+                    path: path.clone(),
+                    before: l.clone(),
+                    after: r.clone(),
+                })
+            }
+        }
+    }
+}
+
+fn elements<'a, E: Clone + PartialEq>(
+    options: DiffOptions,
+    path: &mut Path<'a>,
+    left: &[Save<'a, E>],
+    right: &[Save<'a, E>],
+    changes: &mut Vec<Change<'a, E>>,
+) {
+    let start = changes.len();
+    match options.align_sequences {
+        true => aligned(options, path, left, right, changes),
+        false => by_index(options, path, left, right, changes),
+    }
+    if options.detect_moves {
+        pair_up(path.len() + 1, start, changes, |path, to| Change::Moved {
+            path,
+            to,
+        })
+    }
+}
+
+/// Replace each [`Change::Removed`] child at `depth` which has an equal
+/// [`Change::Added`] sibling with a single change from `pair`.
+fn pair_up<'a, E: PartialEq>(
+    depth: usize,
+    start: usize,
+    changes: &mut Vec<Change<'a, E>>,
+    pair: impl Fn(Path<'a>, Path<'a>) -> Change<'a, E>,
+) {
+    let mut paired = vec![false; changes.len()];
+    for ix in start..changes.len() {
+        let Change::Removed { path, value } = &changes[ix] else {
+            continue;
+        };
+        if path.len() != depth {
+            continue;
+        }
+        let found = (start..changes.len()).find(|it| {
+            !paired[*it]
+                && matches!(
+                    &changes[*it],
+                    Change::Added { path: to, value: added } if to.len() == depth && added == value
+                )
+        });
+        if let Some(added) = found {
+            let Change::Added { path: to, .. } = &changes[added] else {
+                unreachable!()
+            };
+            paired[added] = true;
+            changes[ix] = pair(path.clone(), to.clone())
+        }
+    }
+    let mut paired = paired.into_iter();
+    changes.retain(|_| !paired.next().unwrap_or_default())
+}
+
+fn by_index<'a, E: Clone + PartialEq>(
+    options: DiffOptions,
+    path: &mut Path<'a>,
+    left: &[Save<'a, E>],
+    right: &[Save<'a, E>],
+    changes: &mut Vec<Change<'a, E>>,
+) {
+    for ix in 0..Ord::max(left.len(), right.len()) {
+        path.push(Segment::Index(ix));
+        match (left.get(ix), right.get(ix)) {
+            (Some(l), Some(r)) => diff(options, path, l, r, changes),
+            (Some(l), None) => changes.push(Change::Removed {
+                path: path.clone(),
+                value: l.clone(),
+            }),
+            (None, Some(r)) => changes.push(Change::Added {
+                path: path.clone(),
+                value: r.clone(),
+            }),
+            (None, None) => unreachable!(),
+        }
+        path.pop();
+    }
+}
+
+/// See [`DiffOptions::align_sequences`].
+fn aligned<'a, E: Clone + PartialEq>(
+    options: DiffOptions,
+    path: &mut Path<'a>,
+    left: &[Save<'a, E>],
+    right: &[Save<'a, E>],
+    changes: &mut Vec<Change<'a, E>>,
+) {
+    let (mut lix, mut rix) = (0, 0);
+    for (lmatch, rmatch) in common(left, right)
+        .into_iter()
+        .chain([(left.len(), right.len())])
+    {
+        // Pair up the unmatched elements as updates, and report the rest as
+        // insertions or deletions.
+        let updated = Ord::min(lmatch - lix, rmatch - rix);
+        for offset in 0..updated {
+            path.push(Segment::Index(lix + offset));
+            diff(
+                options,
+                path,
+                &left[lix + offset],
+                &right[rix + offset],
+                changes,
+            );
+            path.pop();
+        }
+        for (ix, l) in left.iter().enumerate().take(lmatch).skip(lix + updated) {
+            changes.push(Change::Removed {
+                path: path.join(Segment::Index(ix)),
+                value: l.clone(),
+            })
+        }
+        for (ix, r) in right.iter().enumerate().take(rmatch).skip(rix + updated) {
+            changes.push(Change::Added {
+                path: path.join(Segment::Index(ix)),
+                value: r.clone(),
+            })
+        }
+        (lix, rix) = (lmatch + 1, rmatch + 1);
+    }
+}
+
+/// The indices of a longest common subsequence of `left` and `right`.
+fn common<T: PartialEq>(left: &[T], right: &[T]) -> Vec<(usize, usize)> {
+    let prefix = left.iter().zip(right).take_while(|(l, r)| l == r).count();
+    let suffix = left[prefix..]
+        .iter()
+        .rev()
+        .zip(right[prefix..].iter().rev())
+        .take_while(|(l, r)| l == r)
+        .count();
+    let (l, r) = (
+        &left[prefix..left.len() - suffix],
+        &right[prefix..right.len() - suffix],
+    );
+    // lengths[i][j] is the length of the LCS of l[i..] and r[j..]
+    let mut lengths = vec![vec![0usize; r.len() + 1]; l.len() + 1];
+    for i in (0..l.len()).rev() {
+        for j in (0..r.len()).rev() {
+            lengths[i][j] = match l[i] == r[j] {
+                true => lengths[i + 1][j + 1] + 1,
+                false => Ord::max(lengths[i + 1][j], lengths[i][j + 1]),
+            }
+        }
+    }
+    let mut common = (0..prefix).map(|ix| (ix, ix)).collect::<Vec<_>>();
+    let (mut i, mut j) = (0, 0);
+    while i < l.len() && j < r.len() {
+        if l[i] == r[j] {
+            common.push((prefix + i, prefix + j));
+            (i, j) = (i + 1, j + 1)
+        } else if lengths[i + 1][j] >= lengths[i][j + 1] {
+            i += 1
+        } else {
+            j += 1
+        }
+    }
+    common.extend((0..suffix).map(|ix| (left.len() - suffix + ix, right.len() - suffix + ix)));
+    common
+}
+
+fn present<'s, 'a, E>(
+    fields: &'s [(&'a str, Option<Save<'a, E>>)],
+    name: &str,
+) -> Option<&'s Save<'a, E>> {
+    fields
+        .iter()
+        .find(|(it, _)| *it == name)
+        .and_then(|(_, it)| it.as_ref())
+}
+
+fn fields<'a, E: Clone + PartialEq>(
+    options: DiffOptions,
+    path: &mut Path<'a>,
+    left: &[(&'a str, Option<Save<'a, E>>)],
+    right: &[(&'a str, Option<Save<'a, E>>)],
+    changes: &mut Vec<Change<'a, E>>,
+) {
+    let start = changes.len();
+    for (name, l) in left {
+        let Some(l) = l else { continue };
+        path.push(Segment::Field(Cow::Borrowed(name)));
+        match present(right, name) {
+            Some(r) => diff(options, path, l, r, changes),
+            None => changes.push(Change::Removed {
+                path: path.clone(),
+                value: l.clone(),
+            }),
+        }
+        path.pop();
+    }
+    for (name, r) in right {
+        let Some(r) = r else { continue };
+        if present(left, name).is_none() {
+            changes.push(Change::Added {
+                path: path.join(Segment::Field(Cow::Borrowed(name))),
+                value: r.clone(),
+            })
+        }
+    }
+    if options.detect_renames {
+        pair_up(path.len() + 1, start, changes, |path, to| {
+            let Some(Segment::Field(to)) = to.last() else {
+                unreachable!()
+            };
+            Change::Renamed {
+                to: to.clone(),
+                path,
+            }
+        })
+    }
+}
+
+fn entries<'a, E: Clone + PartialEq>(
+    options: DiffOptions,
+    path: &mut Path<'a>,
+    left: &[(Save<'a, E>, Save<'a, E>)],
+    right: &[(Save<'a, E>, Save<'a, E>)],
+    changes: &mut Vec<Change<'a, E>>,
+) {
+    let mut matched = vec![false; right.len()];
+    for (lix, (lk, lv)) in left.iter().enumerate() {
+        path.push(Segment::Index(lix));
+        let found = right
+            .iter()
+            .enumerate()
+            .find(|(rix, (rk, _))| !matched[*rix] && lk == rk);
+        match found {
+            Some((rix, (_, rv))) => {
+                matched[rix] = true;
+                diff(options, path, lv, rv, changes)
+            }
+            None => changes.push(Change::Removed {
+                path: path.clone(),
+                value: Save::Tuple(vec![lk.clone(), lv.clone()]),
+            }),
+        }
+        path.pop();
+    }
+    for (rix, (rk, rv)) in right.iter().enumerate() {
+        if !matched[rix] {
+            changes.push(Change::Added {
+                path: path.join(Segment::Index(rix)),
+                value: Save::Tuple(vec![rk.clone(), rv.clone()]),
+            })
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use crate::Save;
+use core::mem;
+
+/// [`Save::Unit`].
+impl<E> Default for Save<'_, E> {
+    fn default() -> Self {
+        Save::Unit
+    }
+}
+
+impl<'a, E> Save<'a, E> {
+    /// Move this node out of the tree, leaving a [`Save::Unit`] in its place.
+    ///
+    /// ```
+    /// # use serde_save::{save, Path, Save};
+    /// let mut saved = save((1, String::from("large"))).unwrap();
+    /// let large = saved.get_mut(&Path::parse("[1]").unwrap()).unwrap().take();
+    /// assert_eq!(large, Save::string("large"));
+    /// assert_eq!(saved, Save::Tuple(vec![Save::I32(1), Save::Unit]));
+    /// ```
+    pub fn take(&mut self) -> Self {
+        mem::take(self)
+    }
+    /// Replace this node with `with`, returning the original.
+    pub fn replace(&mut self, with: Self) -> Self {
+        mem::replace(self, with)
+    }
+    /// If this is a [`Save::Struct`] or [`Save::StructVariant`], remove the
+    /// field called `name`, returning its value.
+    ///
+    /// Returns [`None`] if there is no such field, or it was
+    /// [skipped](serde::ser::SerializeStruct::skip_field),
+    /// in which case it is still removed.
+    ///
+    /// ```
+    /// # use serde_save::Save;
+    /// let mut save = Save::<std::convert::Infallible>::strukt("Point", [("x", 1), ("y", 2)]);
+    /// assert_eq!(save.remove_field("y"), Some(Save::I32(2)));
+    /// assert_eq!(save, Save::strukt("Point", [("x", 1)]));
+    /// ```
+    pub fn remove_field(&mut self, name: &str) -> Option<Self> {
+        match self {
+            Save::Struct { fields, .. } | Save::StructVariant { fields, .. } => {
+                let ix = fields.iter().position(|(it, _)| *it == name)?;
+                fields.remove(ix).1
+            }
+            _ => None,
+        }
+    }
+    /// If this is a [`Save::Map`], remove the first entry whose key is equal
+    /// to `key`, returning its value.
+    ///
+    /// ```
+    /// # use serde_save::{save, Save};
+    /// # use std::collections::BTreeMap;
+    /// let mut saved = save(BTreeMap::from([("a", 1), ("b", 2)])).unwrap();
+    /// assert_eq!(saved.remove_entry(&Save::string("a")), Some(Save::I32(1)));
+    /// assert_eq!(saved, save(BTreeMap::from([("b", 2)])).unwrap());
+    /// ```
+    pub fn remove_entry(&mut self, key: &Self) -> Option<Self>
+    where
+        E: PartialEq,
+    {
+        match self {
+            Save::Map(it) => {
+                let ix = it.iter().position(|(k, _)| k == key)?;
+                Some(it.remove(ix).1)
+            }
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,154 @@
+//! Carry a [`Save`] inside your own types, using serde's [`with`](https://serde.rs/field-attrs.html#with) attribute.
+//!
+//! The tree is written as a byte array in the [archive format](Save::to_bytes),
+//! which preserves every name and variant index, regardless of the outer format.
+//!
+//! Decoded names borrow from the input,
+//! so [`deserialize`] requires a format which can lend out
+//! [borrowed bytes](serde::de::Visitor::visit_borrowed_bytes),
+//! and fails otherwise.
+//! For formats which can't, like those which write bytes as a sequence of numbers,
+//! use an [`Archive`], which owns its bytes instead.
+//!
+//! ```
+//! # use serde::{de::value::{BorrowedBytesDeserializer, BytesDeserializer, Error}, Deserialize, Serialize};
+//! # use serde_save::{save, Save};
+//! #[derive(Serialize, Deserialize)]
+//! struct Message<'a> {
+//!     id: u64,
+//!     #[serde(with = "serde_save::embed", borrow)]
+//!     captured: Save<'a>,
+//! }
+//!
+//! let message = Message {
+//!     id: 1,
+//!     captured: save(("hello", Some(1))).unwrap(),
+//! };
+//! let Save::Struct { fields, .. } = save(&message).unwrap() else { panic!() };
+//! let Some(Save::ByteArray(archive)) = &fields[1].1 else { panic!() };
+//!
+//! let captured = serde_save::embed::deserialize(
+//!     BorrowedBytesDeserializer::<Error>::new(archive)
+//! ).unwrap();
+//! assert_eq!(captured, message.captured);
+//!
+//! // The input must be borrowed.
+//! assert!(serde_save::embed::deserialize(BytesDeserializer::<Error>::new(archive)).is_err());
+//! ```
+
+use crate::Save;
+use core::fmt;
+use serde::{de, Deserialize, Serialize};
+
+/// Serialize `save` as a byte array in the archive format.
+///
+/// [`Save::Error`]s are stored as their messages,
+/// and will fail to [`deserialize`].
+pub fn serialize<E, S>(save: &Save<'_, E>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    E: fmt::Display,
+    S: serde::Serializer,
+{
+    serializer.serialize_bytes(&save.to_bytes())
+}
+
+/// Deserialize a tree written by [`serialize`], borrowing names from the input.
+///
+/// Fails if the deserializer can't lend out borrowed bytes, see [`Archive`].
+pub fn deserialize<'de, D>(deserializer: D) -> Result<Save<'de>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    struct Visitor;
+    impl<'de> de::Visitor<'de> for Visitor {
+        type Value = Save<'de>;
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a borrowed serde-save archive")
+        }
+        fn visit_borrowed_bytes<E: de::Error>(self, v: &'de [u8]) -> Result<Self::Value, E> {
+            Save::from_bytes(v).map_err(E::custom)
+        }
+    }
+    deserializer.deserialize_bytes(Visitor)
+}
+
+/// An encoded tree which owns its bytes,
+/// for formats which can't lend out borrowed bytes to [`deserialize`].
+///
+/// The archive is checked when it is created or deserialized,
+/// and the tree is decoded from it on demand by [`Archive::save`].
+///
+/// ```
+/// # use serde::{de::value::{Error, SeqDeserializer}, Deserialize as _};
+/// # use serde_save::{embed::Archive, save};
+/// let captured = save(("hello", Some(1))).unwrap();
+/// let archive = Archive::new(&captured);
+///
+/// // e.g `serde_json` writes bytes as an array of numbers
+/// let bytes = SeqDeserializer::<_, Error>::new(archive.as_bytes().iter().copied());
+/// let archive = Archive::deserialize(bytes).unwrap();
+/// assert_eq!(archive.save(), captured);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Archive {
+    bytes: Vec<u8>,
+}
+
+impl Archive {
+    /// Encode `save`, see [`Save::to_bytes`].
+    pub fn new(save: &Save<'_>) -> Self {
+        Self {
+            bytes: save.to_bytes(),
+        }
+    }
+    /// Check that `bytes` hold a tree written by [`Save::to_bytes`].
+    pub fn from_bytes(bytes: Vec<u8>) -> Result<Self, crate::ArchiveError> {
+        Save::from_bytes(&bytes)?;
+        Ok(Self { bytes })
+    }
+    /// Decode the tree, with names borrowed from this archive.
+    pub fn save(&self) -> Save<'_> {
+        Save::from_bytes(&self.bytes).expect("archive was checked when created")
+    }
+    /// The encoded tree.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+    /// Return the encoded tree.
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+impl Serialize for Archive {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(&self.bytes)
+    }
+}
+
+impl<'de> Deserialize<'de> for Archive {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Visitor;
+        impl<'de> de::Visitor<'de> for Visitor {
+            type Value = Archive;
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a serde-save archive")
+            }
+            fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+                self.visit_byte_buf(v.to_vec())
+            }
+            fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+                Archive::from_bytes(v).map_err(E::custom)
+            }
+            fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                // The length is not trusted for preallocation.
+                let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or_default().min(4096));
+                while let Some(byte) = seq.next_element()? {
+                    bytes.push(byte)
+                }
+                self.visit_byte_buf(bytes)
+            }
+        }
+        deserializer.deserialize_byte_buf(Visitor)
+    }
+}
@@ -0,0 +1,150 @@
+use crate::Save;
+
+impl<'a, E1> Save<'a, E1> {
+    /// Compare trees structurally, with [`Save::Error`]s compared by `errors`.
+    ///
+    /// Unlike [`PartialEq`], the trees may have different error types,
+    /// so an expected `Save<Infallible>` can be compared against a capture from
+    /// [`save_errors`](crate::save_errors) without converting either.
+    ///
+    /// ```
+    /// # use serde_save::{save_errors, Save};
+    /// # use std::convert::Infallible;
+    /// let expected = Save::<Infallible>::Seq(vec![Save::U8(1), Save::U8(2)]);
+    /// assert!(expected.eq_errors_by(&save_errors(vec![1u8, 2]), &|never, _| match *never {}));
+    ///
+    /// // Compare errors by their message...
+    /// let expected = Save::Seq(vec![Save::<String>::Error(String::from("oops"))]);
+    /// let actual = Save::Seq(vec![Save::<serde_save::Error>::error("oops")]);
+    /// assert!(expected.eq_errors_by(&actual, &|l, r| *l == r.to_string()));
+    /// // ...or treat them as never equal.
+    /// assert!(!expected.eq_errors_by(&actual, &|_, _| false));
+    /// ```
+    pub fn eq_errors_by<E2>(
+        &self,
+        other: &Save<'_, E2>,
+        errors: &impl Fn(&E1, &E2) -> bool,
+    ) -> bool {
+        self.eq_with(other, &|l, r| match (l, r) {
+            (Save::Error(l), Save::Error(r)) => Some(errors(l, r)),
+            _ => None,
+        })
+    }
+    /// Compare trees structurally, calling `hook` on each pair of nodes first.
+    ///
+    /// If `hook` returns [`Some`], that is the result for the pair.
+    /// Otherwise, the nodes must be of the same kind, with equal children.
+    /// Pairs of [`Save::Error`]s are never equal.
+    pub(crate) fn eq_with<'b, E2>(
+        &self,
+        other: &Save<'b, E2>,
+        hook: &impl Fn(&Self, &Save<'b, E2>) -> Option<bool>,
+    ) -> bool {
+        if let Some(it) = hook(self, other) {
+            return it;
+        }
+        let elements = |l: &[Save<'a, E1>], r: &[Save<'b, E2>]| {
+            l.len() == r.len() && l.iter().zip(r).all(|(l, r)| l.eq_with(r, hook))
+        };
+        #[allow(clippy::type_complexity)]
+        let fields = |l: &[(&str, Option<Save<'a, E1>>)], r: &[(&str, Option<Save<'b, E2>>)]| {
+            l.len() == r.len()
+                && l.iter().zip(r).all(|((ln, l), (rn, r))| {
+                    ln == rn
+                        && match (l, r) {
+                            (None, None) => true,
+                            (Some(l), Some(r)) => l.eq_with(r, hook),
+                            _ => false,
+                        }
+                })
+        };
+        match (self, other) {
+            (Save::Bool(l), Save::Bool(r)) => l == r,
+            (Save::I8(l), Save::I8(r)) => l == r,
+            (Save::I16(l), Save::I16(r)) => l == r,
+            (Save::I32(l), Save::I32(r)) => l == r,
+            (Save::I64(l), Save::I64(r)) => l == r,
+            (Save::I128(l), Save::I128(r)) => l == r,
+            (Save::U8(l), Save::U8(r)) => l == r,
+            (Save::U16(l), Save::U16(r)) => l == r,
+            (Save::U32(l), Save::U32(r)) => l == r,
+            (Save::U64(l), Save::U64(r)) => l == r,
+            (Save::U128(l), Save::U128(r)) => l == r,
+            (Save::F32(l), Save::F32(r)) => l == r,
+            (Save::F64(l), Save::F64(r)) => l == r,
+            (Save::Char(l), Save::Char(r)) => l == r,
+            (Save::String(l), Save::String(r)) => l == r,
+            (Save::ByteArray(l), Save::ByteArray(r)) => l == r,
+            (Save::Option(l), Save::Option(r)) => match (l, r) {
+                (None, None) => true,
+                (Some(l), Some(r)) => l.eq_with(r, hook),
+                _ => false,
+            },
+            (Save::Unit, Save::Unit) => true,
+            (Save::UnitStruct(l), Save::UnitStruct(r)) => l == r,
+            (Save::UnitVariant(l), Save::UnitVariant(r)) => l == r,
+            (
+                Save::NewTypeStruct { name: ln, value: l },
+                Save::NewTypeStruct { name: rn, value: r },
+            ) => ln == rn && l.eq_with(r, hook),
+            (
+                Save::NewTypeVariant {
+                    variant: lv,
+                    value: l,
+                },
+                Save::NewTypeVariant {
+                    variant: rv,
+                    value: r,
+                },
+            ) => lv == rv && l.eq_with(r, hook),
+            (Save::Seq(l), Save::Seq(r)) | (Save::Tuple(l), Save::Tuple(r)) => elements(l, r),
+            (Save::Map(l), Save::Map(r)) => {
+                l.len() == r.len()
+                    && l.iter()
+                        .zip(r)
+                        .all(|((lk, lv), (rk, rv))| lk.eq_with(rk, hook) && lv.eq_with(rv, hook))
+            }
+            (
+                Save::TupleStruct {
+                    name: ln,
+                    values: l,
+                },
+                Save::TupleStruct {
+                    name: rn,
+                    values: r,
+                },
+            ) => ln == rn && elements(l, r),
+            (
+                Save::TupleVariant {
+                    variant: lv,
+                    values: l,
+                },
+                Save::TupleVariant {
+                    variant: rv,
+                    values: r,
+                },
+            ) => lv == rv && elements(l, r),
+            (
+                Save::Struct {
+                    name: ln,
+                    fields: l,
+                },
+                Save::Struct {
+                    name: rn,
+                    fields: r,
+                },
+            ) => ln == rn && fields(l, r),
+            (
+                Save::StructVariant {
+                    variant: lv,
+                    fields: l,
+                },
+                Save::StructVariant {
+                    variant: rv,
+                    fields: r,
+                },
+            ) => lv == rv && fields(l, r),
+            _ => false,
+        }
+    }
+}
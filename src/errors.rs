@@ -0,0 +1,111 @@
+use crate::{Error, Path, Save, Segment};
+use std::borrow::Cow;
+
+impl<'a> Save<'a, Error> {
+    /// The number of [`Save::Error`]s in this tree.
+    ///
+    /// Elements omitted by [`Serializer::max_errors`](crate::Serializer::max_errors)
+    /// are only counted once, as their summary.
+    ///
+    /// ```
+    /// # use serde_save::save_errors;
+    /// # use std::{ffi::OsString, os::unix::ffi::OsStringExt as _, path::PathBuf};
+    /// let non_utf8_path = PathBuf::from(OsString::from_vec(vec![u8::MAX]));
+    /// assert_eq!(save_errors(("fine", &non_utf8_path, &non_utf8_path)).error_count(), 2);
+    /// assert_eq!(save_errors("fine").error_count(), 0);
+    /// ```
+    pub fn error_count(&self) -> usize {
+        let mut count = 0;
+        self.walk(|_, it| {
+            if let Save::Error(_) = it {
+                count += 1
+            }
+        });
+        count
+    }
+    /// The first [`Save::Error`] in pre-order, with its location,
+    /// without visiting the rest of the tree.
+    ///
+    /// ```
+    /// # use serde_save::save_errors;
+    /// # use std::{ffi::OsString, os::unix::ffi::OsStringExt as _, path::PathBuf};
+    /// #[derive(serde::Serialize)]
+    /// struct Config {
+    ///     name: &'static str,
+    ///     paths: Vec<PathBuf>,
+    /// }
+    ///
+    /// let non_utf8_path = PathBuf::from(OsString::from_vec(vec![u8::MAX]));
+    /// let saved = save_errors(Config {
+    ///     name: "fine",
+    ///     paths: vec![PathBuf::from("/"), non_utf8_path],
+    /// });
+    /// let (path, e) = saved.first_error().unwrap();
+    /// assert_eq!(path.to_string(), "paths[1]");
+    /// assert_eq!(e.to_string(), "path contains invalid UTF-8 characters");
+    /// ```
+    pub fn first_error(&self) -> Option<(Path<'a>, &Error)> {
+        let mut path = Path::new();
+        let e = self.find_error(&mut path, &|_| true)?;
+        Some((path, e))
+    }
+    /// Returns `true` if this tree contains any [protocol errors](Error::is_protocol),
+    /// stopping at the first.
+    ///
+    /// ```
+    /// # use serde::Serialize;
+    /// # use serde_save::{save_errors, Save};
+    /// struct Liar;
+    /// impl Serialize for Liar {
+    ///     fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    ///         use serde::ser::SerializeSeq as _;
+    ///         serializer.serialize_seq(Some(2))?.end() // promised two elements, but sent none
+    ///     }
+    /// }
+    /// assert!(save_errors((1, Liar)).has_protocol_errors());
+    ///
+    /// let custom = Save::Seq(vec![Save::error("oops")]);
+    /// assert!(!custom.has_protocol_errors());
+    /// ```
+    pub fn has_protocol_errors(&self) -> bool {
+        self.find_error(&mut Path::new(), &Error::is_protocol)
+            .is_some()
+    }
+
+    /// Find the first error matching `pred` in pre-order,
+    /// leaving `path` at its location.
+    fn find_error<'s>(
+        &'s self,
+        path: &mut Path<'a>,
+        pred: &impl Fn(&Error) -> bool,
+    ) -> Option<&'s Error> {
+        let mut descend = |segment, child: &'s Self| {
+            path.push(segment);
+            let found = child.find_error(path, pred);
+            if found.is_none() {
+                path.pop();
+            }
+            found
+        };
+        match self {
+            Save::Error(e) => Some(e).filter(|it| pred(it)),
+            Save::Option(Some(it))
+            | Save::NewTypeStruct { value: it, .. }
+            | Save::NewTypeVariant { value: it, .. } => it.find_error(path, pred),
+            Save::Seq(it)
+            | Save::Tuple(it)
+            | Save::TupleStruct { values: it, .. }
+            | Save::TupleVariant { values: it, .. } => it
+                .iter()
+                .enumerate()
+                .find_map(|(ix, it)| descend(Segment::Index(ix), it)),
+            Save::Map(it) => it.iter().enumerate().find_map(|(ix, (k, v))| {
+                descend(Segment::Key(ix), k).or_else(|| descend(Segment::Index(ix), v))
+            }),
+            Save::Struct { fields, .. } | Save::StructVariant { fields, .. } => fields
+                .iter()
+                .find_map(|(name, it)| descend(Segment::Field(Cow::Borrowed(name)), it.as_ref()?)),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,249 @@
+//! Typed fixtures exercising every corner of the serde data model,
+//! for authors of [`serde::Serializer`]s.
+//!
+//! Unlike the [`conformance`](crate::conformance) corpus, each fixture is a
+//! value of an ordinary Rust type, so it can also be used for round-trip tests
+//! against a format's deserializer.
+//!
+//! ```
+//! # use serde::Serialize;
+//! # use serde_save::{fixtures, Save, Serializer};
+//! struct Check;
+//!
+//! impl fixtures::Visit for Check {
+//!     fn visit<T: Serialize + ?Sized>(&mut self, name: &'static str, value: &T, expected: &Save<'static>) {
+//!         // Substitute your own serializer here.
+//!         let actual = value.serialize(Serializer::new()).unwrap();
+//!         assert_eq!(&actual, expected, "{}", name);
+//!     }
+//! }
+//!
+//! fixtures::for_each(&mut Check);
+//! ```
+
+use crate::Save;
+use serde::{
+    ser::{
+        SerializeMap as _, SerializeSeq as _, SerializeStruct as _, SerializeStructVariant as _,
+        SerializeTupleStruct as _, SerializeTupleVariant as _,
+    },
+    Serialize, Serializer,
+};
+use std::collections::BTreeMap;
+
+/// A unit struct, like `struct UnitStruct;`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct UnitStruct;
+
+impl Serialize for UnitStruct {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_unit_struct("UnitStruct")
+    }
+}
+
+/// A newtype struct, like `struct NewType(u8);`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct NewType(pub u8);
+
+impl Serialize for NewType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_newtype_struct("NewType", &self.0)
+    }
+}
+
+/// A tuple struct of the widest integers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Wide(pub i128, pub u128);
+
+impl Serialize for Wide {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut tup = serializer.serialize_tuple_struct("Wide", 2)?;
+        tup.serialize_field(&self.0)?;
+        tup.serialize_field(&self.1)?;
+        tup.end()
+    }
+}
+
+/// An enum with every kind of variant.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Enum {
+    /// A unit variant.
+    Unit,
+    /// A newtype variant.
+    NewType(u8),
+    /// A tuple variant.
+    Tuple(u8, char),
+    /// A struct variant.
+    Struct {
+        /// A field.
+        a: u8,
+    },
+}
+
+impl Serialize for Enum {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Enum::Unit => serializer.serialize_unit_variant("Enum", 0, "Unit"),
+            Enum::NewType(it) => serializer.serialize_newtype_variant("Enum", 1, "NewType", it),
+            Enum::Tuple(a, b) => {
+                let mut var = serializer.serialize_tuple_variant("Enum", 2, "Tuple", 2)?;
+                var.serialize_field(a)?;
+                var.serialize_field(b)?;
+                var.end()
+            }
+            Enum::Struct { a } => {
+                let mut var = serializer.serialize_struct_variant("Enum", 3, "Struct", 1)?;
+                var.serialize_field("a", a)?;
+                var.end()
+            }
+        }
+    }
+}
+
+/// Bytes which are serialized with
+/// [`serialize_bytes`](serde::Serializer::serialize_bytes),
+/// rather than as a sequence.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Bytes(pub Vec<u8>);
+
+impl Serialize for Bytes {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(&self.0)
+    }
+}
+
+/// A struct with a field of each kind of node, including one which is
+/// [skipped](serde::ser::SerializeStruct::skip_field) if it is [`None`].
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Record {
+    /// An integer.
+    pub id: u64,
+    /// A string.
+    pub name: String,
+    /// Skipped if [`None`], like `#[serde(skip_serializing_if = "Option::is_none")]`.
+    pub nickname: Option<String>,
+    /// Nested options.
+    pub nested: Option<Option<u8>>,
+    /// A sequence of enums.
+    pub tags: Vec<Enum>,
+    /// A map.
+    pub scores: BTreeMap<String, f64>,
+    /// A byte array.
+    pub avatar: Bytes,
+}
+
+impl Serialize for Record {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let len = 7 - usize::from(self.nickname.is_none());
+        let mut strukt = serializer.serialize_struct("Record", len)?;
+        strukt.serialize_field("id", &self.id)?;
+        strukt.serialize_field("name", &self.name)?;
+        match &self.nickname {
+            Some(it) => strukt.serialize_field("nickname", it)?,
+            None => strukt.skip_field("nickname")?,
+        }
+        strukt.serialize_field("nested", &self.nested)?;
+        strukt.serialize_field("tags", &Seq(&self.tags))?;
+        strukt.serialize_field("scores", &Map(&self.scores))?;
+        strukt.serialize_field("avatar", &self.avatar)?;
+        strukt.end()
+    }
+}
+
+struct Seq<'a, T>(&'a [T]);
+
+impl<T: Serialize> Serialize for Seq<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.0.len()))?;
+        for it in self.0 {
+            seq.serialize_element(it)?
+        }
+        seq.end()
+    }
+}
+
+struct Map<'a, K, V>(&'a BTreeMap<K, V>);
+
+impl<K: Serialize, V: Serialize> Serialize for Map<'_, K, V> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.0.len()))?;
+        for (k, v) in self.0 {
+            map.serialize_entry(k, v)?
+        }
+        map.end()
+    }
+}
+
+/// Receives each fixture, see [`for_each`].
+pub trait Visit {
+    /// Called with the fixture's `name`, its `value`,
+    /// and the tree that the value [saves](crate::save) as.
+    fn visit<T: Serialize + ?Sized>(
+        &mut self,
+        name: &'static str,
+        value: &T,
+        expected: &Save<'static>,
+    );
+}
+
+/// Call `visitor` with every fixture.
+pub fn for_each(visitor: &mut impl Visit) {
+    fn visit<T: Serialize + ?Sized>(visitor: &mut impl Visit, name: &'static str, value: &T) {
+        let expected = crate::save(value).expect("fixtures are infallible");
+        visitor.visit(name, value, &expected)
+    }
+    let record = Record {
+        id: u64::MAX,
+        name: String::from("Ferris 🦀"),
+        nickname: Some(String::from("crab")),
+        nested: Some(Some(1)),
+        tags: vec![
+            Enum::Unit,
+            Enum::NewType(1),
+            Enum::Tuple(2, 'é'),
+            Enum::Struct { a: 3 },
+        ],
+        scores: BTreeMap::from([(String::from("a"), 1.5), (String::from("b"), -0.0)]),
+        avatar: Bytes(vec![0, 1, 255]),
+    };
+
+    visit(visitor, "unit", &());
+    visit(visitor, "unit struct", &UnitStruct);
+    visit(visitor, "newtype struct", &NewType(1));
+    visit(
+        visitor,
+        "tuple struct of extremes",
+        &Wide(i128::MIN, u128::MAX),
+    );
+    visit(visitor, "unit variant", &Enum::Unit);
+    visit(visitor, "newtype variant", &Enum::NewType(1));
+    visit(visitor, "tuple variant", &Enum::Tuple(1, '\0'));
+    visit(visitor, "struct variant", &Enum::Struct { a: 1 });
+    visit(visitor, "none", &None::<u8>);
+    visit(visitor, "some none", &Some(None::<u8>));
+    visit(visitor, "some some none", &Some(Some(None::<u8>)));
+    visit(visitor, "empty string", "");
+    visit(visitor, "empty bytes", &Bytes(Vec::new()));
+    visit(visitor, "empty seq", &Seq::<u8>(&[]));
+    visit(visitor, "empty map", &Map::<u8, u8>(&BTreeMap::new()));
+    visit(visitor, "record", &record);
+    visit(visitor, "record with skipped field", &Record::default());
+}
+
+/// Every fixture's name and saved tree.
+pub fn all() -> Vec<(&'static str, Save<'static>)> {
+    struct Collect(Vec<(&'static str, Save<'static>)>);
+    impl Visit for Collect {
+        fn visit<T: Serialize + ?Sized>(
+            &mut self,
+            name: &'static str,
+            _: &T,
+            expected: &Save<'static>,
+        ) {
+            self.0.push((name, expected.clone()))
+        }
+    }
+    let mut collect = Collect(Vec::new());
+    for_each(&mut collect);
+    collect.0
+}
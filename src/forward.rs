@@ -0,0 +1,929 @@
+use crate::{pretty::Base64, Save, Variant};
+use core::{cmp::Ordering, fmt};
+use serde::{
+    ser::{
+        Error as _, SerializeMap as _, SerializeSeq as _, SerializeStruct as _,
+        SerializeStructVariant as _, SerializeTuple as _, SerializeTupleStruct as _,
+        SerializeTupleVariant as _,
+    },
+    Serialize,
+};
+
+/// A [`Save`] tree, with configurable behaviour when forwarded to another
+/// [`serde::Serializer`].
+///
+/// See [`Save::forward`].
+#[derive(Debug)]
+pub struct Forward<'s, E> {
+    save: &'s Save<'static, E>,
+    options: Options,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Options {
+    errors: ErrorMode,
+    bytes: BytesRepr,
+    non_finite: NonFinite,
+    structs_as_maps: bool,
+    raw_json_as_strings: bool,
+    sorted: bool,
+    enums: EnumTagging,
+    faults: Faults,
+}
+
+/// How to [forward](Forward) a [`Save::ByteArray`], see [`Forward::bytes`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[non_exhaustive]
+pub enum BytesRepr {
+    /// Call [`serialize_bytes`](serde::Serializer::serialize_bytes).
+    #[default]
+    Bytes,
+    /// Emit a sequence of [`u8`]s, as `Vec<u8>` does.
+    Seq,
+    /// Emit a standard, padded base64 string.
+    Base64,
+}
+
+/// What to do when [forwarding](Forward) a NaN or infinite float,
+/// see [`Forward::non_finite`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[non_exhaustive]
+pub enum NonFinite {
+    /// Emit the float as is.
+    #[default]
+    Keep,
+    /// Emit [`None`], which many formats represent as `null`.
+    None,
+    /// Emit the float's [`Display`](fmt::Display), like `NaN` or `-inf`.
+    Display,
+    /// Fail serialization.
+    Fail,
+}
+
+/// How to [forward](Forward) enum variants, see [`Forward::enums`].
+///
+/// Each corresponds to one of serde's
+/// [enum representations](https://serde.rs/enum-representations.html).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[non_exhaustive]
+pub enum EnumTagging {
+    /// As captured, like `{"Variant": {...}}`.
+    #[default]
+    External,
+    /// As `#[serde(tag = "...")]`, like `{"type": "Variant", ...}`.
+    ///
+    /// Tuple variants, and newtype variants containing anything but structs,
+    /// maps and units, cannot be forwarded.
+    Internal {
+        /// The name of the field holding the variant's name.
+        tag: &'static str,
+    },
+    /// As `#[serde(tag = "...", content = "...")]`,
+    /// like `{"t": "Variant", "c": {...}}`.
+    Adjacent {
+        /// The name of the field holding the variant's name.
+        tag: &'static str,
+        /// The name of the field holding the variant's contents.
+        content: &'static str,
+    },
+    /// As `#[serde(untagged)]`, with only the variant's contents.
+    Untagged,
+}
+
+/// Protocol violations to inject when [forwarding](Forward) a tree,
+/// see [`Forward::faults`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[non_exhaustive]
+pub struct Faults {
+    /// See [`Faults::wrong_lengths`].
+    pub wrong_lengths: bool,
+    /// See [`Faults::orphan_map_keys`].
+    pub orphan_map_keys: bool,
+    /// See [`Faults::duplicate_fields`].
+    pub duplicate_fields: bool,
+    /// See [`Faults::bad_variant_indices`].
+    pub bad_variant_indices: bool,
+}
+
+impl Faults {
+    /// No faults are injected by default.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Declare one more element than is actually serialized for every
+    /// sequence, map, tuple and struct.
+    pub fn wrong_lengths(mut self, inject: bool) -> Self {
+        self.wrong_lengths = inject;
+        self
+    }
+    /// Serialize an extra key without a value at the end of every map.
+    pub fn orphan_map_keys(mut self, inject: bool) -> Self {
+        self.orphan_map_keys = inject;
+        self
+    }
+    /// Serialize the first field of every struct a second time.
+    pub fn duplicate_fields(mut self, inject: bool) -> Self {
+        self.duplicate_fields = inject;
+        self
+    }
+    /// Use [`u32::MAX`] as the index of every enum variant.
+    pub fn bad_variant_indices(mut self, inject: bool) -> Self {
+        self.bad_variant_indices = inject;
+        self
+    }
+    /// Inject every fault.
+    pub fn all() -> Self {
+        Self {
+            wrong_lengths: true,
+            orphan_map_keys: true,
+            duplicate_fields: true,
+            bad_variant_indices: true,
+        }
+    }
+}
+
+/// What to do when [forwarding](Forward) a [`Save::Error`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[non_exhaustive]
+pub enum ErrorMode {
+    /// Fail serialization with the error's message.
+    #[default]
+    Fail,
+    /// Omit the erroneous field, element, or map entry.
+    ///
+    /// Errors which cannot be omitted are emitted as [`None`] within a
+    /// [`Save::Option`], and as a unit (`()`) elsewhere.
+    Skip,
+    /// Emit the error's message as a string.
+    Display,
+}
+
+impl<E> Save<'static, E> {
+    /// Forward this tree to another [`serde::Serializer`], with configurable behaviour.
+    ///
+    /// With the default options, this is the same as the [`Serialize`] implementation for [`Save`].
+    pub fn forward(&self) -> Forward<'_, E> {
+        Forward {
+            save: self,
+            options: Options::default(),
+        }
+    }
+    /// Forward this tree, emitting any [`Save::Error`]s as their message.
+    ///
+    /// This is useful for logging diagnostic trees.
+    ///
+    /// ```
+    /// # use serde_save::{save_errors, Save};
+    /// # use std::{ffi::OsString, os::unix::ffi::OsStringExt as _, path::PathBuf};
+    /// let non_utf8_path = PathBuf::from(OsString::from_vec(vec![u8::MAX]));
+    /// let save = save_errors(("fine", non_utf8_path));
+    /// assert_eq!(
+    ///     save_errors(save.display_errors()),
+    ///     Save::Tuple(vec![
+    ///         Save::string("fine"),
+    ///         Save::string("path contains invalid UTF-8 characters"),
+    ///     ]),
+    /// );
+    /// ```
+    pub fn display_errors(&self) -> Forward<'_, E> {
+        self.forward().errors(ErrorMode::Display)
+    }
+    /// Forward this tree, omitting any [`Save::Error`]s.
+    ///
+    /// See [`ErrorMode::Skip`] for more.
+    ///
+    /// ```
+    /// # use serde_save::{save_errors, Save};
+    /// let save = Save::Seq(vec![Save::U8(1), Save::error("oops"), Save::U8(3)]);
+    /// assert_eq!(
+    ///     save_errors(save.skip_errors()),
+    ///     Save::Seq(vec![Save::U8(1), Save::U8(3)]),
+    /// );
+    /// ```
+    pub fn skip_errors(&self) -> Forward<'_, E> {
+        self.forward().errors(ErrorMode::Skip)
+    }
+    /// Forward this tree, emitting any [`Save::ByteArray`]s as `repr`.
+    ///
+    /// This is useful for formats which handle
+    /// [`serialize_bytes`](serde::Serializer::serialize_bytes) poorly.
+    ///
+    /// ```
+    /// # use serde_save::{save, BytesRepr, Save};
+    /// let bytes = Save::<std::convert::Infallible>::bytes(*b"hi");
+    /// assert_eq!(
+    ///     save(bytes.with_bytes_as(BytesRepr::Seq)).unwrap(),
+    ///     Save::Seq(vec![Save::U8(b'h'), Save::U8(b'i')]),
+    /// );
+    /// assert_eq!(
+    ///     save(bytes.with_bytes_as(BytesRepr::Base64)).unwrap(),
+    ///     Save::string("aGk="),
+    /// );
+    /// ```
+    pub fn with_bytes_as(&self, repr: BytesRepr) -> Forward<'_, E> {
+        self.forward().bytes(repr)
+    }
+    /// Forward this tree, emitting map entries and struct fields in sorted order.
+    ///
+    /// See [`Forward::sorted`] for more.
+    pub fn sorted(&self) -> Forward<'_, E> {
+        self.forward().sorted(true)
+    }
+}
+
+impl<'s, E> Forward<'s, E> {
+    /// Choose what to do with [`Save::Error`]s.
+    pub fn errors(mut self, mode: ErrorMode) -> Self {
+        self.options.errors = mode;
+        self
+    }
+    /// Choose how to emit [`Save::ByteArray`]s.
+    pub fn bytes(mut self, repr: BytesRepr) -> Self {
+        self.options.bytes = repr;
+        self
+    }
+    /// Choose what to do with NaN and infinite floats,
+    /// which many formats reject.
+    ///
+    /// ```
+    /// # use serde_save::{save, NonFinite, Save};
+    /// let floats = save([1.0, f64::NAN, f64::NEG_INFINITY]).unwrap();
+    /// assert_eq!(
+    ///     save(floats.forward().non_finite(NonFinite::Display)).unwrap(),
+    ///     Save::Tuple(vec![Save::F64(1.0), Save::string("NaN"), Save::string("-inf")]),
+    /// );
+    /// assert_eq!(
+    ///     save(floats.forward().non_finite(NonFinite::Fail)).unwrap_err().to_string(),
+    ///     "cannot serialize non-finite float NaN",
+    /// );
+    /// ```
+    pub fn non_finite(mut self, policy: NonFinite) -> Self {
+        self.options.non_finite = policy;
+        self
+    }
+    /// Emit structs as maps with string keys, and struct variants as a map
+    /// from the variant's name to a map of its fields, as `serde_json` does.
+    ///
+    /// [Skipped](serde::ser::SerializeStruct::skip_field) fields are omitted.
+    ///
+    /// ```
+    /// # use serde_save::{save, Save};
+    /// #[derive(serde::Serialize)]
+    /// struct Point { x: i32, y: i32 }
+    ///
+    /// let point = save(Point { x: 1, y: 2 }).unwrap();
+    /// assert_eq!(
+    ///     save(point.forward().structs_as_maps(true)).unwrap(),
+    ///     Save::Map(vec![
+    ///         (Save::string("x"), Save::I32(1)),
+    ///         (Save::string("y"), Save::I32(2)),
+    ///     ]),
+    /// );
+    /// ```
+    pub fn structs_as_maps(mut self, as_maps: bool) -> Self {
+        self.options.structs_as_maps = as_maps;
+        self
+    }
+    /// Emit [raw JSON](Save::as_raw_json) as strings of their text,
+    /// for formats other than `serde_json`.
+    ///
+    /// Otherwise, they are always emitted as the marker `serde_json` writes verbatim,
+    /// even with [`Self::structs_as_maps`].
+    ///
+    /// ```
+    /// # use serde_save::{save, Save};
+    /// let raw = Save::<std::convert::Infallible>::raw_json("[1, 2]");
+    /// assert_eq!(save(raw.forward().structs_as_maps(true)).unwrap(), raw);
+    /// assert_eq!(
+    ///     save(raw.forward().raw_json_as_strings(true)).unwrap(),
+    ///     Save::string("[1, 2]"),
+    /// );
+    /// ```
+    pub fn raw_json_as_strings(mut self, as_strings: bool) -> Self {
+        self.options.raw_json_as_strings = as_strings;
+        self
+    }
+    /// Emit map entries in the order of their keys by [`Save::cmp_canonical`],
+    /// and struct fields in the order of their names.
+    ///
+    /// This gives deterministic output for golden files, even for captures of
+    /// a [`HashMap`](std::collections::HashMap).
+    /// [`Save::Error`]s are ordered by their message.
+    ///
+    /// ```
+    /// # use serde_save::{save, Save};
+    /// # use std::collections::HashMap;
+    /// #[derive(serde::Serialize)]
+    /// struct Config { name: &'static str, env: HashMap<&'static str, u8> }
+    ///
+    /// let config = save(Config { name: "app", env: HashMap::from([("b", 2), ("a", 1)]) }).unwrap();
+    /// assert_eq!(
+    ///     save(config.sorted()).unwrap(),
+    ///     Save::strukt("Config", [
+    ///         ("env", Save::Map(vec![
+    ///             (Save::string("a"), Save::U8(1)),
+    ///             (Save::string("b"), Save::U8(2)),
+    ///         ])),
+    ///         ("name", Save::string("app")),
+    ///     ]),
+    /// );
+    /// ```
+    pub fn sorted(mut self, sorted: bool) -> Self {
+        self.options.sorted = sorted;
+        self
+    }
+    /// Re-encode enum variants in another representation, as if the enum had
+    /// been declared with a different `#[serde(...)]` attribute.
+    ///
+    /// ```
+    /// # use serde_save::{save, EnumTagging};
+    /// mod external {
+    ///     #[derive(serde::Serialize)]
+    ///     pub enum Shape { Circle { radius: u32 }, Point }
+    /// }
+    /// mod internal {
+    ///     #[derive(serde::Serialize)]
+    ///     #[serde(tag = "type")]
+    ///     pub enum Shape { Circle { radius: u32 }, Point }
+    /// }
+    ///
+    /// let captured = save(external::Shape::Circle { radius: 1 }).unwrap();
+    /// assert_eq!(
+    ///     save(captured.forward().enums(EnumTagging::Internal { tag: "type" })).unwrap(),
+    ///     save(internal::Shape::Circle { radius: 1 }).unwrap(),
+    /// );
+    /// ```
+    pub fn enums(mut self, tagging: EnumTagging) -> Self {
+        self.options.enums = tagging;
+        self
+    }
+    /// Deliberately violate the serde protocol, to test how robust a
+    /// [`serde::Serializer`] is to buggy [`Serialize`] implementations.
+    ///
+    /// ```
+    /// # use serde_save::{save, validate, Faults};
+    /// let saved = save(vec![(1, 2)]).unwrap();
+    /// assert_eq!(validate(&saved.forward()), Ok(()));
+    ///
+    /// let errors = validate(&saved.forward().faults(Faults::new().wrong_lengths(true))).unwrap_err();
+    /// assert_eq!(
+    ///     errors.iter().map(|it| it.to_string()).collect::<Vec<_>>(),
+    ///     [
+    ///         "protocol error: expected a tuple of length 3, got 2",
+    ///         "protocol error: expected a sequence of length 2, got 1",
+    ///     ]
+    /// );
+    /// ```
+    pub fn faults(mut self, faults: Faults) -> Self {
+        self.options.faults = faults;
+        self
+    }
+    /// Emit a NaN or infinite float according to [`Forward::non_finite`].
+    fn non_finite_float<S, F>(&self, serializer: S, float: &F) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+        F: Serialize + fmt::Display,
+    {
+        match self.options.non_finite {
+            NonFinite::Keep => float.serialize(serializer),
+            NonFinite::None => serializer.serialize_none(),
+            NonFinite::Display => serializer.collect_str(float),
+            NonFinite::Fail => Err(S::Error::custom(format_args!(
+                "cannot serialize non-finite float {}",
+                float
+            ))),
+        }
+    }
+    /// The length to declare for a collection of `len` items.
+    fn len(&self, len: usize) -> usize {
+        match self.options.faults.wrong_lengths {
+            true => len + 1,
+            false => len,
+        }
+    }
+    fn variant_index(&self, variant_index: u32) -> u32 {
+        match self.options.faults.bad_variant_indices {
+            true => u32::MAX,
+            false => variant_index,
+        }
+    }
+    fn child(&self, save: &'s Save<'static, E>) -> Self {
+        Self {
+            save,
+            options: self.options,
+        }
+    }
+    /// Whether `save` should be omitted from its parent.
+    fn omit(&self, save: &Save<'static, E>) -> bool {
+        matches!(
+            (self.options.errors, save),
+            (ErrorMode::Skip, Save::Error(_))
+        )
+    }
+}
+
+impl<E> Serialize for Forward<'_, E>
+where
+    E: fmt::Display,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self.save {
+            Save::UnitVariant(_)
+            | Save::NewTypeVariant { .. }
+            | Save::TupleVariant { .. }
+            | Save::StructVariant { .. }
+                if self.options.enums != EnumTagging::External =>
+            {
+                self.retag(serializer)
+            }
+            Save::Bool(it) => serializer.serialize_bool(*it),
+            Save::I8(it) => serializer.serialize_i8(*it),
+            Save::I16(it) => serializer.serialize_i16(*it),
+            Save::I32(it) => serializer.serialize_i32(*it),
+            Save::I64(it) => serializer.serialize_i64(*it),
+            Save::I128(it) => serializer.serialize_i128(*it),
+            Save::U8(it) => serializer.serialize_u8(*it),
+            Save::U16(it) => serializer.serialize_u16(*it),
+            Save::U32(it) => serializer.serialize_u32(*it),
+            Save::U64(it) => serializer.serialize_u64(*it),
+            Save::U128(it) => serializer.serialize_u128(*it),
+            Save::F32(it) if !it.is_finite() => self.non_finite_float(serializer, it),
+            Save::F64(it) if !it.is_finite() => self.non_finite_float(serializer, it),
+            Save::F32(it) => serializer.serialize_f32(*it),
+            Save::F64(it) => serializer.serialize_f64(*it),
+            Save::Char(it) => serializer.serialize_char(*it),
+            Save::String(it) => serializer.serialize_str(it),
+            Save::ByteArray(it) => match self.options.bytes {
+                BytesRepr::Bytes => serializer.serialize_bytes(it),
+                BytesRepr::Seq => serializer.collect_seq(it),
+                BytesRepr::Base64 => serializer.collect_str(&Base64(it)),
+            },
+            Save::Option(None) => serializer.serialize_none(),
+            Save::Option(Some(it)) => match self.omit(it) {
+                true => serializer.serialize_none(),
+                false => serializer.serialize_some(&self.child(it)),
+            },
+            Save::UnitStruct(it) => serializer.serialize_unit_struct(it),
+            Save::UnitVariant(Variant {
+                name,
+                variant_index,
+                variant,
+            }) => {
+                serializer.serialize_unit_variant(name, self.variant_index(*variant_index), variant)
+            }
+            Save::Unit => serializer.serialize_unit(),
+            Save::NewTypeStruct { name, value } => {
+                serializer.serialize_newtype_struct(name, &self.child(value))
+            }
+            Save::NewTypeVariant {
+                variant:
+                    Variant {
+                        name,
+                        variant_index,
+                        variant,
+                    },
+                value,
+            } => serializer.serialize_newtype_variant(
+                name,
+                self.variant_index(*variant_index),
+                variant,
+                &self.child(value),
+            ),
+            Save::Seq(it) => {
+                let mut seq = serializer.serialize_seq(Some(self.len(self.count(it))))?;
+                for it in self.elements(it) {
+                    seq.serialize_element(&it)?
+                }
+                seq.end()
+            }
+            Save::Map(it) => {
+                let kept = |(k, v): &&(_, _)| !self.omit(k) && !self.omit(v);
+                let len = it.iter().filter(kept).count();
+                let mut map = serializer.serialize_map(Some(self.len(len)))?;
+                for (k, v) in self.entries(it).filter(kept) {
+                    map.serialize_entry(&self.child(k), &self.child(v))?
+                }
+                if self.options.faults.orphan_map_keys {
+                    match self.entries(it).find(kept) {
+                        Some((k, _)) => map.serialize_key(&self.child(k))?,
+                        None => map.serialize_key(&())?,
+                    }
+                }
+                map.end()
+            }
+            Save::Tuple(it) => {
+                let mut tup = serializer.serialize_tuple(self.len(self.count(it)))?;
+                for it in self.elements(it) {
+                    tup.serialize_element(&it)?
+                }
+                tup.end()
+            }
+            Save::TupleStruct { name, values } => {
+                let mut tup =
+                    serializer.serialize_tuple_struct(name, self.len(self.count(values)))?;
+                for it in self.elements(values) {
+                    tup.serialize_field(&it)?
+                }
+                tup.end()
+            }
+            Save::TupleVariant {
+                variant:
+                    Variant {
+                        name,
+                        variant_index,
+                        variant,
+                    },
+                values,
+            } => {
+                let mut var = serializer.serialize_tuple_variant(
+                    name,
+                    self.variant_index(*variant_index),
+                    variant,
+                    self.len(self.count(values)),
+                )?;
+                for it in self.elements(values) {
+                    var.serialize_field(&it)?
+                }
+                var.end()
+            }
+            Save::Struct { name, fields } => match self.save.raw_marker() {
+                Some((_, json)) if self.options.raw_json_as_strings => {
+                    serializer.serialize_str(json)
+                }
+                Some((marker, json)) => {
+                    let mut strukt = serializer.serialize_struct(marker, 1)?;
+                    strukt.serialize_field(marker, json)?;
+                    strukt.end()
+                }
+                None => Fields {
+                    forward: self,
+                    name,
+                    tag: None,
+                    fields,
+                }
+                .serialize(serializer),
+            },
+            Save::StructVariant {
+                variant: Variant { variant, .. },
+                fields,
+            } if self.options.structs_as_maps => {
+                let fields = Fields {
+                    forward: self,
+                    name: variant,
+                    tag: None,
+                    fields,
+                };
+                let mut map = serializer.serialize_map(Some(1))?;
+                map.serialize_entry(variant, &fields)?;
+                map.end()
+            }
+            Save::StructVariant {
+                variant:
+                    Variant {
+                        name,
+                        variant_index,
+                        variant,
+                    },
+                fields,
+            } => {
+                let mut var = serializer.serialize_struct_variant(
+                    name,
+                    self.variant_index(*variant_index),
+                    variant,
+                    self.len(fields.len()),
+                )?;
+                for (k, v) in self.fields(fields).chain(self.duplicate(fields)) {
+                    match v {
+                        Some(v) if !self.omit(v) => var.serialize_field(k, &self.child(v))?,
+                        _ => var.skip_field(k)?,
+                    }
+                }
+                var.end()
+            }
+            Save::Error(e) => match self.options.errors {
+                ErrorMode::Fail => Err(S::Error::custom(e)),
+                ErrorMode::Skip => serializer.serialize_unit(),
+                ErrorMode::Display => serializer.collect_str(e),
+            },
+        }
+    }
+}
+
+/// The fields of a struct, emitted as a map if [`Forward::structs_as_maps`].
+struct Fields<'f, 's, E> {
+    forward: &'f Forward<'s, E>,
+    name: &'static str,
+    /// A field to emit first, see [`EnumTagging::Internal`].
+    tag: Option<(&'static str, &'static str)>,
+    fields: &'s [(&'static str, Option<Save<'static, E>>)],
+}
+
+impl<E> Serialize for Fields<'_, '_, E>
+where
+    E: fmt::Display,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let Self {
+            forward,
+            name,
+            tag,
+            fields,
+        } = *self;
+        let fields = forward.fields(fields).chain(forward.duplicate(fields));
+        if forward.options.structs_as_maps {
+            let present = |it| forward.present(it);
+            let len = self.fields.iter().filter_map(present).count()
+                + usize::from(forward.duplicate(self.fields).and_then(present).is_some())
+                + usize::from(tag.is_some());
+            let mut map = serializer.serialize_map(Some(forward.len(len)))?;
+            if let Some((tag, variant)) = tag {
+                map.serialize_entry(tag, variant)?
+            }
+            for (k, v) in fields.filter_map(present) {
+                map.serialize_entry(k, &forward.child(v))?
+            }
+            return map.end();
+        }
+        let len = self.fields.len() + usize::from(tag.is_some());
+        let mut strukt = serializer.serialize_struct(name, forward.len(len))?;
+        if let Some((tag, variant)) = tag {
+            strukt.serialize_field(tag, variant)?
+        }
+        for (k, v) in fields {
+            match v {
+                Some(v) if !forward.omit(v) => strukt.serialize_field(k, &forward.child(v))?,
+                _ => strukt.skip_field(k)?,
+            }
+        }
+        strukt.end()
+    }
+}
+
+/// The elements of a tuple variant, emitted as a tuple.
+struct Elements<'f, 's, E>(&'f Forward<'s, E>, &'s [Save<'static, E>]);
+
+impl<E> Serialize for Elements<'_, '_, E>
+where
+    E: fmt::Display,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let Self(forward, elements) = *self;
+        let mut tup = serializer.serialize_tuple(forward.len(forward.count(elements)))?;
+        for it in forward.elements(elements) {
+            tup.serialize_element(&it)?
+        }
+        tup.end()
+    }
+}
+
+/// A variant's name, emitted as a unit variant, see [`EnumTagging::Adjacent`].
+struct Tag<'f, 's, E>(&'f Forward<'s, E>, &'f Variant<'static>);
+
+impl<E> Serialize for Tag<'_, '_, E> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let Self(forward, variant) = *self;
+        serializer.serialize_unit_variant(
+            variant.name,
+            forward.variant_index(variant.variant_index),
+            variant.variant,
+        )
+    }
+}
+
+impl<'s, E> Forward<'s, E>
+where
+    E: fmt::Display,
+{
+    /// Emit a variant according to [`Forward::enums`].
+    fn retag<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let (variant, unit) = match self.save {
+            Save::UnitVariant(variant) => (variant, true),
+            Save::NewTypeVariant { variant, .. }
+            | Save::TupleVariant { variant, .. }
+            | Save::StructVariant { variant, .. } => (variant, false),
+            _ => unreachable!(),
+        };
+        match (self.options.enums, self.save) {
+            (EnumTagging::External, _) => unreachable!(),
+            (EnumTagging::Internal { tag }, Save::UnitVariant(_)) => {
+                let mut strukt = serializer.serialize_struct(variant.name, self.len(1))?;
+                strukt.serialize_field(tag, variant.variant)?;
+                strukt.end()
+            }
+            (EnumTagging::Internal { tag }, Save::NewTypeVariant { value, .. }) => {
+                self.tagged_newtype(serializer, tag, variant, value)
+            }
+            (EnumTagging::Internal { .. }, Save::TupleVariant { .. }) => {
+                Err(S::Error::custom(format_args!(
+                    "cannot serialize tuple variant {}::{} as internally tagged",
+                    variant.name, variant.variant
+                )))
+            }
+            (EnumTagging::Internal { tag }, Save::StructVariant { fields, .. }) => Fields {
+                forward: self,
+                name: variant.name,
+                tag: Some((tag, variant.variant)),
+                fields,
+            }
+            .serialize(serializer),
+            (EnumTagging::Adjacent { tag, content }, save) => {
+                let len = if unit { 1 } else { 2 };
+                let mut strukt = serializer.serialize_struct(variant.name, self.len(len))?;
+                strukt.serialize_field(tag, &Tag(self, variant))?;
+                match save {
+                    Save::NewTypeVariant { value, .. } => {
+                        strukt.serialize_field(content, &self.child(value))?
+                    }
+                    Save::TupleVariant { values, .. } => {
+                        strukt.serialize_field(content, &Elements(self, values))?
+                    }
+                    Save::StructVariant { fields, .. } => strukt.serialize_field(
+                        content,
+                        &Fields {
+                            forward: self,
+                            name: variant.variant,
+                            tag: None,
+                            fields,
+                        },
+                    )?,
+                    _ => {}
+                }
+                strukt.end()
+            }
+            (EnumTagging::Untagged, Save::NewTypeVariant { value, .. }) => {
+                self.child(value).serialize(serializer)
+            }
+            (EnumTagging::Untagged, Save::TupleVariant { values, .. }) => {
+                Elements(self, values).serialize(serializer)
+            }
+            (EnumTagging::Untagged, Save::StructVariant { fields, .. }) => Fields {
+                forward: self,
+                name: variant.name,
+                tag: None,
+                fields,
+            }
+            .serialize(serializer),
+            (EnumTagging::Untagged, _) => serializer.serialize_unit(),
+            (EnumTagging::Internal { .. }, _) => unreachable!(),
+        }
+    }
+    /// Emit the contents of an internally tagged newtype variant,
+    /// with the tag alongside, as serde does.
+    fn tagged_newtype<S: serde::Serializer>(
+        &self,
+        serializer: S,
+        tag: &'static str,
+        variant: &Variant<'static>,
+        value: &'s Save<'static, E>,
+    ) -> Result<S::Ok, S::Error> {
+        let mut value = value;
+        while let Save::NewTypeStruct { value: it, .. } = value {
+            value = it
+        }
+        let variant = variant.variant;
+        match value {
+            Save::Struct { name, fields } => Fields {
+                forward: self,
+                name,
+                tag: Some((tag, variant)),
+                fields,
+            }
+            .serialize(serializer),
+            Save::Map(entries) => {
+                let entries = self
+                    .entries(entries)
+                    .filter(|(k, v)| !self.omit(k) && !self.omit(v))
+                    .collect::<Vec<_>>();
+                let mut map = serializer.serialize_map(Some(self.len(entries.len() + 1)))?;
+                map.serialize_entry(tag, variant)?;
+                for (k, v) in entries {
+                    map.serialize_entry(&self.child(k), &self.child(v))?
+                }
+                map.end()
+            }
+            Save::Unit | Save::UnitStruct(_) => {
+                let mut map = serializer.serialize_map(Some(self.len(1)))?;
+                map.serialize_entry(tag, variant)?;
+                map.end()
+            }
+            Save::UnitVariant(inner) => {
+                let mut map = serializer.serialize_map(Some(self.len(2)))?;
+                map.serialize_entry(tag, variant)?;
+                map.serialize_entry(inner.variant, &())?;
+                map.end()
+            }
+            Save::NewTypeVariant { variant: inner, .. }
+            | Save::TupleVariant { variant: inner, .. }
+            | Save::StructVariant { variant: inner, .. } => {
+                // The inner variant is externally tagged by its name.
+                let content = Self {
+                    save: value,
+                    options: Options {
+                        enums: EnumTagging::Untagged,
+                        ..self.options
+                    },
+                };
+                let mut map = serializer.serialize_map(Some(self.len(2)))?;
+                map.serialize_entry(tag, variant)?;
+                map.serialize_entry(inner.variant, &content)?;
+                map.end()
+            }
+            other => Err(S::Error::custom(format_args!(
+                "cannot serialize tagged newtype variant containing {}",
+                other.kind_name()
+            ))),
+        }
+    }
+}
+
+impl<'s, E> Forward<'s, E> {
+    /// The field to serialize again, see [`Faults::duplicate_fields`].
+    #[allow(clippy::type_complexity)]
+    fn duplicate<'f>(
+        &self,
+        fields: &'f [(&'static str, Option<Save<'static, E>>)],
+    ) -> Option<&'f (&'static str, Option<Save<'static, E>>)> {
+        match self.options.faults.duplicate_fields {
+            true => fields.first(),
+            false => None,
+        }
+    }
+    /// The entries of a map, in the order to emit them.
+    fn entries<'f>(
+        &self,
+        entries: &'f [(Save<'static, E>, Save<'static, E>)],
+    ) -> impl Iterator<Item = &'f (Save<'static, E>, Save<'static, E>)>
+    where
+        E: fmt::Display,
+    {
+        self.ordered(entries, |(l, _), (r, _)| {
+            l.cmp_canonical_by(r, &|l, r| l.to_string().cmp(&r.to_string()))
+        })
+    }
+    /// The fields of a struct, in the order to emit them.
+    #[allow(clippy::type_complexity)]
+    fn fields<'f>(
+        &self,
+        fields: &'f [(&'static str, Option<Save<'static, E>>)],
+    ) -> impl Iterator<Item = &'f (&'static str, Option<Save<'static, E>>)> {
+        self.ordered(fields, |(l, _), (r, _)| l.cmp(r))
+    }
+    /// Sort `items` by `cmp` if [`Forward::sorted`].
+    ///
+    /// Only sorting allocates.
+    fn ordered<'f, T>(&self, items: &'f [T], cmp: impl Fn(&T, &T) -> Ordering) -> Ordered<'f, T> {
+        match self.options.sorted {
+            true => {
+                let mut items = items.iter().collect::<Vec<_>>();
+                items.sort_by(|l, r| cmp(l, r));
+                Ordered::Sorted(items.into_iter())
+            }
+            false => Ordered::InOrder(items.iter()),
+        }
+    }
+    fn elements(&self, elements: &'s [Save<'static, E>]) -> impl Iterator<Item = Self> + '_ {
+        elements
+            .iter()
+            .filter(|it| !self.omit(it))
+            .map(|it| self.child(it))
+    }
+    /// The name and value of a field, unless it is skipped or [omitted](Self::omit).
+    fn present<'f>(
+        &self,
+        (name, value): &'f (&'static str, Option<Save<'static, E>>),
+    ) -> Option<(&'static str, &'f Save<'static, E>)> {
+        Some((name, value.as_ref().filter(|it| !self.omit(it))?))
+    }
+    /// The number of [`Self::elements`].
+    fn count(&self, elements: &[Save<'static, E>]) -> usize {
+        elements.iter().filter(|it| !self.omit(it)).count()
+    }
+}
+
+/// See [`Forward::ordered`].
+enum Ordered<'f, T> {
+    InOrder(core::slice::Iter<'f, T>),
+    Sorted(std::vec::IntoIter<&'f T>),
+}
+
+impl<'f, T> Iterator for Ordered<'f, T> {
+    type Item = &'f T;
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Ordered::InOrder(it) => it.next(),
+            Ordered::Sorted(it) => it.next(),
+        }
+    }
+}
@@ -0,0 +1,169 @@
+use crate::{diff::DisplayPath, Change, Diff, Edge, Save};
+use core::fmt::{self, Write as _};
+
+impl<E: fmt::Display> Save<'_, E> {
+    /// Render this tree as a collapsible HTML fragment, using nested
+    /// `<details>` elements so that no JavaScript is required.
+    ///
+    /// Elements have the following classes, for styling:
+    /// - `serde-save` on the outermost element.
+    /// - `key` on field names, indices and map keys.
+    /// - `leaf` on nodes without children, and `error` on [`Save::Error`]s.
+    ///
+    /// ```
+    /// # use serde_save::save;
+    /// #[derive(serde::Serialize)]
+    /// struct Point {
+    ///     x: i32,
+    ///     label: &'static str,
+    /// }
+    ///
+    /// assert_eq!(
+    ///     save(Point { x: 1, label: "<origin>" }).unwrap().to_html(),
+    ///     concat!(
+    ///         r#"<div class="serde-save"><details open><summary>Point</summary><ul>"#,
+    ///         r#"<li><span class="key">x</span>: <code class="leaf">i32(1)</code></li>"#,
+    ///         r#"<li><span class="key">label</span>: <code class="leaf">&quot;&lt;origin&gt;&quot;</code></li>"#,
+    ///         r#"</ul></details></div>"#,
+    ///     )
+    /// );
+    /// ```
+    pub fn to_html(&self) -> String {
+        let mut out = String::from(r#"<div class="serde-save">"#);
+        node(&mut out, self);
+        out.push_str("</div>");
+        out
+    }
+}
+
+impl<E: fmt::Debug> Diff<'_, E> {
+    /// Render this diff as an HTML list, with one item per [`Change`].
+    ///
+    /// Items have the class `added`, `removed`, `changed`, `renamed` or `moved`,
+    /// and the list has the class `serde-save-diff`.
+    ///
+    /// ```
+    /// # use serde_save::Save;
+    /// let before = Save::<std::convert::Infallible>::from_iter([1]);
+    /// let after = Save::from_iter([1, 2]);
+    /// assert_eq!(
+    ///     before.diff(&after).to_html(),
+    ///     r#"<ul class="serde-save-diff"><li class="added"><code>[1]</code>: <code>I32(2)</code></li></ul>"#
+    /// );
+    /// ```
+    pub fn to_html(&self) -> String {
+        let mut out = String::from(r#"<ul class="serde-save-diff">"#);
+        for change in self {
+            let (class, path) = match change {
+                Change::Added { path, .. } => ("added", path),
+                Change::Removed { path, .. } => ("removed", path),
+                Change::Changed { path, .. } => ("changed", path),
+                Change::Renamed { path, .. } => ("renamed", path),
+                Change::Moved { path, .. } => ("moved", path),
+            };
+            let _ = write!(
+                out,
+                r#"<li class="{}"><code>{}</code>: "#,
+                class,
+                Escape(&DisplayPath(path).to_string())
+            );
+            let _ = match change {
+                Change::Added { value, .. } | Change::Removed { value, .. } => {
+                    write!(out, "<code>{}</code>", Escape(&format!("{:?}", value)))
+                }
+                Change::Changed { before, after, .. } => write!(
+                    out,
+                    "<code>{}</code> → <code>{}</code>",
+                    Escape(&format!("{:?}", before)),
+                    Escape(&format!("{:?}", after))
+                ),
+                Change::Renamed { to, .. } => write!(out, "renamed to <code>{}</code>", Escape(to)),
+                Change::Moved { to, .. } => write!(
+                    out,
+                    "moved to <code>{}</code>",
+                    Escape(&DisplayPath(to).to_string())
+                ),
+            };
+            out.push_str("</li>")
+        }
+        out.push_str("</ul>");
+        out
+    }
+}
+
+fn node<E: fmt::Display>(out: &mut String, save: &Save<'_, E>) {
+    let mut children = save.children().peekable();
+    if children.peek().is_none() {
+        let class = match save {
+            Save::Error(_) => "leaf error",
+            _ => "leaf",
+        };
+        let _ = write!(
+            out,
+            r#"<code class="{}">{}</code>"#,
+            class,
+            Escape(&save.pretty().to_string())
+        );
+        return;
+    }
+    let _ = write!(
+        out,
+        "<details open><summary>{}</summary><ul>",
+        Summary(save)
+    );
+    for (edge, child) in children {
+        out.push_str(r#"<li><span class="key">"#);
+        let _ = match edge {
+            Edge::Field(name) => write!(out, "{}", Escape(name)),
+            Edge::Index(ix) => write!(out, "[{}]", ix),
+            Edge::Key(key) => write!(out, "{}", Escape(&key.pretty().to_string())),
+            Edge::Value => write!(out, "value"),
+        };
+        out.push_str("</span>: ");
+        node(out, child);
+        out.push_str("</li>");
+    }
+    out.push_str("</ul></details>");
+}
+
+/// The header of a node with children.
+struct Summary<'s, 'a, E>(&'s Save<'a, E>);
+
+impl<E> fmt::Display for Summary<'_, '_, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Save::Option(_) => f.write_str("Some"),
+            Save::NewTypeStruct { name, .. }
+            | Save::Struct { name, .. }
+            | Save::TupleStruct { name, .. } => write!(f, "{}", Escape(name)),
+            Save::NewTypeVariant { variant, .. }
+            | Save::TupleVariant { variant, .. }
+            | Save::StructVariant { variant, .. } => {
+                write!(f, "{}::{}", Escape(variant.name), Escape(variant.variant))
+            }
+            Save::Seq(it) => write!(f, "seq ({})", it.len()),
+            Save::Tuple(it) => write!(f, "tuple ({})", it.len()),
+            Save::Map(it) => write!(f, "map ({})", it.len()),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Escape text for inclusion in HTML.
+struct Escape<'s>(&'s str);
+
+impl fmt::Display for Escape<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for c in self.0.chars() {
+            match c {
+                '&' => f.write_str("&amp;")?,
+                '<' => f.write_str("&lt;")?,
+                '>' => f.write_str("&gt;")?,
+                '"' => f.write_str("&quot;")?,
+                '\'' => f.write_str("&#39;")?,
+                c => f.write_char(c)?,
+            }
+        }
+        Ok(())
+    }
+}
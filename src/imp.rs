@@ -1,6 +1,14 @@
-use crate::{Error, Save, Variant};
+use crate::{session::Pool, Error, Path, ProtocolDetail, Save, Segment, Variant};
 use core::{cmp, convert::Infallible, fmt, marker::PhantomData};
-use std::collections::BTreeSet;
+use serde::ser::Error as _;
+use std::{
+    collections::BTreeSet,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Instant,
+};
 
 mod sealed {
     pub trait Sealed {}
@@ -34,7 +42,7 @@ impl ErrorDiscipline for Persist {
 ///
 /// See [crate documentation](mod@super) for more.
 pub struct Serializer<ErrorDiscipline = ShortCircuit> {
-    config: Config<ErrorDiscipline>,
+    ctx: Context<ErrorDiscipline>,
 }
 
 impl Serializer<ShortCircuit> {
@@ -42,10 +50,32 @@ impl Serializer<ShortCircuit> {
     /// - [human readable](`serde::Serializer::is_human_readable`) (this is the default for serde formats).
     /// - NOT sensitive to [protocol errors](Self::check_for_protocol_errors).
     pub fn new() -> Self {
+        Self::from_config(Config::new())
+    }
+    /// Create a serializer with the given [`Config`], which may have been
+    /// loaded from e.g a configuration file.
+    ///
+    /// ```
+    /// # use serde_save::{Config, Serializer};
+    /// # use serde::{Deserialize as _, de::value::MapDeserializer};
+    /// let config = Config::deserialize(MapDeserializer::<_, serde::de::value::Error>::new(
+    ///     [("protocol_errors", true)].into_iter(),
+    /// ))
+    /// .unwrap();
+    /// assert_eq!(config, Config::new().check_for_protocol_errors(true));
+    /// let serializer = Serializer::from_config(config);
+    /// ```
+    pub fn from_config(config: Config) -> Self {
         Self {
-            config: Config {
-                is_human_readable: true,
-                protocol_errors: false,
+            ctx: Context {
+                config,
+                overrides: None,
+                trail: Trail::default(),
+                budget: None,
+                deadline: None,
+                pool: None,
+                error_context: false,
+                root: None,
                 _error_discipline: PhantomData,
             },
         }
@@ -53,15 +83,123 @@ impl Serializer<ShortCircuit> {
 }
 
 impl<E> Serializer<E> {
+    /// The [`Config`] at the root of the tree.
+    ///
+    /// Note that this does not include any [overrides](Self::override_at).
+    pub fn config(&self) -> Config {
+        self.ctx.config
+    }
     /// See [`serde::Serializer::is_human_readable`].
     pub fn human_readable(mut self, is_human_readable: bool) -> Self {
-        self.config.is_human_readable = is_human_readable;
+        self.ctx.config.is_human_readable = is_human_readable;
         self
     }
     /// Whether to check for incorrect implementations of e.g [`serde::ser::SerializeSeq`].
     /// See documentation on variants of [`Save`] for the invariants which are checked.
     pub fn check_for_protocol_errors(mut self, check: bool) -> Self {
-        self.config.protocol_errors = check;
+        self.ctx.config.protocol_errors = check;
+        self
+    }
+    /// Check that the names of structs, fields, enums and variants follow
+    /// `rule`, reporting any which don't as protocol errors.
+    ///
+    /// This is useful for testing macro-generated [`Serialize`](serde::Serialize)
+    /// implementations.
+    ///
+    /// ```
+    /// # use serde::Serialize;
+    /// # use serde_save::{IdentifierRule, Serializer};
+    /// #[derive(Serialize)]
+    /// #[serde(rename = "user record")]
+    /// struct User {
+    ///     id: u32,
+    /// }
+    ///
+    /// assert!(User { id: 1 }.serialize(Serializer::new()).is_ok());
+    /// assert_eq!(
+    ///     User { id: 1 }
+    ///         .serialize(Serializer::new().check_identifiers(IdentifierRule::NonBlank))
+    ///         .unwrap_err()
+    ///         .to_string(),
+    ///     r#"protocol error: invalid struct name "user record""#
+    /// );
+    /// ```
+    ///
+    /// When [saving errors](Self::save_errors), the rest of the node is kept.
+    /// Unit and newtype nodes have nowhere to record the error,
+    /// so they are saved as structs or tuple structs, with the error as the last
+    /// field or element.
+    ///
+    /// ```
+    /// # use serde::Serialize;
+    /// # use serde_save::{IdentifierRule, Save, Serializer};
+    /// #[derive(Serialize)]
+    /// #[serde(rename = "user id")]
+    /// struct UserId(u32);
+    ///
+    /// let serializer = Serializer::new()
+    ///     .check_identifiers(IdentifierRule::NonBlank)
+    ///     .save_errors();
+    /// assert_eq!(
+    ///     serializer.save(UserId(1)).unwrap().map_error(|it| it.to_string()),
+    ///     Save::TupleStruct {
+    ///         name: "user id",
+    ///         values: vec![
+    ///             Save::U32(1),
+    ///             Save::Error(String::from(r#"protocol error: invalid struct name "user id""#)),
+    ///         ],
+    ///     }
+    /// );
+    /// ```
+    pub fn check_identifiers(mut self, rule: IdentifierRule) -> Self {
+        self.ctx.config.identifiers = rule;
+        self
+    }
+    /// Override the [`Config`] for the subtree at `path`.
+    ///
+    /// `f` is called with the configuration inherited from the parent of `path`.
+    /// See [`Path`] for the syntax, and note that wrapper nodes like [`Save::Option`]
+    /// share the [`Path`] of their contents.
+    ///
+    /// An override at the root (empty) path is applied immediately.
+    ///
+    /// ```
+    /// # use serde::Serialize;
+    /// # use serde_save::{Path, Save, Serializer};
+    /// # use std::net::{IpAddr, Ipv4Addr};
+    /// #[derive(Serialize)]
+    /// struct Addrs {
+    ///     readable: IpAddr,
+    ///     compact: IpAddr,
+    /// }
+    /// let addr = IpAddr::V4(Ipv4Addr::LOCALHOST);
+    /// let save = Addrs { readable: addr, compact: addr }
+    ///     .serialize(Serializer::new().override_at("compact", |it| it.human_readable(false)))
+    ///     .unwrap();
+    /// assert_eq!(save.get(&Path::parse("readable").unwrap()), Some(&Save::string("127.0.0.1")));
+    /// assert!(matches!(
+    ///     save.get(&Path::parse("compact").unwrap()),
+    ///     Some(Save::NewTypeVariant { .. })
+    /// ));
+    /// ```
+    ///
+    /// # Panics
+    /// - If `path` is not a valid [`Path`].
+    pub fn override_at(
+        mut self,
+        path: &str,
+        f: impl Fn(Config) -> Config + Send + Sync + 'static,
+    ) -> Self {
+        let path = match Path::parse(path) {
+            Ok(it) => it.into_owned(),
+            Err(e) => panic!("{}", e),
+        };
+        if path.is_empty() {
+            self.ctx.config = f(self.ctx.config);
+        } else {
+            Arc::make_mut(self.ctx.overrides.get_or_insert_with(Default::default))
+                .push((path, Arc::new(f)));
+        }
         self
     }
     /// Persist the errors in-tree.
@@ -73,21 +211,178 @@ impl<E> Serializer<E> {
     /// will be recorded as the final element(s) of the corresponding collection.
     pub fn save_errors(self) -> Serializer<Persist> {
         let Self {
-            config:
-                Config {
-                    is_human_readable,
-                    protocol_errors,
+            ctx:
+                Context {
+                    config,
+                    overrides,
+                    trail,
+                    budget,
+                    deadline,
+                    pool,
+                    error_context,
+                    root,
                     _error_discipline,
                 },
         } = self;
         Serializer {
-            config: Config {
-                is_human_readable,
-                protocol_errors,
+            ctx: Context {
+                config,
+                overrides,
+                trail,
+                budget,
+                deadline,
+                pool,
+                error_context,
+                root,
                 _error_discipline: PhantomData,
             },
         }
     }
+    /// Abort serialization with an error once `deadline` has passed,
+    /// to avoid stalling on a slow [`Serialize`](serde::Serialize) implementation.
+    ///
+    /// The deadline is checked before each child node is serialized.
+    /// If [errors are persisted](Self::save_errors), the remaining nodes are
+    /// recorded as errors.
+    ///
+    /// ```
+    /// # use serde_save::{Save, Serializer};
+    /// # use std::time::{Duration, Instant};
+    /// let deadline = Instant::now() - Duration::from_secs(1);
+    /// let serializer = Serializer::new().deadline(deadline);
+    /// assert_eq!(
+    ///     serializer.save([1, 2, 3]).unwrap_err().to_string(),
+    ///     "deadline exceeded while serializing"
+    /// );
+    ///
+    /// let serializer = Serializer::new().save_errors().deadline(deadline);
+    /// assert_eq!(
+    ///     serializer.save(("a", [1, 2])).unwrap(),
+    ///     Save::Tuple(vec![
+    ///         Save::error("deadline exceeded while serializing"),
+    ///         Save::error("deadline exceeded while serializing"),
+    ///     ])
+    /// );
+    /// ```
+    pub fn deadline(mut self, deadline: Instant) -> Self {
+        self.ctx.deadline = Some(deadline);
+        self
+    }
+    /// Take string and byte buffers from `pool`, see [`SaveSession`](crate::SaveSession).
+    pub(crate) fn pool(mut self, pool: Arc<Pool>) -> Self {
+        self.ctx.pool = Some(pool);
+        self
+    }
+}
+
+impl<E: ErrorDiscipline> Serializer<E> {
+    /// Save `t` with this serializer's configuration, without consuming it,
+    /// so that a configured serializer may be reused for many values.
+    ///
+    /// Each call has its own [error budget](Serializer::max_errors).
+    ///
+    /// ```
+    /// # use serde_save::{Save, Serializer};
+    /// let serializer = Serializer::new().human_readable(false);
+    /// for value in [1, 2, 3] {
+    ///     assert_eq!(serializer.save(value).unwrap(), Save::I32(value));
+    /// }
+    /// ```
+    pub fn save<T: serde::Serialize>(&self, t: T) -> Result<Save<'static, E::SaveError>, Error> {
+        t.serialize(self.fresh())
+    }
+    /// A copy of this serializer with its own [error budget](Serializer::max_errors).
+    pub(crate) fn fresh(&self) -> Self {
+        let mut ctx = self.ctx.clone();
+        if let Some(budget) = &mut ctx.budget {
+            budget.count = Arc::new(AtomicUsize::new(0))
+        }
+        Serializer { ctx }
+    }
+    /// A serializer for the child of the current node at `segment`,
+    /// with any [overrides](Serializer::override_at) applied.
+    pub(crate) fn child(&self, segment: Segment<'static>) -> Self {
+        self.ctx.child(segment)
+    }
+}
+
+impl Serializer<Persist> {
+    /// Stop recording errors after `max` elements of sequences and tuples have
+    /// failed to serialize, to avoid e.g recording millions of identical errors.
+    ///
+    /// Further failing elements are omitted, and a single [`Save::Error`]
+    /// summarising them is recorded as the final element of their collection.
+    ///
+    /// Only errors in elements of sequences and tuples count towards the limit.
+    ///
+    /// ```
+    /// # use serde::Serialize as _;
+    /// # use serde_save::{Save, Serializer};
+    /// # use std::{ffi::OsString, os::unix::ffi::OsStringExt as _, path::PathBuf};
+    /// let non_utf8_path = PathBuf::from(OsString::from_vec(vec![u8::MAX]));
+    /// let save = vec![non_utf8_path; 1000]
+    ///     .serialize(Serializer::new().save_errors().max_errors(1))
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     save,
+    ///     Save::Seq(vec![
+    ///         Save::error("path contains invalid UTF-8 characters"),
+    ///         Save::error("999 more elements omitted after reaching the limit of 1 errors"),
+    ///     ])
+    /// );
+    /// ```
+    pub fn max_errors(mut self, max: usize) -> Self {
+        let halt = self.ctx.budget.as_ref().is_some_and(|it| it.halt);
+        self.ctx.budget = Some(Budget {
+            max,
+            halt,
+            count: Arc::new(AtomicUsize::new(0)),
+        });
+        self
+    }
+    /// Once the limit set by [`Self::max_errors`] is reached, stop serializing
+    /// elements of sequences and tuples altogether, rather than serializing
+    /// them and omitting any failures.
+    ///
+    /// Omitted elements are summarised in the same way.
+    ///
+    /// Has no effect unless [`Self::max_errors`] is set.
+    pub fn halt_at_max_errors(mut self, halt: bool) -> Self {
+        if let Some(budget) = &mut self.ctx.budget {
+            budget.halt = halt
+        }
+        self
+    }
+    /// Prefix errors returned by [`Serialize`](serde::Serialize) implementations
+    /// with their location in the tree,
+    /// for consumers which only see [`Error::to_string`](std::string::ToString::to_string).
+    ///
+    /// The location starts with the name of the struct or enum at the root, if any.
+    ///
+    /// ```
+    /// # use serde::Serialize;
+    /// # use serde_save::{Path, Save, Serializer};
+    /// # use std::{ffi::OsString, os::unix::ffi::OsStringExt as _, path::PathBuf};
+    /// #[derive(Serialize)]
+    /// struct Config {
+    ///     tls: Tls,
+    /// }
+    /// #[derive(Serialize)]
+    /// struct Tls {
+    ///     cert: PathBuf,
+    /// }
+    ///
+    /// let config = Config { tls: Tls { cert: PathBuf::from(OsString::from_vec(vec![u8::MAX])) } };
+    /// let save = Serializer::new().save_errors().error_context(true).save(&config).unwrap();
+    /// assert_eq!(
+    ///     save.get(&Path::parse("tls.cert").unwrap()),
+    ///     Some(&Save::error("while serializing `Config.tls.cert`: path contains invalid UTF-8 characters")),
+    /// );
+    /// ```
+    pub fn error_context(mut self, context: bool) -> Self {
+        self.ctx.error_context = context;
+        self
+    }
 }
 
 impl Default for Serializer {
@@ -97,18 +392,420 @@ impl Default for Serializer {
     }
 }
 
-struct Config<E = ShortCircuit> {
-    is_human_readable: bool,
-    protocol_errors: bool,
+/// Options for a [`Serializer`], which may be [overridden](Serializer::override_at)
+/// for a subtree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct Config {
+    /// See [`Config::human_readable`].
+    pub is_human_readable: bool,
+    /// See [`Config::check_for_protocol_errors`].
+    pub protocol_errors: bool,
+    /// See [`Config::check_identifiers`].
+    pub identifiers: IdentifierRule,
+}
+
+impl Config {
+    /// The same defaults as [`Serializer::new`].
+    pub fn new() -> Self {
+        Self {
+            is_human_readable: true,
+            protocol_errors: false,
+            identifiers: IdentifierRule::Any,
+        }
+    }
+    /// See [`Serializer::human_readable`].
+    pub fn human_readable(mut self, is_human_readable: bool) -> Self {
+        self.is_human_readable = is_human_readable;
+        self
+    }
+    /// See [`Serializer::check_for_protocol_errors`].
+    pub fn check_for_protocol_errors(mut self, check: bool) -> Self {
+        self.protocol_errors = check;
+        self
+    }
+    /// See [`Serializer::check_identifiers`].
+    pub fn check_identifiers(mut self, rule: IdentifierRule) -> Self {
+        self.identifiers = rule;
+        self
+    }
+}
+
+impl Default for Config {
+    /// See [`Self::new`].
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+const CONFIG_FIELDS: &[&str] = &["is_human_readable", "protocol_errors", "identifiers"];
+
+/// Serialized as a struct named `Config`.
+impl serde::Serialize for Config {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct as _;
+        let Self {
+            is_human_readable,
+            protocol_errors,
+            identifiers,
+        } = self;
+        let mut strukt = serializer.serialize_struct("Config", CONFIG_FIELDS.len())?;
+        strukt.serialize_field("is_human_readable", is_human_readable)?;
+        strukt.serialize_field("protocol_errors", protocol_errors)?;
+        strukt.serialize_field("identifiers", identifiers)?;
+        strukt.end()
+    }
+}
+
+/// Missing fields take their [default](Config::new) values.
+impl<'de> serde::Deserialize<'de> for Config {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Visitor;
+        impl<'de> serde::de::Visitor<'de> for Visitor {
+            type Value = Config;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a `Config`")
+            }
+
+            fn visit_seq<A: serde::de::SeqAccess<'de>>(
+                self,
+                mut seq: A,
+            ) -> Result<Self::Value, A::Error> {
+                let mut config = Config::new();
+                if let Some(it) = seq.next_element()? {
+                    config.is_human_readable = it
+                }
+                if let Some(it) = seq.next_element()? {
+                    config.protocol_errors = it
+                }
+                if let Some(it) = seq.next_element()? {
+                    config.identifiers = it
+                }
+                Ok(config)
+            }
+
+            fn visit_map<A: serde::de::MapAccess<'de>>(
+                self,
+                mut map: A,
+            ) -> Result<Self::Value, A::Error> {
+                let mut config = Config::new();
+                while let Some(key) = map.next_key::<String>()? {
+                    match &*key {
+                        "is_human_readable" => config.is_human_readable = map.next_value()?,
+                        "protocol_errors" => config.protocol_errors = map.next_value()?,
+                        "identifiers" => config.identifiers = map.next_value()?,
+                        other => return Err(serde::de::Error::unknown_field(other, CONFIG_FIELDS)),
+                    }
+                }
+                Ok(config)
+            }
+        }
+        deserializer.deserialize_struct("Config", CONFIG_FIELDS, Visitor)
+    }
+}
+
+/// Which names are valid, see [`Serializer::check_identifiers`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[non_exhaustive]
+pub enum IdentifierRule {
+    /// Any name is valid, and no checks are performed.
+    #[default]
+    Any,
+    /// Names must be non-empty, without whitespace or control characters.
+    NonBlank,
+    /// Names must be ASCII identifiers, like `_foo_1`.
+    Ascii,
+    /// Names must be Unicode identifiers: a letter or `_`, followed by
+    /// letters, digits or `_`.
+    Unicode,
+}
+
+const IDENTIFIER_RULES: &[&str] = &["any", "non_blank", "ascii", "unicode"];
+
+impl IdentifierRule {
+    /// Whether `name` is valid under this rule.
+    pub fn matches(self, name: &str) -> bool {
+        let ident = |start: fn(&char) -> bool, rest: fn(&char) -> bool| {
+            let mut chars = name.chars();
+            chars.next().is_some_and(|it| it == '_' || start(&it))
+                && chars.all(|it| it == '_' || rest(&it))
+        };
+        match self {
+            IdentifierRule::Any => true,
+            IdentifierRule::NonBlank => {
+                !name.is_empty() && !name.chars().any(|it| it.is_whitespace() || it.is_control())
+            }
+            IdentifierRule::Ascii => ident(char::is_ascii_alphabetic, char::is_ascii_alphanumeric),
+            IdentifierRule::Unicode => ident(|it| it.is_alphabetic(), |it| it.is_alphanumeric()),
+        }
+    }
+    fn as_str(self) -> &'static str {
+        match self {
+            IdentifierRule::Any => "any",
+            IdentifierRule::NonBlank => "non_blank",
+            IdentifierRule::Ascii => "ascii",
+            IdentifierRule::Unicode => "unicode",
+        }
+    }
+}
+
+/// Serialized as a lowercase string, e.g `"non_blank"`.
+impl serde::Serialize for IdentifierRule {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for IdentifierRule {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        [
+            IdentifierRule::Any,
+            IdentifierRule::NonBlank,
+            IdentifierRule::Ascii,
+            IdentifierRule::Unicode,
+        ]
+        .into_iter()
+        .find(|it| it.as_str() == s)
+        .ok_or_else(|| serde::de::Error::unknown_variant(&s, IDENTIFIER_RULES))
+    }
+}
+
+/// Errors for each of `names` which don't follow [`Config::identifiers`],
+/// with a description of what they name.
+fn check_identifiers(
+    config: &Config,
+    names: impl IntoIterator<Item = (&'static str, &'static str)>,
+) -> Vec<Error> {
+    names
+        .into_iter()
+        .filter(|(_, name)| !config.identifiers.matches(name) && !crate::raw::is_marker(name))
+        .map(|(what, name)| Error::new_protocol(ProtocolDetail::InvalidIdentifier { what, name }))
+        .collect()
+}
+
+/// [`ErrorDiscipline::handle`] the errors from [`check_identifiers`].
+///
+/// Nodes without anywhere to record them are saved as the equivalent struct or
+/// tuple node, with the errors as extra fields or elements, so that the rest
+/// of the node is kept.
+fn invalid_names<E: ErrorDiscipline>(
+    config: &Config,
+    names: impl IntoIterator<Item = (&'static str, &'static str)>,
+) -> Result<Vec<Save<'static, E::SaveError>>, Error> {
+    check_identifiers(config, names)
+        .into_iter()
+        .map(|e| E::handle(Err(e)))
+        .collect()
+}
+
+/// The names of `variant`, for [`check_identifiers`].
+fn variant_names(variant: &Variant<'static>) -> [(&'static str, &'static str); 2] {
+    [("enum", variant.name), ("variant", variant.variant)]
+}
+
+type Override = (Path<'static>, Arc<dyn Fn(Config) -> Config + Send + Sync>);
+
+struct Context<E = ShortCircuit> {
+    config: Config,
+    overrides: Option<Arc<Vec<Override>>>,
+    /// Only tracked if there are `overrides` or an `error_context`.
+    trail: Trail,
+    budget: Option<Budget>,
+    /// See [`Serializer::deadline`].
+    deadline: Option<Instant>,
+    /// See [`Serializer::pool`].
+    pool: Option<Arc<Pool>>,
+    /// See [`Serializer::error_context`].
+    error_context: bool,
+    /// The name of the struct or enum at the root, for [`Serializer::error_context`].
+    root: Option<&'static str>,
     _error_discipline: PhantomData<fn() -> E>,
 }
 
-impl<E> Clone for Config<E> {
+/// The [`Path`] to a node, sharing its ancestors' segments,
+/// so that each child only allocates its own.
+#[derive(Clone, Default)]
+struct Trail(Option<Arc<Step>>);
+
+struct Step {
+    parent: Trail,
+    segment: Segment<'static>,
+    depth: usize,
+}
+
+impl Trail {
+    fn join(&self, segment: Segment<'static>) -> Self {
+        Self(Some(Arc::new(Step {
+            parent: self.clone(),
+            segment,
+            depth: self.depth() + 1,
+        })))
+    }
+    fn is_root(&self) -> bool {
+        self.0.is_none()
+    }
+    fn depth(&self) -> usize {
+        self.0.as_ref().map_or(0, |it| it.depth)
+    }
+    /// The segments from this node back to the root.
+    fn rev(&self) -> impl Iterator<Item = &Segment<'static>> {
+        let mut next = self.0.as_deref();
+        core::iter::from_fn(move || {
+            let step = next?;
+            next = step.parent.0.as_deref();
+            Some(&step.segment)
+        })
+    }
+    fn leads_to(&self, path: &Path<'static>) -> bool {
+        self.depth() == path.len() && self.rev().eq(path.iter().rev())
+    }
+    fn to_path(&self) -> Path<'static> {
+        let mut segments = self.rev().cloned().collect::<Vec<_>>();
+        segments.reverse();
+        segments.into_iter().collect()
+    }
+}
+
+/// See [`Serializer::max_errors`].
+#[derive(Clone)]
+struct Budget {
+    max: usize,
+    halt: bool,
+    /// Shared across the whole tree.
+    count: Arc<AtomicUsize>,
+}
+
+impl<E> Clone for Context<E> {
     fn clone(&self) -> Self {
-        *self
+        Self {
+            config: self.config,
+            overrides: self.overrides.clone(),
+            trail: self.trail.clone(),
+            budget: self.budget.clone(),
+            deadline: self.deadline,
+            pool: self.pool.clone(),
+            error_context: self.error_context,
+            root: self.root,
+            _error_discipline: PhantomData,
+        }
+    }
+}
+
+impl<E> Context<E> {
+    /// Fail if the [deadline](Serializer::deadline) has passed.
+    fn check_deadline(&self) -> Result<(), Error> {
+        match self.deadline {
+            Some(deadline) if Instant::now() >= deadline => {
+                Err(Error::custom("deadline exceeded while serializing"))
+            }
+            _ => Ok(()),
+        }
+    }
+    /// Create a [`Serializer`] for the child of this node at `segment`.
+    fn child(&self, segment: Segment<'static>) -> Serializer<E> {
+        let mut ctx = self.clone();
+        if self.overrides.is_some() || self.error_context {
+            ctx.trail = self.trail.join(segment);
+        }
+        if let Some(overrides) = &self.overrides {
+            for (path, f) in overrides.iter() {
+                if ctx.trail.leads_to(path) {
+                    ctx.config = f(ctx.config)
+                }
+            }
+        }
+        Serializer { ctx }
+    }
+    /// Record `name` as the [root](Self::root), if this is the root.
+    fn named(&mut self, name: &'static str) {
+        if self.error_context && self.root.is_none() && self.trail.is_root() {
+            self.root = Some(name)
+        }
+    }
+}
+
+impl<E: ErrorDiscipline> Context<E> {
+    /// Serialize the contents of a wrapper node.
+    fn contents<T: ?Sized + serde::Serialize>(
+        self,
+        value: &T,
+    ) -> Result<Save<'static, E::SaveError>, Error> {
+        if let Err(e) = self.check_deadline() {
+            return self.handle(None, Err(e));
+        }
+        match self.error_context {
+            true => self
+                .clone()
+                .handle(None, value.serialize(Serializer { ctx: self })),
+            false => E::handle(value.serialize(Serializer { ctx: self })),
+        }
+    }
+    /// [`ErrorDiscipline::handle`] the result of serializing the child at `segment`,
+    /// or the contents of a wrapper node if [`None`].
+    fn handle(
+        &self,
+        segment: Option<Segment<'static>>,
+        res: Result<Save<'static, E::SaveError>, Error>,
+    ) -> Result<Save<'static, E::SaveError>, Error> {
+        match res {
+            Err(mut e) if self.error_context => {
+                let mut path = self.trail.to_path();
+                if let Some(segment) = segment {
+                    path.push(segment)
+                }
+                let location = match (self.root, path.first()) {
+                    (Some(root), None) => String::from(root),
+                    (Some(root), Some(Segment::Field(_))) => format!("{}.{}", root, path),
+                    (Some(root), Some(_)) => format!("{}{}", root, path),
+                    (None, _) => path.to_string(),
+                };
+                e.msg = format!("while serializing `{}`: {}", location, e.msg);
+                E::handle(Err(e))
+            }
+            res => E::handle(res),
+        }
+    }
+    /// Serialize an element of a sequence or tuple, returning [`None`] if it
+    /// should be omitted because the [`Budget`] is exhausted.
+    fn element<T: ?Sized + serde::Serialize>(
+        &self,
+        ix: usize,
+        value: &T,
+    ) -> Result<Option<Save<'static, E::SaveError>>, Error> {
+        let serialize = || {
+            self.check_deadline()
+                .and_then(|()| value.serialize(self.child(Segment::Index(ix))))
+        };
+        if let Some(Budget { max, halt, count }) = &self.budget {
+            if *halt && count.load(Ordering::Relaxed) >= *max {
+                return Ok(None);
+            }
+            let res = serialize();
+            if res.is_err() && count.fetch_add(1, Ordering::Relaxed) >= *max {
+                return Ok(None);
+            }
+            return self.handle(Some(Segment::Index(ix)), res).map(Some);
+        }
+        self.handle(Some(Segment::Index(ix)), serialize()).map(Some)
+    }
+    /// Record a summary of any elements omitted by [`Self::element`].
+    fn summarise(
+        &self,
+        omitted: usize,
+        elements: &mut Vec<Save<'static, E::SaveError>>,
+    ) -> Result<(), Error> {
+        if let (Some(Budget { max, .. }), 1..) = (&self.budget, omitted) {
+            let e = Error::custom(format_args!(
+                "{} more elements omitted after reaching the limit of {} errors",
+                omitted, max
+            ));
+            elements.push(E::handle(Err(e))?)
+        }
+        Ok(())
     }
 }
-impl<E> Copy for Config<E> {}
 
 macro_rules! simple {
     ($($method:ident($ty:ty) -> $variant:ident);* $(;)?) => {
@@ -135,7 +832,7 @@ where
     type SerializeStructVariant = SerializeStructVariant<E>;
 
     fn is_human_readable(&self) -> bool {
-        self.config.is_human_readable
+        self.ctx.config.is_human_readable
     }
 
     simple! {
@@ -144,23 +841,48 @@ where
         serialize_i16(i16) -> I16;
         serialize_i32(i32) -> I32;
         serialize_i64(i64) -> I64;
+        serialize_i128(i128) -> I128;
         serialize_u8(u8) -> U8;
         serialize_u16(u16) -> U16;
         serialize_u32(u32) -> U32;
         serialize_u64(u64) -> U64;
+        serialize_u128(u128) -> U128;
         serialize_f32(f32) -> F32;
         serialize_f64(f64) -> F64;
         serialize_char(char) -> Char;
     }
 
     fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
-        Ok(Save::String(v.into()))
+        match &self.ctx.pool {
+            Some(pool) => {
+                let mut it = pool.string();
+                it.push_str(v);
+                Ok(Save::String(it))
+            }
+            None => Ok(Save::String(v.into())),
+        }
     }
     fn collect_str<T: ?Sized + fmt::Display>(self, value: &T) -> Result<Self::Ok, Self::Error> {
-        Ok(Save::String(value.to_string()))
+        match &self.ctx.pool {
+            Some(pool) => {
+                use fmt::Write as _;
+                let mut it = pool.string();
+                write!(it, "{}", value)
+                    .expect("a Display implementation returned an error unexpectedly");
+                Ok(Save::String(it))
+            }
+            None => Ok(Save::String(value.to_string())),
+        }
     }
     fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, Self::Error> {
-        Ok(Save::ByteArray(v.into()))
+        match &self.ctx.pool {
+            Some(pool) => {
+                let mut it = pool.bytes();
+                it.extend_from_slice(v);
+                Ok(Save::ByteArray(it))
+            }
+            None => Ok(Save::ByteArray(v.into())),
+        }
     }
     fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
         Ok(Save::Option(None))
@@ -169,15 +891,20 @@ where
         self,
         value: &T,
     ) -> Result<Self::Ok, Self::Error> {
-        Ok(Save::Option(Some(Box::new(E::handle(
-            value.serialize(self),
-        )?))))
+        Ok(Save::Option(Some(Box::new(self.ctx.contents(value)?))))
     }
     fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
         Ok(Save::Unit)
     }
     fn serialize_unit_struct(self, name: &'static str) -> Result<Self::Ok, Self::Error> {
-        Ok(Save::UnitStruct(name))
+        let errors = invalid_names::<E>(&self.ctx.config, [("struct", name)])?;
+        if errors.is_empty() {
+            return Ok(Save::UnitStruct(name));
+        }
+        Ok(Save::Struct {
+            name,
+            fields: errors.into_iter().map(|it| ("!error", Some(it))).collect(),
+        })
     }
     fn serialize_unit_variant(
         self,
@@ -185,20 +912,39 @@ where
         variant_index: u32,
         variant: &'static str,
     ) -> Result<Self::Ok, Self::Error> {
-        Ok(Save::UnitVariant(Variant {
+        let variant = Variant {
             name,
             variant_index,
             variant,
-        }))
+        };
+        let errors = invalid_names::<E>(&self.ctx.config, variant_names(&variant))?;
+        if errors.is_empty() {
+            return Ok(Save::UnitVariant(variant));
+        }
+        Ok(Save::StructVariant {
+            variant,
+            fields: errors.into_iter().map(|it| ("!error", Some(it))).collect(),
+        })
     }
     fn serialize_newtype_struct<T: ?Sized + serde::Serialize>(
         self,
         name: &'static str,
         value: &T,
     ) -> Result<Self::Ok, Self::Error> {
-        Ok(Save::NewTypeStruct {
+        let mut errors = invalid_names::<E>(&self.ctx.config, [("struct", name)])?;
+        let mut ctx = self.ctx;
+        ctx.named(name);
+        let value = ctx.contents(value)?;
+        if errors.is_empty() {
+            return Ok(Save::NewTypeStruct {
+                name,
+                value: Box::new(value),
+            });
+        }
+        errors.insert(0, value);
+        Ok(Save::TupleStruct {
             name,
-            value: Box::new(E::handle(value.serialize(self))?),
+            values: errors,
         })
     }
     fn serialize_newtype_variant<T: ?Sized + serde::Serialize>(
@@ -208,25 +954,39 @@ where
         variant: &'static str,
         value: &T,
     ) -> Result<Self::Ok, Self::Error> {
-        Ok(Save::NewTypeVariant {
-            variant: Variant {
-                name,
-                variant_index,
+        let variant = Variant {
+            name,
+            variant_index,
+            variant,
+        };
+        let mut errors = invalid_names::<E>(&self.ctx.config, variant_names(&variant))?;
+        let mut ctx = self.ctx;
+        ctx.named(name);
+        let value = ctx.contents(value)?;
+        if errors.is_empty() {
+            return Ok(Save::NewTypeVariant {
                 variant,
-            },
-            value: Box::new(E::handle(value.serialize(self))?),
+                value: Box::new(value),
+            });
+        }
+        errors.insert(0, value);
+        Ok(Save::TupleVariant {
+            variant,
+            values: errors,
         })
     }
     fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
         Ok(SerializeSeq {
-            config: self.config,
+            ctx: self.ctx,
+            omitted: 0,
             inner: Vec::with_capacity(len.unwrap_or_default()),
             expected_len: len,
         })
     }
     fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
         Ok(SerializeTuple {
-            config: self.config,
+            ctx: self.ctx,
+            omitted: 0,
             inner: Vec::with_capacity(len),
             expected_len: len,
         })
@@ -236,9 +996,12 @@ where
         name: &'static str,
         len: usize,
     ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        let mut ctx = self.ctx;
+        ctx.named(name);
         Ok(SerializeTupleStruct {
             expected_len: len,
-            config: self.config,
+            ctx,
+            omitted: 0,
             name,
             values: Vec::with_capacity(len),
         })
@@ -250,9 +1013,12 @@ where
         variant: &'static str,
         len: usize,
     ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        let mut ctx = self.ctx;
+        ctx.named(name);
         Ok(SerializeTupleVariant {
             expected_len: len,
-            config: self.config,
+            ctx,
+            omitted: 0,
             variant: Variant {
                 name,
                 variant_index,
@@ -264,7 +1030,7 @@ where
     fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
         let capacity = len.unwrap_or_default();
         Ok(SerializeMap {
-            config: self.config,
+            ctx: self.ctx,
             expected_len: len,
             keys: Vec::with_capacity(capacity),
             values: Vec::with_capacity(capacity),
@@ -275,9 +1041,11 @@ where
         name: &'static str,
         len: usize,
     ) -> Result<Self::SerializeStruct, Self::Error> {
+        let mut ctx = self.ctx;
+        ctx.named(name);
         Ok(SerializeStruct {
             expected_len: len,
-            config: self.config,
+            ctx,
             name,
             fields: Vec::with_capacity(len),
         })
@@ -289,8 +1057,10 @@ where
         variant: &'static str,
         len: usize,
     ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        let mut ctx = self.ctx;
+        ctx.named(name);
         Ok(SerializeStructVariant {
-            config: self.config,
+            ctx,
             variant: Variant {
                 name,
                 variant_index,
@@ -303,24 +1073,23 @@ where
 }
 
 fn check_length<E>(
-    what: &str,
-    config: &Config<E>,
+    what: &'static str,
+    config: &Config,
     expected: usize,
+    omitted: usize,
     pushing: &mut Vec<Save<'static, E::SaveError>>,
 ) -> Result<(), Error>
 where
     E: ErrorDiscipline,
 {
     if config.protocol_errors {
-        let actual = pushing.len();
+        let actual = pushing.len() + omitted;
         if expected != actual {
-            let e = Error {
-                msg: format!(
-                    "protocol error: expected a {} of length {}, got {}",
-                    what, expected, actual
-                ),
-                protocol: true,
-            };
+            let e = Error::new_protocol(ProtocolDetail::Length {
+                what,
+                expected,
+                actual,
+            });
             pushing.push(E::handle(Err(e))?)
         }
     }
@@ -328,7 +1097,8 @@ where
 }
 
 pub struct SerializeSeq<E: ErrorDiscipline> {
-    config: Config<E>,
+    ctx: Context<E>,
+    omitted: usize,
     expected_len: Option<usize>,
     inner: Vec<Save<'static, E::SaveError>>,
 }
@@ -342,21 +1112,30 @@ where
         &mut self,
         value: &T,
     ) -> Result<(), Self::Error> {
-        self.inner.push(E::handle(value.serialize(Serializer {
-            config: self.config,
-        }))?);
+        match self.ctx.element(self.inner.len() + self.omitted, value)? {
+            Some(it) => self.inner.push(it),
+            None => self.omitted += 1,
+        }
         Ok(())
     }
     fn end(mut self) -> Result<Self::Ok, Self::Error> {
         if let Some(expected_len) = self.expected_len {
-            check_length("sequence", &self.config, expected_len, &mut self.inner)?;
+            check_length::<E>(
+                "sequence",
+                &self.ctx.config,
+                expected_len,
+                self.omitted,
+                &mut self.inner,
+            )?;
         }
+        self.ctx.summarise(self.omitted, &mut self.inner)?;
         Ok(Save::Seq(self.inner))
     }
 }
 pub struct SerializeTuple<E: ErrorDiscipline> {
     expected_len: usize,
-    config: Config<E>,
+    ctx: Context<E>,
+    omitted: usize,
     inner: Vec<Save<'static, E::SaveError>>,
 }
 impl<E> serde::ser::SerializeTuple for SerializeTuple<E>
@@ -369,19 +1148,28 @@ where
         &mut self,
         value: &T,
     ) -> Result<(), Self::Error> {
-        self.inner.push(E::handle(value.serialize(Serializer {
-            config: self.config,
-        }))?);
+        match self.ctx.element(self.inner.len() + self.omitted, value)? {
+            Some(it) => self.inner.push(it),
+            None => self.omitted += 1,
+        }
         Ok(())
     }
     fn end(mut self) -> Result<Self::Ok, Self::Error> {
-        check_length("tuple", &self.config, self.expected_len, &mut self.inner)?;
+        check_length::<E>(
+            "tuple",
+            &self.ctx.config,
+            self.expected_len,
+            self.omitted,
+            &mut self.inner,
+        )?;
+        self.ctx.summarise(self.omitted, &mut self.inner)?;
         Ok(Save::Tuple(self.inner))
     }
 }
 pub struct SerializeTupleStruct<E: ErrorDiscipline> {
     expected_len: usize,
-    config: Config<E>,
+    ctx: Context<E>,
+    omitted: usize,
     name: &'static str,
     values: Vec<Save<'static, E::SaveError>>,
 }
@@ -395,19 +1183,25 @@ where
         &mut self,
         value: &T,
     ) -> Result<(), Self::Error> {
-        self.values.push(E::handle(value.serialize(Serializer {
-            config: self.config,
-        }))?);
+        match self.ctx.element(self.values.len() + self.omitted, value)? {
+            Some(it) => self.values.push(it),
+            None => self.omitted += 1,
+        }
         Ok(())
     }
 
     fn end(mut self) -> Result<Self::Ok, Self::Error> {
-        check_length(
+        check_length::<E>(
             "tuple struct",
-            &self.config,
+            &self.ctx.config,
             self.expected_len,
+            self.omitted,
             &mut self.values,
         )?;
+        self.ctx.summarise(self.omitted, &mut self.values)?;
+        for e in check_identifiers(&self.ctx.config, [("struct", self.name)]) {
+            self.values.push(E::handle(Err(e))?)
+        }
         Ok(Save::TupleStruct {
             name: self.name,
             values: self.values,
@@ -416,7 +1210,8 @@ where
 }
 pub struct SerializeTupleVariant<E: ErrorDiscipline> {
     expected_len: usize,
-    config: Config<E>,
+    ctx: Context<E>,
+    omitted: usize,
     variant: Variant<'static>,
     values: Vec<Save<'static, E::SaveError>>,
 }
@@ -430,19 +1225,24 @@ where
         &mut self,
         value: &T,
     ) -> Result<(), Self::Error> {
-        self.values.push(E::handle(value.serialize(Serializer {
-            config: self.config,
-        }))?);
+        match self.ctx.element(self.values.len() + self.omitted, value)? {
+            Some(it) => self.values.push(it),
+            None => self.omitted += 1,
+        }
         Ok(())
     }
     fn end(mut self) -> Result<Self::Ok, Self::Error> {
-        check_length(
+        check_length::<E>(
             "tuple variant",
-            &self.config,
+            &self.ctx.config,
             self.expected_len,
+            self.omitted,
             &mut self.values,
         )?;
-
+        self.ctx.summarise(self.omitted, &mut self.values)?;
+        for e in check_identifiers(&self.ctx.config, variant_names(&self.variant)) {
+            self.values.push(E::handle(Err(e))?)
+        }
         Ok(Save::TupleVariant {
             variant: self.variant,
             values: self.values,
@@ -451,7 +1251,7 @@ where
 }
 pub struct SerializeMap<E: ErrorDiscipline> {
     expected_len: Option<usize>,
-    config: Config<E>,
+    ctx: Context<E>,
     keys: Vec<Save<'static, E::SaveError>>,
     values: Vec<Save<'static, E::SaveError>>,
 }
@@ -462,18 +1262,24 @@ where
     type Ok = Save<'static, E::SaveError>;
     type Error = Error;
     fn serialize_key<T: ?Sized + serde::Serialize>(&mut self, key: &T) -> Result<(), Self::Error> {
-        self.keys.push(E::handle(key.serialize(Serializer {
-            config: self.config,
-        }))?);
+        let segment = Segment::Key(self.keys.len());
+        let res = self
+            .ctx
+            .check_deadline()
+            .and_then(|()| key.serialize(self.ctx.child(segment.clone())));
+        self.keys.push(self.ctx.handle(Some(segment), res)?);
         Ok(())
     }
     fn serialize_value<T: ?Sized + serde::Serialize>(
         &mut self,
         value: &T,
     ) -> Result<(), Self::Error> {
-        self.values.push(E::handle(value.serialize(Serializer {
-            config: self.config,
-        }))?);
+        let segment = Segment::Index(self.values.len());
+        let res = self
+            .ctx
+            .check_deadline()
+            .and_then(|()| value.serialize(self.ctx.child(segment.clone())));
+        self.values.push(self.ctx.handle(Some(segment), res)?);
         Ok(())
     }
     fn end(self) -> Result<Self::Ok, Self::Error> {
@@ -483,24 +1289,22 @@ where
         let mut keys = self.keys.into_iter();
         let mut values = self.values.into_iter();
         loop {
-            let e = || Error {
-                msg: format!(
-                    "protocol error: map has {} keys and {} values",
-                    n_keys, n_values
-                ),
-                protocol: true,
+            let e = || {
+                Error::new_protocol(ProtocolDetail::JaggedMap {
+                    keys: n_keys,
+                    values: n_values,
+                })
             };
             match (keys.next(), values.next()) {
                 (None, None) => {
                     if let Some(expected) = self.expected_len {
-                        if self.config.protocol_errors && expected != map.len() {
-                            let e = || Error {
-                                msg: format!(
-                                    "protocol error: expected a map of length {}, got {}",
+                        if self.ctx.config.protocol_errors && expected != map.len() {
+                            let e = || {
+                                Error::new_protocol(ProtocolDetail::Length {
+                                    what: "map",
                                     expected,
-                                    map.len()
-                                ),
-                                protocol: true,
+                                    actual: map.len(),
+                                })
                             };
                             map.push((E::handle(Err(e()))?, E::handle(Err(e()))?))
                         }
@@ -516,14 +1320,18 @@ where
 }
 
 fn check<E>(
-    what: &str,
-    config: &Config<E>,
+    what: &'static str,
+    config: &Config,
+    names: impl IntoIterator<Item = (&'static str, &'static str)>,
     expected_len: usize,
     fields: &mut Vec<(&'static str, Option<Save<'static, E::SaveError>>)>,
 ) -> Result<(), Error>
 where
     E: ErrorDiscipline,
 {
+    // Before any errors are pushed.
+    let field_names = fields.iter().map(|(it, _)| ("field", *it));
+    let invalid = check_identifiers(config, names.into_iter().chain(field_names));
     if config.protocol_errors {
         let mut seen = BTreeSet::new();
         let mut dups = Vec::new();
@@ -534,35 +1342,29 @@ where
             }
         }
         if !dups.is_empty() {
-            let e = Error {
-                msg: format!(
-                    "protocol error: {} has duplicate field names: {}",
-                    what,
-                    dups.join(", ")
-                ),
-                protocol: true,
-            };
+            let e = Error::new_protocol(ProtocolDetail::DuplicateFields { what, fields: dups });
             fields.push(("!error", Some(E::handle(Err(e))?)))
         }
 
         let actual = fields.len();
         if expected_len != actual {
-            let e = Error {
-                msg: format!(
-                    "protocol error: expected a {} of length {}, got {}",
-                    what, expected_len, actual
-                ),
-                protocol: true,
-            };
+            let e = Error::new_protocol(ProtocolDetail::Length {
+                what,
+                expected: expected_len,
+                actual,
+            });
             fields.push(("!error", Some(E::handle(Err(e))?)))
         }
     }
+    for e in invalid {
+        fields.push(("!error", Some(E::handle(Err(e))?)))
+    }
     Ok(())
 }
 
 pub struct SerializeStruct<E: ErrorDiscipline> {
     expected_len: usize,
-    config: Config<E>,
+    ctx: Context<E>,
     name: &'static str,
     fields: Vec<(&'static str, Option<Save<'static, E::SaveError>>)>,
 }
@@ -577,16 +1379,23 @@ where
         key: &'static str,
         value: &T,
     ) -> Result<(), Self::Error> {
-        self.fields.push((
-            key,
-            Some(E::handle(value.serialize(Serializer {
-                config: self.config,
-            }))?),
-        ));
+        let segment = Segment::Field(key.into());
+        let res = self
+            .ctx
+            .check_deadline()
+            .and_then(|()| value.serialize(self.ctx.child(segment.clone())));
+        self.fields
+            .push((key, Some(self.ctx.handle(Some(segment), res)?)));
         Ok(())
     }
     fn end(mut self) -> Result<Self::Ok, Self::Error> {
-        check("struct", &self.config, self.expected_len, &mut self.fields)?;
+        check::<E>(
+            "struct",
+            &self.ctx.config,
+            [("struct", self.name)],
+            self.expected_len,
+            &mut self.fields,
+        )?;
         Ok(Save::Struct {
             name: self.name,
             fields: self.fields,
@@ -599,7 +1408,7 @@ where
 }
 pub struct SerializeStructVariant<E: ErrorDiscipline> {
     expected_len: usize,
-    config: Config<E>,
+    ctx: Context<E>,
     variant: Variant<'static>,
     fields: Vec<(&'static str, Option<Save<'static, E::SaveError>>)>,
 }
@@ -614,16 +1423,23 @@ where
         key: &'static str,
         value: &T,
     ) -> Result<(), Self::Error> {
-        self.fields.push((
-            key,
-            Some(E::handle(value.serialize(Serializer {
-                config: self.config,
-            }))?),
-        ));
+        let segment = Segment::Field(key.into());
+        let res = self
+            .ctx
+            .check_deadline()
+            .and_then(|()| value.serialize(self.ctx.child(segment.clone())));
+        self.fields
+            .push((key, Some(self.ctx.handle(Some(segment), res)?)));
         Ok(())
     }
     fn end(mut self) -> Result<Self::Ok, Self::Error> {
-        check("struct", &self.config, self.expected_len, &mut self.fields)?;
+        check::<E>(
+            "struct",
+            &self.ctx.config,
+            variant_names(&self.variant),
+            self.expected_len,
+            &mut self.fields,
+        )?;
 
         Ok(Save::StructVariant {
             variant: self.variant,
@@ -0,0 +1,790 @@
+use crate::{pretty::Base64, Change, Diff, Save, Segment};
+use core::{borrow::Borrow, cmp::Reverse, fmt};
+use std::{borrow::Cow, io};
+
+impl<E: fmt::Display> Save<'_, E> {
+    /// Render this tree as compact JSON, as `serde_json` would write the
+    /// captured value.
+    ///
+    /// - Structs and maps are objects, and sequences and tuples are arrays.
+    ///   [Skipped](serde::ser::SerializeStruct::skip_field) fields are omitted.
+    /// - Enums are externally tagged, so unit variants are strings,
+    ///   and other variants are objects with a single key.
+    /// - [`None`], units and NaN or infinite floats are `null`.
+    /// - Byte arrays are arrays of numbers.
+    /// - Map keys which aren't strings are written as their JSON text.
+    /// - [`Save::Error`]s are their message.
+    ///
+    /// ```
+    /// # use serde_save::save;
+    /// #[derive(serde::Serialize)]
+    /// struct Event {
+    ///     id: u32,
+    ///     kind: Kind,
+    ///     tags: Vec<&'static str>,
+    ///     note: Option<&'static str>,
+    /// }
+    /// #[derive(serde::Serialize)]
+    /// enum Kind {
+    ///     Click { x: i32, y: i32 },
+    /// }
+    ///
+    /// let saved = save(Event {
+    ///     id: 7,
+    ///     kind: Kind::Click { x: 1, y: -2 },
+    ///     tags: vec!["a\"b"],
+    ///     note: None,
+    /// })
+    /// .unwrap();
+    /// assert_eq!(
+    ///     saved.to_json(),
+    ///     r#"{"id":7,"kind":{"Click":{"x":1,"y":-2}},"tags":["a\"b"],"note":null}"#
+    /// );
+    /// ```
+    pub fn to_json(&self) -> String {
+        let mut out = String::new();
+        write(&mut out, self);
+        out
+    }
+}
+
+impl Save<'static> {
+    /// Read a tree from a JSON document.
+    ///
+    /// Since JSON has no names, objects become [`Save::Map`]s with string keys,
+    /// `null` becomes [`Save::Unit`],
+    /// and numbers become [`Save::U64`], [`Save::I64`] or [`Save::F64`],
+    /// as `serde_json::Value` would.
+    ///
+    /// Values nested more than 128 deep are rejected.
+    ///
+    /// ```
+    /// # use serde_save::Save;
+    /// assert_eq!(
+    ///     Save::from_json(r#"{"id": 7, "tags": ["a", -1, 0.5, null]}"#).unwrap(),
+    ///     Save::Map(vec![
+    ///         (Save::string("id"), Save::U64(7)),
+    ///         (
+    ///             Save::string("tags"),
+    ///             Save::Seq(vec![Save::string("a"), Save::I64(-1), Save::F64(0.5), Save::Unit]),
+    ///         ),
+    ///     ])
+    /// );
+    /// assert_eq!(
+    ///     Save::from_json("[1,]").unwrap_err().to_string(),
+    ///     "invalid JSON at offset 3: expected a value"
+    /// );
+    /// ```
+    pub fn from_json(json: &str) -> Result<Self, ParseJsonError> {
+        let mut parser = Parser {
+            s: json,
+            offset: 0,
+            depth: 0,
+        };
+        let save = parser.value()?;
+        parser.skip_whitespace();
+        match parser.offset == json.len() {
+            true => Ok(save),
+            false => Err(parser.fail("trailing characters")),
+        }
+    }
+}
+
+/// Write each tree as a line of [JSON](Save::to_json),
+/// so that batches of captures can be appended to a log file,
+/// and processed with standard tooling.
+///
+/// Read them back with [`read_jsonl`].
+///
+/// ```
+/// # use serde_save::{read_jsonl, save, write_jsonl, Save};
+/// let mut log = Vec::new();
+/// write_jsonl(&mut log, [save(1).unwrap(), save(("a", true)).unwrap()]).unwrap();
+/// write_jsonl(&mut log, &[save([2]).unwrap()]).unwrap();
+/// assert_eq!(String::from_utf8(log.clone()).unwrap(), "1\n[\"a\",true]\n[2]\n");
+///
+/// let read = read_jsonl(&log[..]).collect::<Result<Vec<_>, _>>().unwrap();
+/// assert_eq!(
+///     read,
+///     [
+///         Save::U64(1),
+///         Save::Seq(vec![Save::string("a"), Save::Bool(true)]),
+///         Save::Seq(vec![Save::U64(2)]),
+///     ]
+/// );
+/// ```
+pub fn write_jsonl<'a, E, S>(
+    mut writer: impl io::Write,
+    saves: impl IntoIterator<Item = S>,
+) -> io::Result<()>
+where
+    E: fmt::Display + 'a,
+    S: Borrow<Save<'a, E>>,
+{
+    let mut line = String::new();
+    for save in saves {
+        line.clear();
+        write(&mut line, save.borrow());
+        line.push('\n');
+        writer.write_all(line.as_bytes())?
+    }
+    Ok(())
+}
+
+/// Read a tree from each non-blank line of JSON,
+/// as written by [`write_jsonl`].
+///
+/// See [`Save::from_json`] for how JSON is read.
+/// Lines which fail to parse are [`io::ErrorKind::InvalidData`] errors,
+/// with a [`ParseJsonError`] as their source.
+pub fn read_jsonl(reader: impl io::BufRead) -> impl Iterator<Item = io::Result<Save<'static>>> {
+    reader
+        .lines()
+        .filter(|it| !matches!(it, Ok(line) if line.trim().is_empty()))
+        .map(|line| {
+            Save::from_json(&line?).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+        })
+}
+
+impl<'a, E: fmt::Display + PartialEq> Diff<'a, E> {
+    /// Render this diff as a [JSON Patch](https://www.rfc-editor.org/rfc/rfc6902)
+    /// document, which turns the [JSON](Save::to_json) of `before`
+    /// (the left hand side of the diff) into the JSON of the right hand side.
+    ///
+    /// JSON pointers name map keys and enum variants, which a [`Path`](crate::Path)
+    /// doesn't, so they are looked up in `before`.
+    ///
+    /// Operations are ordered so that they may be applied one after the other:
+    /// changes within a container come before elements are removed from it,
+    /// in reverse, and then added to it.
+    /// [Moved](Change::Moved) elements are removed and added again,
+    /// and [renamed](Change::Renamed) fields are `move`s.
+    ///
+    /// ```
+    /// # use serde_save::save;
+    /// # use std::collections::BTreeMap;
+    /// #[derive(serde::Serialize)]
+    /// struct Config {
+    ///     name: &'static str,
+    ///     tags: Vec<&'static str>,
+    ///     limits: BTreeMap<&'static str, u32>,
+    /// }
+    ///
+    /// let before = save(Config {
+    ///     name: "a",
+    ///     tags: vec!["x", "y", "z"],
+    ///     limits: BTreeMap::from([("cpu", 1)]),
+    /// })
+    /// .unwrap();
+    /// let after = save(Config {
+    ///     name: "b/c",
+    ///     tags: vec!["y"],
+    ///     limits: BTreeMap::from([("cpu", 2), ("mem/max", 4)]),
+    /// })
+    /// .unwrap();
+    /// assert_eq!(
+    ///     before.diff(&after).to_json_patch(&before),
+    ///     concat!(
+    ///         r#"[{"op":"replace","path":"/tags/0","value":"y"},"#,
+    ///         r#"{"op":"replace","path":"/limits/cpu","value":2},"#,
+    ///         r#"{"op":"remove","path":"/tags/2"},"#,
+    ///         r#"{"op":"remove","path":"/tags/1"},"#,
+    ///         r#"{"op":"add","path":"/limits/mem~1max","value":4},"#,
+    ///         r#"{"op":"replace","path":"/name","value":"b/c"}]"#,
+    ///     )
+    /// );
+    /// ```
+    ///
+    /// # Panics
+    /// - If this diff was not computed from `before`.
+    pub fn to_json_patch(&self, before: &Save<'a, E>) -> String {
+        const BAD_TREE: &str = "`before` is not the left hand side of this diff";
+        // Removals and additions shift later elements, so they happen after
+        // every change nested in the container.
+        let mut ops = Vec::new();
+        let mut push = |path: &[Segment<'_>], rank, op| {
+            let ix = match path.last() {
+                Some(Segment::Index(ix)) => *ix,
+                _ => 0,
+            };
+            let ix = match rank {
+                1 => usize::MAX - ix,
+                _ => ix,
+            };
+            ops.push(((Reverse(path.len()), rank, ix), op))
+        };
+        for change in self {
+            match change {
+                Change::Added { path, value } => {
+                    let op = add(before, path, value).expect(BAD_TREE);
+                    push(path, 2, op)
+                }
+                Change::Removed { path, .. } => {
+                    let op = remove(before, path).expect(BAD_TREE);
+                    push(path, 1, op)
+                }
+                Change::Changed {
+                    path,
+                    before: target,
+                    after,
+                } => {
+                    let mut pointer = String::new();
+                    let mut node = resolve(&mut pointer, before, path).expect(BAD_TREE);
+                    while node != target {
+                        match inner(&mut pointer, node) {
+                            Some(it) => node = it,
+                            None => break,
+                        }
+                    }
+                    let mut op = String::from(r#"{"op":"replace","path":"#);
+                    string(&mut op, &pointer);
+                    op.push_str(r#","value":"#);
+                    write(&mut op, after);
+                    op.push('}');
+                    push(path, 0, op)
+                }
+                Change::Renamed { path, to } => {
+                    let (from, to) = (|| {
+                        let (last, parent) = path.split_last()?;
+                        let mut pointer = String::new();
+                        let container = container(&mut pointer, before, parent)?;
+                        let mut to_pointer = pointer.clone();
+                        step(&mut pointer, container, last)?;
+                        token(&mut to_pointer, to);
+                        Some((pointer, to_pointer))
+                    })()
+                    .expect(BAD_TREE);
+                    let mut op = String::from(r#"{"op":"move","from":"#);
+                    string(&mut op, &from);
+                    op.push_str(r#","path":"#);
+                    string(&mut op, &to);
+                    op.push('}');
+                    push(path, 0, op)
+                }
+                Change::Moved { path, to } => {
+                    let value = before.get(path).expect(BAD_TREE);
+                    push(path, 1, remove(before, path).expect(BAD_TREE));
+                    push(to, 2, add(before, to, value).expect(BAD_TREE))
+                }
+            }
+        }
+        ops.sort_by_key(|(key, _)| *key);
+        let mut out = String::from("[");
+        for (ix, (_, op)) in ops.iter().enumerate() {
+            if ix != 0 {
+                out.push(',')
+            }
+            out.push_str(op)
+        }
+        out.push(']');
+        out
+    }
+}
+
+/// An `add` operation for a node which is only present in the right hand side.
+///
+/// Only the last segment of `path` refers to the right hand side.
+fn add<E: fmt::Display>(
+    before: &Save<'_, E>,
+    path: &[Segment<'_>],
+    value: &Save<'_, E>,
+) -> Option<String> {
+    let mut pointer = String::new();
+    let value = match path.split_last() {
+        None => value,
+        Some((last, parent)) => {
+            let container = container(&mut pointer, before, parent)?;
+            match (container, last, value) {
+                (Save::Map(_), Segment::Index(_), Save::Tuple(entry)) => {
+                    let [k, v] = entry.as_slice() else {
+                        return None;
+                    };
+                    token(&mut pointer, &key_text(k));
+                    v
+                }
+                (_, Segment::Index(ix), _) => {
+                    token(&mut pointer, &ix.to_string());
+                    value
+                }
+                (_, Segment::Field(name), _) => {
+                    token(&mut pointer, name);
+                    value
+                }
+                (_, Segment::Key(_), _) => return None,
+            }
+        }
+    };
+    let mut op = String::from(r#"{"op":"add","path":"#);
+    string(&mut op, &pointer);
+    op.push_str(r#","value":"#);
+    write(&mut op, value);
+    op.push('}');
+    Some(op)
+}
+
+/// A `remove` operation for a node which is only present in the left hand side.
+fn remove<E: fmt::Display>(before: &Save<'_, E>, path: &[Segment<'_>]) -> Option<String> {
+    let mut pointer = String::new();
+    resolve(&mut pointer, before, path)?;
+    let mut op = String::from(r#"{"op":"remove","path":"#);
+    string(&mut op, &pointer);
+    op.push('}');
+    Some(op)
+}
+
+/// Append the JSON pointer of the node at `path` in `tree` to `pointer`,
+/// returning the outermost node there.
+fn resolve<'s, 'a, E: fmt::Display>(
+    pointer: &mut String,
+    tree: &'s Save<'a, E>,
+    path: &[Segment<'_>],
+) -> Option<&'s Save<'a, E>> {
+    path.iter().try_fold(tree, |node, segment| {
+        let node = unwrap(pointer, node);
+        step(pointer, node, segment)
+    })
+}
+
+/// Like [`resolve`], but through any wrappers of the container at `path`.
+fn container<'s, 'a, E: fmt::Display>(
+    pointer: &mut String,
+    tree: &'s Save<'a, E>,
+    path: &[Segment<'_>],
+) -> Option<&'s Save<'a, E>> {
+    let node = resolve(pointer, tree, path)?;
+    Some(unwrap(pointer, node))
+}
+
+/// Step from a container to one of its children,
+/// through the tag of an enum variant.
+fn step<'s, 'a, E: fmt::Display>(
+    pointer: &mut String,
+    node: &'s Save<'a, E>,
+    segment: &Segment<'_>,
+) -> Option<&'s Save<'a, E>> {
+    if let Save::TupleVariant { variant, .. } | Save::StructVariant { variant, .. } = node {
+        token(pointer, variant.variant)
+    }
+    match (node, segment) {
+        (
+            Save::Seq(it)
+            | Save::Tuple(it)
+            | Save::TupleStruct { values: it, .. }
+            | Save::TupleVariant { values: it, .. },
+            Segment::Index(ix),
+        ) => {
+            let child = it.get(*ix)?;
+            token(pointer, &ix.to_string());
+            Some(child)
+        }
+        (
+            Save::Struct { fields, .. } | Save::StructVariant { fields, .. },
+            Segment::Field(name),
+        ) => {
+            let (_, child) = fields.iter().find(|(it, _)| it == name)?;
+            token(pointer, name);
+            child.as_ref()
+        }
+        (Save::Map(it), Segment::Index(ix)) => {
+            let (k, v) = it.get(*ix)?;
+            token(pointer, &key_text(k));
+            Some(v)
+        }
+        _ => None,
+    }
+}
+
+/// Step through every wrapper node, as [`inner`].
+fn unwrap<'s, 'a, E>(pointer: &mut String, mut node: &'s Save<'a, E>) -> &'s Save<'a, E> {
+    while let Some(it) = inner(pointer, node) {
+        node = it
+    }
+    node
+}
+
+/// Step into a wrapper node, which is transparent in JSON,
+/// except for [`Save::NewTypeVariant`]s, which are externally tagged.
+fn inner<'s, 'a, E>(pointer: &mut String, node: &'s Save<'a, E>) -> Option<&'s Save<'a, E>> {
+    match node {
+        Save::Option(Some(it)) | Save::NewTypeStruct { value: it, .. } => Some(it),
+        Save::NewTypeVariant { variant, value } => {
+            token(pointer, variant.variant);
+            Some(value)
+        }
+        _ => None,
+    }
+}
+
+/// Append a reference token to a JSON pointer.
+fn token(pointer: &mut String, name: &str) {
+    pointer.push('/');
+    for c in name.chars() {
+        match c {
+            '~' => pointer.push_str("~0"),
+            '/' => pointer.push_str("~1"),
+            c => pointer.push(c),
+        }
+    }
+}
+
+fn write<E: fmt::Display>(out: &mut String, save: &Save<'_, E>) {
+    let elements = |out: &mut String, it: &[Save<'_, E>]| {
+        out.push('[');
+        for (ix, it) in it.iter().enumerate() {
+            if ix != 0 {
+                out.push(',')
+            }
+            write(out, it)
+        }
+        out.push(']');
+    };
+    let fields = |out: &mut String, it: &[(&str, Option<Save<'_, E>>)]| {
+        out.push('{');
+        let present = it
+            .iter()
+            .filter_map(|(name, it)| Some((name, it.as_ref()?)));
+        for (ix, (name, it)) in present.enumerate() {
+            if ix != 0 {
+                out.push(',')
+            }
+            string(out, name);
+            out.push(':');
+            write(out, it)
+        }
+        out.push('}');
+    };
+    let tagged = |out: &mut String, variant: &str| {
+        out.push('{');
+        string(out, variant);
+        out.push(':');
+    };
+    match save {
+        Save::Bool(it) => out.push_str(if *it { "true" } else { "false" }),
+        Save::I8(it) => out.push_str(&it.to_string()),
+        Save::I16(it) => out.push_str(&it.to_string()),
+        Save::I32(it) => out.push_str(&it.to_string()),
+        Save::I64(it) => out.push_str(&it.to_string()),
+        Save::I128(it) => out.push_str(&it.to_string()),
+        Save::U8(it) => out.push_str(&it.to_string()),
+        Save::U16(it) => out.push_str(&it.to_string()),
+        Save::U32(it) => out.push_str(&it.to_string()),
+        Save::U64(it) => out.push_str(&it.to_string()),
+        Save::U128(it) => out.push_str(&it.to_string()),
+        // `Debug` always includes a `.` or exponent, like `serde_json`.
+        Save::F32(it) if it.is_finite() => out.push_str(&format!("{:?}", it)),
+        Save::F64(it) if it.is_finite() => out.push_str(&format!("{:?}", it)),
+        Save::F32(_) | Save::F64(_) | Save::Option(None) | Save::Unit | Save::UnitStruct(_) => {
+            out.push_str("null")
+        }
+        Save::Char(it) => string(out, it.encode_utf8(&mut [0; 4])),
+        Save::String(it) => string(out, it),
+        Save::ByteArray(it) => {
+            out.push('[');
+            for (ix, it) in it.iter().enumerate() {
+                if ix != 0 {
+                    out.push(',')
+                }
+                out.push_str(&it.to_string())
+            }
+            out.push(']');
+        }
+        Save::Option(Some(it)) | Save::NewTypeStruct { value: it, .. } => write(out, it),
+        Save::UnitVariant(it) => string(out, it.variant),
+        Save::NewTypeVariant { variant, value } => {
+            tagged(out, variant.variant);
+            write(out, value);
+            out.push('}')
+        }
+        Save::Seq(it) | Save::Tuple(it) | Save::TupleStruct { values: it, .. } => elements(out, it),
+        Save::TupleVariant { variant, values } => {
+            tagged(out, variant.variant);
+            elements(out, values);
+            out.push('}')
+        }
+        Save::Map(it) => {
+            out.push('{');
+            for (ix, (k, v)) in it.iter().enumerate() {
+                if ix != 0 {
+                    out.push(',')
+                }
+                key(out, k);
+                out.push(':');
+                write(out, v)
+            }
+            out.push('}')
+        }
+        Save::Struct { fields: it, .. } => fields(out, it),
+        Save::StructVariant {
+            variant,
+            fields: it,
+        } => {
+            tagged(out, variant.variant);
+            fields(out, it);
+            out.push('}')
+        }
+        Save::Error(e) => string(out, &e.to_string()),
+    }
+}
+
+/// Write a map key, which must be a string in JSON.
+fn key<E: fmt::Display>(out: &mut String, save: &Save<'_, E>) {
+    string(out, &key_text(save))
+}
+
+/// The unescaped text of a map key.
+fn key_text<'s, E: fmt::Display>(mut save: &'s Save<'_, E>) -> Cow<'s, str> {
+    while let Save::Option(Some(it)) | Save::NewTypeStruct { value: it, .. } = save {
+        save = it
+    }
+    match save {
+        Save::String(it) => Cow::Borrowed(it),
+        Save::Char(it) => Cow::Owned(it.to_string()),
+        Save::UnitVariant(it) => Cow::Borrowed(it.variant),
+        Save::Error(e) => Cow::Owned(e.to_string()),
+        Save::ByteArray(it) => Cow::Owned(Base64(it).to_string()),
+        it => {
+            let mut text = String::new();
+            write(&mut text, it);
+            Cow::Owned(text)
+        }
+    }
+}
+
+fn string(out: &mut String, s: &str) {
+    use fmt::Write as _;
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            '\u{8}' => out.push_str("\\b"),
+            '\u{c}' => out.push_str("\\f"),
+            c if c < ' ' => {
+                let _ = write!(out, "\\u{:04x}", u32::from(c));
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// An error returned from [`Save::from_json`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseJsonError {
+    offset: usize,
+    msg: &'static str,
+}
+
+impl fmt::Display for ParseJsonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid JSON at offset {}: {}", self.offset, self.msg)
+    }
+}
+
+impl std::error::Error for ParseJsonError {}
+
+/// The deepest nesting of values which will be parsed.
+const MAX_DEPTH: usize = 128;
+
+struct Parser<'s> {
+    s: &'s str,
+    offset: usize,
+    /// The number of arrays and objects currently being parsed, see [`MAX_DEPTH`].
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn fail(&self, msg: &'static str) -> ParseJsonError {
+        ParseJsonError {
+            offset: self.offset,
+            msg,
+        }
+    }
+    fn rest(&self) -> &str {
+        &self.s[self.offset..]
+    }
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        let trimmed = rest.trim_start_matches([' ', '\t', '\n', '\r']);
+        self.offset += rest.len() - trimmed.len();
+    }
+    fn eat(&mut self, token: &str) -> bool {
+        self.skip_whitespace();
+        match self.rest().starts_with(token) {
+            true => {
+                self.offset += token.len();
+                true
+            }
+            false => false,
+        }
+    }
+    fn value(&mut self) -> Result<Save<'static>, ParseJsonError> {
+        self.skip_whitespace();
+        match self.rest().as_bytes().first().copied() {
+            Some(b'{' | b'[') => {
+                if self.depth == MAX_DEPTH {
+                    return Err(self.fail("nesting too deep"));
+                }
+                self.depth += 1;
+                let it = self.collection();
+                self.depth -= 1;
+                it
+            }
+            Some(b'"') => self.string().map(Save::String),
+            Some(b'-' | b'0'..=b'9') => self.number(),
+            _ if self.eat("true") => Ok(Save::Bool(true)),
+            _ if self.eat("false") => Ok(Save::Bool(false)),
+            _ if self.eat("null") => Ok(Save::Unit),
+            _ => Err(self.fail("expected a value")),
+        }
+    }
+    /// An array or object.
+    fn collection(&mut self) -> Result<Save<'static>, ParseJsonError> {
+        let is_object = self.eat("{");
+        if !is_object {
+            self.eat("[");
+        }
+        let close = if is_object { "}" } else { "]" };
+        let mut elements = Vec::new();
+        let mut entries = Vec::new();
+        if !self.eat(close) {
+            loop {
+                match is_object {
+                    true => {
+                        self.skip_whitespace();
+                        if !self.rest().starts_with('"') {
+                            return Err(self.fail("expected a string key"));
+                        }
+                        let key = self.string()?;
+                        if !self.eat(":") {
+                            return Err(self.fail("expected `:`"));
+                        }
+                        entries.push((Save::String(key), self.value()?))
+                    }
+                    false => elements.push(self.value()?),
+                }
+                if self.eat(close) {
+                    break;
+                }
+                if !self.eat(",") {
+                    return Err(self.fail("expected `,` or a closing delimiter"));
+                }
+            }
+        }
+        Ok(match is_object {
+            true => Save::Map(entries),
+            false => Save::Seq(elements),
+        })
+    }
+    fn string(&mut self) -> Result<String, ParseJsonError> {
+        self.offset += 1;
+        let mut out = String::new();
+        loop {
+            let rest = self.rest();
+            let Some(ix) = rest.find(|it: char| it == '"' || it == '\\' || it < ' ') else {
+                return Err(self.fail("unterminated string"));
+            };
+            out.push_str(&rest[..ix]);
+            self.offset += ix;
+            match self.rest().as_bytes()[0] {
+                b'"' => {
+                    self.offset += 1;
+                    return Ok(out);
+                }
+                b'\\' => {}
+                _ => return Err(self.fail("control character in string")),
+            }
+            let c = match self.rest().as_bytes().get(1) {
+                Some(b'"') => '"',
+                Some(b'\\') => '\\',
+                Some(b'/') => '/',
+                Some(b'b') => '\u{8}',
+                Some(b'f') => '\u{c}',
+                Some(b'n') => '\n',
+                Some(b'r') => '\r',
+                Some(b't') => '\t',
+                Some(b'u') => {
+                    let start = self.offset;
+                    let high = self.hex(start + 2)?;
+                    let c = match high {
+                        0xD800..=0xDBFF => {
+                            let low = match self.s.get(start + 6..start + 8) {
+                                Some("\\u") => self.hex(start + 8)?,
+                                _ => return Err(self.fail("unpaired surrogate")),
+                            };
+                            if !(0xDC00..=0xDFFF).contains(&low) {
+                                return Err(self.fail("unpaired surrogate"));
+                            }
+                            self.offset += 6;
+                            0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+                        }
+                        it => it,
+                    };
+                    self.offset += 4;
+                    char::from_u32(c).ok_or_else(|| self.fail("unpaired surrogate"))?
+                }
+                _ => return Err(self.fail("invalid escape")),
+            };
+            out.push(c);
+            self.offset += 2;
+        }
+    }
+    /// Four hex digits at `offset`.
+    fn hex(&self, offset: usize) -> Result<u32, ParseJsonError> {
+        self.s
+            .get(offset..offset + 4)
+            .filter(|it| it.bytes().all(|it| it.is_ascii_hexdigit()))
+            .and_then(|it| u32::from_str_radix(it, 16).ok())
+            .ok_or_else(|| self.fail("invalid unicode escape"))
+    }
+    fn number(&mut self) -> Result<Save<'static>, ParseJsonError> {
+        let rest = self.rest();
+        let bytes = rest.as_bytes();
+        let digits = |mut ix: usize| {
+            let start = ix;
+            while bytes.get(ix).is_some_and(u8::is_ascii_digit) {
+                ix += 1
+            }
+            (ix != start).then_some(ix)
+        };
+        let mut end = usize::from(bytes[0] == b'-');
+        let int_start = end;
+        end = digits(end).ok_or_else(|| self.fail("invalid number"))?;
+        if bytes[int_start] == b'0' && end - int_start > 1 {
+            return Err(self.fail("invalid number"));
+        }
+        let mut is_float = false;
+        if bytes.get(end) == Some(&b'.') {
+            is_float = true;
+            end = digits(end + 1).ok_or_else(|| self.fail("invalid number"))?;
+        }
+        if let Some(b'e' | b'E') = bytes.get(end) {
+            is_float = true;
+            end += 1;
+            if let Some(b'+' | b'-') = bytes.get(end) {
+                end += 1
+            }
+            end = digits(end).ok_or_else(|| self.fail("invalid number"))?;
+        }
+        let text = &rest[..end];
+        let save = match (is_float, text.starts_with('-')) {
+            (false, false) => text.parse().map(Save::U64).ok(),
+            (false, true) => text.parse().map(Save::I64).ok(),
+            (true, _) => None,
+        };
+        let save = match save {
+            Some(it) => it,
+            None => Save::F64(
+                text.parse()
+                    .ok()
+                    .filter(|it: &f64| it.is_finite())
+                    .ok_or_else(|| self.fail("number out of range"))?,
+            ),
+        };
+        self.offset += end;
+        Ok(save)
+    }
+}
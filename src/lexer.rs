@@ -0,0 +1,159 @@
+/// A syntax error at a byte offset in the input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct LexError {
+    pub offset: usize,
+    pub msg: &'static str,
+}
+
+/// Shared tokenizing for the textual formats in this crate.
+///
+/// Whitespace is insignificant, and lines starting with `#` are ignored.
+pub(crate) struct Lexer<'a> {
+    pub s: &'a str,
+    pub offset: usize,
+}
+
+impl<'a> Lexer<'a> {
+    pub fn new(s: &'a str) -> Self {
+        Self { s, offset: 0 }
+    }
+    pub fn fail(&self, msg: &'static str) -> LexError {
+        LexError {
+            offset: self.offset,
+            msg,
+        }
+    }
+    pub fn rest(&self) -> &'a str {
+        &self.s[self.offset..]
+    }
+    pub fn skip_whitespace(&mut self) {
+        loop {
+            let rest = self.rest();
+            let trimmed = rest.trim_start();
+            self.offset += rest.len() - trimmed.len();
+            match trimmed.starts_with('#') {
+                true => self.offset += trimmed.find('\n').unwrap_or(trimmed.len()),
+                false => break,
+            }
+        }
+    }
+    /// Fail unless the whole input has been consumed.
+    pub fn finish(&mut self) -> Result<(), LexError> {
+        self.skip_whitespace();
+        match self.offset == self.s.len() {
+            true => Ok(()),
+            false => Err(self.fail("trailing characters")),
+        }
+    }
+    /// Consume `token`, returning whether it was present.
+    pub fn eat(&mut self, token: &str) -> bool {
+        self.skip_whitespace();
+        match self.rest().starts_with(token) {
+            true => {
+                self.offset += token.len();
+                true
+            }
+            false => false,
+        }
+    }
+    pub fn expect(&mut self, token: &str, msg: &'static str) -> Result<(), LexError> {
+        match self.eat(token) {
+            true => Ok(()),
+            false => Err(self.fail(msg)),
+        }
+    }
+    pub fn word(&mut self) -> &'a str {
+        self.skip_whitespace();
+        let rest = self.rest();
+        let end = rest
+            .find(|it: char| !(it.is_ascii_alphanumeric() || it == '_'))
+            .unwrap_or(rest.len());
+        self.offset += end;
+        &rest[..end]
+    }
+    pub fn number<T: core::str::FromStr>(&mut self) -> Result<T, LexError> {
+        let start = self.offset;
+        let word = self.word();
+        word.parse().map_err(|_| LexError {
+            offset: start,
+            msg: "expected an integer",
+        })
+    }
+    /// Parse a string delimited by `"`, with Rust's escapes.
+    pub fn string(&mut self) -> Result<String, LexError> {
+        self.expect("\"", "expected a string")?;
+        self.quoted('"')
+    }
+    /// Parse the rest of a literal delimited by `delim`, with Rust's escapes.
+    pub fn quoted(&mut self, delim: char) -> Result<String, LexError> {
+        let mut out = String::new();
+        let mut chars = self.rest().char_indices();
+        let start = self.offset;
+        let fail = |offset, msg| LexError {
+            offset: start + offset,
+            msg,
+        };
+        loop {
+            match chars.next() {
+                None => return Err(fail(self.rest().len(), "unterminated string")),
+                Some((ix, c)) if c == delim => {
+                    self.offset = start + ix + c.len_utf8();
+                    return Ok(out);
+                }
+                Some((ix, '\\')) => match chars.next() {
+                    Some((_, 'n')) => out.push('\n'),
+                    Some((_, 'r')) => out.push('\r'),
+                    Some((_, 't')) => out.push('\t'),
+                    Some((_, '0')) => out.push('\0'),
+                    Some((_, c @ ('\\' | '"' | '\''))) => out.push(c),
+                    Some((_, 'u')) => {
+                        let rest = &self.rest()[ix + 2..];
+                        let hex = rest
+                            .strip_prefix('{')
+                            .and_then(|it| it.split_once('}'))
+                            .map(|(it, _)| it)
+                            .ok_or_else(|| fail(ix, "invalid unicode escape"))?;
+                        let c = u32::from_str_radix(hex, 16)
+                            .ok()
+                            .and_then(char::from_u32)
+                            .ok_or_else(|| fail(ix, "invalid unicode escape"))?;
+                        out.push(c);
+                        for _ in 0..hex.len() + 2 {
+                            chars.next();
+                        }
+                    }
+                    _ => return Err(fail(ix, "invalid escape")),
+                },
+                Some((_, c)) => out.push(c),
+            }
+        }
+    }
+    /// Parse a delimited, comma-separated list, allowing a trailing comma.
+    pub fn list<T, E: From<LexError>>(
+        &mut self,
+        delims: (&str, &str),
+        item: impl FnMut(&mut Self) -> Result<T, E>,
+    ) -> Result<Vec<T>, E> {
+        Ok(self.delimited(delims, item)?.0)
+    }
+    /// Like [`Self::list`], but also return whether there was a trailing comma.
+    pub fn delimited<T, E: From<LexError>>(
+        &mut self,
+        (open, close): (&str, &str),
+        mut item: impl FnMut(&mut Self) -> Result<T, E>,
+    ) -> Result<(Vec<T>, bool), E> {
+        self.expect(open, "expected an opening delimiter")?;
+        let mut items = Vec::new();
+        loop {
+            if self.eat(close) {
+                let trailing_comma = !items.is_empty();
+                return Ok((items, trailing_comma));
+            }
+            items.push(item(self)?);
+            if !self.eat(",") {
+                self.expect(close, "expected `,` or a closing delimiter")?;
+                return Ok((items, false));
+            }
+        }
+    }
+}
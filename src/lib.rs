@@ -56,18 +56,198 @@
 //! See the documentation on [`Save`]s variants to see which invariants are checked.
 //! You can [configure this behaviour](Serializer::check_for_protocol_errors).
 
+mod annotate;
+mod archive;
+mod canonical;
+mod children;
+mod coerce;
+#[cfg(feature = "color")]
+mod color;
+mod compat;
+mod concurrent;
+pub mod conformance;
+mod convert;
+mod debug;
+mod diff;
+mod edit;
+pub mod embed;
+mod eq;
+mod errors;
+pub mod fixtures;
+mod forward;
+mod html;
 mod imp;
+#[cfg(feature = "json")]
+mod json;
+mod lexer;
+mod markdown;
+pub mod matcher;
+mod merge;
+pub mod mock;
+mod mutate;
+mod normalize;
+mod otel;
+mod path;
+mod pattern;
+mod pretty;
+mod profile;
+mod query;
+mod raw;
+mod refresh;
+mod registry;
+mod replay;
+mod session;
+mod shape;
+mod shape_capture;
+mod shape_guided;
+mod shared;
+pub mod snapshot;
+mod stats;
+mod store;
+mod strip;
+mod testing;
+mod text;
+mod tracker;
+mod validate;
+#[cfg(feature = "xml")]
+mod xml;
 
-pub use imp::Serializer;
+pub use annotate::Annotated;
+pub use archive::{ArchiveError, Migration, Migrations};
+pub use children::{Children, Edge, IntoChildren};
+#[cfg(feature = "color")]
+pub use color::ColorChoice;
+pub use compat::{CompatReport, CompatRules, Compatibility};
+pub use concurrent::SeqBuilder;
+pub use debug::DerivedDebug;
+pub use diff::{Change, ChangeClass, Diff, DiffOptions};
+pub use forward::{BytesRepr, EnumTagging, ErrorMode, Faults, Forward, NonFinite};
+pub use imp::{Config, IdentifierRule, Serializer};
+#[cfg(feature = "json")]
+pub use json::{read_jsonl, write_jsonl, ParseJsonError};
+pub use merge::{Conflict, Merge};
+pub use mutate::{Mutation, MutationKind};
+pub use normalize::Normalize;
+pub use otel::{AttributeLimits, AttributeValue, Attributes};
+pub use path::{ParsePathError, Path, Segment};
+pub use pretty::{BytesFormat, Pretty};
+pub use profile::{PathProfile, Profile};
+pub use query::ParseQueryError;
+pub use registry::{Registry, ValidationError};
+pub use replay::{check_compatible, load, load_seed, Deserializer, ReplayError};
+pub use session::SaveSession;
+pub use shape::{ParseShapeError, Shape, ShapeChange, ShapeDiff, ShapeVariant};
+pub use shape_capture::save_shape;
+pub use shared::SharedSave;
+pub use stats::PayloadStats;
+pub use store::{SaveId, SaveStore};
+pub use testing::assert_round_trip;
+pub use text::ParseSaveError;
+pub use tracker::SaveTracker;
+pub use validate::validate;
+#[cfg(feature = "xml")]
+pub use xml::{ParseXmlError, Xml, XmlEvent};
 
-use core::{convert::Infallible, fmt};
-use core::{iter, marker::PhantomData};
+/// Derive functions which build expected trees for a type,
+/// with struct, field and variant names filled in,
+/// so only the values need to be written by hand.
+///
+/// Structs get a `save_template` function,
+/// and each enum variant gets a `save_template_<variant>` function,
+/// with a parameter per field.
+/// Variant names are `snake_case`d with acronyms kept together,
+/// so `HTTPRequest` gets `save_template_http_request`.
+///
+/// ```
+/// # use serde::Serialize;
+/// # use serde_save::{save, Save, SaveTemplate};
+/// #[derive(Serialize, SaveTemplate)]
+/// #[serde(rename_all = "camelCase")]
+/// struct Order {
+///     order_id: u64,
+///     status: Status,
+/// }
+///
+/// #[derive(Serialize, SaveTemplate)]
+/// enum Status {
+///     Pending,
+///     Shipped { carrier: &'static str },
+/// }
+///
+/// assert_eq!(
+///     save(Order { order_id: 1, status: Status::Shipped { carrier: "post" } }).unwrap(),
+///     Order::save_template(
+///         Save::U64(1),
+///         Status::save_template_shipped(Save::string("post")),
+///     ),
+/// );
+/// ```
+///
+/// The `rename`, `rename_all`, `rename_all_fields` and `skip` serde attributes
+/// are respected, following serde's rules for renaming variants and fields,
+/// and attributes which change the structure of the tree,
+/// like `flatten` or `tag`, are rejected.
+///
+/// ```
+/// # use serde::Serialize;
+/// # use serde_save::{save, Save, SaveTemplate};
+/// #[derive(Serialize, SaveTemplate)]
+/// #[serde(rename_all = "snake_case", rename_all_fields = "camelCase")]
+/// enum Event {
+///     UserCreated { user_id: u64 },
+/// }
+///
+/// assert_eq!(
+///     save(Event::UserCreated { user_id: 1 }).unwrap(),
+///     Event::save_template_user_created(Save::U64(1)),
+/// );
+/// # macro_rules! check {
+/// #     ($($rule:literal),*) => {$({
+/// #         #[derive(Serialize, SaveTemplate)]
+/// #         #[serde(rename_all = $rule)]
+/// #         #[allow(non_snake_case)]
+/// #         struct Struct { field_name: u8, mixedCase: u8 }
+/// #         #[derive(Serialize, SaveTemplate)]
+/// #         #[serde(rename_all = $rule, rename_all_fields = $rule)]
+/// #         #[allow(non_snake_case)]
+/// #         enum Enum { VariantName { field_name: u8, mixedCase: u8 }, HTTPRequest }
+/// #         assert_eq!(
+/// #             save(Struct { field_name: 1, mixedCase: 2 }).unwrap(),
+/// #             Struct::save_template(Save::U8(1), Save::U8(2)),
+/// #             "{}", $rule,
+/// #         );
+/// #         assert_eq!(
+/// #             save(Enum::VariantName { field_name: 1, mixedCase: 2 }).unwrap(),
+/// #             Enum::save_template_variant_name(Save::U8(1), Save::U8(2)),
+/// #             "{}", $rule,
+/// #         );
+/// #         assert_eq!(save(Enum::HTTPRequest).unwrap(), Enum::save_template_http_request(), "{}", $rule);
+/// #     })*};
+/// # }
+/// # check!(
+/// #     "lowercase", "UPPERCASE", "PascalCase", "camelCase", "snake_case",
+/// #     "SCREAMING_SNAKE_CASE", "kebab-case", "SCREAMING-KEBAB-CASE"
+/// # );
+/// ```
+#[cfg(feature = "derive")]
+pub use serde_save_derive::SaveTemplate;
+
+#[doc(hidden)]
+pub mod __private {
+    pub use crate::pattern::{assert_save_matches, pattern_mismatches, Pattern};
+    pub use crate::testing::{assert_forwards_to, assert_ser_eq};
+}
+
+use core::{cmp, convert::Infallible, fmt};
+use core::{
+    hash::{Hash, Hasher},
+    iter,
+    marker::PhantomData,
+};
+use std::sync::Arc;
 
 use serde::{
-    ser::{
-        Error as _, SerializeMap as _, SerializeStruct as _, SerializeStructVariant as _,
-        SerializeTuple as _, SerializeTupleStruct as _, SerializeTupleVariant as _,
-    },
+    ser::{Error as _, SerializeStruct as _},
     Deserialize, Serialize,
 };
 
@@ -75,6 +255,9 @@ use serde::{
 ///
 /// Accepts a lifetime to allow users to write dynamic tests.
 ///
+/// Trees are [`Send`] and [`Sync`] if `E` is,
+/// see [`SharedSave`] for sharing a tree between threads.
+///
 /// See [`crate documentation`](mod@self) for more.
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
 pub enum Save<'a, E = Infallible> {
@@ -329,6 +512,156 @@ pub fn save<T: Serialize>(t: T) -> Result<Save<'static>, Error> {
     t.serialize(Serializer::new())
 }
 
+/// Save the serialization tree with the given [`Config`].
+///
+/// See also [`Serializer::save`], for reusing a configured [`Serializer`].
+///
+/// ```
+/// # use serde_save::{save_with, Config, Save};
+/// # use std::net::{IpAddr, Ipv4Addr};
+/// let compact = Config::new().human_readable(false);
+/// assert!(matches!(
+///     save_with(compact, IpAddr::V4(Ipv4Addr::LOCALHOST)).unwrap(),
+///     Save::NewTypeVariant { .. }
+/// ));
+/// ```
+pub fn save_with<T: Serialize>(config: Config, t: T) -> Result<Save<'static>, Error> {
+    t.serialize(Serializer::from_config(config))
+}
+
+/// Like [`save`], but on failure also return the part of the tree that was
+/// captured before the error, for inspection.
+///
+/// The [partial tree](PartialSave::partial) ends with a [`Save::Error`] where
+/// serialization failed: nodes which would have been serialized after it are removed.
+///
+/// The value is serialized a second time on failure, with [errors persisted](save_errors),
+/// so [`Serialize`] implementations should be deterministic.
+///
+/// ```
+/// # use serde_save::{save_partial, Save};
+/// # use std::{ffi::OsString, os::unix::ffi::OsStringExt as _, path::PathBuf};
+/// #[derive(serde::Serialize)]
+/// struct Upload {
+///     name: &'static str,
+///     path: PathBuf,
+///     size: u64,
+/// }
+///
+/// let non_utf8_path = PathBuf::from(OsString::from_vec(vec![u8::MAX]));
+/// let e = save_partial(Upload { name: "report", path: non_utf8_path, size: 1 }).unwrap_err();
+/// assert_eq!(e.to_string(), "path contains invalid UTF-8 characters");
+/// assert_eq!(
+///     e.partial(),
+///     &Save::Struct {
+///         name: "Upload",
+///         fields: vec![
+///             ("name", Some(Save::string("report"))),
+///             ("path", Some(Save::error("path contains invalid UTF-8 characters"))),
+///         ],
+///     }
+/// );
+/// ```
+pub fn save_partial<T: Serialize>(t: T) -> Result<Save<'static>, PartialSave> {
+    match save(&t) {
+        Ok(it) => Ok(it),
+        Err(error) => {
+            let mut partial = t
+                .serialize(Serializer::new().save_errors())
+                .unwrap_or_else(Save::Error);
+            truncate_after_error(&mut partial);
+            Err(PartialSave {
+                error,
+                partial: Box::new(partial),
+            })
+        }
+    }
+}
+
+/// An error returned from [`save_partial`],
+/// along with the part of the tree which was captured before it.
+#[derive(Debug, Clone)]
+pub struct PartialSave {
+    error: Error,
+    partial: Box<Save<'static, Error>>,
+}
+
+impl PartialSave {
+    /// The error which stopped serialization, as returned by [`save`].
+    pub fn error(&self) -> &Error {
+        &self.error
+    }
+    /// The tree up to and including the failing node.
+    pub fn partial(&self) -> &Save<'static, Error> {
+        &self.partial
+    }
+    /// Take the error and the partial tree.
+    pub fn into_parts(self) -> (Error, Save<'static, Error>) {
+        (self.error, *self.partial)
+    }
+}
+
+/// Delegates to the [`Error`], as does [`std::error::Error::source`].
+impl fmt::Display for PartialSave {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.error, f)
+    }
+}
+
+impl std::error::Error for PartialSave {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        std::error::Error::source(&self.error)
+    }
+}
+
+/// Remove every node after the first [`Save::Error`] in pre-order,
+/// returning whether there was one.
+fn truncate_after_error<E>(save: &mut Save<'_, E>) -> bool {
+    match save {
+        Save::Error(_) => true,
+        Save::Option(Some(it))
+        | Save::NewTypeStruct { value: it, .. }
+        | Save::NewTypeVariant { value: it, .. } => truncate_after_error(it),
+        Save::Seq(it)
+        | Save::Tuple(it)
+        | Save::TupleStruct { values: it, .. }
+        | Save::TupleVariant { values: it, .. } => {
+            match it.iter_mut().position(truncate_after_error) {
+                Some(ix) => {
+                    it.truncate(ix + 1);
+                    true
+                }
+                None => false,
+            }
+        }
+        Save::Map(it) => {
+            let failed = it
+                .iter_mut()
+                .position(|(k, v)| truncate_after_error(k) || truncate_after_error(v));
+            match failed {
+                Some(ix) => {
+                    it.truncate(ix + 1);
+                    true
+                }
+                None => false,
+            }
+        }
+        Save::Struct { fields, .. } | Save::StructVariant { fields, .. } => {
+            let failed = fields
+                .iter_mut()
+                .position(|(_, it)| it.as_mut().is_some_and(truncate_after_error));
+            match failed {
+                Some(ix) => {
+                    fields.truncate(ix + 1);
+                    true
+                }
+                None => false,
+            }
+        }
+        _ => false,
+    }
+}
+
 /// Save the serialization tree, annotating it with [`Save::Error`] if:
 /// - Any node's call to [`serde::Serialize::serialize`] fails.
 /// - Any node has any [protocol errors].
@@ -344,23 +677,269 @@ pub fn save_errors<T: Serialize>(t: T) -> Save<'static, Error> {
     .unwrap_or_else(Save::Error)
 }
 
+/// A value captured with both settings of [`serde::Serializer::is_human_readable`].
+///
+/// Types like [`std::net::IpAddr`] serialize differently for human-readable
+/// and compact formats, and this allows checking both representations at once.
+///
+/// ```
+/// # use serde_save::{save_dual, Save};
+/// # use std::net::{IpAddr, Ipv4Addr};
+/// let dual = save_dual(IpAddr::V4(Ipv4Addr::LOCALHOST)).unwrap();
+/// assert_eq!(
+///     dual.human_readable,
+///     Save::string("127.0.0.1"),
+/// );
+/// assert!(matches!(dual.compact, Save::NewTypeVariant { .. }));
+/// assert!(!dual.is_consistent());
+/// ```
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+pub struct Dual<'a, E = Infallible> {
+    /// The tree captured when [`serde::Serializer::is_human_readable`] returns `true`.
+    pub human_readable: Save<'a, E>,
+    /// The tree captured when [`serde::Serializer::is_human_readable`] returns `false`.
+    pub compact: Save<'a, E>,
+}
+
+impl<'a, E> Dual<'a, E> {
+    /// Returns `true` if both representations are the same.
+    pub fn is_consistent(&self) -> bool
+    where
+        E: PartialEq,
+    {
+        self.human_readable == self.compact
+    }
+}
+
+/// Like [`save`], but captures both the [human readable](serde::Serializer::is_human_readable)
+/// and compact representations.
+pub fn save_dual<T: Serialize>(t: T) -> Result<Dual<'static>, Error> {
+    Ok(Dual {
+        human_readable: t.serialize(Serializer::new().human_readable(true))?,
+        compact: t.serialize(Serializer::new().human_readable(false))?,
+    })
+}
+
+/// Like [`save_errors`], but captures both the [human readable](serde::Serializer::is_human_readable)
+/// and compact representations.
+#[must_use]
+pub fn save_errors_dual<T: Serialize>(t: T) -> Dual<'static, Error> {
+    let save = |is_human_readable| {
+        t.serialize(
+            Serializer::new()
+                .human_readable(is_human_readable)
+                .check_for_protocol_errors(true)
+                .save_errors(),
+        )
+        .unwrap_or_else(Save::Error)
+    };
+    Dual {
+        human_readable: save(true),
+        compact: save(false),
+    }
+}
+
+/// A [`Save`] annotated with the [`type_name`](core::any::type_name) of the
+/// value it was captured from.
+///
+/// This is useful for archives, which may be inspected long after (and far
+/// away from) the code that produced them.
+///
+/// ```
+/// # use serde::Serialize;
+/// # use serde_save::{Save, save_typed};
+/// #[derive(Serialize)]
+/// struct MyUnitStruct;
+///
+/// let typed = save_typed(&MyUnitStruct).unwrap();
+/// assert!(typed.type_name.ends_with("MyUnitStruct"));
+/// assert_eq!(typed.save, Save::UnitStruct("MyUnitStruct"));
+/// ```
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+pub struct Typed<'a, E = Infallible> {
+    /// The result of [`core::any::type_name`] for the root value.
+    ///
+    /// Note that this is a best-effort description, and the exact contents may
+    /// change between compiler versions.
+    pub type_name: &'static str,
+    /// The serialization tree.
+    pub save: Save<'a, E>,
+}
+
+/// Like [`save`], but also records the [`type_name`](core::any::type_name) of `T`.
+pub fn save_typed<T: Serialize + ?Sized>(t: &T) -> Result<Typed<'static>, Error> {
+    Ok(Typed {
+        type_name: core::any::type_name::<T>(),
+        save: save(t)?,
+    })
+}
+
+/// Like [`save_errors`], but also records the [`type_name`](core::any::type_name) of `T`.
+#[must_use]
+pub fn save_errors_typed<T: Serialize + ?Sized>(t: &T) -> Typed<'static, Error> {
+    Typed {
+        type_name: core::any::type_name::<T>(),
+        save: save_errors(t),
+    }
+}
+
+/// Serialized as a struct named `Typed`, with the fields `type_name` and `save`.
+impl<E> Serialize for Typed<'static, E>
+where
+    E: fmt::Display,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut strukt = serializer.serialize_struct("Typed", 2)?;
+        strukt.serialize_field("type_name", self.type_name)?;
+        strukt.serialize_field("save", &self.save)?;
+        strukt.end()
+    }
+}
+
 /// An error returned by an implementation of [`serde::Serialize::serialize`], or
 /// [protocol error] checking.
 ///
+/// Errors compare, order and hash by their message and kind only - any
+/// [source](Error::with_source) or backtrace is ignored.
+///
+/// Errors are [`Send`] and [`Sync`].
+///
 /// [protocol error]: Serializer::check_for_protocol_errors
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone)]
+#[derive(Debug, Clone)]
 pub struct Error {
     msg: String,
-    protocol: bool,
+    protocol: Option<ProtocolDetail>,
+    source: Option<Arc<dyn std::error::Error + Send + Sync>>,
+    #[cfg(feature = "backtrace")]
+    backtrace: Arc<std::backtrace::Backtrace>,
 }
 
+const _: () = {
+    fn assert_send_sync<T: Send + Sync>() {}
+    let _ = assert_send_sync::<Save<'static>>;
+    let _ = assert_send_sync::<Save<'static, Error>>;
+    let _ = assert_send_sync::<SharedSave<Error>>;
+    let _ = assert_send_sync::<Error>;
+};
+
 impl Error {
+    /// Create an error which preserves the original error value, rather than
+    /// just its message.
+    ///
+    /// The original error is available from [`std::error::Error::source`],
+    /// for downcasting.
+    ///
+    /// ```
+    /// # use serde_save::Error;
+    /// use std::error::Error as _;
+    ///
+    /// let io = std::io::Error::other("disk on fire");
+    /// let e = Error::with_source(io);
+    /// assert_eq!(e.to_string(), "disk on fire");
+    /// assert!(e.source().unwrap().downcast_ref::<std::io::Error>().is_some());
+    /// ```
+    pub fn with_source(source: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> Self {
+        let source: Arc<dyn std::error::Error + Send + Sync> = Arc::from(source.into());
+        Self::new(source.to_string(), None, Some(source))
+    }
     /// Returns `true` if these error was caused by an incorrect implementation
     /// of the [`serde`] methods.
     ///
     /// See documentation on [`Save`]'s variants for the invariants that are checked.
     pub fn is_protocol(&self) -> bool {
-        self.protocol
+        self.protocol.is_some()
+    }
+    /// Structured information about this error, if it is a [protocol error](Self::is_protocol).
+    ///
+    /// ```
+    /// # use serde::Serialize;
+    /// # use serde_save::{save_errors, ProtocolDetail, Save};
+    /// struct Liar;
+    /// impl Serialize for Liar {
+    ///     fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    ///         use serde::ser::SerializeSeq as _;
+    ///         serializer.serialize_seq(Some(2))?.end() // promised two elements, but sent none
+    ///     }
+    /// }
+    /// let Save::Seq(elements) = save_errors(Liar) else { unreachable!() };
+    /// let Save::Error(e) = &elements[0] else { unreachable!() };
+    /// assert_eq!(e.expected_len(), Some(2));
+    /// assert_eq!(e.actual_len(), Some(0));
+    /// assert!(matches!(e.detail(), Some(ProtocolDetail::Length { what: "sequence", .. })));
+    /// ```
+    pub fn detail(&self) -> Option<&ProtocolDetail> {
+        self.protocol.as_ref()
+    }
+    /// The expected length, if this is a [`ProtocolDetail::Length`] error.
+    pub fn expected_len(&self) -> Option<usize> {
+        match self.protocol {
+            Some(ProtocolDetail::Length { expected, .. }) => Some(expected),
+            _ => None,
+        }
+    }
+    /// The actual length, if this is a [`ProtocolDetail::Length`] error.
+    pub fn actual_len(&self) -> Option<usize> {
+        match self.protocol {
+            Some(ProtocolDetail::Length { actual, .. }) => Some(actual),
+            _ => None,
+        }
+    }
+    /// The [`Backtrace`](std::backtrace::Backtrace) captured when this error
+    /// was created.
+    ///
+    /// Backtraces are always captured when the `backtrace` feature is enabled,
+    /// regardless of the `RUST_BACKTRACE` environment variable.
+    #[cfg(feature = "backtrace")]
+    pub fn backtrace(&self) -> &std::backtrace::Backtrace {
+        &self.backtrace
+    }
+    pub(crate) fn new_protocol(detail: ProtocolDetail) -> Self {
+        Self::new(format!("protocol error: {}", detail), Some(detail), None)
+    }
+    fn new(
+        msg: String,
+        protocol: Option<ProtocolDetail>,
+        source: Option<Arc<dyn std::error::Error + Send + Sync>>,
+    ) -> Self {
+        Self {
+            msg,
+            protocol,
+            source,
+            #[cfg(feature = "backtrace")]
+            backtrace: Arc::new(std::backtrace::Backtrace::force_capture()),
+        }
+    }
+    fn key(&self) -> (&str, bool) {
+        (&self.msg, self.is_protocol())
+    }
+}
+
+impl PartialEq for Error {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for Error {}
+
+impl PartialOrd for Error {
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Error {
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+impl Hash for Error {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key().hash(state)
     }
 }
 
@@ -372,14 +951,88 @@ impl fmt::Display for Error {
 
 impl serde::ser::Error for Error {
     fn custom<T: fmt::Display>(msg: T) -> Self {
-        Self {
-            msg: msg.to_string(),
-            protocol: false,
+        Self::new(msg.to_string(), None, None)
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &self.source {
+            Some(it) => Some(&**it),
+            None => None,
         }
     }
 }
 
-impl std::error::Error for Error {}
+/// Structured information about a [protocol error](Error::is_protocol).
+///
+/// See documentation on [`Save`]'s variants for the invariants that are checked.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum ProtocolDetail {
+    /// A collection had a different number of items than the length passed to
+    /// e.g [`serde::Serializer::serialize_seq`].
+    Length {
+        /// A description of the collection, e.g `"sequence"` or `"tuple struct"`.
+        what: &'static str,
+        /// The length passed to the [`serde::Serializer`].
+        expected: usize,
+        /// The number of items actually serialized.
+        actual: usize,
+    },
+    /// A [`Save::Map`] had an unequal number of keys and values.
+    JaggedMap {
+        /// The number of keys serialized.
+        keys: usize,
+        /// The number of values serialized.
+        values: usize,
+    },
+    /// A [`Save::Struct`] or [`Save::StructVariant`] had repeated field names.
+    DuplicateFields {
+        /// A description of the collection, e.g `"struct"`.
+        what: &'static str,
+        /// The repeated field names.
+        fields: Vec<&'static str>,
+    },
+    /// A name did not follow the [`IdentifierRule`] passed to
+    /// [`Serializer::check_identifiers`].
+    InvalidIdentifier {
+        /// What was named, e.g `"struct"` or `"field"`.
+        what: &'static str,
+        /// The offending name.
+        name: &'static str,
+    },
+}
+
+impl fmt::Display for ProtocolDetail {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProtocolDetail::Length {
+                what,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "expected a {} of length {}, got {}",
+                what, expected, actual
+            ),
+            ProtocolDetail::JaggedMap { keys, values } => {
+                write!(f, "map has {} keys and {} values", keys, values)
+            }
+            ProtocolDetail::DuplicateFields { what, fields } => {
+                write!(
+                    f,
+                    "{} has duplicate field names: {}",
+                    what,
+                    fields.join(", ")
+                )
+            }
+            ProtocolDetail::InvalidIdentifier { what, name } => {
+                write!(f, "invalid {} name {:?}", what, name)
+            }
+        }
+    }
+}
 
 /// Information about a serialized `enum` variant.
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
@@ -461,6 +1114,50 @@ where
     }
 }
 
+/// Push elements onto a [`Save::Seq`], [`Save::Tuple`], [`Save::TupleStruct`]
+/// or [`Save::TupleVariant`].
+///
+/// # Panics
+/// - If this is any other kind of node.
+///
+/// ```
+/// # use serde_save::Save;
+/// let mut save = Save::<std::convert::Infallible>::from_iter([1, 2]);
+/// save.extend([3]);
+/// assert_eq!(save, Save::from_iter([1, 2, 3]));
+/// ```
+impl<'a, E, T> Extend<T> for Save<'a, E>
+where
+    T: Into<Save<'a, E>>,
+{
+    fn extend<II: IntoIterator<Item = T>>(&mut self, iter: II) {
+        match self {
+            Save::Seq(it)
+            | Save::Tuple(it)
+            | Save::TupleStruct { values: it, .. }
+            | Save::TupleVariant { values: it, .. } => it.extend(iter.into_iter().map(Into::into)),
+            _ => panic!("can only push elements onto sequences and tuples"),
+        }
+    }
+}
+
+/// Push entries onto a [`Save::Map`].
+///
+/// # Panics
+/// - If this is any other kind of node.
+impl<'a, E, K, V> Extend<(K, V)> for Save<'a, E>
+where
+    K: Into<Save<'a, E>>,
+    V: Into<Save<'a, E>>,
+{
+    fn extend<II: IntoIterator<Item = (K, V)>>(&mut self, iter: II) {
+        match self {
+            Save::Map(it) => it.extend(iter.into_iter().map(|(k, v)| (k.into(), v.into()))),
+            _ => panic!("can only push entries onto maps"),
+        }
+    }
+}
+
 macro_rules! from_tuple {
     ($($ident:ident),* $(,)?) => {
         #[doc(hidden)]
@@ -542,123 +1239,23 @@ where
     where
         S: serde::Serializer,
     {
-        match self {
-            Save::Bool(it) => serializer.serialize_bool(*it),
-            Save::I8(it) => serializer.serialize_i8(*it),
-            Save::I16(it) => serializer.serialize_i16(*it),
-            Save::I32(it) => serializer.serialize_i32(*it),
-            Save::I64(it) => serializer.serialize_i64(*it),
-            Save::I128(it) => serializer.serialize_i128(*it),
-            Save::U8(it) => serializer.serialize_u8(*it),
-            Save::U16(it) => serializer.serialize_u16(*it),
-            Save::U32(it) => serializer.serialize_u32(*it),
-            Save::U64(it) => serializer.serialize_u64(*it),
-            Save::U128(it) => serializer.serialize_u128(*it),
-            Save::F32(it) => serializer.serialize_f32(*it),
-            Save::F64(it) => serializer.serialize_f64(*it),
-            Save::Char(it) => serializer.serialize_char(*it),
-            Save::String(it) => serializer.serialize_str(it),
-            Save::ByteArray(it) => serializer.serialize_bytes(it),
-            Save::Option(None) => serializer.serialize_none(),
-            Save::Option(Some(it)) => serializer.serialize_some(it),
-            Save::UnitStruct(it) => serializer.serialize_unit_struct(it),
-            Save::UnitVariant(Variant {
-                name,
-                variant_index,
-                variant,
-            }) => serializer.serialize_unit_variant(name, *variant_index, variant),
-            Save::Unit => serializer.serialize_unit(),
-            Save::NewTypeStruct { name, value } => serializer.serialize_newtype_struct(name, value),
-            Save::NewTypeVariant {
-                variant:
-                    Variant {
-                        name,
-                        variant_index,
-                        variant,
-                    },
-                value,
-            } => serializer.serialize_newtype_variant(name, *variant_index, variant, value),
-            Save::Seq(it) => it.serialize(serializer),
-            Save::Map(it) => {
-                let mut map = serializer.serialize_map(Some(it.len()))?;
-                for (k, v) in it {
-                    map.serialize_entry(k, v)?
-                }
-                map.end()
-            }
-            Save::Tuple(it) => {
-                let mut tup = serializer.serialize_tuple(it.len())?;
-                for it in it {
-                    tup.serialize_element(it)?
-                }
-                tup.end()
-            }
-            Save::TupleStruct { name, values } => {
-                let mut tup = serializer.serialize_tuple_struct(name, values.len())?;
-                for it in values {
-                    tup.serialize_field(it)?
-                }
-                tup.end()
-            }
-            Save::TupleVariant {
-                variant:
-                    Variant {
-                        name,
-                        variant_index,
-                        variant,
-                    },
-                values,
-            } => {
-                let mut var = serializer.serialize_tuple_variant(
-                    name,
-                    *variant_index,
-                    variant,
-                    values.len(),
-                )?;
-                for it in values {
-                    var.serialize_field(it)?
-                }
-                var.end()
-            }
-            Save::Struct { name, fields } => {
-                let mut strukt = serializer.serialize_struct(name, fields.len())?;
-                for (k, v) in fields {
-                    match v {
-                        Some(v) => strukt.serialize_field(k, v)?,
-                        None => strukt.skip_field(k)?,
-                    }
-                }
-                strukt.end()
-            }
-            Save::StructVariant {
-                variant:
-                    Variant {
-                        name,
-                        variant_index,
-                        variant,
-                    },
-                fields,
-            } => {
-                let mut var = serializer.serialize_struct_variant(
-                    name,
-                    *variant_index,
-                    variant,
-                    fields.len(),
-                )?;
-                for (k, v) in fields {
-                    match v {
-                        Some(v) => var.serialize_field(k, v)?,
-                        None => var.skip_field(k)?,
-                    }
-                }
-                var.end()
-            }
-            Save::Error(e) => Err(S::Error::custom(e)),
-        }
+        self.forward().serialize(serializer)
     }
 }
 
 /// This is a best-effort deserialization, provided for completeness.
+///
+/// Newtype structs are transparent, since [`serde::de::Visitor::visit_newtype_struct`]
+/// does not receive the struct's name.
+///
+/// ```
+/// # use serde_save::{load, save, Save};
+/// #[derive(serde::Serialize)]
+/// struct Meters(u32);
+///
+/// let saved = save(vec![Meters(5)]).unwrap();
+/// assert_eq!(load::<Save, _>(&saved).unwrap(), Save::Seq(vec![Save::U32(5)]));
+/// ```
 impl<'a, 'de> Deserialize<'de> for Save<'a> {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -744,11 +1341,7 @@ impl<'a, 'de> Deserialize<'de> for Save<'a> {
             where
                 D: serde::Deserializer<'de>,
             {
-                let _ = deserializer;
-                Err(serde::de::Error::invalid_type(
-                    serde::de::Unexpected::NewtypeStruct,
-                    &self,
-                ))
+                deserializer.deserialize_any(self)
             }
 
             fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
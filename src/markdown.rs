@@ -0,0 +1,150 @@
+use crate::Save;
+use core::fmt::{self, Write as _};
+
+impl<E: fmt::Display> Save<'_, E> {
+    /// Render this tree as Markdown, for pasting into issues and documents.
+    ///
+    /// - [`Save::Struct`]s and [`Save::StructVariant`]s are rendered as a table
+    ///   of fields.
+    /// - A [`Save::Seq`] of structs is rendered as a table, with one row per
+    ///   element and one column per field.
+    /// - Anything else is rendered as a [pretty](Save::pretty) code block.
+    ///
+    /// Values within tables are rendered on a single line.
+    ///
+    /// ```
+    /// # use serde_save::save;
+    /// #[derive(serde::Serialize)]
+    /// struct User {
+    ///     name: &'static str,
+    ///     #[serde(skip_serializing_if = "Option::is_none")]
+    ///     admin: Option<bool>,
+    /// }
+    ///
+    /// let users = vec![
+    ///     User { name: "alice", admin: Some(true) },
+    ///     User { name: "bob", admin: None },
+    /// ];
+    /// assert_eq!(
+    ///     save(&users).unwrap().to_markdown(),
+    ///     "\
+    /// | name | admin |
+    /// | --- | --- |
+    /// | `\"alice\"` | `Some(true)` |
+    /// | `\"bob\"` |  |
+    /// "
+    /// );
+    /// assert_eq!(
+    ///     save(&users[0]).unwrap().to_markdown(),
+    ///     "\
+    /// **User**
+    ///
+    /// | Field | Value |
+    /// | --- | --- |
+    /// | name | `\"alice\"` |
+    /// | admin | `Some(true)` |
+    /// "
+    /// );
+    /// ```
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        match self {
+            Save::Struct { name, fields } => {
+                let _ = writeln!(out, "**{}**\n", name);
+                self::fields(&mut out, fields)
+            }
+            Save::StructVariant { variant, fields } => {
+                let _ = writeln!(out, "**{}::{}**\n", variant.name, variant.variant);
+                self::fields(&mut out, fields)
+            }
+            Save::Seq(elements) if !elements.is_empty() => match table(elements) {
+                Some(columns) => rows(&mut out, &columns, elements),
+                None => code_block(&mut out, self),
+            },
+            _ => code_block(&mut out, self),
+        }
+        out
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn fields<E: fmt::Display>(out: &mut String, fields: &[(&str, Option<Save<'_, E>>)]) {
+    out.push_str("| Field | Value |\n| --- | --- |\n");
+    for (name, value) in fields {
+        let _ = writeln!(out, "| {} | {} |", Escape(name), Cell(value.as_ref()));
+    }
+}
+
+/// If every element is a struct, return the union of their field names.
+fn table<'a, E>(elements: &[Save<'a, E>]) -> Option<Vec<&'a str>> {
+    let mut columns = Vec::<&str>::new();
+    for it in elements {
+        let (Save::Struct { fields, .. } | Save::StructVariant { fields, .. }) = it else {
+            return None;
+        };
+        for (name, _) in fields {
+            if !columns.contains(name) {
+                columns.push(name)
+            }
+        }
+    }
+    Some(columns)
+}
+
+fn rows<E: fmt::Display>(out: &mut String, columns: &[&str], elements: &[Save<'_, E>]) {
+    out.push('|');
+    for it in columns {
+        let _ = write!(out, " {} |", Escape(it));
+    }
+    out.push_str("\n|");
+    for _ in columns {
+        out.push_str(" --- |")
+    }
+    out.push('\n');
+    for it in elements {
+        let (Save::Struct { fields, .. } | Save::StructVariant { fields, .. }) = it else {
+            unreachable!("checked by `table`")
+        };
+        out.push('|');
+        for column in columns {
+            let value = fields
+                .iter()
+                .find(|(name, _)| name == column)
+                .and_then(|(_, it)| it.as_ref());
+            let _ = write!(out, " {} |", Cell(value));
+        }
+        out.push('\n');
+    }
+}
+
+fn code_block<E: fmt::Display>(out: &mut String, save: &Save<'_, E>) {
+    let _ = writeln!(out, "```\n{}\n```", save.pretty().multiline(true));
+}
+
+/// A value within a table, or nothing if it is absent.
+struct Cell<'s, 'a, E>(Option<&'s Save<'a, E>>);
+
+impl<E: fmt::Display> fmt::Display for Cell<'_, '_, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(it) => write!(f, "`{}`", Escape(&it.pretty().to_string())),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Escape text for inclusion in a table.
+struct Escape<'s>(&'s str);
+
+impl fmt::Display for Escape<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for c in self.0.chars() {
+            match c {
+                '|' => f.write_str("\\|")?,
+                '\n' => f.write_str(" ")?,
+                c => f.write_char(c)?,
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,193 @@
+//! Placeholders for asserting on the structure of a tree,
+//! while ignoring volatile values like timestamps and UUIDs.
+//!
+//! A pattern is a `Save<Matcher>`: [`Save::Error`] nodes hold placeholders,
+//! which are created with the functions in this module.
+//! Every other node must match exactly, see [`Save::matches`].
+//!
+//! ```
+//! # use serde_save::{matcher::{any_len_seq, any_number, glob_string}, save, Save};
+//! #[derive(serde::Serialize)]
+//! struct Event {
+//!     id: String,
+//!     at: u64,
+//!     kind: &'static str,
+//!     tags: Vec<&'static str>,
+//! }
+//!
+//! let event = save(Event {
+//!     id: String::from("evt-5b0f"),
+//!     at: 1_700_000_000,
+//!     kind: "login",
+//!     tags: vec!["web", "eu"],
+//! })
+//! .unwrap();
+//!
+//! let pattern = Save::strukt("Event", [
+//!     ("id", glob_string("evt-*")),
+//!     ("at", any_number()),
+//!     ("kind", Save::string("login")),
+//!     ("tags", any_len_seq(2)),
+//! ]);
+//! assert!(event.matches(&pattern));
+//! ```
+
+use crate::Save;
+use core::fmt;
+use std::sync::Arc;
+
+/// A placeholder in a pattern, see the [module documentation](self).
+#[derive(Clone)]
+pub struct Matcher {
+    kind: Kind,
+}
+
+#[derive(Clone)]
+enum Kind {
+    Any,
+    AnyString,
+    AnyNumber,
+    AnyLenSeq(usize),
+    Glob(String),
+    StringSatisfying(Arc<dyn Fn(&str) -> bool + Send + Sync>),
+}
+
+impl fmt::Debug for Matcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            Kind::Any => f.write_str("any()"),
+            Kind::AnyString => f.write_str("any_string()"),
+            Kind::AnyNumber => f.write_str("any_number()"),
+            Kind::AnyLenSeq(len) => write!(f, "any_len_seq({})", len),
+            Kind::Glob(pattern) => write!(f, "glob_string({:?})", pattern),
+            Kind::StringSatisfying(_) => f.write_str("string_satisfying(..)"),
+        }
+    }
+}
+
+fn placeholder<'a>(kind: Kind) -> Save<'a, Matcher> {
+    Save::Error(Matcher { kind })
+}
+
+/// Match any node.
+pub fn any<'a>() -> Save<'a, Matcher> {
+    placeholder(Kind::Any)
+}
+
+/// Match any [`Save::String`].
+pub fn any_string<'a>() -> Save<'a, Matcher> {
+    placeholder(Kind::AnyString)
+}
+
+/// Match any integer or float.
+pub fn any_number<'a>() -> Save<'a, Matcher> {
+    placeholder(Kind::AnyNumber)
+}
+
+/// Match any [`Save::Seq`] with `len` elements.
+pub fn any_len_seq<'a>(len: usize) -> Save<'a, Matcher> {
+    placeholder(Kind::AnyLenSeq(len))
+}
+
+/// Match a [`Save::String`] against a glob `pattern`,
+/// where `*` matches any run of characters, and `?` matches a single character.
+///
+/// ```
+/// # use serde_save::{matcher::glob_string, Save};
+/// # use std::convert::Infallible;
+/// let matches = |pattern, text| Save::<Infallible>::string(text).matches(&glob_string(pattern));
+/// assert!(matches("evt-*", "evt-1"));
+/// assert!(matches("*-?", "evt-1"));
+/// assert!(matches("e**1", "evt-1"));
+/// assert!(!matches("evt-?", "evt-"));
+/// assert!(!matches("*x*", "evt-1"));
+///
+/// // Stars don't backtrack exponentially.
+/// assert!(!matches(&"a*".repeat(32), &"a".repeat(31)));
+/// ```
+pub fn glob_string<'a>(pattern: impl Into<String>) -> Save<'a, Matcher> {
+    placeholder(Kind::Glob(pattern.into()))
+}
+
+/// Match a [`Save::String`] for which `f` returns `true`.
+///
+/// ```
+/// # use serde_save::{matcher::string_satisfying, Save};
+/// let uuid = string_satisfying(|it| it.len() == 36 && it.chars().filter(|c| *c == '-').count() == 4);
+/// assert!(Save::<std::convert::Infallible>::string("67e55044-10b1-426f-9247-bb680e5fe0c8").matches(&uuid));
+/// ```
+pub fn string_satisfying<'a>(
+    f: impl Fn(&str) -> bool + Send + Sync + 'static,
+) -> Save<'a, Matcher> {
+    placeholder(Kind::StringSatisfying(Arc::new(f)))
+}
+
+impl Matcher {
+    fn matches<E>(&self, node: &Save<'_, E>) -> bool {
+        match (&self.kind, node) {
+            (Kind::Any, _) => true,
+            (Kind::AnyString, Save::String(_)) => true,
+            (
+                Kind::AnyNumber,
+                Save::I8(_)
+                | Save::I16(_)
+                | Save::I32(_)
+                | Save::I64(_)
+                | Save::I128(_)
+                | Save::U8(_)
+                | Save::U16(_)
+                | Save::U32(_)
+                | Save::U64(_)
+                | Save::U128(_)
+                | Save::F32(_)
+                | Save::F64(_),
+            ) => true,
+            (Kind::AnyLenSeq(len), Save::Seq(it)) => it.len() == *len,
+            (Kind::Glob(pattern), Save::String(it)) => glob(
+                &pattern.chars().collect::<Vec<_>>(),
+                &it.chars().collect::<Vec<_>>(),
+            ),
+            (Kind::StringSatisfying(f), Save::String(it)) => f(it),
+            _ => false,
+        }
+    }
+}
+
+/// Whether `text` matches the glob `pattern`, in `O(pattern × text)` time.
+fn glob(pattern: &[char], text: &[char]) -> bool {
+    let (mut p, mut t) = (0, 0);
+    // After a mismatch, retry from the last `*`, with it matching one more character.
+    let mut star = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p + 1, t));
+                p += 1
+            }
+            Some(c) if *c == '?' || *c == text[t] => {
+                p += 1;
+                t += 1
+            }
+            _ => match star {
+                Some((after, matched)) => {
+                    star = Some((after, matched + 1));
+                    (p, t) = (after, matched + 1)
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|it| *it == '*')
+}
+
+impl<'a, E> Save<'a, E> {
+    /// Whether this tree matches `pattern`, which may contain placeholders.
+    ///
+    /// See the [`matcher`](crate::matcher) module for more.
+    pub fn matches(&self, pattern: &Save<'_, Matcher>) -> bool {
+        self.eq_with(pattern, &|node, pattern| match pattern {
+            Save::Error(matcher) => Some(matcher.matches(node)),
+            _ => None,
+        })
+    }
+}
@@ -0,0 +1,310 @@
+use crate::{Path, Save, Segment};
+use core::convert::Infallible;
+use std::borrow::Cow;
+
+/// The result of a [three-way merge](Save::merge3).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Merge<'a, E = Infallible> {
+    /// The merged tree.
+    ///
+    /// Conflicting nodes take the value from `ours`.
+    pub tree: Save<'a, E>,
+    /// Nodes which were changed differently on each side, in pre-order.
+    pub conflicts: Vec<Conflict<'a, E>>,
+}
+
+impl<'a, E> Merge<'a, E> {
+    /// Returns `true` if there were no conflicts.
+    pub fn is_clean(&self) -> bool {
+        self.conflicts.is_empty()
+    }
+}
+
+/// A node which was changed differently by each side of a [three-way merge](Save::merge3).
+///
+/// Each candidate is [`None`] if the node was absent from that tree.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conflict<'a, E = Infallible> {
+    /// Location of the node in the merged tree.
+    pub path: Path<'a>,
+    /// The common ancestor.
+    pub base: Option<Save<'a, E>>,
+    /// Our side, which is kept in the merged tree.
+    pub ours: Option<Save<'a, E>>,
+    /// Their side.
+    pub theirs: Option<Save<'a, E>>,
+}
+
+impl<'a, E> Save<'a, E>
+where
+    E: Clone + PartialEq,
+{
+    /// Merge the changes from `base` to `ours` and from `base` to `theirs`.
+    ///
+    /// A node changed on only one side takes that side's value.
+    /// Struct fields are matched by name, map entries by key, and the elements
+    /// of sequences and tuples by index, if their lengths agree.
+    /// Any other node changed differently on both sides is a [`Conflict`].
+    ///
+    /// Floats are compared by their bits, so an unchanged NaN is not a change.
+    ///
+    /// ```
+    /// # use serde_save::{Conflict, Path, Save};
+    /// let base = Save::<std::convert::Infallible>::strukt("Config", [("port", 80), ("workers", 4)]);
+    /// let ours = Save::strukt("Config", [("port", 8080), ("workers", 4)]);
+    /// let theirs = Save::strukt("Config", [("port", 80), ("workers", 8)]);
+    ///
+    /// let merge = Save::merge3(&base, &ours, &theirs);
+    /// assert!(merge.is_clean());
+    /// assert_eq!(merge.tree, Save::strukt("Config", [("port", 8080), ("workers", 8)]));
+    ///
+    /// let ours = Save::strukt("Config", [("port", 8080), ("workers", 4)]);
+    /// let theirs = Save::strukt("Config", [("port", 9090), ("workers", 4)]);
+    /// let merge = Save::merge3(&base, &ours, &theirs);
+    /// assert_eq!(
+    ///     merge.conflicts,
+    ///     [Conflict {
+    ///         path: Path::parse("port").unwrap(),
+    ///         base: Some(Save::I32(80)),
+    ///         ours: Some(Save::I32(8080)),
+    ///         theirs: Some(Save::I32(9090)),
+    ///     }]
+    /// );
+    ///
+    /// let base = Save::<std::convert::Infallible>::from_iter([f64::NAN, 1.0]);
+    /// let ours = Save::from_iter([f64::NAN, 2.0]);
+    /// assert!(Save::merge3(&base, &ours, &base).is_clean());
+    /// ```
+    pub fn merge3(base: &Self, ours: &Self, theirs: &Self) -> Merge<'a, E> {
+        let mut conflicts = Vec::new();
+        let tree = merge(&mut Path::new(), base, ours, theirs, &mut conflicts);
+        Merge { tree, conflicts }
+    }
+}
+
+/// Resolve a node which was changed on at most one side.
+fn pick<T: Same + Clone>(base: &T, ours: &T, theirs: &T) -> Option<T> {
+    if ours.same(theirs) || theirs.same(base) {
+        return Some(ours.clone());
+    }
+    if ours.same(base) {
+        return Some(theirs.clone());
+    }
+    None
+}
+
+/// Equality for [`pick`], which compares floats by their bits,
+/// like [`Save::cmp_canonical`].
+trait Same {
+    fn same(&self, other: &Self) -> bool;
+}
+
+impl<E: PartialEq> Same for Save<'_, E> {
+    fn same(&self, other: &Self) -> bool {
+        self.eq_with(other, &|l, r| match (l, r) {
+            (Save::F32(l), Save::F32(r)) => Some(l.total_cmp(r).is_eq()),
+            (Save::F64(l), Save::F64(r)) => Some(l.total_cmp(r).is_eq()),
+            (Save::Error(l), Save::Error(r)) => Some(l == r),
+            _ => None,
+        })
+    }
+}
+
+impl<T: Same + ?Sized> Same for &T {
+    fn same(&self, other: &Self) -> bool {
+        (**self).same(*other)
+    }
+}
+
+impl<T: Same> Same for Option<T> {
+    fn same(&self, other: &Self) -> bool {
+        match (self, other) {
+            (None, None) => true,
+            (Some(l), Some(r)) => l.same(r),
+            _ => false,
+        }
+    }
+}
+
+fn conflict<'a, E: Clone>(
+    path: &Path<'a>,
+    base: Option<&Save<'a, E>>,
+    ours: Option<&Save<'a, E>>,
+    theirs: Option<&Save<'a, E>>,
+    conflicts: &mut Vec<Conflict<'a, E>>,
+) {
+    conflicts.push(Conflict {
+        path: path.clone(),
+        base: base.cloned(),
+        ours: ours.cloned(),
+        theirs: theirs.cloned(),
+    })
+}
+
+fn merge<'a, E: Clone + PartialEq>(
+    path: &mut Path<'a>,
+    base: &Save<'a, E>,
+    ours: &Save<'a, E>,
+    theirs: &Save<'a, E>,
+    conflicts: &mut Vec<Conflict<'a, E>>,
+) -> Save<'a, E> {
+    if let Some(it) = pick(base, ours, theirs) {
+        return it;
+    }
+    let mut merged = ours.clone();
+    let merged_children = same_header(base, ours)
+        && same_header(base, theirs)
+        && children(path, base, &mut merged, theirs, conflicts);
+    if !merged_children {
+        conflict(path, Some(base), Some(ours), Some(theirs), conflicts);
+        return ours.clone();
+    }
+    merged
+}
+
+/// Merge the children of `base` and `theirs` into `ours`, which has the
+/// same header as both.
+///
+/// Returns `false` if the children could not be matched up.
+fn children<'a, E: Clone + PartialEq>(
+    path: &mut Path<'a>,
+    base: &Save<'a, E>,
+    ours: &mut Save<'a, E>,
+    theirs: &Save<'a, E>,
+    conflicts: &mut Vec<Conflict<'a, E>>,
+) -> bool {
+    match ours {
+        Save::Option(Some(it))
+        | Save::NewTypeStruct { value: it, .. }
+        | Save::NewTypeVariant { value: it, .. } => {
+            let (Some((_, base)), Some((_, theirs))) =
+                (base.children().next(), theirs.children().next())
+            else {
+                return false;
+            };
+            **it = merge(path, base, it, theirs, conflicts)
+        }
+        Save::Seq(it)
+        | Save::Tuple(it)
+        | Save::TupleStruct { values: it, .. }
+        | Save::TupleVariant { values: it, .. } => {
+            let (base, theirs) = (base.elements().as_slice(), theirs.elements().as_slice());
+            if base.len() != it.len() || theirs.len() != it.len() {
+                return false;
+            }
+            for (ix, it) in it.iter_mut().enumerate() {
+                path.push(Segment::Index(ix));
+                *it = merge(path, &base[ix], it, &theirs[ix], conflicts);
+                path.pop();
+            }
+        }
+        Save::Struct { fields, .. } | Save::StructVariant { fields, .. } => {
+            let (
+                Save::Struct { fields: base, .. } | Save::StructVariant { fields: base, .. },
+                Save::Struct { fields: theirs, .. } | Save::StructVariant { fields: theirs, .. },
+            ) = (base, theirs)
+            else {
+                return false;
+            };
+            let ours = core::mem::take(fields);
+            let mut names = ours.iter().map(|(it, _)| *it).collect::<Vec<_>>();
+            for (name, _) in theirs.iter().chain(base) {
+                if !names.contains(name) {
+                    names.push(name)
+                }
+            }
+            for name in names {
+                let (b, o, t) = (field(base, name), field(&ours, name), field(theirs, name));
+                path.push(Segment::Field(Cow::Borrowed(name)));
+                let value = match (pick(&b, &o, &t), (b, o, t)) {
+                    (Some(it), _) => it.cloned(),
+                    (None, (Some(Some(b)), Some(Some(o)), Some(Some(t)))) => {
+                        Some(Some(merge(path, b, o, t, conflicts)))
+                    }
+                    (None, _) => {
+                        let flat = Option::as_ref;
+                        conflict(
+                            path,
+                            b.and_then(flat),
+                            o.and_then(flat),
+                            t.and_then(flat),
+                            conflicts,
+                        );
+                        o.cloned()
+                    }
+                };
+                path.pop();
+                if let Some(value) = value {
+                    fields.push((name, value))
+                }
+            }
+        }
+        Save::Map(entries) => {
+            let (Save::Map(base), Save::Map(theirs)) = (base, theirs) else {
+                return false;
+            };
+            let ours = core::mem::take(entries);
+            let mut keys = ours.iter().map(|(k, _)| k).collect::<Vec<_>>();
+            for (k, _) in theirs.iter().chain(base) {
+                if !keys.contains(&k) {
+                    keys.push(k)
+                }
+            }
+            for key in keys {
+                let (b, o, t) = (entry(base, key), entry(&ours, key), entry(theirs, key));
+                path.push(Segment::Index(entries.len()));
+                let value = match (pick(&b, &o, &t), (b, o, t)) {
+                    (Some(it), _) => it.cloned(),
+                    (None, (Some(b), Some(o), Some(t))) => Some(merge(path, b, o, t, conflicts)),
+                    (None, _) => {
+                        conflict(path, b, o, t, conflicts);
+                        o.cloned()
+                    }
+                };
+                path.pop();
+                if let Some(value) = value {
+                    entries.push((key.clone(), value))
+                }
+            }
+        }
+        _ => return false,
+    }
+    true
+}
+
+/// The value of the field called `name`, which is [`None`] if the field is
+/// absent, and `Some(None)` if it was skipped.
+#[allow(clippy::type_complexity)]
+fn field<'s, 'a, E>(
+    fields: &'s [(&'a str, Option<Save<'a, E>>)],
+    name: &str,
+) -> Option<&'s Option<Save<'a, E>>> {
+    fields.iter().find(|(it, _)| *it == name).map(|(_, it)| it)
+}
+
+/// The value of the first entry with the given `key`.
+fn entry<'s, 'a, E: PartialEq>(
+    entries: &'s [(Save<'a, E>, Save<'a, E>)],
+    key: &Save<'a, E>,
+) -> Option<&'s Save<'a, E>> {
+    entries.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+}
+
+/// Whether two nodes are the same kind of node, with the same name.
+fn same_header<E>(left: &Save<'_, E>, right: &Save<'_, E>) -> bool {
+    match (left, right) {
+        (Save::Option(Some(_)), Save::Option(Some(_)))
+        | (Save::Seq(_), Save::Seq(_))
+        | (Save::Tuple(_), Save::Tuple(_))
+        | (Save::Map(_), Save::Map(_)) => true,
+        (Save::NewTypeStruct { name: l, .. }, Save::NewTypeStruct { name: r, .. })
+        | (Save::TupleStruct { name: l, .. }, Save::TupleStruct { name: r, .. })
+        | (Save::Struct { name: l, .. }, Save::Struct { name: r, .. }) => l == r,
+        (Save::NewTypeVariant { variant: l, .. }, Save::NewTypeVariant { variant: r, .. })
+        | (Save::TupleVariant { variant: l, .. }, Save::TupleVariant { variant: r, .. })
+        | (Save::StructVariant { variant: l, .. }, Save::StructVariant { variant: r, .. }) => {
+            l == r
+        }
+        _ => false,
+    }
+}
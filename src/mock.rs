@@ -0,0 +1,887 @@
+//! Mock-testing for [`Serialize`] implementations.
+//!
+//! Declare the calls a [`Serialize`] implementation should make as an
+//! [`Expect`]ation, and get a [`serde::Serializer`] which fails with a precise
+//! message on the first deviation.
+//!
+//! ```
+//! # use serde::{ser::SerializeStruct as _, Serialize, Serializer};
+//! # use serde_save::{mock::{any, eq, expect_struct}, Save};
+//! struct Foo;
+//!
+//! impl Serialize for Foo {
+//!     fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+//!         let mut strukt = serializer.serialize_struct("Foo", 2)?;
+//!         strukt.serialize_field("a", &1u8)?;
+//!         strukt.serialize_field("c", "oops")?;
+//!         strukt.end()
+//!     }
+//! }
+//!
+//! let expect = expect_struct("Foo", 2)
+//!     .field("a", eq(Save::U8(1)))
+//!     .field("b", any());
+//! assert_eq!(
+//!     Foo.serialize(expect.serializer()).unwrap_err().to_string(),
+//!     r#"at <root>: expected field "b", got field "c""#
+//! );
+//! ```
+//!
+//! Each node is matched as soon as it is serialized,
+//! except for nodes expected by a matcher like [`eq`], which are [saved](crate::save)
+//! in full before being matched.
+
+use crate::{
+    diff::DisplayPath,
+    imp::{self, ShortCircuit},
+    Error, Path, Save, Segment, Serializer, Variant,
+};
+use core::fmt;
+use serde::{
+    ser::{
+        Error as _, SerializeMap as _, SerializeSeq as _, SerializeStruct as _,
+        SerializeStructVariant as _, SerializeTuple as _, SerializeTupleStruct as _,
+        SerializeTupleVariant as _,
+    },
+    Serialize,
+};
+use std::borrow::Cow;
+
+/// The expected calls for a node and its children.
+///
+/// Create one with a matcher like [`eq`], or a function like [`expect_struct`].
+pub struct Expect {
+    node: Node,
+}
+
+enum Node {
+    Match(Matcher),
+    Skipped,
+    Some(Box<Expect>),
+    NewTypeStruct {
+        name: &'static str,
+        value: Box<Expect>,
+    },
+    NewTypeVariant {
+        name: &'static str,
+        variant: &'static str,
+        value: Box<Expect>,
+    },
+    Seq(Vec<Expect>),
+    Tuple(Vec<Expect>),
+    TupleStruct {
+        name: &'static str,
+        elements: Vec<Expect>,
+    },
+    TupleVariant {
+        name: &'static str,
+        variant: &'static str,
+        elements: Vec<Expect>,
+    },
+    Map(Vec<(Expect, Expect)>),
+    Struct {
+        name: &'static str,
+        len: usize,
+        fields: Vec<(&'static str, Expect)>,
+    },
+    StructVariant {
+        name: &'static str,
+        variant: &'static str,
+        len: usize,
+        fields: Vec<(&'static str, Expect)>,
+    },
+}
+
+#[allow(clippy::type_complexity)]
+struct Matcher {
+    description: String,
+    f: Box<dyn Fn(&Save<'static>) -> bool + Send + Sync>,
+}
+
+impl fmt::Debug for Expect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.node.describe())
+    }
+}
+
+/// Match any node.
+pub fn any() -> Expect {
+    satisfies("anything", |_| true)
+}
+
+/// Match a node which [saves](crate::save) as `save`.
+pub fn eq(save: Save<'static>) -> Expect {
+    let description = save.pretty().to_string();
+    satisfies(description, move |it| *it == save)
+}
+
+/// Match a node for which `f` returns `true`.
+///
+/// `description` is used in failure messages.
+pub fn satisfies(
+    description: impl Into<String>,
+    f: impl Fn(&Save<'static>) -> bool + Send + Sync + 'static,
+) -> Expect {
+    Expect::new(Node::Match(Matcher {
+        description: description.into(),
+        f: Box::new(f),
+    }))
+}
+
+/// Match a struct field which is [skipped](serde::ser::SerializeStruct::skip_field).
+pub fn skipped() -> Expect {
+    Expect::new(Node::Skipped)
+}
+
+/// Expect [`serialize_some`](serde::Serializer::serialize_some).
+pub fn expect_some(value: Expect) -> Expect {
+    Expect::new(Node::Some(Box::new(value)))
+}
+
+/// Expect [`serialize_newtype_struct`](serde::Serializer::serialize_newtype_struct).
+pub fn expect_newtype_struct(name: &'static str, value: Expect) -> Expect {
+    Expect::new(Node::NewTypeStruct {
+        name,
+        value: Box::new(value),
+    })
+}
+
+/// Expect [`serialize_newtype_variant`](serde::Serializer::serialize_newtype_variant).
+pub fn expect_newtype_variant(name: &'static str, variant: &'static str, value: Expect) -> Expect {
+    Expect::new(Node::NewTypeVariant {
+        name,
+        variant,
+        value: Box::new(value),
+    })
+}
+
+/// Expect [`serialize_seq`](serde::Serializer::serialize_seq),
+/// with elements added by [`Expect::element`].
+///
+/// If a length hint is given, it must be the number of elements.
+///
+/// ```
+/// # use serde_save::mock::{any, expect_seq};
+/// let expect = expect_seq().element(any());
+/// assert!(expect.verify(&vec![1]).is_ok());
+/// assert_eq!(
+///     expect.verify(&vec![1, 2]).unwrap_err().to_string(),
+///     "at <root>: expected a sequence of length 1, got a sequence of length 2"
+/// );
+/// ```
+pub fn expect_seq() -> Expect {
+    Expect::new(Node::Seq(Vec::new()))
+}
+
+/// Expect [`serialize_tuple`](serde::Serializer::serialize_tuple),
+/// with elements added by [`Expect::element`].
+pub fn expect_tuple() -> Expect {
+    Expect::new(Node::Tuple(Vec::new()))
+}
+
+/// Expect [`serialize_tuple_struct`](serde::Serializer::serialize_tuple_struct),
+/// with elements added by [`Expect::element`].
+///
+/// ```
+/// # use serde_save::{mock::{eq, expect_tuple_struct}, Save};
+/// #[derive(serde::Serialize)]
+/// struct Point(u8, u8);
+///
+/// let expect = expect_tuple_struct("Point")
+///     .element(eq(Save::U8(1)))
+///     .element(eq(Save::U8(2)));
+/// assert!(expect.verify(&Point(1, 2)).is_ok());
+/// assert_eq!(
+///     expect.verify(&Point(1, 3)).unwrap_err().to_string(),
+///     "at [1]: expected u8(2), got u8(3)"
+/// );
+/// ```
+pub fn expect_tuple_struct(name: &'static str) -> Expect {
+    Expect::new(Node::TupleStruct {
+        name,
+        elements: Vec::new(),
+    })
+}
+
+/// Expect [`serialize_tuple_variant`](serde::Serializer::serialize_tuple_variant),
+/// with elements added by [`Expect::element`].
+pub fn expect_tuple_variant(name: &'static str, variant: &'static str) -> Expect {
+    Expect::new(Node::TupleVariant {
+        name,
+        variant,
+        elements: Vec::new(),
+    })
+}
+
+/// Expect [`serialize_map`](serde::Serializer::serialize_map),
+/// with entries added by [`Expect::entry`].
+///
+/// If a length hint is given, it must be the number of entries.
+pub fn expect_map() -> Expect {
+    Expect::new(Node::Map(Vec::new()))
+}
+
+/// Expect [`serialize_struct`](serde::Serializer::serialize_struct)
+/// with the given `len`, and fields added by [`Expect::field`].
+pub fn expect_struct(name: &'static str, len: usize) -> Expect {
+    Expect::new(Node::Struct {
+        name,
+        len,
+        fields: Vec::new(),
+    })
+}
+
+/// Expect [`serialize_struct_variant`](serde::Serializer::serialize_struct_variant)
+/// with the given `len`, and fields added by [`Expect::field`].
+pub fn expect_struct_variant(name: &'static str, variant: &'static str, len: usize) -> Expect {
+    Expect::new(Node::StructVariant {
+        name,
+        variant,
+        len,
+        fields: Vec::new(),
+    })
+}
+
+impl Expect {
+    fn new(node: Node) -> Self {
+        Self { node }
+    }
+    /// Expect another element of a sequence, tuple, tuple struct or tuple variant.
+    ///
+    /// # Panics
+    /// - If this is not a sequence, tuple, tuple struct or tuple variant.
+    pub fn element(mut self, element: Expect) -> Self {
+        match &mut self.node {
+            Node::Seq(it)
+            | Node::Tuple(it)
+            | Node::TupleStruct { elements: it, .. }
+            | Node::TupleVariant { elements: it, .. } => it.push(element),
+            _ => panic!("`element` called on {}", self.node.describe()),
+        }
+        self
+    }
+    /// Expect another entry of a map.
+    ///
+    /// # Panics
+    /// - If this is not a map.
+    pub fn entry(mut self, key: Expect, value: Expect) -> Self {
+        match &mut self.node {
+            Node::Map(it) => it.push((key, value)),
+            _ => panic!("`entry` called on {}", self.node.describe()),
+        }
+        self
+    }
+    /// Expect another field of a struct.
+    ///
+    /// # Panics
+    /// - If this is not a struct.
+    pub fn field(mut self, name: &'static str, value: Expect) -> Self {
+        match &mut self.node {
+            Node::Struct { fields, .. } | Node::StructVariant { fields, .. } => {
+                fields.push((name, value))
+            }
+            _ => panic!("`field` called on {}", self.node.describe()),
+        }
+        self
+    }
+    /// A [`serde::Serializer`] which checks each call against this expectation.
+    pub fn serializer(&self) -> Mock<'_> {
+        Mock {
+            node: &self.node,
+            path: Path::new(),
+        }
+    }
+    /// Check that `t` serializes as expected.
+    ///
+    /// ```
+    /// # use serde_save::{mock::{any, eq, expect_seq, expect_some, expect_struct, skipped}, Save};
+    /// #[derive(serde::Serialize)]
+    /// struct User {
+    ///     id: u32,
+    ///     #[serde(skip_serializing_if = "Option::is_none")]
+    ///     email: Option<String>,
+    ///     tags: Vec<Option<&'static str>>,
+    /// }
+    ///
+    /// // `len` excludes skipped fields.
+    /// let expect = expect_struct("User", 2)
+    ///     .field("id", any())
+    ///     .field("email", skipped())
+    ///     .field("tags", expect_seq().element(expect_some(eq(Save::string("admin")))));
+    ///
+    /// let user = User { id: 1, email: None, tags: vec![Some("admin")] };
+    /// assert!(expect.verify(&user).is_ok());
+    ///
+    /// let user = User { id: 1, email: None, tags: vec![None] };
+    /// assert_eq!(
+    ///     expect.verify(&user).unwrap_err().to_string(),
+    ///     "at tags[0]: expected Some, got None"
+    /// );
+    /// ```
+    pub fn verify<T: Serialize + ?Sized>(&self, t: &T) -> Result<(), Error> {
+        t.serialize(self.serializer())
+    }
+}
+
+impl Node {
+    fn describe(&self) -> String {
+        match self {
+            Node::Match(it) => it.description.clone(),
+            Node::Skipped => String::from("a skipped field"),
+            Node::Some(_) => String::from("Some"),
+            Node::NewTypeStruct { name, .. } => format!("newtype struct {:?}", name),
+            Node::NewTypeVariant { name, variant, .. } => {
+                format!("newtype variant {}::{}", name, variant)
+            }
+            Node::Seq(it) => format!("a sequence of length {}", it.len()),
+            Node::Tuple(it) => format!("a tuple of length {}", it.len()),
+            Node::TupleStruct { name, elements } => {
+                format!("tuple struct {:?} of length {}", name, elements.len())
+            }
+            Node::TupleVariant {
+                name,
+                variant,
+                elements,
+            } => format!(
+                "tuple variant {}::{} of length {}",
+                name,
+                variant,
+                elements.len()
+            ),
+            Node::Map(it) => format!("a map of length {}", it.len()),
+            Node::Struct { name, len, .. } => format!("struct {:?} with {} fields", name, len),
+            Node::StructVariant {
+                name, variant, len, ..
+            } => format!("struct variant {}::{} with {} fields", name, variant, len),
+        }
+    }
+}
+
+/// A [`serde::Serializer`] which checks each call against an [`Expect`]ation,
+/// see [`Expect::serializer`].
+pub struct Mock<'e> {
+    node: &'e Node,
+    path: Path<'static>,
+}
+
+impl<'e> Mock<'e> {
+    fn fail(&self, expected: &dyn fmt::Display, got: &dyn fmt::Display) -> Error {
+        Error::custom(format_args!(
+            "at {}: expected {}, got {}",
+            DisplayPath(&self.path),
+            expected,
+            got
+        ))
+    }
+    fn mismatch(&self, got: &dyn fmt::Display) -> Error {
+        self.fail(&self.node.describe(), got)
+    }
+    fn child(&self, node: &'e Node, segment: Option<Segment<'static>>) -> Self {
+        let mut path = self.path.clone();
+        if let Some(segment) = segment {
+            path.push(segment)
+        }
+        Self { node, path }
+    }
+    /// Check a complete node against a matcher.
+    fn check(&self, matcher: &Matcher, save: Save<'static>) -> Result<(), Error> {
+        match (matcher.f)(&save) {
+            true => Ok(()),
+            false => Err(self.fail(&matcher.description, &save.pretty())),
+        }
+    }
+    fn leaf(self, save: Save<'static>) -> Result<(), Error> {
+        match self.node {
+            Node::Match(it) => self.check(it, save),
+            _ => Err(self.mismatch(&save.pretty())),
+        }
+    }
+    /// Serialize a child `value`, which must match `node`.
+    fn value<T: Serialize + ?Sized>(
+        &self,
+        node: &'e Node,
+        segment: Option<Segment<'static>>,
+        value: &T,
+    ) -> Result<(), Error> {
+        let child = self.child(node, segment);
+        match node {
+            Node::Match(it) => child.check(it, value.serialize(Serializer::new())?),
+            _ => value.serialize(child),
+        }
+    }
+    fn capture(
+        self,
+        capture: impl FnOnce(Serializer) -> Result<Capture, Error>,
+    ) -> Result<Compound<'e>, Error> {
+        let Node::Match(matcher) = self.node else {
+            unreachable!()
+        };
+        Ok(Compound(Inner::Capture {
+            capture: capture(Serializer::new())?,
+            matcher,
+            mock: self,
+        }))
+    }
+}
+
+macro_rules! leaves {
+    ($($method:ident($ty:ty) -> $variant:ident);* $(;)?) => {
+        $(
+            fn $method(self, v: $ty) -> Result<(), Error> {
+                self.leaf(Save::$variant(v.into()))
+            }
+        )*
+    };
+}
+
+impl<'e> serde::Serializer for Mock<'e> {
+    type Ok = ();
+    type Error = Error;
+    type SerializeSeq = Compound<'e>;
+    type SerializeTuple = Compound<'e>;
+    type SerializeTupleStruct = Compound<'e>;
+    type SerializeTupleVariant = Compound<'e>;
+    type SerializeMap = Compound<'e>;
+    type SerializeStruct = Compound<'e>;
+    type SerializeStructVariant = Compound<'e>;
+
+    leaves! {
+        serialize_bool(bool) -> Bool;
+        serialize_i8(i8) -> I8;
+        serialize_i16(i16) -> I16;
+        serialize_i32(i32) -> I32;
+        serialize_i64(i64) -> I64;
+        serialize_i128(i128) -> I128;
+        serialize_u8(u8) -> U8;
+        serialize_u16(u16) -> U16;
+        serialize_u32(u32) -> U32;
+        serialize_u64(u64) -> U64;
+        serialize_u128(u128) -> U128;
+        serialize_f32(f32) -> F32;
+        serialize_f64(f64) -> F64;
+        serialize_char(char) -> Char;
+        serialize_str(&str) -> String;
+        serialize_bytes(&[u8]) -> ByteArray;
+        serialize_unit_struct(&'static str) -> UnitStruct;
+    }
+
+    fn serialize_none(self) -> Result<(), Error> {
+        self.leaf(Save::Option(None))
+    }
+    fn serialize_unit(self) -> Result<(), Error> {
+        self.leaf(Save::Unit)
+    }
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+    ) -> Result<(), Error> {
+        self.leaf(Save::UnitVariant(Variant {
+            name,
+            variant_index,
+            variant,
+        }))
+    }
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<(), Error> {
+        match self.node {
+            Node::Some(it) => self.value(&it.node, None, value),
+            Node::Match(it) => self.check(it, Serializer::new().serialize_some(value)?),
+            _ => Err(self.mismatch(&"Some")),
+        }
+    }
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        match self.node {
+            Node::NewTypeStruct {
+                name: expected,
+                value: it,
+            } if *expected == name => self.value(&it.node, None, value),
+            Node::Match(it) => {
+                self.check(it, Serializer::new().serialize_newtype_struct(name, value)?)
+            }
+            _ => Err(self.mismatch(&format_args!("newtype struct {:?}", name))),
+        }
+    }
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        match self.node {
+            Node::NewTypeVariant {
+                name: expected_name,
+                variant: expected_variant,
+                value: it,
+            } if (*expected_name, *expected_variant) == (name, variant) => {
+                self.value(&it.node, None, value)
+            }
+            Node::Match(it) => self.check(
+                it,
+                Serializer::new().serialize_newtype_variant(name, variant_index, variant, value)?,
+            ),
+            _ => Err(self.mismatch(&format_args!("newtype variant {}::{}", name, variant))),
+        }
+    }
+    fn serialize_seq(self, len: Option<usize>) -> Result<Compound<'e>, Error> {
+        match self.node {
+            Node::Seq(it) if len.is_none() || len == Some(it.len()) => {
+                Ok(Compound(Inner::Expect { mock: self, ix: 0 }))
+            }
+            Node::Match(_) => self.capture(|it| it.serialize_seq(len).map(Capture::Seq)),
+            _ => Err(self.mismatch(&Hint("a sequence", len))),
+        }
+    }
+    fn serialize_tuple(self, len: usize) -> Result<Compound<'e>, Error> {
+        match self.node {
+            Node::Tuple(it) if it.len() == len => Ok(Compound(Inner::Expect { mock: self, ix: 0 })),
+            Node::Match(_) => self.capture(|it| it.serialize_tuple(len).map(Capture::Tuple)),
+            _ => Err(self.mismatch(&format_args!("a tuple of length {}", len))),
+        }
+    }
+    fn serialize_tuple_struct(self, name: &'static str, len: usize) -> Result<Compound<'e>, Error> {
+        match self.node {
+            Node::TupleStruct {
+                name: expected,
+                elements,
+            } if (*expected, elements.len()) == (name, len) => {
+                Ok(Compound(Inner::Expect { mock: self, ix: 0 }))
+            }
+            Node::Match(_) => self.capture(|it| {
+                it.serialize_tuple_struct(name, len)
+                    .map(Capture::TupleStruct)
+            }),
+            _ => Err(self.mismatch(&format_args!("tuple struct {:?} of length {}", name, len))),
+        }
+    }
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Compound<'e>, Error> {
+        match self.node {
+            Node::TupleVariant {
+                name: expected_name,
+                variant: expected_variant,
+                elements,
+            } if (*expected_name, *expected_variant, elements.len()) == (name, variant, len) => {
+                Ok(Compound(Inner::Expect { mock: self, ix: 0 }))
+            }
+            Node::Match(_) => self.capture(|it| {
+                it.serialize_tuple_variant(name, variant_index, variant, len)
+                    .map(Capture::TupleVariant)
+            }),
+            _ => Err(self.mismatch(&format_args!(
+                "tuple variant {}::{} of length {}",
+                name, variant, len
+            ))),
+        }
+    }
+    fn serialize_map(self, len: Option<usize>) -> Result<Compound<'e>, Error> {
+        match self.node {
+            Node::Map(it) if len.is_none() || len == Some(it.len()) => {
+                Ok(Compound(Inner::Expect { mock: self, ix: 0 }))
+            }
+            Node::Match(_) => self.capture(|it| it.serialize_map(len).map(Capture::Map)),
+            _ => Err(self.mismatch(&Hint("a map", len))),
+        }
+    }
+    fn serialize_struct(self, name: &'static str, len: usize) -> Result<Compound<'e>, Error> {
+        match self.node {
+            Node::Struct {
+                name: expected_name,
+                len: expected_len,
+                ..
+            } if (*expected_name, *expected_len) == (name, len) => {
+                Ok(Compound(Inner::Expect { mock: self, ix: 0 }))
+            }
+            Node::Match(_) => {
+                self.capture(|it| it.serialize_struct(name, len).map(Capture::Struct))
+            }
+            _ => Err(self.mismatch(&format_args!("struct {:?} with {} fields", name, len))),
+        }
+    }
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Compound<'e>, Error> {
+        match self.node {
+            Node::StructVariant {
+                name: expected_name,
+                variant: expected_variant,
+                len: expected_len,
+                ..
+            } if (*expected_name, *expected_variant, *expected_len) == (name, variant, len) => {
+                Ok(Compound(Inner::Expect { mock: self, ix: 0 }))
+            }
+            Node::Match(_) => self.capture(|it| {
+                it.serialize_struct_variant(name, variant_index, variant, len)
+                    .map(Capture::StructVariant)
+            }),
+            _ => Err(self.mismatch(&format_args!(
+                "struct variant {}::{} with {} fields",
+                name, variant, len
+            ))),
+        }
+    }
+}
+
+/// Describes a collection with an optional length hint.
+struct Hint(&'static str, Option<usize>);
+
+impl fmt::Display for Hint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.1 {
+            Some(len) => write!(f, "{} of length {}", self.0, len),
+            None => f.write_str(self.0),
+        }
+    }
+}
+
+/// A compound node being serialized to a [`Mock`].
+pub struct Compound<'e>(Inner<'e>);
+
+enum Inner<'e> {
+    /// Children are checked as they are serialized.
+    Expect { mock: Mock<'e>, ix: usize },
+    /// The node is saved in full, then checked against `matcher`.
+    Capture {
+        capture: Capture,
+        matcher: &'e Matcher,
+        mock: Mock<'e>,
+    },
+}
+
+enum Capture {
+    Seq(imp::SerializeSeq<ShortCircuit>),
+    Tuple(imp::SerializeTuple<ShortCircuit>),
+    TupleStruct(imp::SerializeTupleStruct<ShortCircuit>),
+    TupleVariant(imp::SerializeTupleVariant<ShortCircuit>),
+    Map(imp::SerializeMap<ShortCircuit>),
+    Struct(imp::SerializeStruct<ShortCircuit>),
+    StructVariant(imp::SerializeStructVariant<ShortCircuit>),
+}
+
+impl<'e> Compound<'e> {
+    fn element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        let Inner::Expect { mock, ix } = &mut self.0 else {
+            unreachable!()
+        };
+        let (Node::Seq(elements)
+        | Node::Tuple(elements)
+        | Node::TupleStruct { elements, .. }
+        | Node::TupleVariant { elements, .. }) = mock.node
+        else {
+            unreachable!()
+        };
+        let Some(it) = elements.get(*ix) else {
+            return Err(mock.fail(&"no more elements", &format_args!("element [{}]", ix)));
+        };
+        mock.value(&it.node, Some(Segment::Index(*ix)), value)?;
+        *ix += 1;
+        Ok(())
+    }
+    fn key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), Error> {
+        let Inner::Expect { mock, ix } = &mut self.0 else {
+            unreachable!()
+        };
+        let Node::Map(entries) = mock.node else {
+            unreachable!()
+        };
+        let Some((it, _)) = entries.get(*ix) else {
+            return Err(mock.fail(&"no more entries", &format_args!("key {{{}}}", ix)));
+        };
+        mock.value(&it.node, Some(Segment::Key(*ix)), key)
+    }
+    fn value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        let Inner::Expect { mock, ix } = &mut self.0 else {
+            unreachable!()
+        };
+        let Node::Map(entries) = mock.node else {
+            unreachable!()
+        };
+        let Some((_, it)) = entries.get(*ix) else {
+            return Err(mock.fail(&"no more entries", &format_args!("value [{}]", ix)));
+        };
+        mock.value(&it.node, Some(Segment::Index(*ix)), value)?;
+        *ix += 1;
+        Ok(())
+    }
+    /// Check a field called `key`, which has a `value` unless it was skipped.
+    fn field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: Option<&T>,
+    ) -> Result<(), Error> {
+        let Inner::Expect { mock, ix } = &mut self.0 else {
+            unreachable!()
+        };
+        let (Node::Struct { fields, .. } | Node::StructVariant { fields, .. }) = mock.node else {
+            unreachable!()
+        };
+        let got = format!("field {:?}", key);
+        match fields.get(*ix) {
+            Some((name, _)) if *name != key => {
+                return Err(mock.fail(&format_args!("field {:?}", name), &got))
+            }
+            None => return Err(mock.fail(&"no more fields", &got)),
+            Some((_, it)) => {
+                let segment = Some(Segment::Field(Cow::Borrowed(key)));
+                match (&it.node, value) {
+                    (Node::Skipped, None) => {}
+                    (Node::Skipped, Some(_)) => {
+                        return Err(mock.child(&it.node, segment).mismatch(&"a value"))
+                    }
+                    (_, None) => {
+                        return Err(mock.child(&it.node, segment).mismatch(&"a skipped field"))
+                    }
+                    (node, Some(value)) => mock.value(node, segment, value)?,
+                }
+            }
+        }
+        *ix += 1;
+        Ok(())
+    }
+    fn finish(self) -> Result<(), Error> {
+        match self.0 {
+            Inner::Expect { mock, ix } => {
+                let expected = match mock.node {
+                    Node::Seq(it)
+                    | Node::Tuple(it)
+                    | Node::TupleStruct { elements: it, .. }
+                    | Node::TupleVariant { elements: it, .. }
+                        if ix < it.len() =>
+                    {
+                        format!("element [{}]", ix)
+                    }
+                    Node::Map(it) if ix < it.len() => format!("key {{{}}}", ix),
+                    Node::Struct { fields, .. } | Node::StructVariant { fields, .. }
+                        if ix < fields.len() =>
+                    {
+                        format!("field {:?}", fields[ix].0)
+                    }
+                    _ => return Ok(()),
+                };
+                Err(mock.fail(&expected, &"the end"))
+            }
+            Inner::Capture {
+                capture,
+                matcher,
+                mock,
+            } => {
+                let save = match capture {
+                    Capture::Seq(it) => it.end(),
+                    Capture::Tuple(it) => it.end(),
+                    Capture::TupleStruct(it) => it.end(),
+                    Capture::TupleVariant(it) => it.end(),
+                    Capture::Map(it) => it.end(),
+                    Capture::Struct(it) => it.end(),
+                    Capture::StructVariant(it) => it.end(),
+                }?;
+                mock.check(matcher, save)
+            }
+        }
+    }
+}
+
+macro_rules! elements {
+    ($($trait:ident::$method:ident => $capture:ident),* $(,)?) => {
+        $(
+            impl serde::ser::$trait for Compound<'_> {
+                type Ok = ();
+                type Error = Error;
+                fn $method<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+                    match &mut self.0 {
+                        Inner::Capture { capture: Capture::$capture(it), .. } => it.$method(value),
+                        Inner::Capture { .. } => unreachable!(),
+                        Inner::Expect { .. } => self.element(value),
+                    }
+                }
+                fn end(self) -> Result<(), Error> {
+                    self.finish()
+                }
+            }
+        )*
+    };
+}
+
+elements! {
+    SerializeSeq::serialize_element => Seq,
+    SerializeTuple::serialize_element => Tuple,
+    SerializeTupleStruct::serialize_field => TupleStruct,
+    SerializeTupleVariant::serialize_field => TupleVariant,
+}
+
+impl serde::ser::SerializeMap for Compound<'_> {
+    type Ok = ();
+    type Error = Error;
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), Error> {
+        match &mut self.0 {
+            Inner::Capture {
+                capture: Capture::Map(it),
+                ..
+            } => it.serialize_key(key),
+            Inner::Capture { .. } => unreachable!(),
+            Inner::Expect { .. } => self.key(key),
+        }
+    }
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        match &mut self.0 {
+            Inner::Capture {
+                capture: Capture::Map(it),
+                ..
+            } => it.serialize_value(value),
+            Inner::Capture { .. } => unreachable!(),
+            Inner::Expect { .. } => self.value(value),
+        }
+    }
+    fn end(self) -> Result<(), Error> {
+        self.finish()
+    }
+}
+
+macro_rules! fields {
+    ($($trait:ident => $capture:ident),* $(,)?) => {
+        $(
+            impl serde::ser::$trait for Compound<'_> {
+                type Ok = ();
+                type Error = Error;
+                fn serialize_field<T: ?Sized + Serialize>(
+                    &mut self,
+                    key: &'static str,
+                    value: &T,
+                ) -> Result<(), Error> {
+                    match &mut self.0 {
+                        Inner::Capture { capture: Capture::$capture(it), .. } => {
+                            it.serialize_field(key, value)
+                        }
+                        Inner::Capture { .. } => unreachable!(),
+                        Inner::Expect { .. } => self.field(key, Some(value)),
+                    }
+                }
+                fn skip_field(&mut self, key: &'static str) -> Result<(), Error> {
+                    match &mut self.0 {
+                        Inner::Capture { capture: Capture::$capture(it), .. } => it.skip_field(key),
+                        Inner::Capture { .. } => unreachable!(),
+                        Inner::Expect { .. } => self.field::<()>(key, None),
+                    }
+                }
+                fn end(self) -> Result<(), Error> {
+                    self.finish()
+                }
+            }
+        )*
+    };
+}
+
+fields!(SerializeStruct => Struct, SerializeStructVariant => StructVariant);
@@ -0,0 +1,387 @@
+use crate::{Path, Save, Segment, Variant};
+use core::convert::Infallible;
+use std::borrow::Cow;
+
+/// The name given to variants by [`MutationKind::ChangeVariant`].
+const UNKNOWN_VARIANT: &str = "__unknown_variant";
+/// The name given to fields by [`MutationKind::InjectElement`].
+const UNKNOWN_FIELD: &str = "__unknown_field";
+
+/// A malformed variant of a [`Save`] tree.
+///
+/// See [`Save::mutations`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mutation<'a, E = Infallible> {
+    /// What was changed.
+    pub kind: MutationKind,
+    /// The location of the change in [`Self::save`].
+    pub path: Path<'a>,
+    /// The whole tree, with the mutation applied.
+    pub save: Save<'a, E>,
+}
+
+/// A kind of [`Mutation`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum MutationKind {
+    /// A struct field was removed.
+    DropField,
+    /// An enum variant was replaced with one named `__unknown_variant`,
+    /// with index [`u32::MAX`].
+    ChangeVariant,
+    /// A number was replaced with the same value in the next larger type,
+    /// e.g [`Save::I32`] to [`Save::I64`].
+    Widen,
+    /// A number was replaced with the same value in the next smaller type,
+    /// e.g [`Save::I32`] to [`Save::I16`].
+    ///
+    /// Only generated if the value fits.
+    Narrow,
+    /// An extra item was appended to a container:
+    /// - Sequences and tuples repeat their last element (or get a [`Save::Unit`]).
+    /// - Maps repeat their last entry.
+    /// - Structs get a field named `__unknown_field`, with a [`Save::Unit`] value.
+    InjectElement,
+}
+
+impl<'a, E: Clone> Save<'a, E> {
+    /// Systematically generate malformed variants of this tree,
+    /// for checking that deserializers reject or tolerate them as intended.
+    ///
+    /// Each [`Mutation`] changes a single node.
+    ///
+    /// ```
+    /// # use serde_save::{MutationKind, Save};
+    /// let save = Save::<std::convert::Infallible>::strukt("Point", [("x", 1), ("y", 2)]);
+    /// let mutations = save.mutations();
+    /// assert_eq!(
+    ///     mutations
+    ///         .iter()
+    ///         .map(|it| (it.kind, it.path.to_string()))
+    ///         .collect::<Vec<_>>(),
+    ///     [
+    ///         (MutationKind::DropField, String::from("x")),
+    ///         (MutationKind::DropField, String::from("y")),
+    ///         (MutationKind::InjectElement, String::from("__unknown_field")),
+    ///         (MutationKind::Widen, String::from("x")),
+    ///         (MutationKind::Narrow, String::from("x")),
+    ///         (MutationKind::Widen, String::from("y")),
+    ///         (MutationKind::Narrow, String::from("y")),
+    ///     ]
+    /// );
+    /// assert_eq!(
+    ///     mutations[3].save,
+    ///     Save::strukt("Point", [("x", Save::I64(1)), ("y", Save::I32(2))])
+    /// );
+    /// ```
+    pub fn mutations(&self) -> Vec<Mutation<'a, E>> {
+        let mut mutations = Vec::new();
+        mutate(&mut Path::new(), self, &mut |path, kind, save| {
+            mutations.push(Mutation {
+                kind,
+                path: path.clone(),
+                save,
+            })
+        });
+        mutations
+    }
+}
+
+/// Call `emit` with every mutation of `node`, in pre-order.
+///
+/// Mutations of children are passed up to their parent, which rebuilds itself.
+fn mutate<'a, E: Clone>(
+    path: &mut Path<'a>,
+    node: &Save<'a, E>,
+    emit: &mut dyn FnMut(&Path<'a>, MutationKind, Save<'a, E>),
+) {
+    local(path, node, emit);
+    match node {
+        Save::Option(Some(it)) => mutate(path, it, &mut |path, kind, it| {
+            emit(path, kind, Save::Option(Some(Box::new(it))))
+        }),
+        Save::NewTypeStruct { name, value } => mutate(path, value, &mut |path, kind, it| {
+            emit(
+                path,
+                kind,
+                Save::NewTypeStruct {
+                    name,
+                    value: Box::new(it),
+                },
+            )
+        }),
+        Save::NewTypeVariant { variant, value } => mutate(path, value, &mut |path, kind, it| {
+            emit(
+                path,
+                kind,
+                Save::NewTypeVariant {
+                    variant: *variant,
+                    value: Box::new(it),
+                },
+            )
+        }),
+        Save::Seq(it) => elements(path, it, emit, &Save::Seq),
+        Save::Tuple(it) => elements(path, it, emit, &Save::Tuple),
+        Save::TupleStruct { name, values } => elements(path, values, emit, &|values| {
+            Save::TupleStruct { name, values }
+        }),
+        Save::TupleVariant { variant, values } => {
+            elements(path, values, emit, &|values| Save::TupleVariant {
+                variant: *variant,
+                values,
+            })
+        }
+        Save::Map(entries) => {
+            for (ix, (k, v)) in entries.iter().enumerate() {
+                path.push(Segment::Key(ix));
+                mutate(path, k, &mut |path, kind, it| {
+                    let mut entries = entries.clone();
+                    entries[ix].0 = it;
+                    emit(path, kind, Save::Map(entries))
+                });
+                path.pop();
+                path.push(Segment::Index(ix));
+                mutate(path, v, &mut |path, kind, it| {
+                    let mut entries = entries.clone();
+                    entries[ix].1 = it;
+                    emit(path, kind, Save::Map(entries))
+                });
+                path.pop();
+            }
+        }
+        Save::Struct { name, fields } => {
+            self::fields(path, fields, emit, &|fields| Save::Struct { name, fields })
+        }
+        Save::StructVariant { variant, fields } => {
+            self::fields(path, fields, emit, &|fields| Save::StructVariant {
+                variant: *variant,
+                fields,
+            })
+        }
+        _ => {}
+    }
+}
+
+fn elements<'a, E: Clone>(
+    path: &mut Path<'a>,
+    elements: &[Save<'a, E>],
+    emit: &mut dyn FnMut(&Path<'a>, MutationKind, Save<'a, E>),
+    rebuild: &dyn Fn(Vec<Save<'a, E>>) -> Save<'a, E>,
+) {
+    for (ix, it) in elements.iter().enumerate() {
+        path.push(Segment::Index(ix));
+        mutate(path, it, &mut |path, kind, it| {
+            let mut elements = elements.to_vec();
+            elements[ix] = it;
+            emit(path, kind, rebuild(elements))
+        });
+        path.pop();
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn fields<'a, E: Clone>(
+    path: &mut Path<'a>,
+    fields: &[(&'a str, Option<Save<'a, E>>)],
+    emit: &mut dyn FnMut(&Path<'a>, MutationKind, Save<'a, E>),
+    rebuild: &dyn Fn(Vec<(&'a str, Option<Save<'a, E>>)>) -> Save<'a, E>,
+) {
+    for (ix, (name, it)) in fields.iter().enumerate() {
+        let Some(it) = it else { continue };
+        path.push(Segment::Field(Cow::Borrowed(name)));
+        mutate(path, it, &mut |path, kind, it| {
+            let mut fields = fields.to_vec();
+            fields[ix].1 = Some(it);
+            emit(path, kind, rebuild(fields))
+        });
+        path.pop();
+    }
+}
+
+/// Mutations which replace `node` itself.
+fn local<'a, E: Clone>(
+    path: &Path<'a>,
+    node: &Save<'a, E>,
+    emit: &mut dyn FnMut(&Path<'a>, MutationKind, Save<'a, E>),
+) {
+    if let Some(it) = widen(node) {
+        emit(path, MutationKind::Widen, it)
+    }
+    if let Some(it) = narrow(node) {
+        emit(path, MutationKind::Narrow, it)
+    }
+    let unknown = |it: &Variant<'a>| Variant {
+        name: it.name,
+        variant_index: u32::MAX,
+        variant: UNKNOWN_VARIANT,
+    };
+    match node {
+        Save::UnitVariant(variant) => emit(
+            path,
+            MutationKind::ChangeVariant,
+            Save::UnitVariant(unknown(variant)),
+        ),
+        Save::NewTypeVariant { variant, value } => emit(
+            path,
+            MutationKind::ChangeVariant,
+            Save::NewTypeVariant {
+                variant: unknown(variant),
+                value: value.clone(),
+            },
+        ),
+        Save::TupleVariant { variant, values } => emit(
+            path,
+            MutationKind::ChangeVariant,
+            Save::TupleVariant {
+                variant: unknown(variant),
+                values: values.clone(),
+            },
+        ),
+        Save::StructVariant { variant, fields } => emit(
+            path,
+            MutationKind::ChangeVariant,
+            Save::StructVariant {
+                variant: unknown(variant),
+                fields: fields.clone(),
+            },
+        ),
+        _ => {}
+    }
+    match node {
+        Save::Seq(it) => emit(
+            &path.join(Segment::Index(it.len())),
+            MutationKind::InjectElement,
+            Save::Seq(injected(it)),
+        ),
+        Save::Tuple(it) => emit(
+            &path.join(Segment::Index(it.len())),
+            MutationKind::InjectElement,
+            Save::Tuple(injected(it)),
+        ),
+        Save::TupleStruct { name, values } => emit(
+            &path.join(Segment::Index(values.len())),
+            MutationKind::InjectElement,
+            Save::TupleStruct {
+                name,
+                values: injected(values),
+            },
+        ),
+        Save::TupleVariant { variant, values } => emit(
+            &path.join(Segment::Index(values.len())),
+            MutationKind::InjectElement,
+            Save::TupleVariant {
+                variant: *variant,
+                values: injected(values),
+            },
+        ),
+        Save::Map(entries) => {
+            if let Some(last) = entries.last() {
+                let mut entries = entries.clone();
+                entries.push(last.clone());
+                emit(
+                    &path.join(Segment::Index(entries.len() - 1)),
+                    MutationKind::InjectElement,
+                    Save::Map(entries),
+                )
+            }
+        }
+        Save::Struct { name, fields } => {
+            dropped(path, fields, emit, &|fields| Save::Struct { name, fields });
+            emit(
+                &path.join(Segment::Field(Cow::Borrowed(UNKNOWN_FIELD))),
+                MutationKind::InjectElement,
+                Save::Struct {
+                    name,
+                    fields: unknown_field(fields),
+                },
+            )
+        }
+        Save::StructVariant { variant, fields } => {
+            dropped(path, fields, emit, &|fields| Save::StructVariant {
+                variant: *variant,
+                fields,
+            });
+            emit(
+                &path.join(Segment::Field(Cow::Borrowed(UNKNOWN_FIELD))),
+                MutationKind::InjectElement,
+                Save::StructVariant {
+                    variant: *variant,
+                    fields: unknown_field(fields),
+                },
+            )
+        }
+        _ => {}
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn dropped<'a, E: Clone>(
+    path: &Path<'a>,
+    fields: &[(&'a str, Option<Save<'a, E>>)],
+    emit: &mut dyn FnMut(&Path<'a>, MutationKind, Save<'a, E>),
+    rebuild: &dyn Fn(Vec<(&'a str, Option<Save<'a, E>>)>) -> Save<'a, E>,
+) {
+    for (ix, (name, it)) in fields.iter().enumerate() {
+        if it.is_none() {
+            continue;
+        }
+        let mut fields = fields.to_vec();
+        fields.remove(ix);
+        emit(
+            &path.join(Segment::Field(Cow::Borrowed(name))),
+            MutationKind::DropField,
+            rebuild(fields),
+        )
+    }
+}
+
+fn injected<'a, E: Clone>(elements: &[Save<'a, E>]) -> Vec<Save<'a, E>> {
+    let mut elements = elements.to_vec();
+    elements.push(elements.last().cloned().unwrap_or(Save::Unit));
+    elements
+}
+
+#[allow(clippy::type_complexity)]
+fn unknown_field<'a, E: Clone>(
+    fields: &[(&'a str, Option<Save<'a, E>>)],
+) -> Vec<(&'a str, Option<Save<'a, E>>)> {
+    let mut fields = fields.to_vec();
+    fields.push((UNKNOWN_FIELD, Some(Save::Unit)));
+    fields
+}
+
+fn widen<'a, E>(node: &Save<'a, E>) -> Option<Save<'a, E>> {
+    Some(match *node {
+        Save::I8(it) => Save::I16(it.into()),
+        Save::I16(it) => Save::I32(it.into()),
+        Save::I32(it) => Save::I64(it.into()),
+        Save::I64(it) => Save::I128(it.into()),
+        Save::U8(it) => Save::U16(it.into()),
+        Save::U16(it) => Save::U32(it.into()),
+        Save::U32(it) => Save::U64(it.into()),
+        Save::U64(it) => Save::U128(it.into()),
+        Save::F32(it) => Save::F64(it.into()),
+        _ => return None,
+    })
+}
+
+fn narrow<'a, E>(node: &Save<'a, E>) -> Option<Save<'a, E>> {
+    match *node {
+        Save::I16(it) => it.try_into().ok().map(Save::I8),
+        Save::I32(it) => it.try_into().ok().map(Save::I16),
+        Save::I64(it) => it.try_into().ok().map(Save::I32),
+        Save::I128(it) => it.try_into().ok().map(Save::I64),
+        Save::U16(it) => it.try_into().ok().map(Save::U8),
+        Save::U32(it) => it.try_into().ok().map(Save::U16),
+        Save::U64(it) => it.try_into().ok().map(Save::U32),
+        Save::U128(it) => it.try_into().ok().map(Save::U64),
+        Save::F64(it) => {
+            let narrowed = it as f32;
+            match f64::from(narrowed) == it || it.is_nan() {
+                true => Some(Save::F32(narrowed)),
+                false => None,
+            }
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,306 @@
+use crate::{Save, Variant};
+
+/// Options for [normalizing](Save::normalize) a tree, so that comparisons
+/// ignore differences which are irrelevant to a test.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[non_exhaustive]
+pub struct Normalize {
+    /// See [`Normalize::unwrap_newtypes`].
+    pub unwrap_newtypes: bool,
+    /// See [`Normalize::collapse_tuple_structs`].
+    pub collapse_tuple_structs: bool,
+    /// See [`Normalize::ignore_variant_index`].
+    pub ignore_variant_index: bool,
+    /// See [`Normalize::remove_skipped_fields`].
+    pub remove_skipped_fields: bool,
+    /// See [`Normalize::skip_none_fields`].
+    pub skip_none_fields: bool,
+    /// See [`Normalize::unify_units`].
+    pub unify_units: bool,
+    /// See [`Normalize::chars_as_strings`].
+    pub chars_as_strings: bool,
+    /// See [`Normalize::strings_as_chars`].
+    pub strings_as_chars: bool,
+}
+
+impl Normalize {
+    /// All options are disabled by default.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Replace each [`Save::NewTypeStruct`] with its contents.
+    pub fn unwrap_newtypes(mut self, unwrap: bool) -> Self {
+        self.unwrap_newtypes = unwrap;
+        self
+    }
+    /// Replace each single-field [`Save::TupleStruct`] with its field.
+    pub fn collapse_tuple_structs(mut self, collapse: bool) -> Self {
+        self.collapse_tuple_structs = collapse;
+        self
+    }
+    /// Set every [`Variant::variant_index`] to `0`, so that variants are
+    /// matched by name only when comparing or [diffing](Save::diff).
+    ///
+    /// This allows enums to be reordered without breaking comparisons against
+    /// archived trees.
+    ///
+    /// ```
+    /// # use serde_save::{save, Normalize};
+    /// mod before {
+    ///     #[derive(serde::Serialize)]
+    ///     pub enum Status { Active, Retired }
+    /// }
+    /// mod after {
+    ///     #[derive(serde::Serialize)]
+    ///     pub enum Status { Retired, Active }
+    /// }
+    ///
+    /// let before = save(before::Status::Active).unwrap();
+    /// let after = save(after::Status::Active).unwrap();
+    /// assert_ne!(before, after);
+    ///
+    /// let options = Normalize::new().ignore_variant_index(true);
+    /// assert_eq!(before.normalize(options), after.normalize(options));
+    /// ```
+    pub fn ignore_variant_index(mut self, ignore: bool) -> Self {
+        self.ignore_variant_index = ignore;
+        self
+    }
+    /// Remove [skipped](serde::ser::SerializeStruct::skip_field) fields from
+    /// structs, so that a skipped field compares equal to an absent one.
+    ///
+    /// ```
+    /// # use serde_save::{save, Normalize};
+    /// #[derive(serde::Serialize)]
+    /// struct Before {
+    ///     #[serde(skip_serializing_if = "Option::is_none")]
+    ///     nickname: Option<String>,
+    /// }
+    /// #[derive(serde::Serialize)]
+    /// #[serde(rename = "Before")]
+    /// struct After {}
+    ///
+    /// let options = Normalize::new().remove_skipped_fields(true);
+    /// let before = save(Before { nickname: None }).unwrap();
+    /// assert_ne!(before, save(After {}).unwrap());
+    /// assert_eq!(before.normalize(options), save(After {}).unwrap());
+    /// ```
+    pub fn remove_skipped_fields(mut self, remove: bool) -> Self {
+        self.remove_skipped_fields = remove;
+        self
+    }
+    /// Treat struct fields of [`Save::Option(None)`](Save::Option) as
+    /// [skipped](serde::ser::SerializeStruct::skip_field),
+    /// so that `skip_serializing_if = "Option::is_none"` makes no difference.
+    ///
+    /// Combine with [`Self::remove_skipped_fields`] to also treat them as absent.
+    ///
+    /// ```
+    /// # use serde_save::{save, Normalize};
+    /// #[derive(serde::Serialize)]
+    /// struct User {
+    ///     #[serde(skip_serializing_if = "Option::is_none")]
+    ///     nickname: Option<String>,
+    /// }
+    /// #[derive(serde::Serialize)]
+    /// #[serde(rename = "User")]
+    /// struct Always {
+    ///     nickname: Option<String>,
+    /// }
+    ///
+    /// let options = Normalize::new().skip_none_fields(true);
+    /// assert_eq!(
+    ///     save(User { nickname: None }).unwrap().normalize(options),
+    ///     save(Always { nickname: None }).unwrap().normalize(options),
+    /// );
+    /// ```
+    pub fn skip_none_fields(mut self, skip: bool) -> Self {
+        self.skip_none_fields = skip;
+        self
+    }
+    /// Replace each [`Save::Unit`] and [`Save::UnitStruct`] with
+    /// [`Save::Option(None)`](Save::Option), for formats which represent them
+    /// all in the same way, like `null` in JSON.
+    ///
+    /// ```
+    /// # use serde_save::{save, Normalize};
+    /// #[derive(serde::Serialize)]
+    /// struct Marker;
+    ///
+    /// let options = Normalize::new().unify_units(true);
+    /// let none = save(None::<u8>).unwrap();
+    /// assert_ne!(save(()).unwrap(), none);
+    /// assert_eq!(save(()).unwrap().normalize(options), none);
+    /// assert_eq!(save(Marker).unwrap().normalize(options), none);
+    /// ```
+    pub fn unify_units(mut self, unify: bool) -> Self {
+        self.unify_units = unify;
+        self
+    }
+    /// Replace each [`Save::Char`] with a one-character [`Save::String`],
+    /// for formats without a `char` type.
+    ///
+    /// ```
+    /// # use serde_save::{save, Normalize};
+    /// let options = Normalize::new().chars_as_strings(true);
+    /// assert_ne!(save('x').unwrap(), save("x").unwrap());
+    /// assert_eq!(save('x').unwrap().normalize(options), save("x").unwrap());
+    /// ```
+    pub fn chars_as_strings(mut self, chars_as_strings: bool) -> Self {
+        self.chars_as_strings = chars_as_strings;
+        self
+    }
+    /// Replace each one-character [`Save::String`] with a [`Save::Char`],
+    /// the inverse of [`Self::chars_as_strings`].
+    ///
+    /// Ignored if [`Self::chars_as_strings`] is enabled.
+    pub fn strings_as_chars(mut self, strings_as_chars: bool) -> Self {
+        self.strings_as_chars = strings_as_chars;
+        self
+    }
+    fn variant<'a>(&self, variant: Variant<'a>) -> Variant<'a> {
+        match self.ignore_variant_index {
+            true => Variant {
+                variant_index: 0,
+                ..variant
+            },
+            false => variant,
+        }
+    }
+}
+
+impl<'a, E> Save<'a, E> {
+    /// Rewrite this tree according to `options`.
+    ///
+    /// ```
+    /// # use serde_save::{save, Normalize};
+    /// #[derive(serde::Serialize)]
+    /// struct Meters(f64);
+    ///
+    /// let options = Normalize::new().unwrap_newtypes(true);
+    /// assert_ne!(save(Meters(1.0)).unwrap(), save(1.0).unwrap());
+    /// assert_eq!(save(Meters(1.0)).unwrap().normalize(options), save(1.0).unwrap());
+    /// ```
+    pub fn normalize(self, options: Normalize) -> Self {
+        let recurse = |it: Box<Self>| Box::new(it.normalize(options));
+        let elements = |it: Vec<Self>| {
+            it.into_iter()
+                .map(|it| it.normalize(options))
+                .collect::<Vec<_>>()
+        };
+        #[allow(clippy::type_complexity)]
+        let fields = |it: Vec<(&'a str, Option<Self>)>| {
+            it.into_iter()
+                .map(|(k, v)| match v.map(|it| it.normalize(options)) {
+                    Some(Save::Option(None)) if options.skip_none_fields => (k, None),
+                    v => (k, v),
+                })
+                .filter(|(_, v)| !(options.remove_skipped_fields && v.is_none()))
+                .collect::<Vec<_>>()
+        };
+        match self {
+            Save::Option(it) => Save::Option(it.map(recurse)),
+            Save::NewTypeStruct { value, .. } if options.unwrap_newtypes => {
+                value.normalize(options)
+            }
+            Save::NewTypeStruct { name, value } => Save::NewTypeStruct {
+                name,
+                value: recurse(value),
+            },
+            Save::Char(it) if options.chars_as_strings => Save::String(it.into()),
+            Save::String(it) if options.strings_as_chars && !options.chars_as_strings => {
+                let mut chars = it.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => Save::Char(c),
+                    _ => Save::String(it),
+                }
+            }
+            Save::Unit | Save::UnitStruct(_) if options.unify_units => Save::Option(None),
+            Save::UnitVariant(variant) => Save::UnitVariant(options.variant(variant)),
+            Save::NewTypeVariant { variant, value } => Save::NewTypeVariant {
+                variant: options.variant(variant),
+                value: recurse(value),
+            },
+            Save::Seq(it) => Save::Seq(elements(it)),
+            Save::Map(it) => Save::Map(
+                it.into_iter()
+                    .map(|(k, v)| (k.normalize(options), v.normalize(options)))
+                    .collect(),
+            ),
+            Save::Tuple(it) => Save::Tuple(elements(it)),
+            Save::TupleStruct { mut values, .. }
+                if options.collapse_tuple_structs && values.len() == 1 =>
+            {
+                values.remove(0).normalize(options)
+            }
+            Save::TupleStruct { name, values } => Save::TupleStruct {
+                name,
+                values: elements(values),
+            },
+            Save::TupleVariant { variant, values } => Save::TupleVariant {
+                variant: options.variant(variant),
+                values: elements(values),
+            },
+            Save::Struct { name, fields: it } => Save::Struct {
+                name,
+                fields: fields(it),
+            },
+            Save::StructVariant {
+                variant,
+                fields: it,
+            } => Save::StructVariant {
+                variant: options.variant(variant),
+                fields: fields(it),
+            },
+            leaf => leaf,
+        }
+    }
+    /// Replace each [`Save::NewTypeStruct`] with its contents.
+    ///
+    /// See [`Normalize::unwrap_newtypes`].
+    pub fn unwrap_newtypes(self) -> Self {
+        self.normalize(Normalize::new().unwrap_newtypes(true))
+    }
+    /// Replace each [`Save::Char`] with a one-character [`Save::String`].
+    ///
+    /// See [`Normalize::chars_as_strings`].
+    pub fn chars_to_strings(self) -> Self {
+        self.normalize(Normalize::new().chars_as_strings(true))
+    }
+    /// Replace each one-character [`Save::String`] with a [`Save::Char`].
+    ///
+    /// See [`Normalize::strings_as_chars`].
+    ///
+    /// ```
+    /// # use serde_save::Save;
+    /// let save = Save::<std::convert::Infallible>::Seq(vec![Save::string("a"), Save::string("bc")]);
+    /// assert_eq!(
+    ///     save.strings_to_chars(),
+    ///     Save::Seq(vec![Save::Char('a'), Save::string("bc")])
+    /// );
+    /// ```
+    pub fn strings_to_chars(self) -> Self {
+        self.normalize(Normalize::new().strings_as_chars(true))
+    }
+    /// Strip wrapper types, by [unwrapping newtypes](Normalize::unwrap_newtypes)
+    /// and [collapsing single-field tuple structs](Normalize::collapse_tuple_structs).
+    ///
+    /// ```
+    /// # use serde_save::Save;
+    /// let wrapped: Save = Save::TupleStruct {
+    ///     name: "Wrapper",
+    ///     values: vec![Save::NewTypeStruct {
+    ///         name: "Id",
+    ///         value: Box::new(Save::U64(1)),
+    ///     }],
+    /// };
+    /// assert_eq!(wrapped.simplify(), Save::U64(1));
+    /// ```
+    pub fn simplify(self) -> Self {
+        self.normalize(
+            Normalize::new()
+                .unwrap_newtypes(true)
+                .collapse_tuple_structs(true),
+        )
+    }
+}
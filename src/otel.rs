@@ -0,0 +1,235 @@
+use crate::{pretty::Base64, Save};
+use core::fmt;
+
+/// The value of an OpenTelemetry-style attribute, see [`Save::to_attributes`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum AttributeValue {
+    /// A boolean.
+    Bool(bool),
+    /// An integer.
+    I64(i64),
+    /// A floating point number.
+    F64(f64),
+    /// A string.
+    String(String),
+}
+
+impl fmt::Display for AttributeValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AttributeValue::Bool(it) => it.fmt(f),
+            AttributeValue::I64(it) => it.fmt(f),
+            AttributeValue::F64(it) => it.fmt(f),
+            AttributeValue::String(it) => it.fmt(f),
+        }
+    }
+}
+
+/// Bounds on the output of [`Save::to_attributes`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct AttributeLimits {
+    /// See [`AttributeLimits::max_attributes`].
+    pub max_attributes: usize,
+    /// See [`AttributeLimits::max_value_len`].
+    pub max_value_len: usize,
+}
+
+impl AttributeLimits {
+    /// The defaults of the OpenTelemetry SDKs: at most 128 attributes,
+    /// with unlimited value lengths.
+    pub fn new() -> Self {
+        Self {
+            max_attributes: 128,
+            max_value_len: usize::MAX,
+        }
+    }
+    /// Drop attributes after the first `max`, counting them in
+    /// [`Attributes::dropped`].
+    pub fn max_attributes(mut self, max: usize) -> Self {
+        self.max_attributes = max;
+        self
+    }
+    /// Truncate string values to at most `max` bytes, on a character boundary.
+    pub fn max_value_len(mut self, max: usize) -> Self {
+        self.max_value_len = max;
+        self
+    }
+}
+
+impl Default for AttributeLimits {
+    /// See [`Self::new`].
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A flat list of attributes, see [`Save::to_attributes`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Attributes {
+    /// Dotted keys and scalar values, in pre-order.
+    pub attributes: Vec<(String, AttributeValue)>,
+    /// The number of attributes dropped because of
+    /// [`AttributeLimits::max_attributes`].
+    pub dropped: usize,
+}
+
+impl<E: fmt::Display> Save<'_, E> {
+    /// Flatten this tree into OpenTelemetry-style attributes, for annotating
+    /// spans and metrics.
+    ///
+    /// - Keys are the dotted path to each leaf, with indices for elements of
+    ///   sequences and tuples.
+    ///   Entries of maps use their key if it is a leaf, and their index otherwise.
+    /// - Integers which don't fit in an [`i64`] are stored as strings,
+    ///   as are [`char`]s, byte arrays (in base64), unit variants and errors.
+    /// - Enum variants with contents contribute their name to the key.
+    /// - [`None`], unit types and empty containers have no attributes.
+    ///
+    /// ```
+    /// # use serde_save::{save, AttributeLimits, AttributeValue};
+    /// #[derive(serde::Serialize)]
+    /// struct Request {
+    ///     method: &'static str,
+    ///     retries: Vec<u8>,
+    ///     timeout: Option<f64>,
+    /// }
+    ///
+    /// let saved = save(Request { method: "GET", retries: vec![1, 2], timeout: None }).unwrap();
+    /// let attributes = saved.to_attributes(AttributeLimits::new().max_attributes(2));
+    /// assert_eq!(
+    ///     attributes.attributes,
+    ///     [
+    ///         (String::from("method"), AttributeValue::String("GET".into())),
+    ///         (String::from("retries.0"), AttributeValue::I64(1)),
+    ///     ]
+    /// );
+    /// assert_eq!(attributes.dropped, 1);
+    /// ```
+    pub fn to_attributes(&self, limits: AttributeLimits) -> Attributes {
+        let mut out = Attributes::default();
+        flatten(&mut String::new(), self, limits, &mut out);
+        out
+    }
+}
+
+/// The value of a leaf, or [`None`] if it has no attribute.
+fn scalar<E: fmt::Display>(save: &Save<'_, E>) -> Option<AttributeValue> {
+    let int = |it: i128| match i64::try_from(it) {
+        Ok(it) => AttributeValue::I64(it),
+        Err(_) => AttributeValue::String(it.to_string()),
+    };
+    Some(match save {
+        Save::Bool(it) => AttributeValue::Bool(*it),
+        Save::I8(it) => AttributeValue::I64((*it).into()),
+        Save::I16(it) => AttributeValue::I64((*it).into()),
+        Save::I32(it) => AttributeValue::I64((*it).into()),
+        Save::I64(it) => AttributeValue::I64(*it),
+        Save::I128(it) => int(*it),
+        Save::U8(it) => AttributeValue::I64((*it).into()),
+        Save::U16(it) => AttributeValue::I64((*it).into()),
+        Save::U32(it) => AttributeValue::I64((*it).into()),
+        Save::U64(it) => int((*it).into()),
+        Save::U128(it) => match i64::try_from(*it) {
+            Ok(it) => AttributeValue::I64(it),
+            Err(_) => AttributeValue::String(it.to_string()),
+        },
+        Save::F32(it) => AttributeValue::F64((*it).into()),
+        Save::F64(it) => AttributeValue::F64(*it),
+        Save::Char(it) => AttributeValue::String(it.to_string()),
+        Save::String(it) => AttributeValue::String(it.clone()),
+        Save::ByteArray(it) => AttributeValue::String(Base64(it).to_string()),
+        Save::UnitVariant(it) => AttributeValue::String(it.variant.into()),
+        Save::Error(it) => AttributeValue::String(it.to_string()),
+        _ => return None,
+    })
+}
+
+fn flatten<E: fmt::Display>(
+    key: &mut String,
+    save: &Save<'_, E>,
+    limits: AttributeLimits,
+    out: &mut Attributes,
+) {
+    match save {
+        Save::Option(Some(it)) | Save::NewTypeStruct { value: it, .. } => {
+            flatten(key, it, limits, out)
+        }
+        Save::NewTypeVariant { variant, value } => {
+            child(key, variant.variant, |key| flatten(key, value, limits, out))
+        }
+        Save::Seq(it) | Save::Tuple(it) | Save::TupleStruct { values: it, .. } => {
+            elements(key, it, limits, out)
+        }
+        Save::TupleVariant { variant, values } => child(key, variant.variant, |key| {
+            elements(key, values, limits, out)
+        }),
+        Save::Map(it) => {
+            for (ix, (k, v)) in it.iter().enumerate() {
+                let k = match scalar(k) {
+                    Some(it) => it.to_string(),
+                    None => ix.to_string(),
+                };
+                child(key, &k, |key| flatten(key, v, limits, out))
+            }
+        }
+        Save::Struct { fields, .. } => self::fields(key, fields, limits, out),
+        Save::StructVariant { variant, fields } => child(key, variant.variant, |key| {
+            self::fields(key, fields, limits, out)
+        }),
+        leaf => {
+            let Some(value) = scalar(leaf) else { return };
+            if out.attributes.len() >= limits.max_attributes {
+                out.dropped += 1;
+                return;
+            }
+            let value = match value {
+                AttributeValue::String(mut it) if it.len() > limits.max_value_len => {
+                    let mut len = limits.max_value_len;
+                    while !it.is_char_boundary(len) {
+                        len -= 1
+                    }
+                    it.truncate(len);
+                    AttributeValue::String(it)
+                }
+                it => it,
+            };
+            out.attributes.push((key.clone(), value))
+        }
+    }
+}
+
+/// Call `f` with `segment` appended to `key`.
+fn child(key: &mut String, segment: &str, f: impl FnOnce(&mut String)) {
+    let len = key.len();
+    if !key.is_empty() {
+        key.push('.')
+    }
+    key.push_str(segment);
+    f(key);
+    key.truncate(len)
+}
+
+fn elements<E: fmt::Display>(
+    key: &mut String,
+    elements: &[Save<'_, E>],
+    limits: AttributeLimits,
+    out: &mut Attributes,
+) {
+    for (ix, it) in elements.iter().enumerate() {
+        child(key, &ix.to_string(), |key| flatten(key, it, limits, out))
+    }
+}
+
+fn fields<E: fmt::Display>(
+    key: &mut String,
+    fields: &[(&str, Option<Save<'_, E>>)],
+    limits: AttributeLimits,
+    out: &mut Attributes,
+) {
+    for (name, it) in fields {
+        if let Some(it) = it {
+            child(key, name, |key| flatten(key, it, limits, out))
+        }
+    }
+}
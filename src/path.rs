@@ -0,0 +1,291 @@
+use crate::Save;
+use core::{fmt, ops::Deref};
+use std::borrow::Cow;
+
+/// A single step from a node in a [`Save`] tree to one of its children.
+///
+/// Wrapper nodes ([`Save::Option`], [`Save::NewTypeStruct`] and
+/// [`Save::NewTypeVariant`]) are transparent, so their contents share the
+/// wrapper's [`Path`].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Segment<'a> {
+    /// A named field of a [`Save::Struct`] or [`Save::StructVariant`].
+    Field(Cow<'a, str>),
+    /// An element of a [`Save::Seq`], [`Save::Tuple`], [`Save::TupleStruct`]
+    /// or [`Save::TupleVariant`], or the value of the n'th entry of a [`Save::Map`].
+    Index(usize),
+    /// The key of the n'th entry of a [`Save::Map`].
+    Key(usize),
+}
+
+impl Segment<'_> {
+    /// Detach this segment from any borrowed field name.
+    pub fn into_owned(self) -> Segment<'static> {
+        match self {
+            Segment::Field(it) => Segment::Field(Cow::Owned(it.into_owned())),
+            Segment::Index(it) => Segment::Index(it),
+            Segment::Key(it) => Segment::Key(it),
+        }
+    }
+}
+
+/// The location of a node within a [`Save`] tree.
+///
+/// The [`Display`](fmt::Display) representation is also accepted by [`Path::parse`]:
+/// - fields are separated by `.`, as in `outer.inner`.
+/// - indices are written `[n]`, as in `items[0]`.
+/// - map keys are written `{n}`, as in `map{0}`, for the key of the first entry.
+///
+/// The empty path refers to the root of the tree.
+///
+/// ```
+/// # use serde_save::{Path, Save};
+/// let save = Save::<std::convert::Infallible>::strukt(
+///     "Outer",
+///     [("items", Save::Seq(vec![Save::U8(1), Save::U8(2)]))],
+/// );
+/// let path = Path::parse("items[1]").unwrap();
+/// assert_eq!(save.get(&path), Some(&Save::U8(2)));
+/// assert_eq!(path.to_string(), "items[1]");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Path<'a> {
+    segments: Vec<Segment<'a>>,
+}
+
+impl<'a> Path<'a> {
+    /// The empty path, which refers to the root of the tree.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Append a [`Segment`] to the end of this path.
+    pub fn push(&mut self, segment: Segment<'a>) {
+        self.segments.push(segment)
+    }
+    /// Remove the last [`Segment`] of this path, if any.
+    pub fn pop(&mut self) -> Option<Segment<'a>> {
+        self.segments.pop()
+    }
+    /// Return a new path, with `segment` appended.
+    pub fn join(&self, segment: Segment<'a>) -> Self {
+        let mut it = self.clone();
+        it.push(segment);
+        it
+    }
+    /// Detach this path from any borrowed field names.
+    pub fn into_owned(self) -> Path<'static> {
+        Path {
+            segments: self.segments.into_iter().map(Segment::into_owned).collect(),
+        }
+    }
+    /// Parse the [`Display`](fmt::Display) representation of a path.
+    ///
+    /// Field names may not contain `.`, `[` or `{`.
+    pub fn parse(s: &'a str) -> Result<Self, ParsePathError> {
+        let mut segments = Vec::new();
+        let mut rest = s;
+        while !rest.is_empty() {
+            let offset = s.len() - rest.len();
+            let err = |msg| ParsePathError { offset, msg };
+            let (close, ctor): (_, fn(usize) -> Segment<'a>) = match rest.as_bytes()[0] {
+                b'[' => (']', Segment::Index),
+                b'{' => ('}', Segment::Key),
+                b'.' if segments.is_empty() => return Err(err("unexpected `.`")),
+                first => {
+                    let start = usize::from(first == b'.');
+                    if offset != 0 && start == 0 {
+                        return Err(err("expected `.`, `[` or `{`"));
+                    }
+                    let end = rest[start..]
+                        .find(['.', '[', '{'])
+                        .map_or(rest.len(), |it| it + start);
+                    let name = &rest[start..end];
+                    if name.is_empty() {
+                        return Err(err("empty field name"));
+                    }
+                    segments.push(Segment::Field(Cow::Borrowed(name)));
+                    rest = &rest[end..];
+                    continue;
+                }
+            };
+            let Some(end) = rest.find(close) else {
+                return Err(err("unterminated index"));
+            };
+            let index = rest[1..end]
+                .parse()
+                .map_err(|_| err("expected an integer index"))?;
+            segments.push(ctor(index));
+            rest = &rest[end + 1..];
+        }
+        Ok(Self { segments })
+    }
+}
+
+impl<'a> Deref for Path<'a> {
+    type Target = [Segment<'a>];
+    fn deref(&self) -> &Self::Target {
+        &self.segments
+    }
+}
+
+impl<'a> FromIterator<Segment<'a>> for Path<'a> {
+    fn from_iter<II: IntoIterator<Item = Segment<'a>>>(iter: II) -> Self {
+        Self {
+            segments: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl<'a> IntoIterator for Path<'a> {
+    type Item = Segment<'a>;
+    type IntoIter = std::vec::IntoIter<Segment<'a>>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.segments.into_iter()
+    }
+}
+
+impl fmt::Display for Path<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (ix, segment) in self.segments.iter().enumerate() {
+            match segment {
+                Segment::Field(it) if ix == 0 => f.write_str(it)?,
+                Segment::Field(it) => write!(f, ".{}", it)?,
+                Segment::Index(it) => write!(f, "[{}]", it)?,
+                Segment::Key(it) => write!(f, "{{{}}}", it)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An error returned from [`Path::parse`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsePathError {
+    offset: usize,
+    msg: &'static str,
+}
+
+impl fmt::Display for ParsePathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid path at offset {}: {}", self.offset, self.msg)
+    }
+}
+
+impl std::error::Error for ParsePathError {}
+
+impl<'a, E> Save<'a, E> {
+    /// Look up the node at the given [`Path`], if it exists.
+    ///
+    /// [Skip](serde::ser::SerializeStruct::skip_field)ped fields do not exist.
+    pub fn get(&self, path: &[Segment<'_>]) -> Option<&Self> {
+        path.iter()
+            .try_fold(self, |node, segment| node.step(segment))
+    }
+    /// Mutable version of [`Save::get`].
+    pub fn get_mut(&mut self, path: &[Segment<'_>]) -> Option<&mut Self> {
+        path.iter()
+            .try_fold(self, |node, segment| node.step_mut(segment))
+    }
+    /// Visit every node in the tree in pre-order, along with its [`Path`].
+    ///
+    /// Note that the contents of wrapper nodes share their wrapper's [`Path`].
+    pub fn walk(&self, mut f: impl FnMut(&Path<'a>, &Self)) {
+        self.walk_inner(&mut Path::new(), &mut f)
+    }
+
+    fn walk_inner(&self, path: &mut Path<'a>, f: &mut impl FnMut(&Path<'a>, &Self)) {
+        f(path, self);
+        match self {
+            Save::Option(Some(it))
+            | Save::NewTypeStruct { value: it, .. }
+            | Save::NewTypeVariant { value: it, .. } => it.walk_inner(path, f),
+            Save::Seq(it)
+            | Save::Tuple(it)
+            | Save::TupleStruct { values: it, .. }
+            | Save::TupleVariant { values: it, .. } => {
+                for (ix, it) in it.iter().enumerate() {
+                    path.push(Segment::Index(ix));
+                    it.walk_inner(path, f);
+                    path.pop();
+                }
+            }
+            Save::Map(it) => {
+                for (ix, (k, v)) in it.iter().enumerate() {
+                    path.push(Segment::Key(ix));
+                    k.walk_inner(path, f);
+                    path.pop();
+                    path.push(Segment::Index(ix));
+                    v.walk_inner(path, f);
+                    path.pop();
+                }
+            }
+            Save::Struct { fields, .. } | Save::StructVariant { fields, .. } => {
+                for (name, it) in fields {
+                    if let Some(it) = it {
+                        path.push(Segment::Field(Cow::Borrowed(name)));
+                        it.walk_inner(path, f);
+                        path.pop();
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Descend through a single [`Segment`], seeing through wrapper nodes.
+    fn step(&self, segment: &Segment<'_>) -> Option<&Self> {
+        match (self, segment) {
+            (
+                Save::Option(Some(it))
+                | Save::NewTypeStruct { value: it, .. }
+                | Save::NewTypeVariant { value: it, .. },
+                _,
+            ) => it.step(segment),
+            (
+                Save::Seq(it)
+                | Save::Tuple(it)
+                | Save::TupleStruct { values: it, .. }
+                | Save::TupleVariant { values: it, .. },
+                Segment::Index(ix),
+            ) => it.get(*ix),
+            (Save::Map(it), Segment::Index(ix)) => it.get(*ix).map(|(_, v)| v),
+            (Save::Map(it), Segment::Key(ix)) => it.get(*ix).map(|(k, _)| k),
+            (
+                Save::Struct { fields, .. } | Save::StructVariant { fields, .. },
+                Segment::Field(name),
+            ) => fields
+                .iter()
+                .find(|(it, _)| it == name)
+                .and_then(|(_, it)| it.as_ref()),
+            _ => None,
+        }
+    }
+
+    fn step_mut(&mut self, segment: &Segment<'_>) -> Option<&mut Self> {
+        match (self, segment) {
+            (
+                Save::Option(Some(it))
+                | Save::NewTypeStruct { value: it, .. }
+                | Save::NewTypeVariant { value: it, .. },
+                _,
+            ) => it.step_mut(segment),
+            (
+                Save::Seq(it)
+                | Save::Tuple(it)
+                | Save::TupleStruct { values: it, .. }
+                | Save::TupleVariant { values: it, .. },
+                Segment::Index(ix),
+            ) => it.get_mut(*ix),
+            (Save::Map(it), Segment::Index(ix)) => it.get_mut(*ix).map(|(_, v)| v),
+            (Save::Map(it), Segment::Key(ix)) => it.get_mut(*ix).map(|(k, _)| k),
+            (
+                Save::Struct { fields, .. } | Save::StructVariant { fields, .. },
+                Segment::Field(name),
+            ) => fields
+                .iter_mut()
+                .find(|(it, _)| it == name)
+                .and_then(|(_, it)| it.as_mut()),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,445 @@
+use crate::{diff::DisplayPath, Path, Save, Segment};
+use core::fmt;
+use std::borrow::Cow;
+
+/// Check a tree against a partial pattern, returning whether it matches.
+///
+/// See [`assert_save_matches!`](crate::assert_save_matches) for the syntax,
+/// and a version which panics with the mismatches.
+///
+/// ```
+/// # use serde_save::{save, save_matches};
+/// #[derive(serde::Serialize)]
+/// struct Foo {
+///     a: u8,
+///     b: Vec<&'static str>,
+///     c: Option<f64>,
+/// }
+///
+/// let saved = save(Foo { a: 1, b: vec!["x", "y"], c: None }).unwrap();
+/// assert!(save_matches!(saved, Struct("Foo") { "a": U8(_), "b": Seq(len >= 2), .. }));
+/// assert!(!save_matches!(saved, Struct("Foo") { "a": U8(2), .. }));
+/// ```
+#[macro_export]
+macro_rules! save_matches {
+    ($actual:expr, $($pattern:tt)+) => {
+        $crate::__private::pattern_mismatches(&$actual, &$crate::__save_pattern!($($pattern)+))
+            .is_empty()
+    };
+}
+
+/// Assert that a tree matches a partial pattern,
+/// printing each mismatch, like a [`Diff`](crate::Diff), on failure.
+///
+/// Patterns are written in terms of the variants of [`Save`]:
+/// - `_` matches anything.
+/// - `U8(_)`, `Seq(_)`, `Struct(_)` etc. match any node of that kind.
+/// - `U8(1)` and `String("x")` match that exact value.
+/// - `Seq(len >= 2)` matches a node of that kind with a length,
+///   using any comparison operator.
+/// - `None`, `Some(pattern)`, `UnitVariant("Variant")`,
+///   `NewTypeStruct("Name")(pattern)` and `NewTypeVariant("Variant")(pattern)`
+///   match wrappers.
+/// - `Struct("Name") { "field": pattern, .. }`, `Struct { .. }` and
+///   `StructVariant("Variant") { .. }` match fields by name, in any order.
+/// - `Seq[pattern, ..]` and `Tuple(pattern, ..)` match elements in order.
+/// - Anything else is an expression which is converted [`Into`] a [`Save`],
+///   and compared exactly.
+///
+/// Without a trailing `..`, extra fields or elements are mismatches.
+/// Variant names may be written either as `Variant` or `Enum::Variant`.
+///
+/// ```
+/// # use serde_save::{assert_save_matches, save};
+/// #[derive(serde::Serialize)]
+/// enum Event {
+///     Login { user: &'static str, at: u64 },
+///     Logout(u64),
+/// }
+///
+/// let saved = save(vec![Event::Login { user: "alice", at: 1 }, Event::Logout(2)]).unwrap();
+/// assert_save_matches!(
+///     saved,
+///     Seq[
+///         StructVariant("Event::Login") { "user": String("alice"), "at": U64(_) },
+///         NewTypeVariant("Logout")(U64(2)),
+///     ]
+/// );
+/// ```
+///
+/// # Panics
+/// If the tree doesn't match, with a line per mismatch.
+///
+/// ```should_panic
+/// # use serde_save::{assert_save_matches, save};
+/// let saved = save((1u8, "x")).unwrap();
+/// // ~ [0]: expected U8(2), found U8(1)
+/// // + [1]: String("x")
+/// assert_save_matches!(saved, Tuple(U8(2)));
+/// ```
+#[macro_export]
+macro_rules! assert_save_matches {
+    ($actual:expr, $($pattern:tt)+) => {
+        $crate::__private::assert_save_matches(
+            &$actual,
+            &$crate::__save_pattern!($($pattern)+),
+        )
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __save_pattern {
+    (_) => {
+        $crate::__private::Pattern::Any
+    };
+    (None) => {
+        $crate::__private::Pattern::None
+    };
+    (Some($($inner:tt)+)) => {
+        $crate::__private::Pattern::Some(::std::boxed::Box::new(
+            $crate::__save_pattern!($($inner)+),
+        ))
+    };
+    (Struct { $($fields:tt)* }) => {{
+        let (fields, exhaustive) = $crate::__save_items!(field [] [] [$($fields)*]);
+        $crate::__private::Pattern::Struct { name: None, fields, exhaustive }
+    }};
+    (Struct($name:expr) { $($fields:tt)* }) => {{
+        let (fields, exhaustive) = $crate::__save_items!(field [] [] [$($fields)*]);
+        $crate::__private::Pattern::Struct { name: Some($name), fields, exhaustive }
+    }};
+    (StructVariant($variant:expr) { $($fields:tt)* }) => {{
+        let (fields, exhaustive) = $crate::__save_items!(field [] [] [$($fields)*]);
+        $crate::__private::Pattern::StructVariant { variant: $variant, fields, exhaustive }
+    }};
+    (NewTypeStruct($name:expr)($($inner:tt)+)) => {
+        $crate::__private::Pattern::NewTypeStruct(
+            $name,
+            ::std::boxed::Box::new($crate::__save_pattern!($($inner)+)),
+        )
+    };
+    (NewTypeVariant($variant:expr)($($inner:tt)+)) => {
+        $crate::__private::Pattern::NewTypeVariant(
+            $variant,
+            ::std::boxed::Box::new($crate::__save_pattern!($($inner)+)),
+        )
+    };
+    (UnitVariant($variant:expr)) => {
+        $crate::__private::Pattern::UnitVariant($variant)
+    };
+    ($kind:ident(_)) => {{
+        let _ = |it: &$crate::Save| ::core::matches!(it, $crate::Save::$kind { .. });
+        $crate::__private::Pattern::Kind(::core::stringify!($kind))
+    }};
+    ($kind:ident(len $op:tt $len:expr)) => {{
+        let _ = |it: &$crate::Save| ::core::matches!(it, $crate::Save::$kind { .. });
+        $crate::__private::Pattern::Len {
+            kind: ::core::stringify!($kind),
+            op: ::core::stringify!($op),
+            len: $len,
+            test: |l: usize, r: usize| l $op r,
+        }
+    }};
+    (Seq[$($elements:tt)*]) => {{
+        let (elements, exhaustive) = $crate::__save_items!(element [] [] [$($elements)*]);
+        $crate::__private::Pattern::Elements { kind: "Seq", elements, exhaustive }
+    }};
+    (Tuple($($elements:tt)*)) => {{
+        let (elements, exhaustive) = $crate::__save_items!(element [] [] [$($elements)*]);
+        $crate::__private::Pattern::Elements { kind: "Tuple", elements, exhaustive }
+    }};
+    (String($value:expr)) => {
+        $crate::__private::Pattern::Eq($crate::Save::String(::std::string::String::from($value)))
+    };
+    ($kind:ident($value:expr)) => {
+        $crate::__private::Pattern::Eq($crate::Save::$kind($value))
+    };
+    ($value:expr) => {
+        $crate::__private::Pattern::Eq(::core::convert::Into::into($value))
+    };
+}
+
+/// Split comma-separated fields or elements, with an optional trailing `..`.
+///
+/// Items of up to five tokens are taken at once, to stay within the
+/// recursion limit, and longer items are accumulated a token at a time.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __save_items {
+    ($mode:ident [$($out:expr,)*] [] []) => {
+        (::std::vec![$($out,)*], true)
+    };
+    ($mode:ident [$($out:expr,)*] [] [.. $(,)?]) => {
+        (::std::vec![$($out,)*], false)
+    };
+    ($mode:ident [$($out:expr,)*] [] [$a:tt $(, $($rest:tt)*)?]) => {
+        $crate::__save_items!($mode [$($out,)* $crate::__save_item!($mode $a),] [] [$($($rest)*)?])
+    };
+    ($mode:ident [$($out:expr,)*] [] [$a:tt $b:tt $(, $($rest:tt)*)?]) => {
+        $crate::__save_items!($mode [$($out,)* $crate::__save_item!($mode $a $b),] [] [$($($rest)*)?])
+    };
+    ($mode:ident [$($out:expr,)*] [] [$a:tt $b:tt $c:tt $(, $($rest:tt)*)?]) => {
+        $crate::__save_items!($mode [$($out,)* $crate::__save_item!($mode $a $b $c),] [] [$($($rest)*)?])
+    };
+    ($mode:ident [$($out:expr,)*] [] [$a:tt $b:tt $c:tt $d:tt $(, $($rest:tt)*)?]) => {
+        $crate::__save_items!($mode [$($out,)* $crate::__save_item!($mode $a $b $c $d),] [] [$($($rest)*)?])
+    };
+    ($mode:ident [$($out:expr,)*] [] [$a:tt $b:tt $c:tt $d:tt $e:tt $(, $($rest:tt)*)?]) => {
+        $crate::__save_items!($mode [$($out,)* $crate::__save_item!($mode $a $b $c $d $e),] [] [$($($rest)*)?])
+    };
+    ($mode:ident [$($out:expr,)*] [$($cur:tt)+] [$(, $($rest:tt)*)?]) => {
+        $crate::__save_items!($mode [$($out,)* $crate::__save_item!($mode $($cur)+),] [] [$($($rest)*)?])
+    };
+    ($mode:ident [$($out:expr,)*] [$($cur:tt)*] [$next:tt $($rest:tt)*]) => {
+        $crate::__save_items!($mode [$($out,)*] [$($cur)* $next] [$($rest)*])
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __save_item {
+    (field $name:literal : $($pattern:tt)+) => {
+        ($name, $crate::__save_pattern!($($pattern)+))
+    };
+    (element $($pattern:tt)+) => {
+        $crate::__save_pattern!($($pattern)+)
+    };
+}
+
+/// A pattern built by [`assert_save_matches!`](crate::assert_save_matches).
+#[doc(hidden)]
+pub enum Pattern<'p> {
+    Any,
+    Kind(&'static str),
+    Eq(Save<'p>),
+    None,
+    Some(Box<Self>),
+    UnitVariant(&'p str),
+    NewTypeStruct(&'p str, Box<Self>),
+    NewTypeVariant(&'p str, Box<Self>),
+    Struct {
+        name: Option<&'p str>,
+        fields: Vec<(&'p str, Self)>,
+        exhaustive: bool,
+    },
+    StructVariant {
+        variant: &'p str,
+        fields: Vec<(&'p str, Self)>,
+        exhaustive: bool,
+    },
+    Elements {
+        kind: &'static str,
+        elements: Vec<Self>,
+        exhaustive: bool,
+    },
+    Len {
+        kind: &'static str,
+        op: &'static str,
+        len: usize,
+        test: fn(usize, usize) -> bool,
+    },
+}
+
+impl fmt::Display for Pattern<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Pattern::Any => f.write_str("_"),
+            Pattern::Kind(kind) => write!(f, "{}(_)", kind),
+            Pattern::Eq(it) => write!(f, "{:?}", it),
+            Pattern::None => f.write_str("None"),
+            Pattern::Some(it) => write!(f, "Some({})", it),
+            Pattern::UnitVariant(variant) => write!(f, "UnitVariant({:?})", variant),
+            Pattern::NewTypeStruct(name, it) => write!(f, "NewTypeStruct({:?})({})", name, it),
+            Pattern::NewTypeVariant(variant, it) => {
+                write!(f, "NewTypeVariant({:?})({})", variant, it)
+            }
+            Pattern::Struct { name: None, .. } => f.write_str("Struct { .. }"),
+            Pattern::Struct {
+                name: Some(name), ..
+            } => write!(f, "Struct({:?}) {{ .. }}", name),
+            Pattern::StructVariant { variant, .. } => {
+                write!(f, "StructVariant({:?}) {{ .. }}", variant)
+            }
+            Pattern::Elements { kind: "Seq", .. } => f.write_str("Seq[..]"),
+            Pattern::Elements { kind, .. } => write!(f, "{}(..)", kind),
+            Pattern::Len { kind, op, len, .. } => write!(f, "{}(len {} {})", kind, op, len),
+        }
+    }
+}
+
+#[doc(hidden)]
+pub fn pattern_mismatches<E: fmt::Debug>(
+    actual: &Save<'_, E>,
+    pattern: &Pattern<'_>,
+) -> Vec<String> {
+    let mut out = Vec::new();
+    check(actual, pattern, &mut Path::new(), &mut out);
+    out
+}
+
+#[doc(hidden)]
+#[track_caller]
+pub fn assert_save_matches<E: fmt::Debug>(actual: &Save<'_, E>, pattern: &Pattern<'_>) {
+    let mismatches = pattern_mismatches(actual, pattern);
+    if !mismatches.is_empty() {
+        panic!("tree doesn't match pattern:\n{}", mismatches.join("\n"))
+    }
+}
+
+fn check<'a, E: fmt::Debug>(
+    actual: &Save<'a, E>,
+    pattern: &Pattern<'_>,
+    path: &mut Path<'a>,
+    out: &mut Vec<String>,
+) {
+    let mut mismatch = || {
+        out.push(format!(
+            "~ {}: expected {}, found {:?}",
+            DisplayPath(path),
+            pattern,
+            actual
+        ))
+    };
+    match (pattern, actual) {
+        (Pattern::Any, _) => {}
+        (Pattern::Kind(kind), _) => {
+            if *kind != actual.variant_name() {
+                mismatch()
+            }
+        }
+        (Pattern::Eq(expected), _) => {
+            if !expected.eq_errors_by(actual, &|never, _| match *never {}) {
+                mismatch()
+            }
+        }
+        (Pattern::None, Save::Option(None)) => {}
+        (Pattern::Some(pattern), Save::Option(Some(it))) => check(it, pattern, path, out),
+        (Pattern::UnitVariant(expected), Save::UnitVariant(variant)) => {
+            if !variant_is(expected, variant) {
+                mismatch()
+            }
+        }
+        (Pattern::NewTypeStruct(expected, pattern), Save::NewTypeStruct { name, value }) => {
+            match expected == name {
+                true => check(value, pattern, path, out),
+                false => mismatch(),
+            }
+        }
+        (Pattern::NewTypeVariant(expected, pattern), Save::NewTypeVariant { variant, value }) => {
+            match variant_is(expected, variant) {
+                true => check(value, pattern, path, out),
+                false => mismatch(),
+            }
+        }
+        (
+            Pattern::Struct {
+                name: expected,
+                fields: patterns,
+                exhaustive,
+            },
+            Save::Struct { name, fields },
+        ) => match expected.is_none_or(|it| it == *name) {
+            true => self::fields(fields, patterns, *exhaustive, path, out),
+            false => mismatch(),
+        },
+        (
+            Pattern::StructVariant {
+                variant: expected,
+                fields: patterns,
+                exhaustive,
+            },
+            Save::StructVariant { variant, fields },
+        ) => match variant_is(expected, variant) {
+            true => self::fields(fields, patterns, *exhaustive, path, out),
+            false => mismatch(),
+        },
+        (
+            Pattern::Elements {
+                kind,
+                elements: patterns,
+                exhaustive,
+            },
+            Save::Seq(elements) | Save::Tuple(elements),
+        ) if *kind == actual.variant_name() => {
+            for (ix, (element, pattern)) in elements.iter().zip(patterns).enumerate() {
+                path.push(Segment::Index(ix));
+                check(element, pattern, path, out);
+                path.pop();
+            }
+            for (ix, pattern) in patterns.iter().enumerate().skip(elements.len()) {
+                path.push(Segment::Index(ix));
+                out.push(format!("- {}: expected {}", DisplayPath(path), pattern));
+                path.pop();
+            }
+            if *exhaustive {
+                for (ix, element) in elements.iter().enumerate().skip(patterns.len()) {
+                    path.push(Segment::Index(ix));
+                    out.push(format!("+ {}: {:?}", DisplayPath(path), element));
+                    path.pop();
+                }
+            }
+        }
+        (
+            Pattern::Len {
+                kind, len, test, ..
+            },
+            _,
+        ) => {
+            let actual_len = match actual {
+                Save::Map(it) => Some(it.len()),
+                Save::ByteArray(it) => Some(it.len()),
+                Save::Seq(it)
+                | Save::Tuple(it)
+                | Save::TupleStruct { values: it, .. }
+                | Save::TupleVariant { values: it, .. } => Some(it.len()),
+                Save::Struct { fields, .. } | Save::StructVariant { fields, .. } => {
+                    Some(fields.len())
+                }
+                Save::String(it) => Some(it.chars().count()),
+                _ => None,
+            };
+            match actual_len {
+                Some(actual_len) if *kind == actual.variant_name() && test(actual_len, *len) => {}
+                _ => mismatch(),
+            }
+        }
+        _ => mismatch(),
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn fields<'a, E: fmt::Debug>(
+    fields: &[(&'a str, Option<Save<'a, E>>)],
+    patterns: &[(&str, Pattern<'_>)],
+    exhaustive: bool,
+    path: &mut Path<'a>,
+    out: &mut Vec<String>,
+) {
+    for (name, pattern) in patterns {
+        let found = fields.iter().find(|(it, _)| it == name);
+        path.push(Segment::Field(Cow::Owned(String::from(*name))));
+        match found {
+            Some((_, Some(value))) => check(value, pattern, path, out),
+            _ => out.push(format!("- {}: expected {}", DisplayPath(path), pattern)),
+        }
+        path.pop();
+    }
+    if exhaustive {
+        for (name, value) in fields {
+            if let (false, Some(value)) = (patterns.iter().any(|(it, _)| it == name), value) {
+                path.push(Segment::Field(Cow::Borrowed(name)));
+                out.push(format!("+ {}: {:?}", DisplayPath(path), value));
+                path.pop();
+            }
+        }
+    }
+}
+
+/// Whether `expected` is either `Variant` or `Enum::Variant`.
+fn variant_is(expected: &str, variant: &crate::Variant<'_>) -> bool {
+    match expected.split_once("::") {
+        Some((name, it)) => name == variant.name && it == variant.variant,
+        None => expected == variant.variant,
+    }
+}
@@ -0,0 +1,665 @@
+//! A shared renderer for human-readable representations of a [`Save`].
+
+use crate::{Path, Save, Segment, Variant};
+use core::{
+    cmp::Ordering,
+    fmt::{self, Write as _},
+};
+use std::borrow::Cow;
+
+/// A configurable, human-readable rendition of a [`Save`].
+///
+/// Display this to get the text.
+/// By default, the whole tree is written on a single line.
+///
+/// ```
+/// # use serde_save::save;
+/// #[derive(serde::Serialize)]
+/// struct Request {
+///     path: &'static str,
+///     body: Vec<Vec<u8>>,
+/// }
+///
+/// let saved = save(Request {
+///     path: "/some/long/path",
+///     body: vec![vec![1, 2], vec![3]],
+/// })
+/// .unwrap();
+/// assert_eq!(
+///     saved.pretty().to_string(),
+///     r#"Request { path: "/some/long/path", body: [[u8(1), u8(2)], [u8(3)]] }"#
+/// );
+/// assert_eq!(
+///     saved.pretty().max_depth(1).max_string(5).to_string(),
+///     r#"Request { path: "/some"…, body: […] }"#
+/// );
+/// assert_eq!(
+///     saved.pretty().max_width(40).to_string(),
+///     r#"Request {
+///     path: "/some/long/path",
+///     body: [[u8(1), u8(2)], [u8(3)]],
+/// }"#
+/// );
+/// ```
+pub struct Pretty<'s, 'a, E> {
+    save: &'s Save<'a, E>,
+    indent: usize,
+    multiline: bool,
+    max_width: Option<usize>,
+    max_depth: Option<usize>,
+    max_string: Option<usize>,
+    variant_indices: bool,
+    bytes: BytesFormat,
+    max_bytes: Option<usize>,
+    color: bool,
+}
+
+/// How to render a [`Save::ByteArray`], see [`Pretty::bytes`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[non_exhaustive]
+pub enum BytesFormat {
+    /// As an escaped byte string, like `b"hello\n"`.
+    #[default]
+    Escaped,
+    /// As hexadecimal, like `hex(68 65 6c 6c 6f)`.
+    ///
+    /// Multiline renditions are formatted as a hexdump,
+    /// with offset and ASCII columns.
+    Hex,
+    /// As standard, padded base64, like `base64(aGVsbG8=)`.
+    Base64,
+}
+
+impl<'a, E> Save<'a, E> {
+    /// Render this tree for humans, see [`Pretty`].
+    pub fn pretty(&self) -> Pretty<'_, 'a, E> {
+        Pretty {
+            save: self,
+            indent: 4,
+            multiline: false,
+            max_width: None,
+            max_depth: None,
+            max_string: None,
+            variant_indices: false,
+            bytes: BytesFormat::Escaped,
+            max_bytes: None,
+            color: false,
+        }
+    }
+}
+
+impl<'s, 'a, E> Pretty<'s, 'a, E> {
+    /// Set the number of spaces per level of indentation.
+    ///
+    /// Defaults to four.
+    pub fn indent(mut self, indent: usize) -> Self {
+        self.indent = indent;
+        self
+    }
+    /// Spread every non-empty container over multiple lines.
+    pub fn multiline(mut self, multiline: bool) -> Self {
+        self.multiline = multiline;
+        self
+    }
+    /// Spread containers over multiple lines only if they would otherwise be
+    /// wider than `width` columns, including indentation.
+    pub fn max_width(mut self, width: usize) -> Self {
+        self.multiline = true;
+        self.max_width = Some(width);
+        self
+    }
+    /// Elide the contents of containers nested more than `depth` levels deep
+    /// with `…`.
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = Some(depth);
+        self
+    }
+    /// Truncate strings longer than `len` characters, marking them with `…`.
+    pub fn max_string(mut self, len: usize) -> Self {
+        self.max_string = Some(len);
+        self
+    }
+    /// Show the [`Variant::variant_index`] of enum variants, as in `Enum::Variant@1`.
+    pub fn variant_indices(mut self, show: bool) -> Self {
+        self.variant_indices = show;
+        self
+    }
+    /// Choose how to render [`Save::ByteArray`]s.
+    ///
+    /// ```
+    /// # use serde_save::{BytesFormat, Save};
+    /// let save = Save::<std::convert::Infallible>::bytes(*b"hello, world!\n\0\x01\x02");
+    /// assert_eq!(save.pretty().to_string(), r#"b"hello, world!\n\x00\x01\x02""#);
+    /// assert_eq!(save.pretty().bytes(BytesFormat::Base64).to_string(), "base64(aGVsbG8sIHdvcmxkIQoAAQI=)");
+    /// assert_eq!(
+    ///     save.pretty().bytes(BytesFormat::Hex).max_bytes(4).to_string(),
+    ///     "hex(68 65 6c 6c …)"
+    /// );
+    /// assert_eq!(
+    ///     save.pretty().bytes(BytesFormat::Hex).multiline(true).to_string(),
+    ///     "\
+    /// hex(
+    ///     00000000  68 65 6c 6c 6f 2c 20 77  6f 72 6c 64 21 0a 00 01  |hello, world!...|
+    ///     00000010  02                                                |.|
+    /// )"
+    /// );
+    /// ```
+    pub fn bytes(mut self, format: BytesFormat) -> Self {
+        self.bytes = format;
+        self
+    }
+    /// Only render the first `len` bytes of each [`Save::ByteArray`],
+    /// marking the rest with `…`.
+    pub fn max_bytes(mut self, len: usize) -> Self {
+        self.max_bytes = Some(len);
+        self
+    }
+    /// Highlight names, kinds and values with ANSI escape codes.
+    ///
+    /// Colors are disabled by default.
+    #[cfg(feature = "color")]
+    pub fn color(mut self, choice: crate::ColorChoice) -> Self {
+        self.color = choice.enabled();
+        self
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for Pretty<'_, '_, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let options = Options {
+            indent: self.indent,
+            multiline: self.multiline,
+            max_width: self.max_width,
+            max_depth: self.max_depth,
+            max_string: self.max_string,
+            variant_indices: self.variant_indices,
+            bytes: self.bytes,
+            max_bytes: self.max_bytes,
+            color: self.color,
+            ..Options::default()
+        };
+        fmt::Display::fmt(
+            &Render {
+                options: &options,
+                save: self.save,
+            },
+            f,
+        )
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub(crate) struct Options<'r, 'a, E> {
+    pub indent: usize,
+    /// Emit containers over multiple lines.
+    pub multiline: bool,
+    /// If [`Self::multiline`], only break containers wider than this.
+    pub max_width: Option<usize>,
+    /// Elide the contents of containers deeper than this.
+    pub max_depth: Option<usize>,
+    /// Truncate longer strings.
+    pub max_string: Option<usize>,
+    /// Write `@n` after variant names.
+    pub variant_indices: bool,
+    /// How to write byte arrays.
+    pub bytes: BytesFormat,
+    /// Truncate longer byte arrays.
+    pub max_bytes: Option<usize>,
+    /// Emit ANSI escape codes.
+    pub color: bool,
+    /// Sort map entries with this comparison.
+    pub sort_maps: Option<fn(&Save<'a, E>, &Save<'a, E>) -> Ordering>,
+    /// Print `-0.0` as `0.0`, and all NaNs identically.
+    pub normalize_floats: bool,
+    /// Replace matching nodes with `<redacted>`.
+    pub redact: &'r [Box<dyn Fn(&Path<'a>, &Save<'a, E>) -> bool + 'r>],
+}
+
+impl<E> Default for Options<'_, '_, E> {
+    fn default() -> Self {
+        Self {
+            indent: 4,
+            multiline: false,
+            max_width: None,
+            max_depth: None,
+            max_string: None,
+            variant_indices: false,
+            bytes: BytesFormat::Escaped,
+            max_bytes: None,
+            color: false,
+            sort_maps: None,
+            normalize_floats: false,
+            redact: &[],
+        }
+    }
+}
+
+/// Render `save` according to `options`.
+pub(crate) struct Render<'r, 's, 'a, E> {
+    pub options: &'r Options<'r, 'a, E>,
+    pub save: &'s Save<'a, E>,
+}
+
+impl<E: fmt::Display> fmt::Display for Render<'_, '_, '_, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Renderer {
+            options: self.options,
+            f,
+        }
+        .node(&mut Path::new(), self.save, 0, self.options.multiline)
+    }
+}
+
+struct Renderer<'r, 'f, 'a, E> {
+    options: &'r Options<'r, 'a, E>,
+    f: &'f mut dyn fmt::Write,
+}
+
+enum Delim {
+    Paren,
+    Bracket,
+    Brace,
+}
+
+impl<'a, E: fmt::Display> Renderer<'_, '_, 'a, E> {
+    fn node(
+        &mut self,
+        path: &mut Path<'a>,
+        save: &Save<'a, E>,
+        depth: usize,
+        multiline: bool,
+    ) -> fmt::Result {
+        if self.options.redact.iter().any(|it| it(path, save)) {
+            return self.paint(style::ELIDED, format_args!("<redacted>"));
+        }
+        if let (true, Some(width)) = (multiline, self.options.max_width) {
+            let mut line = String::new();
+            Renderer {
+                options: &Options {
+                    color: false,
+                    ..*self.options
+                },
+                f: &mut line,
+            }
+            .node(path, save, depth, false)?;
+            if depth * self.options.indent + line.chars().count() <= width {
+                return self.f.write_str(&line);
+            }
+        }
+        match save {
+            Save::Bool(it) => self.paint(style::LEAF, format_args!("{}", it)),
+            Save::I8(it) => self.leaf("i8", it),
+            Save::I16(it) => self.leaf("i16", it),
+            Save::I32(it) => self.leaf("i32", it),
+            Save::I64(it) => self.leaf("i64", it),
+            Save::I128(it) => self.leaf("i128", it),
+            Save::U8(it) => self.leaf("u8", it),
+            Save::U16(it) => self.leaf("u16", it),
+            Save::U32(it) => self.leaf("u32", it),
+            Save::U64(it) => self.leaf("u64", it),
+            Save::U128(it) => self.leaf("u128", it),
+            Save::F32(it) => match self.options.normalize_floats {
+                true => self.leaf("f32", &(normalize(f64::from(*it)) as f32)),
+                false => self.leaf("f32", it),
+            },
+            Save::F64(it) => match self.options.normalize_floats {
+                true => self.leaf("f64", &normalize(*it)),
+                false => self.leaf("f64", it),
+            },
+            Save::Char(it) => self.leaf("char", it),
+            Save::String(it) => match self.options.max_string {
+                Some(max) if it.chars().nth(max).is_some() => {
+                    let end = it.char_indices().nth(max).map_or(it.len(), |(ix, _)| ix);
+                    self.paint(style::STRING, format_args!("{:?}", &it[..end]))?;
+                    self.paint(style::ELIDED, format_args!("…"))
+                }
+                _ => self.paint(style::STRING, format_args!("{:?}", it)),
+            },
+            Save::ByteArray(it) => self.bytes(it, depth, multiline),
+            Save::Option(None) => self.f.write_str("None"),
+            Save::Option(Some(it)) => {
+                self.f.write_str("Some(")?;
+                self.node(path, it, depth, multiline)?;
+                self.f.write_str(")")
+            }
+            Save::Unit => self.f.write_str("()"),
+            Save::UnitStruct(name) => self.paint(style::NAME, format_args!("{}", name)),
+            Save::UnitVariant(variant) => self.variant(variant),
+            Save::NewTypeStruct { name, value } => {
+                self.paint(style::NAME, format_args!("{}", name))?;
+                self.f.write_str("(")?;
+                self.node(path, value, depth, multiline)?;
+                self.f.write_str(")")
+            }
+            Save::NewTypeVariant { variant, value } => {
+                self.variant(variant)?;
+                self.f.write_str("(")?;
+                self.node(path, value, depth, multiline)?;
+                self.f.write_str(")")
+            }
+            Save::Seq(it) => self.elements(path, Delim::Bracket, it, depth, multiline),
+            Save::Tuple(it) => self.elements(path, Delim::Paren, it, depth, multiline),
+            Save::TupleStruct { name, values } => {
+                self.paint(style::NAME, format_args!("{}", name))?;
+                self.elements(path, Delim::Paren, values, depth, multiline)
+            }
+            Save::TupleVariant { variant, values } => {
+                self.variant(variant)?;
+                self.elements(path, Delim::Paren, values, depth, multiline)
+            }
+            Save::Map(it) => {
+                let mut order = (0..it.len()).collect::<Vec<_>>();
+                if let Some(cmp) = self.options.sort_maps {
+                    order.sort_by(|l, r| {
+                        cmp(&it[*l].0, &it[*r].0).then_with(|| cmp(&it[*l].1, &it[*r].1))
+                    })
+                }
+                self.items(Delim::Brace, order, depth, multiline, |this, ix, depth| {
+                    path.push(Segment::Key(ix));
+                    this.node(path, &it[ix].0, depth, false)?;
+                    path.pop();
+                    this.f.write_str(": ")?;
+                    path.push(Segment::Index(ix));
+                    this.node(path, &it[ix].1, depth, multiline)?;
+                    path.pop();
+                    Ok(())
+                })
+            }
+            Save::Struct { name, fields } => {
+                self.paint(style::NAME, format_args!("{}", name))?;
+                self.f.write_str(" ")?;
+                self.fields(path, fields, depth, multiline)
+            }
+            Save::StructVariant { variant, fields } => {
+                self.variant(variant)?;
+                self.f.write_str(" ")?;
+                self.fields(path, fields, depth, multiline)
+            }
+            Save::Error(e) => self.paint(style::ERROR, format_args!("err({:?})", e.to_string())),
+        }
+    }
+
+    fn variant(&mut self, variant: &Variant<'_>) -> fmt::Result {
+        let Variant {
+            name,
+            variant_index,
+            variant,
+        } = variant;
+        match self.options.variant_indices {
+            true => self.paint(
+                style::NAME,
+                format_args!("{}::{}@{}", name, variant, variant_index),
+            ),
+            false => self.paint(style::NAME, format_args!("{}::{}", name, variant)),
+        }
+    }
+
+    fn elements(
+        &mut self,
+        path: &mut Path<'a>,
+        delim: Delim,
+        elements: &[Save<'a, E>],
+        depth: usize,
+        multiline: bool,
+    ) -> fmt::Result {
+        let trailing_comma = matches!(delim, Delim::Paren) && elements.len() == 1 && !multiline;
+        self.items(
+            delim,
+            0..elements.len(),
+            depth,
+            multiline,
+            |this, ix, depth| {
+                path.push(Segment::Index(ix));
+                this.node(path, &elements[ix], depth, multiline)?;
+                path.pop();
+                if trailing_comma {
+                    this.f.write_str(",")?
+                }
+                Ok(())
+            },
+        )
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn fields(
+        &mut self,
+        path: &mut Path<'a>,
+        fields: &[(&'a str, Option<Save<'a, E>>)],
+        depth: usize,
+        multiline: bool,
+    ) -> fmt::Result {
+        self.items(
+            Delim::Brace,
+            0..fields.len(),
+            depth,
+            multiline,
+            |this, ix, depth| {
+                let (name, value) = &fields[ix];
+                write!(this.f, "{}: ", name)?;
+                match value {
+                    Some(value) => {
+                        path.push(Segment::Field(Cow::Borrowed(name)));
+                        this.node(path, value, depth, multiline)?;
+                        path.pop();
+                        Ok(())
+                    }
+                    None => this.paint(style::ELIDED, format_args!("<skipped>")),
+                }
+            },
+        )
+    }
+
+    /// Write a delimited list of items, calling `item` for each.
+    fn items(
+        &mut self,
+        delim: Delim,
+        items: impl IntoIterator<Item = usize>,
+        depth: usize,
+        multiline: bool,
+        mut item: impl FnMut(&mut Self, usize, usize) -> fmt::Result,
+    ) -> fmt::Result {
+        let (open, close) = match delim {
+            Delim::Paren => ("(", ")"),
+            Delim::Bracket => ("[", "]"),
+            Delim::Brace => ("{", "}"),
+        };
+        self.f.write_str(open)?;
+        let mut items = items.into_iter().peekable();
+        if items.peek().is_none() {
+            return self.f.write_str(close);
+        }
+        if self.options.max_depth.is_some_and(|max| depth >= max) {
+            let pad = match delim {
+                Delim::Brace => " ",
+                _ => "",
+            };
+            self.f.write_str(pad)?;
+            self.paint(style::ELIDED, format_args!("…"))?;
+            self.f.write_str(pad)?;
+            return self.f.write_str(close);
+        }
+        match multiline {
+            true => {
+                for ix in items {
+                    writeln!(self.f)?;
+                    self.indent(depth + 1)?;
+                    item(self, ix, depth + 1)?;
+                    self.f.write_str(",")?;
+                }
+                writeln!(self.f)?;
+                self.indent(depth)?;
+            }
+            false => {
+                let pad = matches!(delim, Delim::Brace);
+                if pad {
+                    self.f.write_str(" ")?
+                }
+                let mut first = true;
+                for ix in items {
+                    if !first {
+                        self.f.write_str(", ")?
+                    }
+                    first = false;
+                    item(self, ix, depth + 1)?
+                }
+                if pad {
+                    self.f.write_str(" ")?
+                }
+            }
+        }
+        self.f.write_str(close)
+    }
+
+    fn bytes(&mut self, bytes: &[u8], depth: usize, multiline: bool) -> fmt::Result {
+        let (bytes, elided) = match self.options.max_bytes {
+            Some(max) if bytes.len() > max => (&bytes[..max], true),
+            _ => (bytes, false),
+        };
+        let elision = match elided {
+            true => " …",
+            false => "",
+        };
+        match self.options.bytes {
+            BytesFormat::Escaped => {
+                self.paint(style::STRING, format_args!("b\"{}\"", bytes.escape_ascii()))?;
+                match elided {
+                    true => self.paint(style::ELIDED, format_args!("…")),
+                    false => Ok(()),
+                }
+            }
+            BytesFormat::Base64 => {
+                self.paint(style::KIND, format_args!("base64"))?;
+                self.f.write_str("(")?;
+                self.paint(style::STRING, format_args!("{}", Base64(bytes)))?;
+                self.paint(style::ELIDED, format_args!("{}", elision))?;
+                self.f.write_str(")")
+            }
+            BytesFormat::Hex if multiline && !bytes.is_empty() => {
+                self.paint(style::KIND, format_args!("hex"))?;
+                self.f.write_str("(")?;
+                for (line, chunk) in bytes.chunks(16).enumerate() {
+                    writeln!(self.f)?;
+                    self.indent(depth + 1)?;
+                    write!(self.f, "{:08x} ", line * 16)?;
+                    for ix in 0..16 {
+                        if ix % 8 == 0 {
+                            self.f.write_str(" ")?
+                        }
+                        match chunk.get(ix) {
+                            Some(byte) => {
+                                self.paint(style::STRING, format_args!("{:02x} ", byte))?
+                            }
+                            None => self.f.write_str("   ")?,
+                        }
+                    }
+                    self.f.write_str(" |")?;
+                    for byte in chunk {
+                        let c = match byte.is_ascii_graphic() || *byte == b' ' {
+                            true => char::from(*byte),
+                            false => '.',
+                        };
+                        self.paint(style::STRING, format_args!("{}", c))?
+                    }
+                    self.f.write_str("|")?;
+                }
+                if elided {
+                    writeln!(self.f)?;
+                    self.indent(depth + 1)?;
+                    self.paint(style::ELIDED, format_args!("…"))?;
+                }
+                writeln!(self.f)?;
+                self.indent(depth)?;
+                self.f.write_str(")")
+            }
+            BytesFormat::Hex => {
+                self.paint(style::KIND, format_args!("hex"))?;
+                self.f.write_str("(")?;
+                for (ix, byte) in bytes.iter().enumerate() {
+                    if ix != 0 {
+                        self.f.write_str(" ")?
+                    }
+                    self.paint(style::STRING, format_args!("{:02x}", byte))?
+                }
+                self.paint(style::ELIDED, format_args!("{}", elision))?;
+                self.f.write_str(")")
+            }
+        }
+    }
+
+    /// Write a number-like leaf, as in `i32(1)`.
+    fn leaf(&mut self, kind: &str, it: &dyn fmt::Debug) -> fmt::Result {
+        self.paint(style::KIND, format_args!("{}", kind))?;
+        self.f.write_str("(")?;
+        self.paint(style::LEAF, format_args!("{:?}", it))?;
+        self.f.write_str(")")
+    }
+
+    fn paint(&mut self, style: &str, args: fmt::Arguments<'_>) -> fmt::Result {
+        paint(self.f, self.options.color.then_some(style), args)
+    }
+
+    fn indent(&mut self, depth: usize) -> fmt::Result {
+        write!(self.f, "{:1$}", "", depth * self.options.indent)
+    }
+}
+
+/// ANSI escape codes.
+pub(crate) mod style {
+    pub const NAME: &str = "\x1b[1;36m";
+    pub const KIND: &str = "\x1b[2m";
+    pub const LEAF: &str = "\x1b[33m";
+    pub const STRING: &str = "\x1b[32m";
+    pub const ERROR: &str = "\x1b[1;31m";
+    pub const ELIDED: &str = "\x1b[2m";
+    pub const ADDED: &str = "\x1b[32m";
+    pub const REMOVED: &str = "\x1b[31m";
+    pub const CHANGED: &str = "\x1b[33m";
+    pub const RESET: &str = "\x1b[0m";
+}
+
+/// Write `args`, wrapped in `style` if it is [`Some`].
+pub(crate) fn paint(
+    f: &mut dyn fmt::Write,
+    style: Option<&str>,
+    args: fmt::Arguments<'_>,
+) -> fmt::Result {
+    match style {
+        Some(style) => write!(f, "{}{}{}", style, args, style::RESET),
+        None => f.write_fmt(args),
+    }
+}
+
+/// Standard, padded base64.
+pub(crate) struct Base64<'s>(pub &'s [u8]);
+
+impl fmt::Display for Base64<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const ALPHABET: &[u8; 64] =
+            b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+        for chunk in self.0.chunks(3) {
+            let n = chunk.iter().enumerate().fold(0u32, |acc, (ix, byte)| {
+                acc | u32::from(*byte) << (16 - 8 * ix)
+            });
+            for ix in 0..4 {
+                match ix <= chunk.len() {
+                    true => {
+                        let sextet = (n >> (18 - 6 * ix)) & 0x3f;
+                        f.write_char(char::from(ALPHABET[sextet as usize]))?
+                    }
+                    false => f.write_char('=')?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn normalize(it: f64) -> f64 {
+    if it.is_nan() {
+        f64::NAN
+    } else if it == 0.0 {
+        0.0
+    } else {
+        it
+    }
+}
@@ -0,0 +1,268 @@
+use crate::{canonical::KINDS, Save};
+use core::fmt::{self, Write as _};
+use std::collections::HashMap;
+
+/// Statistics about the nodes at each path in a tree, or a corpus of trees,
+/// see [`Save::profile`].
+///
+/// Elements of sequences and values of maps are pooled, at paths ending in `[*]`,
+/// so the paths are [queries](Save::find_all).
+/// Elements of tuples keep their index.
+/// Map keys are not profiled.
+///
+/// Like [`Path`](crate::Path)s, profiles see through [`Save::Option`],
+/// [`Save::NewTypeStruct`] and [`Save::NewTypeVariant`].
+#[derive(Debug, Clone, Default)]
+pub struct Profile {
+    /// In the order they were first seen.
+    paths: Vec<(String, PathProfile)>,
+    /// Into `paths`.
+    index: HashMap<String, usize>,
+}
+
+/// Statistics about the nodes at a single path, see [`Profile`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathProfile {
+    count: usize,
+    nones: usize,
+    skipped: usize,
+    kinds: [usize; KINDS.len()],
+    lens: Option<(usize, usize)>,
+}
+
+impl Default for PathProfile {
+    fn default() -> Self {
+        Self {
+            count: 0,
+            nones: 0,
+            skipped: 0,
+            kinds: [0; KINDS.len()],
+            lens: None,
+        }
+    }
+}
+
+impl PathProfile {
+    /// The number of nodes seen at this path, including [`None`]s.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+    /// The number of nodes which were [`None`].
+    pub fn nones(&self) -> usize {
+        self.nones
+    }
+    /// The proportion of nodes which were [`None`], between `0.0` and `1.0`.
+    pub fn none_rate(&self) -> f64 {
+        match self.count {
+            0 => 0.0,
+            count => self.nones as f64 / count as f64,
+        }
+    }
+    /// The number of times this path was a [skipped](serde::ser::SerializeStruct::skip_field)
+    /// field, which are not included in [`Self::count`].
+    pub fn skipped(&self) -> usize {
+        self.skipped
+    }
+    /// The number of nodes of each kind, in the order of [`Save`]'s variants,
+    /// excluding [`None`]s.
+    ///
+    /// Kinds are named as in the [`Display`](fmt::Display) of a [`Shape`](crate::Shape).
+    pub fn kinds(&self) -> Vec<(&'static str, usize)> {
+        KINDS
+            .into_iter()
+            .zip(self.kinds)
+            .filter(|(_, count)| *count != 0)
+            .collect()
+    }
+    /// The shortest and longest [`Save::String`] or [`Save::ByteArray`] in bytes,
+    /// or sequence or map in elements, if any.
+    pub fn len_range(&self) -> Option<(usize, usize)> {
+        self.lens
+    }
+}
+
+impl Profile {
+    /// An empty profile, see [`Self::add`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Include the nodes of `save` in this profile.
+    pub fn add<E>(&mut self, save: &Save<'_, E>) {
+        self.visit(&mut String::new(), save)
+    }
+    /// The statistics for `path`, if any nodes were seen there.
+    pub fn get(&self, path: &str) -> Option<&PathProfile> {
+        self.index.get(path).map(|ix| &self.paths[*ix].1)
+    }
+    /// Every path, with its statistics,
+    /// in the order they were first seen.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &PathProfile)> {
+        self.paths.iter().map(|(path, it)| (path.as_str(), it))
+    }
+
+    fn entry(&mut self, path: &str) -> &mut PathProfile {
+        let ix = match self.index.get(path) {
+            Some(ix) => *ix,
+            None => {
+                self.paths
+                    .push((String::from(path), PathProfile::default()));
+                self.index.insert(String::from(path), self.paths.len() - 1);
+                self.paths.len() - 1
+            }
+        };
+        &mut self.paths[ix].1
+    }
+
+    fn visit<E>(&mut self, path: &mut String, mut save: &Save<'_, E>) {
+        while let Save::Option(Some(it))
+        | Save::NewTypeStruct { value: it, .. }
+        | Save::NewTypeVariant { value: it, .. } = save
+        {
+            save = it
+        }
+        let entry = self.entry(path);
+        entry.count += 1;
+        if let Save::Option(None) = save {
+            entry.nones += 1;
+            return;
+        }
+        entry.kinds[usize::from(save.kind_index())] += 1;
+        let len = match save {
+            Save::String(it) => Some(it.len()),
+            Save::ByteArray(it) => Some(it.len()),
+            Save::Seq(it) => Some(it.len()),
+            Save::Map(it) => Some(it.len()),
+            _ => None,
+        };
+        if let Some(len) = len {
+            entry.lens = Some(match entry.lens {
+                Some((min, max)) => (min.min(len), max.max(len)),
+                None => (len, len),
+            })
+        }
+        let len = path.len();
+        match save {
+            Save::Seq(it) => {
+                path.push_str("[*]");
+                for it in it {
+                    self.visit(path, it)
+                }
+            }
+            Save::Map(it) => {
+                path.push_str("[*]");
+                for (_, it) in it {
+                    self.visit(path, it)
+                }
+            }
+            Save::Tuple(it)
+            | Save::TupleStruct { values: it, .. }
+            | Save::TupleVariant { values: it, .. } => {
+                for (ix, it) in it.iter().enumerate() {
+                    path.truncate(len);
+                    let _ = write!(path, "[{}]", ix);
+                    self.visit(path, it)
+                }
+            }
+            Save::Struct { fields, .. } | Save::StructVariant { fields, .. } => {
+                for (name, it) in fields {
+                    path.truncate(len);
+                    if len != 0 {
+                        path.push('.')
+                    }
+                    path.push_str(name);
+                    match it {
+                        Some(it) => self.visit(path, it),
+                        None => self.entry(path).skipped += 1,
+                    }
+                }
+            }
+            _ => {}
+        }
+        path.truncate(len)
+    }
+}
+
+impl<'s, 'a: 's, E: 's> FromIterator<&'s Save<'a, E>> for Profile {
+    fn from_iter<II: IntoIterator<Item = &'s Save<'a, E>>>(iter: II) -> Self {
+        let mut profile = Self::new();
+        for it in iter {
+            profile.add(it)
+        }
+        profile
+    }
+}
+
+/// One line per path, with its count, kinds, [`None`]s and lengths.
+impl fmt::Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (path, it) in &self.paths {
+            match path.is_empty() {
+                true => write!(f, "<root>: {}", it.count)?,
+                false => write!(f, "{}: {}", path, it.count)?,
+            }
+            for (ix, (kind, count)) in it.kinds().into_iter().enumerate() {
+                let sep = if ix == 0 { " (" } else { ", " };
+                write!(f, "{}{} {}", sep, kind, count)?;
+            }
+            if it.nones != it.count {
+                f.write_str(")")?;
+            }
+            if it.nones != 0 {
+                write!(f, ", none {:.0}%", it.none_rate() * 100.0)?;
+            }
+            if it.skipped != 0 {
+                write!(f, ", skipped {}", it.skipped)?;
+            }
+            if let Some((min, max)) = it.lens {
+                write!(f, ", len {}..={}", min, max)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl<E> Save<'_, E> {
+    /// Summarise the kinds, [`None`]s and lengths of the nodes at each path,
+    /// to understand messy data from external systems.
+    ///
+    /// Use [`Profile::add`] or [`Profile::from_iter`] to profile many trees at once.
+    ///
+    /// ```
+    /// # use serde_save::{save, Profile};
+    /// #[derive(serde::Serialize)]
+    /// struct User {
+    ///     name: &'static str,
+    ///     email: Option<&'static str>,
+    ///     tags: Vec<&'static str>,
+    /// }
+    ///
+    /// let saved = save(vec![
+    ///     User { name: "alice", email: Some("alice@example.com"), tags: vec!["admin"] },
+    ///     User { name: "bob", email: None, tags: vec![] },
+    /// ])
+    /// .unwrap();
+    /// let profile = saved.profile();
+    /// assert_eq!(profile.get("[*].email").unwrap().none_rate(), 0.5);
+    /// assert_eq!(profile.get("[*].tags").unwrap().len_range(), Some((0, 1)));
+    /// assert_eq!(
+    ///     profile.to_string(),
+    ///     "\
+    /// <root>: 1 (seq 1), len 2..=2
+    /// [*]: 2 (struct 2)
+    /// [*].name: 2 (string 2), len 3..=5
+    /// [*].email: 2 (string 1), none 50%, len 17..=17
+    /// [*].tags: 2 (seq 2), len 0..=1
+    /// [*].tags[*]: 1 (string 1), len 5..=5
+    /// "
+    /// );
+    ///
+    /// let corpus = [saved.clone(), saved];
+    /// assert_eq!(corpus.iter().collect::<Profile>().get("[*]").unwrap().count(), 4);
+    /// ```
+    pub fn profile(&self) -> Profile {
+        let mut profile = Profile::new();
+        profile.add(self);
+        profile
+    }
+}
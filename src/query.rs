@@ -0,0 +1,384 @@
+use crate::{
+    lexer::{LexError, Lexer},
+    Path, Save, Segment,
+};
+use core::{cmp::Ordering, fmt};
+use std::borrow::Cow;
+
+/// An error returned from [`Save::find_all`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseQueryError {
+    offset: usize,
+    msg: &'static str,
+}
+
+impl fmt::Display for ParseQueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid query at offset {}: {}", self.offset, self.msg)
+    }
+}
+
+impl std::error::Error for ParseQueryError {}
+
+impl From<LexError> for ParseQueryError {
+    fn from(LexError { offset, msg }: LexError) -> Self {
+        Self { offset, msg }
+    }
+}
+
+impl<'a, E> Save<'a, E> {
+    /// Find every node matching `query`, along with its [`Path`], in pre-order.
+    ///
+    /// Queries extend the syntax of [`Path`]:
+    /// - `name` or `."quoted name"` selects a struct field,
+    ///   or the value of a map entry with that string key.
+    /// - `[n]` selects an element, or the value of the n'th map entry,
+    ///   and `{n}` selects the key of the n'th map entry.
+    /// - `*` or `[*]` selects every field, element and map value.
+    /// - `[?predicate]` selects every field, element and map value for which
+    ///   the predicate holds.
+    ///
+    /// A predicate is a query relative to the candidate node, optionally
+    /// starting with `@`, and optionally followed by a comparison with a
+    /// literal, using one of `==`, `!=`, `<`, `<=`, `>` or `>=`.
+    /// The predicate holds if any node it selects exists and compares truthfully.
+    ///
+    /// Literals are numbers, `true`, `false` or strings.
+    /// Strings are compared with [`Save::String`]s, [`Save::Char`]s and the
+    /// names of [`Save::UnitVariant`]s.
+    ///
+    /// Like [`Path`]s, queries see through [`Save::Option`],
+    /// [`Save::NewTypeStruct`] and [`Save::NewTypeVariant`].
+    ///
+    /// ```
+    /// # use serde_save::{save, Save};
+    /// #[derive(serde::Serialize)]
+    /// struct Server {
+    ///     port: u16,
+    ///     tls: Option<Tls>,
+    /// }
+    /// #[derive(serde::Serialize)]
+    /// struct Tls {
+    ///     cert: &'static str,
+    /// }
+    ///
+    /// let saved = save(vec![
+    ///     Server { port: 80, tls: None },
+    ///     Server { port: 443, tls: Some(Tls { cert: "a.pem" }) },
+    ///     Server { port: 8443, tls: Some(Tls { cert: "b.pem" }) },
+    /// ])
+    /// .unwrap();
+    ///
+    /// let certs = saved.find_all("[*].tls.cert").unwrap();
+    /// assert_eq!(certs[0].0.to_string(), "[1].tls.cert");
+    /// assert_eq!(certs[1].1, &Save::string("b.pem"));
+    ///
+    /// let high = saved.find_all("[?port > 1024].tls.cert").unwrap();
+    /// assert_eq!(high.len(), 1);
+    /// assert_eq!(high[0].0.to_string(), "[2].tls.cert");
+    ///
+    /// assert_eq!(saved.find_all(r#"[?tls.cert == "a.pem"]"#).unwrap().len(), 1);
+    /// assert!(saved.find_all("[?port >]").is_err());
+    /// ```
+    #[allow(clippy::type_complexity)]
+    pub fn find_all<'s>(
+        &'s self,
+        query: &str,
+    ) -> Result<Vec<(Path<'a>, &'s Self)>, ParseQueryError> {
+        let mut lexer = Lexer::new(query);
+        let steps = steps(&mut lexer, false)?;
+        lexer.finish()?;
+        let mut out = Vec::new();
+        select(self, &steps, &mut Path::new(), &mut |path, it| {
+            out.push((path.clone(), it))
+        });
+        Ok(out)
+    }
+}
+
+enum Step<'q> {
+    Field(Cow<'q, str>),
+    Index(usize),
+    Key(usize),
+    Wildcard,
+    Filter(Predicate<'q>),
+}
+
+struct Predicate<'q> {
+    steps: Vec<Step<'q>>,
+    test: Option<(Op, Literal)>,
+}
+
+#[derive(Clone, Copy)]
+enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+enum Literal {
+    Int(i128),
+    Float(f64),
+    Str(String),
+    Bool(bool),
+}
+
+fn steps<'q>(lexer: &mut Lexer<'q>, relative: bool) -> Result<Vec<Step<'q>>, ParseQueryError> {
+    let mut steps = Vec::new();
+    if relative {
+        lexer.eat("@");
+    }
+    if let Some(step) = name(lexer)? {
+        steps.push(step)
+    }
+    loop {
+        if lexer.eat(".") {
+            match name(lexer)? {
+                Some(step) => steps.push(step),
+                None => return Err(lexer.fail("expected a field name or `*`").into()),
+            }
+        } else if lexer.eat("[") {
+            steps.push(if lexer.eat("*") {
+                Step::Wildcard
+            } else if lexer.eat("?") {
+                Step::Filter(predicate(lexer)?)
+            } else {
+                Step::Index(lexer.number()?)
+            });
+            lexer.expect("]", "expected `]`")?;
+        } else if lexer.eat("{") {
+            steps.push(Step::Key(lexer.number()?));
+            lexer.expect("}", "expected `}`")?;
+        } else {
+            return Ok(steps);
+        }
+    }
+}
+
+fn name<'q>(lexer: &mut Lexer<'q>) -> Result<Option<Step<'q>>, ParseQueryError> {
+    if lexer.eat("*") {
+        return Ok(Some(Step::Wildcard));
+    }
+    if lexer.eat("\"") {
+        return Ok(Some(Step::Field(Cow::Owned(lexer.quoted('"')?))));
+    }
+    Ok(match lexer.word() {
+        "" => None,
+        it => Some(Step::Field(Cow::Borrowed(it))),
+    })
+}
+
+fn predicate<'q>(lexer: &mut Lexer<'q>) -> Result<Predicate<'q>, ParseQueryError> {
+    let steps = steps(lexer, true)?;
+    let op = [
+        ("==", Op::Eq),
+        ("!=", Op::Ne),
+        ("<=", Op::Le),
+        (">=", Op::Ge),
+        ("<", Op::Lt),
+        (">", Op::Gt),
+    ]
+    .into_iter()
+    .find(|(token, _)| lexer.eat(token));
+    let test = match op {
+        Some((_, op)) => Some((op, literal(lexer)?)),
+        None => None,
+    };
+    Ok(Predicate { steps, test })
+}
+
+fn literal(lexer: &mut Lexer<'_>) -> Result<Literal, ParseQueryError> {
+    if lexer.eat("\"") {
+        return Ok(Literal::Str(lexer.quoted('"')?));
+    }
+    lexer.skip_whitespace();
+    let rest = lexer.rest();
+    let end = rest
+        .find(|it: char| !(it.is_ascii_alphanumeric() || matches!(it, '_' | '.' | '+' | '-')))
+        .unwrap_or(rest.len());
+    let literal = match &rest[..end] {
+        "true" => Literal::Bool(true),
+        "false" => Literal::Bool(false),
+        it => match (it.parse(), it.parse()) {
+            (Ok(it), _) => Literal::Int(it),
+            (_, Ok(it)) => Literal::Float(it),
+            _ => return Err(lexer.fail("expected a literal").into()),
+        },
+    };
+    lexer.offset += end;
+    Ok(literal)
+}
+
+#[allow(clippy::type_complexity)]
+fn select<'s, 'a, E>(
+    save: &'s Save<'a, E>,
+    steps: &[Step<'_>],
+    path: &mut Path<'a>,
+    out: &mut dyn FnMut(&Path<'a>, &'s Save<'a, E>),
+) {
+    let Some((step, rest)) = steps.split_first() else {
+        return out(path, save);
+    };
+    let node = unwrap(save);
+    let mut descend = |path: &mut Path<'a>, segment, child| {
+        path.push(segment);
+        select(child, rest, path, out);
+        path.pop();
+    };
+    match step {
+        Step::Field(name) => match node {
+            Save::Struct { fields, .. } | Save::StructVariant { fields, .. } => {
+                for (k, v) in fields {
+                    if let (true, Some(v)) = (k == name, v) {
+                        descend(path, Segment::Field(Cow::Borrowed(k)), v)
+                    }
+                }
+            }
+            Save::Map(it) => {
+                for (ix, (k, v)) in it.iter().enumerate() {
+                    if matches!(k, Save::String(k) if k == name) {
+                        descend(path, Segment::Index(ix), v)
+                    }
+                }
+            }
+            _ => {}
+        },
+        Step::Index(ix) => match node {
+            Save::Map(it) => {
+                if let Some((_, v)) = it.get(*ix) {
+                    descend(path, Segment::Index(*ix), v)
+                }
+            }
+            _ => {
+                if let Some(it) = node.elements().nth(*ix) {
+                    descend(path, Segment::Index(*ix), it)
+                }
+            }
+        },
+        Step::Key(ix) => {
+            if let Some((k, _)) = node.entries().nth(*ix) {
+                descend(path, Segment::Key(*ix), k)
+            }
+        }
+        Step::Wildcard => {
+            for (segment, child) in children(node) {
+                descend(path, segment, child)
+            }
+        }
+        Step::Filter(predicate) => {
+            for (segment, child) in children(node) {
+                if predicate.holds(child) {
+                    descend(path, segment, child)
+                }
+            }
+        }
+    }
+}
+
+/// Every field, element and map value of `node`.
+fn children<'s, 'a, E>(node: &'s Save<'a, E>) -> Vec<(Segment<'a>, &'s Save<'a, E>)> {
+    match node {
+        Save::Struct { fields, .. } | Save::StructVariant { fields, .. } => fields
+            .iter()
+            .filter_map(|(k, v)| Some((Segment::Field(Cow::Borrowed(*k)), v.as_ref()?)))
+            .collect(),
+        Save::Map(it) => it
+            .iter()
+            .enumerate()
+            .map(|(ix, (_, v))| (Segment::Index(ix), v))
+            .collect(),
+        _ => node
+            .elements()
+            .enumerate()
+            .map(|(ix, it)| (Segment::Index(ix), it))
+            .collect(),
+    }
+}
+
+/// See through wrapper nodes, as [`Path`]s do.
+fn unwrap<'s, 'a, E>(save: &'s Save<'a, E>) -> &'s Save<'a, E> {
+    match save {
+        Save::Option(Some(it))
+        | Save::NewTypeStruct { value: it, .. }
+        | Save::NewTypeVariant { value: it, .. } => unwrap(it),
+        it => it,
+    }
+}
+
+impl Predicate<'_> {
+    fn holds<E>(&self, save: &Save<'_, E>) -> bool {
+        let mut holds = false;
+        select(save, &self.steps, &mut Path::new(), &mut |_, it| {
+            holds |= match &self.test {
+                None => true,
+                Some((op, literal)) => {
+                    let ordering = compare(unwrap(it), literal);
+                    match op {
+                        Op::Eq => ordering == Some(Ordering::Equal),
+                        Op::Ne => ordering != Some(Ordering::Equal),
+                        Op::Lt => ordering == Some(Ordering::Less),
+                        Op::Le => matches!(ordering, Some(Ordering::Less | Ordering::Equal)),
+                        Op::Gt => ordering == Some(Ordering::Greater),
+                        Op::Ge => matches!(ordering, Some(Ordering::Greater | Ordering::Equal)),
+                    }
+                }
+            }
+        });
+        holds
+    }
+}
+
+fn compare<E>(save: &Save<'_, E>, literal: &Literal) -> Option<Ordering> {
+    match (save, literal) {
+        (Save::Bool(l), Literal::Bool(r)) => Some(l.cmp(r)),
+        (Save::String(l), Literal::Str(r)) => Some(l.cmp(r)),
+        (Save::Char(l), Literal::Str(r)) => Some(String::from(*l).cmp(r)),
+        (Save::UnitVariant(l), Literal::Str(r)) => Some(l.variant.cmp(r)),
+        (_, Literal::Int(r)) => compare_int(save, *r),
+        (_, Literal::Float(r)) => float(save)?.partial_cmp(r),
+        _ => None,
+    }
+}
+
+fn compare_int<E>(save: &Save<'_, E>, r: i128) -> Option<Ordering> {
+    let unsigned = |l: u128| match u128::try_from(r) {
+        Ok(r) => Some(l.cmp(&r)),
+        Err(_) => Some(Ordering::Greater),
+    };
+    match *save {
+        Save::I8(l) => Some(i128::from(l).cmp(&r)),
+        Save::I16(l) => Some(i128::from(l).cmp(&r)),
+        Save::I32(l) => Some(i128::from(l).cmp(&r)),
+        Save::I64(l) => Some(i128::from(l).cmp(&r)),
+        Save::I128(l) => Some(l.cmp(&r)),
+        Save::U8(l) => unsigned(l.into()),
+        Save::U16(l) => unsigned(l.into()),
+        Save::U32(l) => unsigned(l.into()),
+        Save::U64(l) => unsigned(l.into()),
+        Save::U128(l) => unsigned(l),
+        Save::F32(l) => f64::from(l).partial_cmp(&(r as f64)),
+        Save::F64(l) => l.partial_cmp(&(r as f64)),
+        _ => None,
+    }
+}
+
+fn float<E>(save: &Save<'_, E>) -> Option<f64> {
+    Some(match *save {
+        Save::I8(it) => it.into(),
+        Save::I16(it) => it.into(),
+        Save::I32(it) => it.into(),
+        Save::I64(it) => it as f64,
+        Save::I128(it) => it as f64,
+        Save::U8(it) => it.into(),
+        Save::U16(it) => it.into(),
+        Save::U32(it) => it.into(),
+        Save::U64(it) => it as f64,
+        Save::U128(it) => it as f64,
+        Save::F32(it) => it.into(),
+        Save::F64(it) => it,
+        _ => return None,
+    })
+}
@@ -0,0 +1,74 @@
+use crate::Save;
+
+/// The name `serde_json::value::RawValue` serializes as.
+const RAW_VALUE: &str = "$serde_json::private::RawValue";
+/// The name `serde_json::Number` serializes as, with `arbitrary_precision`.
+const NUMBER: &str = "$serde_json::private::Number";
+
+/// Names which formats treat specially, which carry their raw text in a
+/// single field of the same name.
+const MARKERS: &[&str] = &[RAW_VALUE, NUMBER];
+
+/// Whether `name` is one of the [`MARKERS`], which are exempt from
+/// [`check_identifiers`](crate::Serializer::check_identifiers).
+pub(crate) fn is_marker(name: &str) -> bool {
+    MARKERS.contains(&name)
+}
+
+impl<'a, E> Save<'a, E> {
+    /// Create a node holding raw JSON text,
+    /// as saved from a `serde_json::value::RawValue`.
+    ///
+    /// See [`Self::as_raw_json`].
+    pub fn raw_json(json: impl Into<String>) -> Self {
+        Self::Struct {
+            name: RAW_VALUE,
+            fields: vec![(RAW_VALUE, Some(Self::String(json.into())))],
+        }
+    }
+    /// If this node holds raw JSON text, return it.
+    ///
+    /// `serde_json::value::RawValue` (and `serde_json::Number` with
+    /// `arbitrary_precision`) serialize as a [`Save::Struct`] with a special name,
+    /// which `serde_json` recognises to write the text verbatim.
+    /// These are saved unchanged, so [forwarding](Save::forward) them to `serde_json`
+    /// emits the original text, rather than an object.
+    /// See [`Forward::raw_json_as_strings`](crate::Forward::raw_json_as_strings) for other formats.
+    ///
+    /// ```
+    /// # use serde::ser::{Serialize, Serializer, SerializeStruct as _};
+    /// # use serde_save::{save, Save};
+    /// // What `serde_json::value::RawValue` does.
+    /// struct RawValue(&'static str);
+    /// impl Serialize for RawValue {
+    ///     fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    ///         const TOKEN: &str = "$serde_json::private::RawValue";
+    ///         let mut strukt = serializer.serialize_struct(TOKEN, 1)?;
+    ///         strukt.serialize_field(TOKEN, self.0)?;
+    ///         strukt.end()
+    ///     }
+    /// }
+    ///
+    /// let saved = save(("id", RawValue(r#"{"a": [1, 2]}"#))).unwrap();
+    /// assert_eq!(
+    ///     saved,
+    ///     Save::Tuple(vec![Save::string("id"), Save::raw_json(r#"{"a": [1, 2]}"#)])
+    /// );
+    /// let Save::Tuple(elements) = &saved else { unreachable!() };
+    /// assert_eq!(elements[1].as_raw_json(), Some(r#"{"a": [1, 2]}"#));
+    /// ```
+    pub fn as_raw_json(&self) -> Option<&str> {
+        self.raw_marker().map(|(_, json)| json)
+    }
+    /// The marker name and text of a raw JSON node.
+    pub(crate) fn raw_marker(&self) -> Option<(&'static str, &str)> {
+        let Save::Struct { name, fields } = self else {
+            return None;
+        };
+        let marker = MARKERS.iter().find(|it| **it == *name)?;
+        match fields.as_slice() {
+            [(field, Some(Save::String(json)))] if field == marker => Some((marker, json)),
+            _ => None,
+        }
+    }
+}
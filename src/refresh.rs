@@ -0,0 +1,316 @@
+use crate::{
+    imp::{ErrorDiscipline, ShortCircuit},
+    Error, Path, Save, Segment, Serializer,
+};
+use serde::{ser::Error as _, Serialize};
+use std::borrow::Cow;
+
+impl Save<'static> {
+    /// Re-serialize only the subtrees of `value` at `dirty_paths`,
+    /// and splice them into this capture of `value`.
+    ///
+    /// Fields and elements which do not lead to a dirty path are not serialized,
+    /// so this is much cheaper than a full capture of a large,
+    /// mostly-unchanged structure.
+    ///
+    /// ```
+    /// # use serde_save::{save, Path, Save};
+    /// #[derive(serde::Serialize)]
+    /// struct Cache {
+    ///     hits: u64,
+    ///     entries: Vec<String>,
+    /// }
+    ///
+    /// let mut cache = Cache { hits: 0, entries: vec![String::from("a"); 1000] };
+    /// let mut saved = save(&cache).unwrap();
+    ///
+    /// cache.hits += 1;
+    /// cache.entries[7] = String::from("b");
+    /// saved
+    ///     .refresh(&cache, &[Path::parse("hits").unwrap(), Path::parse("entries[7]").unwrap()])
+    ///     .unwrap();
+    /// assert_eq!(saved, save(&cache).unwrap());
+    /// ```
+    ///
+    /// See [`Serializer::refresh`] for more.
+    pub fn refresh<T: Serialize + ?Sized>(
+        &mut self,
+        value: &T,
+        dirty_paths: &[Path<'_>],
+    ) -> Result<(), Error> {
+        Serializer::<ShortCircuit>::new().refresh(self, value, dirty_paths)
+    }
+}
+
+impl<D: ErrorDiscipline> Serializer<D> {
+    /// Re-serialize only the subtrees of `value` at `dirty_paths` with this serializer,
+    /// and splice them into `save`, an earlier capture of `value`.
+    ///
+    /// A dirty path inside another dirty path is redundant.
+    /// Wrapper nodes share their contents' [`Path`], so a dirty path refreshes
+    /// the outermost node at that location, as returned by [`Save::get`].
+    ///
+    /// Fails without changing `save` if serialization fails,
+    /// or a dirty path does not exist in both `value` and `save`,
+    /// e.g because a sequence has changed length.
+    /// In that case, take a full capture instead.
+    pub fn refresh<T: Serialize + ?Sized>(
+        &self,
+        save: &mut Save<'static, D::SaveError>,
+        value: &T,
+        dirty_paths: &[Path<'_>],
+    ) -> Result<(), Error> {
+        let dirty = dirty_paths
+            .iter()
+            .filter(|path| {
+                !dirty_paths
+                    .iter()
+                    .any(|it| it.len() < path.len() && path.starts_with(it))
+            })
+            .map(|it| it.clone().into_owned())
+            .collect::<Vec<_>>();
+        let mut found = Vec::new();
+        match dirty.iter().any(|it| it.is_empty()) {
+            true => found.push((Path::new(), D::handle(value.serialize(self.fresh()))?)),
+            false => value.serialize(Focus {
+                serializer: self.fresh(),
+                path: Path::new(),
+                dirty: &dirty,
+                found: &mut found,
+                ix: 0,
+            })?,
+        }
+        for path in &dirty {
+            if !found.iter().any(|(it, _)| it == path) || save.get(path).is_none() {
+                return Err(Error::custom(format_args!(
+                    "cannot refresh `{}`, which is not present in both the value and the capture",
+                    path
+                )));
+            }
+        }
+        for (path, it) in found {
+            if let Some(node) = save.get_mut(&path) {
+                *node = it
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Walks a value, only descending into children which lead to a dirty path,
+/// and capturing the subtrees at dirty paths.
+struct Focus<'f, D: ErrorDiscipline> {
+    serializer: Serializer<D>,
+    path: Path<'static>,
+    /// With no path inside another.
+    dirty: &'f [Path<'static>],
+    found: &'f mut Vec<(Path<'static>, Save<'static, D::SaveError>)>,
+    /// The index of the next element or entry, for collections.
+    ix: usize,
+}
+
+impl<D: ErrorDiscipline> Focus<'_, D> {
+    /// Capture or descend into the child at `segment`, if it leads to a dirty path.
+    fn child<T: Serialize + ?Sized>(
+        &mut self,
+        segment: Segment<'static>,
+        value: &T,
+    ) -> Result<(), Error> {
+        let path = self.path.join(segment.clone());
+        if !self.dirty.iter().any(|it| it.starts_with(&path)) {
+            return Ok(());
+        }
+        let serializer = self.serializer.child(segment);
+        match self.dirty.contains(&path) {
+            true => {
+                let save = D::handle(value.serialize(serializer))?;
+                self.found.push((path, save));
+                Ok(())
+            }
+            false => value.serialize(Focus {
+                serializer,
+                path,
+                dirty: self.dirty,
+                found: &mut *self.found,
+                ix: 0,
+            }),
+        }
+    }
+    /// Like [`Self::child`], for the next element of a collection.
+    fn element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.ix += 1;
+        self.child(Segment::Index(self.ix - 1), value)
+    }
+}
+
+macro_rules! leaves {
+    ($($method:ident($ty:ty));* $(;)?) => {
+        $(
+            fn $method(self, _: $ty) -> Result<Self::Ok, Self::Error> {
+                Ok(())
+            }
+        )*
+    };
+}
+
+impl<'f, D: ErrorDiscipline> serde::Serializer for Focus<'f, D> {
+    type Ok = ();
+    type Error = Error;
+    type SerializeSeq = Self;
+    type SerializeTuple = Self;
+    type SerializeTupleStruct = Self;
+    type SerializeTupleVariant = Self;
+    type SerializeMap = Self;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Self;
+
+    fn is_human_readable(&self) -> bool {
+        self.serializer.config().is_human_readable
+    }
+
+    leaves! {
+        serialize_bool(bool);
+        serialize_i8(i8);
+        serialize_i16(i16);
+        serialize_i32(i32);
+        serialize_i64(i64);
+        serialize_i128(i128);
+        serialize_u8(u8);
+        serialize_u16(u16);
+        serialize_u32(u32);
+        serialize_u64(u64);
+        serialize_u128(u128);
+        serialize_f32(f32);
+        serialize_f64(f64);
+        serialize_char(char);
+        serialize_str(&str);
+        serialize_bytes(&[u8]);
+        serialize_unit_struct(&'static str);
+    }
+
+    fn serialize_none(self) -> Result<(), Error> {
+        Ok(())
+    }
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<(), Error> {
+        value.serialize(self)
+    }
+    fn serialize_unit(self) -> Result<(), Error> {
+        Ok(())
+    }
+    fn serialize_unit_variant(self, _: &'static str, _: u32, _: &'static str) -> Result<(), Error> {
+        Ok(())
+    }
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        value.serialize(self)
+    }
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        value.serialize(self)
+    }
+    fn serialize_seq(self, _: Option<usize>) -> Result<Self, Error> {
+        Ok(self)
+    }
+    fn serialize_tuple(self, _: usize) -> Result<Self, Error> {
+        Ok(self)
+    }
+    fn serialize_tuple_struct(self, _: &'static str, _: usize) -> Result<Self, Error> {
+        Ok(self)
+    }
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self, Error> {
+        Ok(self)
+    }
+    fn serialize_map(self, _: Option<usize>) -> Result<Self, Error> {
+        Ok(self)
+    }
+    fn serialize_struct(self, _: &'static str, _: usize) -> Result<Self, Error> {
+        Ok(self)
+    }
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self, Error> {
+        Ok(self)
+    }
+    fn collect_str<T: ?Sized + core::fmt::Display>(self, _: &T) -> Result<Self::Ok, Self::Error> {
+        Ok(())
+    }
+}
+
+macro_rules! elements {
+    ($($trait:ident::$method:ident),* $(,)?) => {
+        $(
+            impl<D: ErrorDiscipline> serde::ser::$trait for Focus<'_, D> {
+                type Ok = ();
+                type Error = Error;
+                fn $method<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+                    self.element(value)
+                }
+                fn end(self) -> Result<(), Error> {
+                    Ok(())
+                }
+            }
+        )*
+    };
+}
+
+elements! {
+    SerializeSeq::serialize_element,
+    SerializeTuple::serialize_element,
+    SerializeTupleStruct::serialize_field,
+    SerializeTupleVariant::serialize_field,
+}
+
+impl<D: ErrorDiscipline> serde::ser::SerializeMap for Focus<'_, D> {
+    type Ok = ();
+    type Error = Error;
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), Error> {
+        self.child(Segment::Key(self.ix), key)
+    }
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        self.element(value)
+    }
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+macro_rules! fields {
+    ($($trait:ident),* $(,)?) => {
+        $(
+            impl<D: ErrorDiscipline> serde::ser::$trait for Focus<'_, D> {
+                type Ok = ();
+                type Error = Error;
+                fn serialize_field<T: ?Sized + Serialize>(
+                    &mut self,
+                    key: &'static str,
+                    value: &T,
+                ) -> Result<(), Error> {
+                    self.child(Segment::Field(Cow::Borrowed(key)), value)
+                }
+                fn end(self) -> Result<(), Error> {
+                    Ok(())
+                }
+            }
+        )*
+    };
+}
+
+fields!(SerializeStruct, SerializeStructVariant);
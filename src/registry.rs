@@ -0,0 +1,276 @@
+use crate::{Path, Save, Segment, Shape, ShapeChange, ShapeDiff, ShapeVariant};
+use core::fmt;
+use std::{borrow::Cow, collections::BTreeMap};
+
+/// [`Shape`]s registered by name, for enforcing payload contracts at runtime.
+///
+/// Use names like `OrderV2` to keep several versions of a contract.
+///
+/// ```
+/// # use serde_save::{save, Registry};
+/// #[derive(serde::Serialize)]
+/// struct Order {
+///     id: u64,
+///     items: Vec<&'static str>,
+///     note: Option<&'static str>,
+/// }
+///
+/// let mut registry = Registry::new();
+/// registry.register("OrderV2", &save(Order { id: 1, items: vec!["apple"], note: None }).unwrap());
+///
+/// // Sequence lengths and the contents of `None` options may vary.
+/// let order = save(Order { id: 2, items: vec![], note: Some("fragile") }).unwrap();
+/// assert_eq!(registry.validate("OrderV2", &order), Ok(()));
+///
+/// let wrong = save(("not", "an", "order")).unwrap();
+/// assert_eq!(
+///     registry.validate("OrderV2", &wrong).unwrap_err().to_string(),
+///     r#"payload does not conform to "OrderV2":
+/// ~ <root>: struct "Order" {"id": u64, "items": seq(1) [string], "note": none} -> tuple(string, string, string)
+/// "#,
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Registry {
+    shapes: BTreeMap<String, Shape>,
+}
+
+/// An error returned from [`Registry::validate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ValidationError {
+    /// No shape is registered under the name.
+    Unregistered(String),
+    /// The tree doesn't conform to the registered shape.
+    Mismatch {
+        /// The name of the registered shape.
+        name: String,
+        /// How the tree differs from the registered shape.
+        ///
+        /// Each [`ShapeChange`] is relative to the registered shape.
+        diff: ShapeDiff,
+    },
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationError::Unregistered(name) => write!(f, "no shape registered for {:?}", name),
+            ValidationError::Mismatch { name, diff } => {
+                writeln!(f, "payload does not conform to {:?}:", name)?;
+                fmt::Display::fmt(diff, f)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+impl Registry {
+    /// An empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Register `shape` under `name`, returning the previously registered shape, if any.
+    pub fn register(&mut self, name: impl Into<String>, shape: impl Into<Shape>) -> Option<Shape> {
+        self.shapes.insert(name.into(), shape.into())
+    }
+    /// Get the shape registered under `name`.
+    pub fn get(&self, name: &str) -> Option<&Shape> {
+        self.shapes.get(name)
+    }
+    /// The registered names and shapes, in order of name.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Shape)> {
+        self.shapes
+            .iter()
+            .map(|(name, shape)| (name.as_str(), shape))
+    }
+    /// Check that `save` conforms to the shape registered under `name`.
+    ///
+    /// - Lengths of sequences and maps are ignored,
+    ///   and each element or entry must conform to one of the registered shapes
+    ///   for the collection's contents.
+    /// - An [`Option`] which was [`None`] when registered matches any [`Option`],
+    ///   and [`None`] is always allowed.
+    /// - Struct fields are matched by name.
+    ///   A field which was [skip](serde::ser::SerializeStruct::skip_field)ped
+    ///   when registered matches anything,
+    ///   and any field may be skipped.
+    pub fn validate<E>(&self, name: &str, save: &Save<'_, E>) -> Result<(), ValidationError> {
+        let shape = self
+            .get(name)
+            .ok_or_else(|| ValidationError::Unregistered(String::from(name)))?;
+        let mut changes = Vec::new();
+        conform(&mut Path::new(), save, shape, &mut changes);
+        match changes.is_empty() {
+            true => Ok(()),
+            false => Err(ValidationError::Mismatch {
+                name: String::from(name),
+                diff: ShapeDiff { changes },
+            }),
+        }
+    }
+}
+
+/// Check that `save` conforms to `shape`, recording any differences in `changes`.
+fn conform<E>(
+    path: &mut Path<'static>,
+    save: &Save<'_, E>,
+    shape: &Shape,
+    changes: &mut Vec<ShapeChange>,
+) {
+    let within = |path: &mut Path<'static>, segment, save, shape, changes: &mut _| {
+        path.push(segment);
+        conform(path, save, shape, changes);
+        path.pop();
+    };
+    match (save, shape) {
+        (Save::Option(None), Shape::Option(_)) | (Save::Option(Some(_)), Shape::Option(None)) => {}
+        (Save::Option(Some(save)), Shape::Option(Some(shape))) => {
+            conform(path, save, shape, changes)
+        }
+        (Save::NewTypeStruct { name, value }, Shape::NewTypeStruct { name: n, value: v })
+            if name == n =>
+        {
+            conform(path, value, v, changes)
+        }
+        (
+            Save::NewTypeVariant { variant, value },
+            Shape::NewTypeVariant {
+                variant: sv,
+                value: v,
+            },
+        ) if *sv == ShapeVariant::from(*variant) => conform(path, value, v, changes),
+        (Save::Seq(elements), Shape::Seq { items, .. }) => {
+            for (ix, element) in elements.iter().enumerate() {
+                if let Some(item) = best(items, |item| attempt(element, item)) {
+                    within(path, Segment::Index(ix), element, item, changes)
+                }
+            }
+        }
+        (
+            Save::Map(entries),
+            Shape::Map {
+                entries: shapes, ..
+            },
+        ) => {
+            for (ix, (k, v)) in entries.iter().enumerate() {
+                if let Some((sk, sv)) = best(shapes, |(sk, sv)| attempt(k, sk) + attempt(v, sv)) {
+                    within(path, Segment::Key(ix), k, sk, changes);
+                    within(path, Segment::Index(ix), v, sv, changes);
+                }
+            }
+        }
+        (Save::Tuple(l), Shape::Tuple(r)) if l.len() == r.len() => {
+            for (ix, (save, shape)) in l.iter().zip(r).enumerate() {
+                within(path, Segment::Index(ix), save, shape, changes)
+            }
+        }
+        (Save::TupleStruct { name, values }, Shape::TupleStruct { name: n, values: r })
+            if name == n && values.len() == r.len() =>
+        {
+            for (ix, (save, shape)) in values.iter().zip(r).enumerate() {
+                within(path, Segment::Index(ix), save, shape, changes)
+            }
+        }
+        (
+            Save::TupleVariant { variant, values },
+            Shape::TupleVariant {
+                variant: sv,
+                values: r,
+            },
+        ) if *sv == ShapeVariant::from(*variant) && values.len() == r.len() => {
+            for (ix, (save, shape)) in values.iter().zip(r).enumerate() {
+                within(path, Segment::Index(ix), save, shape, changes)
+            }
+        }
+        (Save::Struct { name, fields }, Shape::Struct { name: n, fields: r }) if name == n => {
+            conform_fields(path, fields, r, changes)
+        }
+        (
+            Save::StructVariant { variant, fields },
+            Shape::StructVariant {
+                variant: sv,
+                fields: r,
+            },
+        ) if *sv == ShapeVariant::from(*variant) => conform_fields(path, fields, r, changes),
+        (save, shape) => {
+            let actual = Shape::of(save);
+            let same_leaf = !matches!(
+                actual,
+                Shape::Option(_)
+                    | Shape::NewTypeStruct { .. }
+                    | Shape::NewTypeVariant { .. }
+                    | Shape::Seq { .. }
+                    | Shape::Map { .. }
+                    | Shape::Tuple(_)
+                    | Shape::TupleStruct { .. }
+                    | Shape::TupleVariant { .. }
+                    | Shape::Struct { .. }
+                    | Shape::StructVariant { .. }
+            ) && actual == *shape;
+            if !same_leaf {
+                changes.push(ShapeChange::Changed {
+                    path: path.clone(),
+                    before: shape.clone(),
+                    after: actual,
+                })
+            }
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn conform_fields<E>(
+    path: &mut Path<'static>,
+    fields: &[(&str, Option<Save<'_, E>>)],
+    shapes: &[(String, Option<Shape>)],
+    changes: &mut Vec<ShapeChange>,
+) {
+    for (name, shape) in shapes {
+        let field = Segment::Field(Cow::Owned(name.clone()));
+        match fields.iter().find(|(it, _)| it == name) {
+            Some((_, Some(save))) => {
+                if let Some(shape) = shape {
+                    path.push(field);
+                    conform(path, save, shape, changes);
+                    path.pop();
+                }
+            }
+            Some((_, None)) => {}
+            None => changes.push(ShapeChange::Removed {
+                path: path.join(field),
+                shape: shape.clone().unwrap_or(Shape::Unit),
+            }),
+        }
+    }
+    for (name, save) in fields {
+        if !shapes.iter().any(|(it, _)| it == name) {
+            changes.push(ShapeChange::Added {
+                path: path.join(Segment::Field(Cow::Owned(String::from(*name)))),
+                shape: save.as_ref().map(Shape::of).unwrap_or(Shape::Unit),
+            })
+        }
+    }
+}
+
+/// The number of differences between `save` and `shape`.
+fn attempt<E>(save: &Save<'_, E>, shape: &Shape) -> usize {
+    let mut changes = Vec::new();
+    conform(&mut Path::new(), save, shape, &mut changes);
+    changes.len()
+}
+
+/// The candidate with the fewest differences, or [`None`] if one matches exactly,
+/// or there are no candidates.
+fn best<T>(candidates: &[T], differences: impl Fn(&T) -> usize) -> Option<&T> {
+    let mut best = None;
+    for it in candidates {
+        match differences(it) {
+            0 => return None,
+            n if best.is_none_or(|(least, _)| n < least) => best = Some((n, it)),
+            _ => {}
+        }
+    }
+    best.map(|(_, it)| it)
+}
@@ -0,0 +1,596 @@
+use crate::{Path, Save, Segment, Variant};
+use core::fmt;
+use serde::de::{
+    value::BorrowedStrDeserializer, DeserializeSeed, EnumAccess, Error as _, IntoDeserializer,
+    MapAccess, SeqAccess, VariantAccess, Visitor,
+};
+use serde::Deserialize;
+use std::borrow::Cow;
+
+/// A [`serde::Deserializer`] which replays a captured [`Save`] tree.
+///
+/// Errors record the [`Path`] of the node that failed, see [`ReplayError`].
+///
+/// ```
+/// # use serde::Deserialize;
+/// # use serde_save::{save, Deserializer};
+/// let saved = save((1, "two")).unwrap();
+/// let (one, two) = <(u8, String)>::deserialize(Deserializer::new(&saved)).unwrap();
+/// assert_eq!((one, two.as_str()), (1, "two"));
+/// ```
+pub struct Deserializer<'de, 'a, E> {
+    save: &'de Save<'a, E>,
+    is_human_readable: bool,
+}
+
+impl<'de, 'a, E> Deserializer<'de, 'a, E> {
+    /// Replay `save`.
+    ///
+    /// The deserializer is [human readable](Self::human_readable) by default,
+    /// like [`Serializer::new`](crate::Serializer::new).
+    pub fn new(save: &'de Save<'a, E>) -> Self {
+        Self {
+            save,
+            is_human_readable: true,
+        }
+    }
+    /// Set the value returned by [`serde::Deserializer::is_human_readable`],
+    /// for this node and all its children.
+    ///
+    /// This should match [`Serializer::human_readable`](crate::Serializer::human_readable)
+    /// when `save` was captured,
+    /// so that types with different human-readable and compact representations
+    /// deserialize from the representation that was actually captured.
+    ///
+    /// ```
+    /// # use serde::Deserialize as _;
+    /// # use serde_save::{save_dual, Deserializer};
+    /// # use std::net::{IpAddr, Ipv4Addr};
+    /// let dual = save_dual(IpAddr::V4(Ipv4Addr::LOCALHOST)).unwrap();
+    ///
+    /// let compact = Deserializer::new(&dual.compact).human_readable(false);
+    /// assert_eq!(IpAddr::deserialize(compact).unwrap(), Ipv4Addr::LOCALHOST);
+    ///
+    /// // The compact tree is an enum, not a string.
+    /// assert!(IpAddr::deserialize(Deserializer::new(&dual.compact)).is_err());
+    /// ```
+    pub fn human_readable(mut self, is_human_readable: bool) -> Self {
+        self.is_human_readable = is_human_readable;
+        self
+    }
+}
+
+/// Replay a borrowed tree.
+///
+/// Replaying borrows strings and bytes from the tree,
+/// so there is no implementation for an owned [`Save`].
+///
+/// ```
+/// # use serde::{de::IntoDeserializer as _, Deserialize};
+/// # use serde_save::save;
+/// let saved = save(Some(4)).unwrap();
+/// assert_eq!(Option::<u16>::deserialize((&saved).into_deserializer()).unwrap(), Some(4));
+/// ```
+impl<'de, 'a: 'de, E: fmt::Display> IntoDeserializer<'de, ReplayError> for &'de Save<'a, E> {
+    type Deserializer = Deserializer<'de, 'a, E>;
+    fn into_deserializer(self) -> Self::Deserializer {
+        Deserializer::new(self)
+    }
+}
+
+impl<'de, 'a: 'de, E: fmt::Display> IntoDeserializer<'de, ReplayError>
+    for Deserializer<'de, 'a, E>
+{
+    type Deserializer = Self;
+    fn into_deserializer(self) -> Self::Deserializer {
+        self
+    }
+}
+
+/// An error from [replaying](Deserializer) a [`Save`] tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplayError {
+    msg: String,
+    /// The path to the failing node, innermost segment first.
+    reversed: Vec<Segment<'static>>,
+}
+
+impl ReplayError {
+    /// The location of the node which failed to deserialize.
+    pub fn path(&self) -> Path<'static> {
+        self.reversed.iter().rev().cloned().collect()
+    }
+    /// The error message, without the location.
+    pub fn message(&self) -> &str {
+        &self.msg
+    }
+    fn within(segment: Segment<'_>) -> impl FnOnce(Self) -> Self {
+        let segment = segment.into_owned();
+        move |mut it| {
+            it.reversed.push(segment);
+            it
+        }
+    }
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.reversed.is_empty() {
+            true => f.write_str(&self.msg),
+            false => write!(f, "{}: {}", self.path(), self.msg),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+impl serde::de::Error for ReplayError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Self {
+            msg: msg.to_string(),
+            reversed: Vec::new(),
+        }
+    }
+}
+
+/// Deserialize a `T` from a captured tree.
+///
+/// ```
+/// # use serde_save::{load, save};
+/// let saved = save(vec![1, 2, 3]).unwrap();
+/// assert_eq!(load::<Vec<u8>, _>(&saved).unwrap(), [1, 2, 3]);
+/// ```
+pub fn load<'de, T, E>(save: &'de Save<'_, E>) -> Result<T, ReplayError>
+where
+    T: Deserialize<'de>,
+    E: fmt::Display,
+{
+    T::deserialize(Deserializer::new(save))
+}
+
+/// Deserialize a value from a captured tree using a [`DeserializeSeed`],
+/// for types which need runtime context to deserialize.
+///
+/// ```
+/// # use serde::{de::DeserializeSeed, Deserialize, Deserializer};
+/// # use serde_save::{load_seed, save};
+/// /// Looks up names in a registry.
+/// struct Lookup<'r>(&'r [&'static str]);
+///
+/// impl<'de> DeserializeSeed<'de> for Lookup<'_> {
+///     type Value = usize;
+///     fn deserialize<D: Deserializer<'de>>(self, d: D) -> Result<usize, D::Error> {
+///         let name = String::deserialize(d)?;
+///         self.0
+///             .iter()
+///             .position(|it| *it == name)
+///             .ok_or_else(|| serde::de::Error::custom("unregistered"))
+///     }
+/// }
+///
+/// let registry = ["alice", "bob"];
+/// assert_eq!(load_seed(Lookup(&registry), &save("bob").unwrap()).unwrap(), 1);
+/// assert_eq!(
+///     load_seed(Lookup(&registry), &save("eve").unwrap()).unwrap_err().to_string(),
+///     "unregistered",
+/// );
+/// ```
+pub fn load_seed<'de, T, E>(seed: T, save: &'de Save<'_, E>) -> Result<T::Value, ReplayError>
+where
+    T: DeserializeSeed<'de>,
+    E: fmt::Display,
+{
+    seed.deserialize(Deserializer::new(save))
+}
+
+/// Check that a tree captured from an old version of a type can still be
+/// deserialized by `T`, reporting the [`Path`] of the node that failed if not.
+///
+/// ```
+/// # use serde::{Deserialize, Serialize};
+/// # use serde_save::{check_compatible, save};
+/// #[derive(Serialize)]
+/// struct Old {
+///     points: Vec<(i32, i32)>,
+/// }
+///
+/// #[derive(Deserialize)]
+/// struct New {
+///     points: Vec<(u32, u32)>,
+/// }
+///
+/// let archived = save(Old { points: vec![(0, 0), (-1, 1)] }).unwrap();
+/// let error = check_compatible::<New, _>(&archived).unwrap_err();
+/// assert_eq!(error.path().to_string(), "points[1][0]");
+/// assert_eq!(
+///     error.to_string(),
+///     "points[1][0]: invalid value: integer `-1`, expected u32"
+/// );
+/// ```
+pub fn check_compatible<'de, T, E>(save: &'de Save<'_, E>) -> Result<(), ReplayError>
+where
+    T: Deserialize<'de>,
+    E: fmt::Display,
+{
+    load::<T, E>(save).map(drop)
+}
+
+macro_rules! forward {
+    ($($method:ident)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+                self.deserialize_any(visitor)
+            }
+        )*
+    };
+}
+
+impl<'de, 'a: 'de, E: fmt::Display> serde::Deserializer<'de> for Deserializer<'de, 'a, E> {
+    type Error = ReplayError;
+
+    fn is_human_readable(&self) -> bool {
+        self.is_human_readable
+    }
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.save {
+            Save::Bool(it) => visitor.visit_bool(*it),
+            Save::I8(it) => visitor.visit_i8(*it),
+            Save::I16(it) => visitor.visit_i16(*it),
+            Save::I32(it) => visitor.visit_i32(*it),
+            Save::I64(it) => visitor.visit_i64(*it),
+            Save::I128(it) => visitor.visit_i128(*it),
+            Save::U8(it) => visitor.visit_u8(*it),
+            Save::U16(it) => visitor.visit_u16(*it),
+            Save::U32(it) => visitor.visit_u32(*it),
+            Save::U64(it) => visitor.visit_u64(*it),
+            Save::U128(it) => visitor.visit_u128(*it),
+            Save::F32(it) => visitor.visit_f32(*it),
+            Save::F64(it) => visitor.visit_f64(*it),
+            Save::Char(it) => visitor.visit_char(*it),
+            Save::String(it) => visitor.visit_borrowed_str(it),
+            Save::ByteArray(it) => visitor.visit_borrowed_bytes(it),
+            Save::Option(None) => visitor.visit_none(),
+            Save::Option(Some(it)) => {
+                visitor.visit_some(Deserializer::new(&**it).human_readable(self.is_human_readable))
+            }
+            Save::Unit | Save::UnitStruct(_) => visitor.visit_unit(),
+            Save::NewTypeStruct { value, .. } => visitor.visit_newtype_struct(
+                Deserializer::new(&**value).human_readable(self.is_human_readable),
+            ),
+            Save::Seq(it) | Save::Tuple(it) | Save::TupleStruct { values: it, .. } => {
+                Seq::new(it, self.is_human_readable).visit(visitor)
+            }
+            Save::Map(it) => visitor.visit_map(Map {
+                entries: it.iter().enumerate(),
+                value: None,
+                is_human_readable: self.is_human_readable,
+            }),
+            Save::Struct { fields, .. } => {
+                visitor.visit_map(Fields::new(fields, self.is_human_readable))
+            }
+            Save::UnitVariant(variant) => visitor.visit_enum(Enum {
+                variant,
+                contents: Contents::<E>::Unit,
+                is_human_readable: self.is_human_readable,
+            }),
+            Save::NewTypeVariant { variant, value } => visitor.visit_enum(Enum {
+                variant,
+                contents: Contents::NewType(value),
+                is_human_readable: self.is_human_readable,
+            }),
+            Save::TupleVariant { variant, values } => visitor.visit_enum(Enum {
+                variant,
+                contents: Contents::Tuple(values),
+                is_human_readable: self.is_human_readable,
+            }),
+            Save::StructVariant { variant, fields } => visitor.visit_enum(Enum {
+                variant,
+                contents: Contents::Struct(fields),
+                is_human_readable: self.is_human_readable,
+            }),
+            Save::Error(e) => Err(ReplayError::custom(format_args!("captured error: {}", e))),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.save {
+            Save::Option(_) => self.deserialize_any(visitor),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        match self.save {
+            Save::NewTypeStruct { .. } => self.deserialize_any(visitor),
+            _ => visitor.visit_newtype_struct(self),
+        }
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        _: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        match self.save {
+            Save::String(it) => visitor.visit_enum(BorrowedStrDeserializer::new(it)),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    forward! {
+        deserialize_bool deserialize_i8 deserialize_i16 deserialize_i32 deserialize_i64
+        deserialize_i128 deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64
+        deserialize_u128 deserialize_f32 deserialize_f64 deserialize_char deserialize_str
+        deserialize_string deserialize_bytes deserialize_byte_buf deserialize_unit
+        deserialize_seq deserialize_map deserialize_identifier deserialize_ignored_any
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        _: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        _: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_any(visitor)
+    }
+}
+
+struct Seq<'de, 'a, E> {
+    elements: core::iter::Enumerate<core::slice::Iter<'de, Save<'a, E>>>,
+    len: usize,
+    is_human_readable: bool,
+}
+
+impl<'de, 'a, E> Seq<'de, 'a, E> {
+    fn new(elements: &'de [Save<'a, E>], is_human_readable: bool) -> Self {
+        Self {
+            elements: elements.iter().enumerate(),
+            len: elements.len(),
+            is_human_readable,
+        }
+    }
+    /// Visit the elements, failing if the visitor doesn't consume them all.
+    fn visit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ReplayError>
+    where
+        'a: 'de,
+        E: fmt::Display,
+    {
+        let len = self.len;
+        let mut seq = self;
+        let value = visitor.visit_seq(&mut seq)?;
+        match seq.elements.len() {
+            0 => Ok(value),
+            remaining => Err(ReplayError::invalid_length(len, &LenHint(len - remaining))),
+        }
+    }
+}
+
+struct LenHint(usize);
+
+impl serde::de::Expected for LenHint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} elements", self.0)
+    }
+}
+
+impl<'de, 'a: 'de, E: fmt::Display> SeqAccess<'de> for Seq<'de, 'a, E> {
+    type Error = ReplayError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Self::Error> {
+        match self.elements.next() {
+            Some((ix, it)) => seed
+                .deserialize(Deserializer::new(it).human_readable(self.is_human_readable))
+                .map(Some)
+                .map_err(ReplayError::within(Segment::Index(ix))),
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.elements.len())
+    }
+}
+
+struct Map<'de, 'a, E> {
+    entries: core::iter::Enumerate<core::slice::Iter<'de, (Save<'a, E>, Save<'a, E>)>>,
+    value: Option<(usize, &'de Save<'a, E>)>,
+    is_human_readable: bool,
+}
+
+impl<'de, 'a: 'de, E: fmt::Display> MapAccess<'de> for Map<'de, 'a, E> {
+    type Error = ReplayError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Self::Error> {
+        match self.entries.next() {
+            Some((ix, (k, v))) => {
+                self.value = Some((ix, v));
+                seed.deserialize(Deserializer::new(k).human_readable(self.is_human_readable))
+                    .map(Some)
+                    .map_err(ReplayError::within(Segment::Key(ix)))
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, Self::Error> {
+        match self.value.take() {
+            Some((ix, it)) => seed
+                .deserialize(Deserializer::new(it).human_readable(self.is_human_readable))
+                .map_err(ReplayError::within(Segment::Index(ix))),
+            None => Err(ReplayError::custom("value requested before key")),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.entries.len())
+    }
+}
+
+/// The present fields of a [`Save::Struct`] or [`Save::StructVariant`],
+/// presented as a map from field names to values.
+struct Fields<'de, 'a, E> {
+    fields: core::slice::Iter<'de, (&'a str, Option<Save<'a, E>>)>,
+    value: Option<(&'a str, &'de Save<'a, E>)>,
+    is_human_readable: bool,
+}
+
+impl<'de, 'a, E> Fields<'de, 'a, E> {
+    #[allow(clippy::type_complexity)]
+    fn new(fields: &'de [(&'a str, Option<Save<'a, E>>)], is_human_readable: bool) -> Self {
+        Self {
+            fields: fields.iter(),
+            value: None,
+            is_human_readable,
+        }
+    }
+}
+
+impl<'de, 'a: 'de, E: fmt::Display> MapAccess<'de> for Fields<'de, 'a, E> {
+    type Error = ReplayError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Self::Error> {
+        for (name, it) in self.fields.by_ref() {
+            if let Some(it) = it {
+                self.value = Some((name, it));
+                return seed
+                    .deserialize(BorrowedStrDeserializer::new(name))
+                    .map(Some);
+            }
+        }
+        Ok(None)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, Self::Error> {
+        match self.value.take() {
+            Some((name, it)) => seed
+                .deserialize(Deserializer::new(it).human_readable(self.is_human_readable))
+                .map_err(ReplayError::within(Segment::Field(Cow::Borrowed(name)))),
+            None => Err(ReplayError::custom("value requested before key")),
+        }
+    }
+}
+
+struct Enum<'de, 'a, E> {
+    variant: &'de Variant<'a>,
+    contents: Contents<'de, 'a, E>,
+    is_human_readable: bool,
+}
+
+enum Contents<'de, 'a, E> {
+    Unit,
+    NewType(&'de Save<'a, E>),
+    Tuple(&'de [Save<'a, E>]),
+    Struct(&'de [(&'a str, Option<Save<'a, E>>)]),
+}
+
+impl<'de, 'a: 'de, E: fmt::Display> EnumAccess<'de> for Enum<'de, 'a, E> {
+    type Error = ReplayError;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Self::Variant), Self::Error> {
+        let variant = seed.deserialize(BorrowedStrDeserializer::new(self.variant.variant))?;
+        Ok((variant, self))
+    }
+}
+
+impl<'de, 'a: 'de, E: fmt::Display> Enum<'de, 'a, E> {
+    fn unexpected(&self, expected: &str) -> ReplayError {
+        let actual = match self.contents {
+            Contents::Unit => "unit variant",
+            Contents::NewType(_) => "newtype variant",
+            Contents::Tuple(_) => "tuple variant",
+            Contents::Struct(_) => "struct variant",
+        };
+        ReplayError::invalid_type(serde::de::Unexpected::Other(actual), &expected)
+    }
+}
+
+impl<'de, 'a: 'de, E: fmt::Display> VariantAccess<'de> for Enum<'de, 'a, E> {
+    type Error = ReplayError;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        match self.contents {
+            Contents::Unit => Ok(()),
+            _ => Err(self.unexpected("unit variant")),
+        }
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(
+        self,
+        seed: T,
+    ) -> Result<T::Value, Self::Error> {
+        match self.contents {
+            Contents::NewType(it) => {
+                seed.deserialize(Deserializer::new(it).human_readable(self.is_human_readable))
+            }
+            _ => Err(self.unexpected("newtype variant")),
+        }
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _: usize, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.contents {
+            Contents::Tuple(it) => Seq::new(it, self.is_human_readable).visit(visitor),
+            _ => Err(self.unexpected("tuple variant")),
+        }
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        match self.contents {
+            Contents::Struct(it) => visitor.visit_map(Fields::new(it, self.is_human_readable)),
+            _ => Err(self.unexpected("struct variant")),
+        }
+    }
+}
@@ -0,0 +1,217 @@
+use crate::{
+    imp::{ErrorDiscipline, Persist, ShortCircuit},
+    Error, Save, Serializer,
+};
+use core::fmt;
+use std::{
+    io,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+/// Capture many values with the same [`Serializer`], keeping every capture
+/// for later export.
+///
+/// This is intended for long-running services which periodically snapshot
+/// their state: the serializer (including any [overrides](Serializer::override_at))
+/// is configured once, and shared by every capture.
+///
+/// The session also owns a pool of string and byte buffers.
+/// Captures which are no longer needed may be [recycled](Self::recycle),
+/// and later captures reuse their allocations,
+/// so that periodic snapshots settle into allocating very little.
+/// (Names are `&'static str`s given by [`Serialize`](serde::Serialize)
+/// implementations, so they are already shared by every capture.)
+///
+/// ```
+/// # use serde_save::{Save, SaveSession, Serializer};
+/// let mut session = SaveSession::new(Serializer::new().human_readable(false));
+/// for tick in 0..3u64 {
+///     session.capture(tick).unwrap();
+/// }
+/// assert_eq!(session.len(), 3);
+/// assert_eq!(
+///     session.into_save(),
+///     Save::Seq(vec![Save::U64(0), Save::U64(1), Save::U64(2)])
+/// );
+/// ```
+pub struct SaveSession<D: ErrorDiscipline = ShortCircuit> {
+    serializer: Serializer<D>,
+    captures: Vec<Save<'static, D::SaveError>>,
+    pool: Arc<Pool>,
+}
+
+impl<D: ErrorDiscipline> SaveSession<D> {
+    /// Create a session which captures values with `serializer`.
+    pub fn new(serializer: Serializer<D>) -> Self {
+        let pool = Arc::new(Pool::default());
+        Self {
+            serializer: serializer.pool(pool.clone()),
+            captures: Vec::new(),
+            pool,
+        }
+    }
+    /// Capture `t`, returning a reference to the stored capture.
+    ///
+    /// Failed captures are not stored.
+    /// See [`Serializer::save`].
+    pub fn capture<T: serde::Serialize>(
+        &mut self,
+        t: T,
+    ) -> Result<&Save<'static, D::SaveError>, Error> {
+        let save = self.serializer.save(t)?;
+        self.captures.push(save);
+        Ok(self.captures.last().expect("just pushed"))
+    }
+    /// The serializer shared by every capture.
+    pub fn serializer(&self) -> &Serializer<D> {
+        &self.serializer
+    }
+    /// Every capture so far, in order.
+    pub fn captures(&self) -> &[Save<'static, D::SaveError>] {
+        &self.captures
+    }
+    /// The number of captures so far.
+    pub fn len(&self) -> usize {
+        self.captures.len()
+    }
+    /// Whether there have been no captures so far.
+    pub fn is_empty(&self) -> bool {
+        self.captures.is_empty()
+    }
+    /// Remove and return every capture so far, keeping the serializer
+    /// for further captures.
+    pub fn drain(&mut self) -> std::vec::Drain<'_, Save<'static, D::SaveError>> {
+        self.captures.drain(..)
+    }
+    /// Return the string and byte buffers of `save`,
+    /// such as a [drained](Self::drain) capture,
+    /// to be reused by later captures.
+    ///
+    /// ```
+    /// # use serde_save::{Save, SaveSession};
+    /// let mut session = SaveSession::with_defaults();
+    /// let old = session.capture("a string which was allocated").unwrap().clone();
+    /// session.recycle(old);
+    ///
+    /// let Save::String(new) = session.capture("short").unwrap() else { unreachable!() };
+    /// assert_eq!(new, "short");
+    /// assert!(new.capacity() >= "a string which was allocated".len());
+    /// ```
+    pub fn recycle(&self, save: Save<'static, D::SaveError>) {
+        self.pool.recycle(save)
+    }
+    /// [Recycle](Self::recycle) every capture so far.
+    pub fn clear(&mut self) {
+        for save in self.captures.drain(..) {
+            self.pool.recycle(save)
+        }
+    }
+    /// Consume the session, returning every capture as a single [`Save::Seq`].
+    pub fn into_save(self) -> Save<'static, D::SaveError> {
+        Save::Seq(self.captures)
+    }
+    /// Write every capture to `dir`, see [`Save::write_corpus`].
+    pub fn write_corpus(&self, dir: impl AsRef<Path>) -> io::Result<Vec<PathBuf>>
+    where
+        D::SaveError: fmt::Display,
+    {
+        Save::write_corpus(dir, &self.captures)
+    }
+}
+
+impl SaveSession<ShortCircuit> {
+    /// A session which captures with [`Serializer::new`].
+    pub fn with_defaults() -> Self {
+        Self::new(Serializer::new())
+    }
+}
+
+impl SaveSession<Persist> {
+    /// A session which persists errors in-tree, like [`save_errors`](crate::save_errors).
+    pub fn with_errors() -> Self {
+        Self::new(
+            Serializer::new()
+                .check_for_protocol_errors(true)
+                .save_errors(),
+        )
+    }
+}
+
+impl<D: ErrorDiscipline> fmt::Debug for SaveSession<D>
+where
+    D::SaveError: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SaveSession")
+            .field("config", &self.serializer.config())
+            .field("captures", &self.captures)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The most buffers of each kind that a [`Pool`] keeps.
+const MAX_POOLED: usize = 4096;
+
+/// Buffers [recycled](SaveSession::recycle) from old captures,
+/// shared with the session's [`Serializer`].
+#[derive(Default)]
+pub(crate) struct Pool {
+    strings: Mutex<Vec<String>>,
+    bytes: Mutex<Vec<Vec<u8>>>,
+}
+
+impl Pool {
+    /// An empty string, reusing a recycled allocation if there is one.
+    pub(crate) fn string(&self) -> String {
+        lock(&self.strings).pop().unwrap_or_default()
+    }
+    /// An empty byte buffer, reusing a recycled allocation if there is one.
+    pub(crate) fn bytes(&self) -> Vec<u8> {
+        lock(&self.bytes).pop().unwrap_or_default()
+    }
+    fn recycle<E>(&self, save: Save<'_, E>) {
+        buffers(save, &mut lock(&self.strings), &mut lock(&self.bytes))
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Move the allocated buffers of `save` into the pools, up to [`MAX_POOLED`].
+fn buffers<E>(save: Save<'_, E>, strings: &mut Vec<String>, bytes: &mut Vec<Vec<u8>>) {
+    match save {
+        Save::String(mut it) if strings.len() < MAX_POOLED && it.capacity() != 0 => {
+            it.clear();
+            strings.push(it)
+        }
+        Save::ByteArray(mut it) if bytes.len() < MAX_POOLED && it.capacity() != 0 => {
+            it.clear();
+            bytes.push(it)
+        }
+        Save::Option(Some(it))
+        | Save::NewTypeStruct { value: it, .. }
+        | Save::NewTypeVariant { value: it, .. } => buffers(*it, strings, bytes),
+        Save::Seq(it)
+        | Save::Tuple(it)
+        | Save::TupleStruct { values: it, .. }
+        | Save::TupleVariant { values: it, .. } => {
+            for it in it {
+                buffers(it, strings, bytes)
+            }
+        }
+        Save::Map(it) => {
+            for (k, v) in it {
+                buffers(k, strings, bytes);
+                buffers(v, strings, bytes)
+            }
+        }
+        Save::Struct { fields, .. } | Save::StructVariant { fields, .. } => {
+            for it in fields.into_iter().filter_map(|(_, it)| it) {
+                buffers(it, strings, bytes)
+            }
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,989 @@
+use crate::{
+    diff::DisplayPath,
+    lexer::{LexError, Lexer},
+    Path, Save, Segment, Variant,
+};
+use core::fmt;
+use std::borrow::Cow;
+
+/// The structure of a [`Save`] tree, without any of its leaf values.
+///
+/// A [`Shape`] records type names, field names, enum variants and kinds of leaves,
+/// so can be checked in to source control as a lockfile,
+/// and compared to catch unexpected changes to a type's serialized structure.
+///
+/// ```
+/// # use serde_save::{save, Shape};
+/// #[derive(serde::Serialize)]
+/// struct Point {
+///     x: i32,
+///     tags: Vec<String>,
+/// }
+///
+/// let shape = Shape::of(&save(Point { x: 1, tags: vec![] }).unwrap());
+/// let lockfile = shape.to_lockfile();
+/// assert_eq!(
+///     lockfile,
+///     r#"struct "Point" {
+///     "x": i32,
+///     "tags": seq(0) [],
+/// }
+/// "#
+/// );
+/// assert_eq!(Shape::from_lockfile(&lockfile).unwrap(), shape);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Shape {
+    /// A [`Save::Bool`].
+    Bool,
+    /// A [`Save::I8`].
+    I8,
+    /// A [`Save::I16`].
+    I16,
+    /// A [`Save::I32`].
+    I32,
+    /// A [`Save::I64`].
+    I64,
+    /// A [`Save::I128`].
+    I128,
+    /// A [`Save::U8`].
+    U8,
+    /// A [`Save::U16`].
+    U16,
+    /// A [`Save::U32`].
+    U32,
+    /// A [`Save::U64`].
+    U64,
+    /// A [`Save::U128`].
+    U128,
+    /// A [`Save::F32`].
+    F32,
+    /// A [`Save::F64`].
+    F64,
+    /// A [`Save::Char`].
+    Char,
+    /// A [`Save::String`].
+    String,
+    /// A [`Save::ByteArray`].
+    ByteArray,
+    /// A [`Save::Option`], with the shape of its contents if it was [`Some`].
+    Option(Option<Box<Self>>),
+    /// A [`Save::Unit`].
+    Unit,
+    /// A [`Save::UnitStruct`].
+    UnitStruct(String),
+    /// A [`Save::UnitVariant`].
+    UnitVariant(ShapeVariant),
+    /// A [`Save::NewTypeStruct`].
+    NewTypeStruct { name: String, value: Box<Self> },
+    /// A [`Save::NewTypeVariant`].
+    NewTypeVariant {
+        variant: ShapeVariant,
+        value: Box<Self>,
+    },
+    /// A [`Save::Seq`].
+    Seq {
+        /// The number of elements.
+        len: usize,
+        /// The distinct shapes of the elements, in the order they were first seen.
+        items: Vec<Self>,
+    },
+    /// A [`Save::Map`].
+    Map {
+        /// The number of entries.
+        len: usize,
+        /// The distinct shapes of the entries, in the order they were first seen.
+        entries: Vec<(Self, Self)>,
+    },
+    /// A [`Save::Tuple`].
+    Tuple(Vec<Self>),
+    /// A [`Save::TupleStruct`].
+    TupleStruct { name: String, values: Vec<Self> },
+    /// A [`Save::TupleVariant`].
+    TupleVariant {
+        variant: ShapeVariant,
+        values: Vec<Self>,
+    },
+    /// A [`Save::Struct`].
+    ///
+    /// [Skip](serde::ser::SerializeStruct::skip_field)ped fields have no shape.
+    Struct {
+        name: String,
+        fields: Vec<(String, Option<Self>)>,
+    },
+    /// A [`Save::StructVariant`].
+    ///
+    /// [Skip](serde::ser::SerializeStruct::skip_field)ped fields have no shape.
+    StructVariant {
+        variant: ShapeVariant,
+        fields: Vec<(String, Option<Self>)>,
+    },
+    /// A [`Save::Error`].
+    Error,
+}
+
+/// An owned version of [`Variant`], for use in a [`Shape`].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ShapeVariant {
+    /// The name of the outer `enum`.
+    pub name: String,
+    /// The index of this variant within the outer `enum`.
+    pub variant_index: u32,
+    /// The name of the inhabited variant within the outer `enum`
+    pub variant: String,
+}
+
+impl From<Variant<'_>> for ShapeVariant {
+    fn from(value: Variant<'_>) -> Self {
+        let Variant {
+            name,
+            variant_index,
+            variant,
+        } = value;
+        Self {
+            name: name.into(),
+            variant_index,
+            variant: variant.into(),
+        }
+    }
+}
+
+impl<E> From<&Save<'_, E>> for Shape {
+    fn from(value: &Save<'_, E>) -> Self {
+        Self::of(value)
+    }
+}
+
+impl Shape {
+    /// Record the shape of `save`.
+    pub fn of<E>(save: &Save<'_, E>) -> Self {
+        let fields = |fields: &[(&str, Option<Save<'_, E>>)]| {
+            fields
+                .iter()
+                .map(|(name, it)| (String::from(*name), it.as_ref().map(Self::of)))
+                .collect()
+        };
+        match save {
+            Save::Bool(_) => Self::Bool,
+            Save::I8(_) => Self::I8,
+            Save::I16(_) => Self::I16,
+            Save::I32(_) => Self::I32,
+            Save::I64(_) => Self::I64,
+            Save::I128(_) => Self::I128,
+            Save::U8(_) => Self::U8,
+            Save::U16(_) => Self::U16,
+            Save::U32(_) => Self::U32,
+            Save::U64(_) => Self::U64,
+            Save::U128(_) => Self::U128,
+            Save::F32(_) => Self::F32,
+            Save::F64(_) => Self::F64,
+            Save::Char(_) => Self::Char,
+            Save::String(_) => Self::String,
+            Save::ByteArray(_) => Self::ByteArray,
+            Save::Option(it) => Self::Option(it.as_deref().map(Self::of).map(Box::new)),
+            Save::Unit => Self::Unit,
+            Save::UnitStruct(name) => Self::UnitStruct(String::from(*name)),
+            Save::UnitVariant(variant) => Self::UnitVariant((*variant).into()),
+            Save::NewTypeStruct { name, value } => Self::NewTypeStruct {
+                name: String::from(*name),
+                value: Box::new(Self::of(value)),
+            },
+            Save::NewTypeVariant { variant, value } => Self::NewTypeVariant {
+                variant: (*variant).into(),
+                value: Box::new(Self::of(value)),
+            },
+            Save::Seq(it) => {
+                let mut items = Vec::new();
+                for it in it {
+                    let it = Self::of(it);
+                    if !items.iter().any(|seen| same_structure(seen, &it)) {
+                        items.push(it)
+                    }
+                }
+                Self::Seq {
+                    len: it.len(),
+                    items,
+                }
+            }
+            Save::Map(it) => {
+                let mut entries = Vec::<(Self, Self)>::new();
+                for (k, v) in it {
+                    let (k, v) = (Self::of(k), Self::of(v));
+                    if !entries
+                        .iter()
+                        .any(|(sk, sv)| same_structure(sk, &k) && same_structure(sv, &v))
+                    {
+                        entries.push((k, v))
+                    }
+                }
+                Self::Map {
+                    len: it.len(),
+                    entries,
+                }
+            }
+            Save::Tuple(it) => Self::Tuple(it.iter().map(Self::of).collect()),
+            Save::TupleStruct { name, values } => Self::TupleStruct {
+                name: String::from(*name),
+                values: values.iter().map(Self::of).collect(),
+            },
+            Save::TupleVariant { variant, values } => Self::TupleVariant {
+                variant: (*variant).into(),
+                values: values.iter().map(Self::of).collect(),
+            },
+            Save::Struct { name, fields: it } => Self::Struct {
+                name: String::from(*name),
+                fields: fields(it),
+            },
+            Save::StructVariant {
+                variant,
+                fields: it,
+            } => Self::StructVariant {
+                variant: (*variant).into(),
+                fields: fields(it),
+            },
+            Save::Error(_) => Self::Error,
+        }
+    }
+
+    /// A stable, line-oriented textual representation of this shape,
+    /// suitable for checking in to source control.
+    ///
+    /// See [`Shape::from_lockfile`] for loading it again.
+    pub fn to_lockfile(&self) -> String {
+        let mut s = String::new();
+        write(&mut s, self, Some(0)).expect("writing to a String is infallible");
+        s.push('\n');
+        s
+    }
+
+    /// Parse the output of [`Shape::to_lockfile`].
+    ///
+    /// Whitespace is insignificant, and lines starting with `#` are ignored.
+    pub fn from_lockfile(s: &str) -> Result<Self, ParseShapeError> {
+        let mut parser = Parser::new(s);
+        let shape = parser.shape()?;
+        parser.finish()?;
+        Ok(shape)
+    }
+
+    /// Compare `self` (the old shape) to `other` (the new shape).
+    ///
+    /// - Lengths of sequences and maps are ignored.
+    /// - An [`Option`] which was [`None`] on either side matches any [`Option`].
+    /// - [Skip](serde::ser::SerializeStruct::skip_field)ped fields are treated as absent.
+    /// - A removed field and an added field at the same position with the same shape
+    ///   are reported as a rename.
+    ///
+    /// ```
+    /// # use serde_save::{Save, Shape};
+    /// let before = Shape::of(&Save::<std::convert::Infallible>::strukt(
+    ///     "Point",
+    ///     [("x", Save::I32(1)), ("y", Save::I32(2)), ("z", Save::I32(3))],
+    /// ));
+    /// let after = Shape::of(&Save::<std::convert::Infallible>::strukt(
+    ///     "Point",
+    ///     [("x", Save::I64(1)), ("why", Save::I32(2)), ("w", Save::Bool(true))],
+    /// ));
+    /// assert_eq!(
+    ///     before.diff(&after).to_string(),
+    ///     "\
+    /// ~ x: i32 -> i64
+    /// ~ y: renamed \"y\" -> \"why\"
+    /// - z: i32
+    /// + w: bool
+    /// "
+    /// );
+    /// ```
+    pub fn diff(&self, other: &Self) -> ShapeDiff {
+        let mut changes = Vec::new();
+        diff(&mut Path::new(), self, other, &mut changes);
+        ShapeDiff { changes }
+    }
+}
+
+/// Returns `true` if the shapes are identical, except for lengths.
+pub(crate) fn same_structure(left: &Shape, right: &Shape) -> bool {
+    match (left, right) {
+        (Shape::Seq { items: l, .. }, Shape::Seq { items: r, .. }) => {
+            l.len() == r.len() && l.iter().zip(r).all(|(l, r)| same_structure(l, r))
+        }
+        (Shape::Map { entries: l, .. }, Shape::Map { entries: r, .. }) => {
+            l.len() == r.len()
+                && l.iter()
+                    .zip(r)
+                    .all(|((lk, lv), (rk, rv))| same_structure(lk, rk) && same_structure(lv, rv))
+        }
+        (Shape::Option(Some(l)), Shape::Option(Some(r))) => same_structure(l, r),
+        (
+            Shape::NewTypeStruct { name: ln, value: l },
+            Shape::NewTypeStruct { name: rn, value: r },
+        ) => ln == rn && same_structure(l, r),
+        (
+            Shape::NewTypeVariant {
+                variant: lv,
+                value: l,
+            },
+            Shape::NewTypeVariant {
+                variant: rv,
+                value: r,
+            },
+        ) => lv == rv && same_structure(l, r),
+        (Shape::Tuple(l), Shape::Tuple(r)) => all_same_structure(l, r),
+        (
+            Shape::TupleStruct {
+                name: ln,
+                values: l,
+            },
+            Shape::TupleStruct {
+                name: rn,
+                values: r,
+            },
+        ) => ln == rn && all_same_structure(l, r),
+        (
+            Shape::TupleVariant {
+                variant: lv,
+                values: l,
+            },
+            Shape::TupleVariant {
+                variant: rv,
+                values: r,
+            },
+        ) => lv == rv && all_same_structure(l, r),
+        (
+            Shape::Struct {
+                name: ln,
+                fields: l,
+            },
+            Shape::Struct {
+                name: rn,
+                fields: r,
+            },
+        ) => ln == rn && all_same_fields(l, r),
+        (
+            Shape::StructVariant {
+                variant: lv,
+                fields: l,
+            },
+            Shape::StructVariant {
+                variant: rv,
+                fields: r,
+            },
+        ) => lv == rv && all_same_fields(l, r),
+        (l, r) => l == r,
+    }
+}
+
+fn all_same_structure(left: &[Shape], right: &[Shape]) -> bool {
+    left.len() == right.len() && left.iter().zip(right).all(|(l, r)| same_structure(l, r))
+}
+
+#[allow(clippy::type_complexity)]
+fn all_same_fields(left: &[(String, Option<Shape>)], right: &[(String, Option<Shape>)]) -> bool {
+    left.len() == right.len()
+        && left.iter().zip(right).all(|((ln, l), (rn, r))| {
+            ln == rn
+                && match (l, r) {
+                    (Some(l), Some(r)) => same_structure(l, r),
+                    (l, r) => l == r,
+                }
+        })
+}
+
+/// The single-line textual representation, as used in lockfiles.
+impl fmt::Display for Shape {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write(f, self, None)
+    }
+}
+
+/// Write `shape`, spread across lines if `depth` is [`Some`].
+fn write(f: &mut dyn fmt::Write, shape: &Shape, depth: Option<usize>) -> fmt::Result {
+    match shape {
+        Shape::Bool => f.write_str("bool"),
+        Shape::I8 => f.write_str("i8"),
+        Shape::I16 => f.write_str("i16"),
+        Shape::I32 => f.write_str("i32"),
+        Shape::I64 => f.write_str("i64"),
+        Shape::I128 => f.write_str("i128"),
+        Shape::U8 => f.write_str("u8"),
+        Shape::U16 => f.write_str("u16"),
+        Shape::U32 => f.write_str("u32"),
+        Shape::U64 => f.write_str("u64"),
+        Shape::U128 => f.write_str("u128"),
+        Shape::F32 => f.write_str("f32"),
+        Shape::F64 => f.write_str("f64"),
+        Shape::Char => f.write_str("char"),
+        Shape::String => f.write_str("string"),
+        Shape::ByteArray => f.write_str("bytes"),
+        Shape::Option(None) => f.write_str("none"),
+        Shape::Option(Some(it)) => {
+            f.write_str("some(")?;
+            write(f, it, depth)?;
+            f.write_str(")")
+        }
+        Shape::Unit => f.write_str("unit"),
+        Shape::UnitStruct(name) => write!(f, "unit_struct {:?}", name),
+        Shape::UnitVariant(variant) => write!(f, "unit_variant {}", DisplayVariant(variant)),
+        Shape::NewTypeStruct { name, value } => {
+            write!(f, "newtype_struct {:?}(", name)?;
+            write(f, value, depth)?;
+            f.write_str(")")
+        }
+        Shape::NewTypeVariant { variant, value } => {
+            write!(f, "newtype_variant {}(", DisplayVariant(variant))?;
+            write(f, value, depth)?;
+            f.write_str(")")
+        }
+        Shape::Seq { len, items } => {
+            write!(f, "seq({}) ", len)?;
+            list(f, ('[', ']'), items, depth, |f, it, depth| {
+                write(f, it, depth)
+            })
+        }
+        Shape::Map { len, entries } => {
+            write!(f, "map({}) ", len)?;
+            list(f, ('{', '}'), entries, depth, |f, (k, v), depth| {
+                write(f, k, depth)?;
+                f.write_str(": ")?;
+                write(f, v, depth)
+            })
+        }
+        Shape::Tuple(it) => {
+            f.write_str("tuple")?;
+            list(f, ('(', ')'), it, depth, |f, it, depth| write(f, it, depth))
+        }
+        Shape::TupleStruct { name, values } => {
+            write!(f, "tuple_struct {:?}", name)?;
+            list(f, ('(', ')'), values, depth, |f, it, depth| {
+                write(f, it, depth)
+            })
+        }
+        Shape::TupleVariant { variant, values } => {
+            write!(f, "tuple_variant {}", DisplayVariant(variant))?;
+            list(f, ('(', ')'), values, depth, |f, it, depth| {
+                write(f, it, depth)
+            })
+        }
+        Shape::Struct { name, fields } => {
+            write!(f, "struct {:?} ", name)?;
+            self::fields(f, fields, depth)
+        }
+        Shape::StructVariant { variant, fields } => {
+            write!(f, "struct_variant {} ", DisplayVariant(variant))?;
+            self::fields(f, fields, depth)
+        }
+        Shape::Error => f.write_str("error"),
+    }
+}
+
+fn fields(
+    f: &mut dyn fmt::Write,
+    fields: &[(String, Option<Shape>)],
+    depth: Option<usize>,
+) -> fmt::Result {
+    list(f, ('{', '}'), fields, depth, |f, (name, it), depth| {
+        write!(f, "{:?}: ", name)?;
+        match it {
+            Some(it) => write(f, it, depth),
+            None => f.write_str("skipped"),
+        }
+    })
+}
+
+fn list<T>(
+    f: &mut dyn fmt::Write,
+    (open, close): (char, char),
+    items: &[T],
+    depth: Option<usize>,
+    mut item: impl FnMut(&mut dyn fmt::Write, &T, Option<usize>) -> fmt::Result,
+) -> fmt::Result {
+    f.write_char(open)?;
+    match depth {
+        Some(depth) if !items.is_empty() => {
+            for it in items {
+                write!(f, "\n{:1$}", "", (depth + 1) * 4)?;
+                item(f, it, Some(depth + 1))?;
+                f.write_char(',')?;
+            }
+            write!(f, "\n{:1$}", "", depth * 4)?;
+        }
+        _ => {
+            for (ix, it) in items.iter().enumerate() {
+                if ix != 0 {
+                    f.write_str(", ")?
+                }
+                item(f, it, None)?
+            }
+        }
+    }
+    f.write_char(close)
+}
+
+struct DisplayVariant<'a>(&'a ShapeVariant);
+
+impl fmt::Display for DisplayVariant<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ShapeVariant {
+            name,
+            variant_index,
+            variant,
+        } = self.0;
+        write!(f, "{:?}::{:?}@{}", name, variant, variant_index)
+    }
+}
+
+/// An error returned from [`Shape::from_lockfile`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseShapeError {
+    offset: usize,
+    msg: &'static str,
+}
+
+impl fmt::Display for ParseShapeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid shape at offset {}: {}", self.offset, self.msg)
+    }
+}
+
+impl std::error::Error for ParseShapeError {}
+
+impl From<LexError> for ParseShapeError {
+    fn from(LexError { offset, msg }: LexError) -> Self {
+        Self { offset, msg }
+    }
+}
+
+type Parser<'a> = Lexer<'a>;
+
+impl<'a> Parser<'a> {
+    fn variant(&mut self) -> Result<ShapeVariant, ParseShapeError> {
+        let name = self.string()?;
+        self.expect("::", "expected `::`")?;
+        let variant = self.string()?;
+        self.expect("@", "expected `@`")?;
+        Ok(ShapeVariant {
+            name,
+            variant_index: self.number()?,
+            variant,
+        })
+    }
+    fn shapes(&mut self, delims: (&str, &str)) -> Result<Vec<Shape>, ParseShapeError> {
+        self.list(delims, Self::shape)
+    }
+    fn fields(&mut self) -> Result<Vec<(String, Option<Shape>)>, ParseShapeError> {
+        self.list(("{", "}"), |this| {
+            let name = this.string()?;
+            this.expect(":", "expected `:`")?;
+            match this.eat("skipped") {
+                true => Ok((name, None)),
+                false => Ok((name, Some(this.shape()?))),
+            }
+        })
+    }
+    fn wrapped(&mut self) -> Result<Box<Shape>, ParseShapeError> {
+        self.expect("(", "expected `(`")?;
+        let it = self.shape()?;
+        self.expect(")", "expected `)`")?;
+        Ok(Box::new(it))
+    }
+    fn len(&mut self) -> Result<usize, ParseShapeError> {
+        self.expect("(", "expected `(`")?;
+        let len = self.number()?;
+        self.expect(")", "expected `)`")?;
+        Ok(len)
+    }
+    fn shape(&mut self) -> Result<Shape, ParseShapeError> {
+        self.skip_whitespace();
+        let start = self.offset;
+        Ok(match self.word() {
+            "bool" => Shape::Bool,
+            "i8" => Shape::I8,
+            "i16" => Shape::I16,
+            "i32" => Shape::I32,
+            "i64" => Shape::I64,
+            "i128" => Shape::I128,
+            "u8" => Shape::U8,
+            "u16" => Shape::U16,
+            "u32" => Shape::U32,
+            "u64" => Shape::U64,
+            "u128" => Shape::U128,
+            "f32" => Shape::F32,
+            "f64" => Shape::F64,
+            "char" => Shape::Char,
+            "string" => Shape::String,
+            "bytes" => Shape::ByteArray,
+            "none" => Shape::Option(None),
+            "some" => Shape::Option(Some(self.wrapped()?)),
+            "unit" => Shape::Unit,
+            "unit_struct" => Shape::UnitStruct(self.string()?),
+            "unit_variant" => Shape::UnitVariant(self.variant()?),
+            "newtype_struct" => Shape::NewTypeStruct {
+                name: self.string()?,
+                value: self.wrapped()?,
+            },
+            "newtype_variant" => Shape::NewTypeVariant {
+                variant: self.variant()?,
+                value: self.wrapped()?,
+            },
+            "seq" => Shape::Seq {
+                len: self.len()?,
+                items: self.shapes(("[", "]"))?,
+            },
+            "map" => Shape::Map {
+                len: self.len()?,
+                entries: self.list(("{", "}"), |this| {
+                    let k = this.shape()?;
+                    this.expect(":", "expected `:`")?;
+                    Ok::<_, ParseShapeError>((k, this.shape()?))
+                })?,
+            },
+            "tuple" => Shape::Tuple(self.shapes(("(", ")"))?),
+            "tuple_struct" => Shape::TupleStruct {
+                name: self.string()?,
+                values: self.shapes(("(", ")"))?,
+            },
+            "tuple_variant" => Shape::TupleVariant {
+                variant: self.variant()?,
+                values: self.shapes(("(", ")"))?,
+            },
+            "struct" => Shape::Struct {
+                name: self.string()?,
+                fields: self.fields()?,
+            },
+            "struct_variant" => Shape::StructVariant {
+                variant: self.variant()?,
+                fields: self.fields()?,
+            },
+            "error" => Shape::Error,
+            _ => {
+                self.offset = start;
+                return Err(self.fail("expected a shape").into());
+            }
+        })
+    }
+}
+
+/// The structural differences between two [`Shape`]s.
+///
+/// See [`Shape::diff`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShapeDiff {
+    pub(crate) changes: Vec<ShapeChange>,
+}
+
+/// A single difference between two [`Shape`]s.
+///
+/// See [`Shape::diff`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ShapeChange {
+    /// A field or element is only present in the new shape.
+    Added {
+        /// Location in the new shape.
+        path: Path<'static>,
+        /// The shape that was added.
+        shape: Shape,
+    },
+    /// A field or element is only present in the old shape.
+    Removed {
+        /// Location in the old shape.
+        path: Path<'static>,
+        /// The shape that was removed.
+        shape: Shape,
+    },
+    /// A field, struct or enum has a different name.
+    Renamed {
+        /// Location in the old shape.
+        path: Path<'static>,
+        /// The old name.
+        before: String,
+        /// The new name.
+        after: String,
+    },
+    /// A node has a different kind, or a different enum variant.
+    Changed {
+        /// Location in the old shape.
+        path: Path<'static>,
+        /// The old shape.
+        before: Shape,
+        /// The new shape.
+        after: Shape,
+    },
+}
+
+impl ShapeChange {
+    /// The location of this change.
+    pub fn path(&self) -> &Path<'static> {
+        match self {
+            ShapeChange::Added { path, .. }
+            | ShapeChange::Removed { path, .. }
+            | ShapeChange::Renamed { path, .. }
+            | ShapeChange::Changed { path, .. } => path,
+        }
+    }
+}
+
+impl ShapeDiff {
+    /// Returns `true` if the shapes were structurally identical.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+    /// The number of [`ShapeChange`]s.
+    pub fn len(&self) -> usize {
+        self.changes.len()
+    }
+    /// The [`ShapeChange`]s, in pre-order.
+    pub fn iter(&self) -> core::slice::Iter<'_, ShapeChange> {
+        self.changes.iter()
+    }
+}
+
+impl IntoIterator for ShapeDiff {
+    type Item = ShapeChange;
+    type IntoIter = std::vec::IntoIter<ShapeChange>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.changes.into_iter()
+    }
+}
+
+impl<'s> IntoIterator for &'s ShapeDiff {
+    type Item = &'s ShapeChange;
+    type IntoIter = core::slice::Iter<'s, ShapeChange>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.changes.iter()
+    }
+}
+
+/// One line per change, prefixed with `+`, `-` or `~`.
+impl fmt::Display for ShapeDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for change in &self.changes {
+            match change {
+                ShapeChange::Added { path, shape } => {
+                    writeln!(f, "+ {}: {}", DisplayPath(path), shape)?
+                }
+                ShapeChange::Removed { path, shape } => {
+                    writeln!(f, "- {}: {}", DisplayPath(path), shape)?
+                }
+                ShapeChange::Renamed {
+                    path,
+                    before,
+                    after,
+                } => writeln!(
+                    f,
+                    "~ {}: renamed {:?} -> {:?}",
+                    DisplayPath(path),
+                    before,
+                    after
+                )?,
+                ShapeChange::Changed {
+                    path,
+                    before,
+                    after,
+                } => writeln!(f, "~ {}: {} -> {}", DisplayPath(path), before, after)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+fn renamed(path: &Path<'static>, before: &str, after: &str, changes: &mut Vec<ShapeChange>) {
+    if before != after {
+        changes.push(ShapeChange::Renamed {
+            path: path.clone(),
+            before: before.into(),
+            after: after.into(),
+        })
+    }
+}
+
+fn diff(path: &mut Path<'static>, left: &Shape, right: &Shape, changes: &mut Vec<ShapeChange>) {
+    match (left, right) {
+        (Shape::Option(Some(l)), Shape::Option(Some(r))) => diff(path, l, r, changes),
+        (Shape::Option(_), Shape::Option(_)) => {}
+        (Shape::UnitStruct(ln), Shape::UnitStruct(rn)) => renamed(path, ln, rn, changes),
+        (
+            Shape::NewTypeStruct { name: ln, value: l },
+            Shape::NewTypeStruct { name: rn, value: r },
+        ) => {
+            renamed(path, ln, rn, changes);
+            diff(path, l, r, changes)
+        }
+        (
+            Shape::NewTypeVariant {
+                variant: lv,
+                value: l,
+            },
+            Shape::NewTypeVariant {
+                variant: rv,
+                value: r,
+            },
+        ) if lv.variant == rv.variant => {
+            renamed(path, &lv.name, &rv.name, changes);
+            diff(path, l, r, changes)
+        }
+        (Shape::Seq { items: l, .. }, Shape::Seq { items: r, .. })
+        | (Shape::Tuple(l), Shape::Tuple(r)) => elements(path, l, r, changes),
+        (
+            Shape::TupleStruct {
+                name: ln,
+                values: l,
+            },
+            Shape::TupleStruct {
+                name: rn,
+                values: r,
+            },
+        ) => {
+            renamed(path, ln, rn, changes);
+            elements(path, l, r, changes)
+        }
+        (
+            Shape::TupleVariant {
+                variant: lv,
+                values: l,
+            },
+            Shape::TupleVariant {
+                variant: rv,
+                values: r,
+            },
+        ) if lv.variant == rv.variant => {
+            renamed(path, &lv.name, &rv.name, changes);
+            elements(path, l, r, changes)
+        }
+        (Shape::Map { entries: l, .. }, Shape::Map { entries: r, .. }) => {
+            for ix in 0..Ord::max(l.len(), r.len()) {
+                match (l.get(ix), r.get(ix)) {
+                    (Some((lk, lv)), Some((rk, rv))) => {
+                        path.push(Segment::Key(ix));
+                        diff(path, lk, rk, changes);
+                        path.pop();
+                        path.push(Segment::Index(ix));
+                        diff(path, lv, rv, changes);
+                        path.pop();
+                    }
+                    (Some((k, v)), None) => changes.push(ShapeChange::Removed {
+                        path: path.join(Segment::Index(ix)),
+                        shape: Shape::Tuple(vec![k.clone(), v.clone()]),
+                    }),
+                    (None, Some((k, v))) => changes.push(ShapeChange::Added {
+                        path: path.join(Segment::Index(ix)),
+                        shape: Shape::Tuple(vec![k.clone(), v.clone()]),
+                    }),
+                    (None, None) => unreachable!(),
+                }
+            }
+        }
+        (
+            Shape::Struct {
+                name: ln,
+                fields: l,
+            },
+            Shape::Struct {
+                name: rn,
+                fields: r,
+            },
+        ) => {
+            renamed(path, ln, rn, changes);
+            fields_diff(path, l, r, changes)
+        }
+        (
+            Shape::StructVariant {
+                variant: lv,
+                fields: l,
+            },
+            Shape::StructVariant {
+                variant: rv,
+                fields: r,
+            },
+        ) if lv.variant == rv.variant => {
+            renamed(path, &lv.name, &rv.name, changes);
+            fields_diff(path, l, r, changes)
+        }
+        (l, r) => {
+            if !same_structure(l, r) {
+                changes.push(ShapeChange::Changed {
+                    path: path.clone(),
+                    before: l.clone(),
+                    after: r.clone(),
+                })
+            }
+        }
+    }
+}
+
+fn elements(
+    path: &mut Path<'static>,
+    left: &[Shape],
+    right: &[Shape],
+    changes: &mut Vec<ShapeChange>,
+) {
+    for ix in 0..Ord::max(left.len(), right.len()) {
+        path.push(Segment::Index(ix));
+        match (left.get(ix), right.get(ix)) {
+            (Some(l), Some(r)) => diff(path, l, r, changes),
+            (Some(l), None) => changes.push(ShapeChange::Removed {
+                path: path.clone(),
+                shape: l.clone(),
+            }),
+            (None, Some(r)) => changes.push(ShapeChange::Added {
+                path: path.clone(),
+                shape: r.clone(),
+            }),
+            (None, None) => unreachable!(),
+        }
+        path.pop();
+    }
+}
+
+/// The fields which weren't skipped.
+fn present(fields: &[(String, Option<Shape>)]) -> Vec<(&str, &Shape)> {
+    fields
+        .iter()
+        .filter_map(|(name, it)| Some((name.as_str(), it.as_ref()?)))
+        .collect()
+}
+
+fn fields_diff(
+    path: &mut Path<'static>,
+    left: &[(String, Option<Shape>)],
+    right: &[(String, Option<Shape>)],
+    changes: &mut Vec<ShapeChange>,
+) {
+    let (left, right) = (present(left), present(right));
+    let find =
+        |fields: &[(&str, &Shape)], name: &str| fields.iter().position(|(it, _)| *it == name);
+    let mut added = right
+        .iter()
+        .enumerate()
+        .filter(|(_, (name, _))| find(&left, name).is_none())
+        .map(|(ix, _)| ix)
+        .collect::<Vec<_>>();
+    for (lix, (name, l)) in left.iter().enumerate() {
+        path.push(Segment::Field(Cow::Owned(String::from(*name))));
+        match find(&right, name) {
+            Some(rix) => diff(path, l, right[rix].1, changes),
+            None => {
+                let rename = added
+                    .iter()
+                    .position(|rix| *rix == lix && same_structure(l, right[*rix].1));
+                match rename {
+                    Some(it) => {
+                        let rix = added.remove(it);
+                        renamed(path, name, right[rix].0, changes)
+                    }
+                    None => changes.push(ShapeChange::Removed {
+                        path: path.clone(),
+                        shape: (*l).clone(),
+                    }),
+                }
+            }
+        }
+        path.pop();
+    }
+    for rix in added {
+        let (name, r) = right[rix];
+        changes.push(ShapeChange::Added {
+            path: path.join(Segment::Field(Cow::Owned(String::from(name)))),
+            shape: r.clone(),
+        })
+    }
+}
@@ -0,0 +1,359 @@
+use crate::{shape::same_structure, Error, Shape, ShapeVariant, Variant};
+use serde::Serialize;
+
+/// Record the [`Shape`] of `t`, without storing any of its leaf values.
+///
+/// Only the distinct shapes of sequence elements and map entries are kept,
+/// so this uses far less memory than capturing a [`Save`](crate::Save) for
+/// large collections.
+///
+/// Errors are recorded as [`Shape::Error`].
+///
+/// ```
+/// # use serde_save::{save, save_shape, Shape};
+/// #[derive(serde::Serialize)]
+/// struct Reading {
+///     sensor: &'static str,
+///     value: f64,
+/// }
+///
+/// let readings = (0..1000)
+///     .map(|it| Reading { sensor: "thermometer", value: f64::from(it) })
+///     .collect::<Vec<_>>();
+/// let shape = save_shape(&readings);
+/// assert_eq!(shape, Shape::of(&save(&readings).unwrap()));
+/// assert_eq!(shape.to_string(), r#"seq(1000) [struct "Reading" {"sensor": string, "value": f64}]"#);
+/// ```
+pub fn save_shape<T: Serialize + ?Sized>(t: &T) -> Shape {
+    t.serialize(ShapeSerializer).unwrap_or(Shape::Error)
+}
+
+struct ShapeSerializer;
+
+fn child<T: Serialize + ?Sized>(value: &T) -> Shape {
+    value.serialize(ShapeSerializer).unwrap_or(Shape::Error)
+}
+
+fn variant(name: &'static str, variant_index: u32, variant: &'static str) -> ShapeVariant {
+    Variant {
+        name,
+        variant_index,
+        variant,
+    }
+    .into()
+}
+
+fn push_distinct<T>(seen: &mut Vec<T>, it: T, same: impl Fn(&T, &T) -> bool) {
+    if !seen.iter().any(|seen| same(seen, &it)) {
+        seen.push(it)
+    }
+}
+
+macro_rules! leaves {
+    ($($method:ident($ty:ty) -> $variant:ident);* $(;)?) => {
+        $(
+            fn $method(self, _: $ty) -> Result<Self::Ok, Self::Error> {
+                Ok(Shape::$variant)
+            }
+        )*
+    };
+}
+
+impl serde::Serializer for ShapeSerializer {
+    type Ok = Shape;
+    type Error = Error;
+    type SerializeSeq = Seq;
+    type SerializeTuple = Values;
+    type SerializeTupleStruct = Values;
+    type SerializeTupleVariant = Values;
+    type SerializeMap = Map;
+    type SerializeStruct = Fields;
+    type SerializeStructVariant = Fields;
+
+    leaves! {
+        serialize_bool(bool) -> Bool;
+        serialize_i8(i8) -> I8;
+        serialize_i16(i16) -> I16;
+        serialize_i32(i32) -> I32;
+        serialize_i64(i64) -> I64;
+        serialize_i128(i128) -> I128;
+        serialize_u8(u8) -> U8;
+        serialize_u16(u16) -> U16;
+        serialize_u32(u32) -> U32;
+        serialize_u64(u64) -> U64;
+        serialize_u128(u128) -> U128;
+        serialize_f32(f32) -> F32;
+        serialize_f64(f64) -> F64;
+        serialize_char(char) -> Char;
+        serialize_str(&str) -> String;
+        serialize_bytes(&[u8]) -> ByteArray;
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+        Ok(Shape::Option(None))
+    }
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<Self::Ok, Self::Error> {
+        Ok(Shape::Option(Some(Box::new(child(value)))))
+    }
+    fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+        Ok(Shape::Unit)
+    }
+    fn serialize_unit_struct(self, name: &'static str) -> Result<Self::Ok, Self::Error> {
+        Ok(Shape::UnitStruct(name.into()))
+    }
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Self::Ok, Self::Error> {
+        Ok(Shape::UnitVariant(self::variant(
+            name,
+            variant_index,
+            variant,
+        )))
+    }
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error> {
+        Ok(Shape::NewTypeStruct {
+            name: name.into(),
+            value: Box::new(child(value)),
+        })
+    }
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error> {
+        Ok(Shape::NewTypeVariant {
+            variant: self::variant(name, variant_index, variant),
+            value: Box::new(child(value)),
+        })
+    }
+    fn serialize_seq(self, _: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        Ok(Seq {
+            len: 0,
+            items: Vec::new(),
+        })
+    }
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        Ok(Values::new(None, len))
+    }
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        Ok(Values::new(Some(Container::Struct(name)), len))
+    }
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        Ok(Values::new(
+            Some(Container::Variant(self::variant(
+                name,
+                variant_index,
+                variant,
+            ))),
+            len,
+        ))
+    }
+    fn serialize_map(self, _: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        Ok(Map {
+            len: 0,
+            key: None,
+            entries: Vec::new(),
+        })
+    }
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, Self::Error> {
+        Ok(Fields::new(Container::Struct(name), len))
+    }
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        Ok(Fields::new(
+            Container::Variant(self::variant(name, variant_index, variant)),
+            len,
+        ))
+    }
+    fn collect_str<T: ?Sized + core::fmt::Display>(self, _: &T) -> Result<Self::Ok, Self::Error> {
+        Ok(Shape::String)
+    }
+}
+
+struct Seq {
+    len: usize,
+    items: Vec<Shape>,
+}
+
+impl serde::ser::SerializeSeq for Seq {
+    type Ok = Shape;
+    type Error = Error;
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        self.len += 1;
+        push_distinct(&mut self.items, child(value), same_structure);
+        Ok(())
+    }
+    fn end(self) -> Result<Shape, Error> {
+        Ok(Shape::Seq {
+            len: self.len,
+            items: self.items,
+        })
+    }
+}
+
+/// The type of a compound value, if it has a name.
+enum Container {
+    Struct(&'static str),
+    Variant(ShapeVariant),
+}
+
+struct Values {
+    /// [`None`] for tuples.
+    container: Option<Container>,
+    values: Vec<Shape>,
+}
+
+impl Values {
+    fn new(container: Option<Container>, len: usize) -> Self {
+        Self {
+            container,
+            values: Vec::with_capacity(len),
+        }
+    }
+    fn end(self) -> Shape {
+        let Self { container, values } = self;
+        match container {
+            None => Shape::Tuple(values),
+            Some(Container::Struct(name)) => Shape::TupleStruct {
+                name: name.into(),
+                values,
+            },
+            Some(Container::Variant(variant)) => Shape::TupleVariant { variant, values },
+        }
+    }
+}
+
+macro_rules! values {
+    ($($trait:ident::$method:ident),* $(,)?) => {
+        $(
+            impl serde::ser::$trait for Values {
+                type Ok = Shape;
+                type Error = Error;
+                fn $method<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+                    self.values.push(child(value));
+                    Ok(())
+                }
+                fn end(self) -> Result<Shape, Error> {
+                    Ok(Values::end(self))
+                }
+            }
+        )*
+    };
+}
+
+values! {
+    SerializeTuple::serialize_element,
+    SerializeTupleStruct::serialize_field,
+    SerializeTupleVariant::serialize_field,
+}
+
+struct Map {
+    len: usize,
+    key: Option<Shape>,
+    entries: Vec<(Shape, Shape)>,
+}
+
+impl serde::ser::SerializeMap for Map {
+    type Ok = Shape;
+    type Error = Error;
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), Error> {
+        self.key = Some(child(key));
+        Ok(())
+    }
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        let key = self.key.take().unwrap_or(Shape::Error);
+        self.len += 1;
+        push_distinct(
+            &mut self.entries,
+            (key, child(value)),
+            |(lk, lv), (rk, rv)| same_structure(lk, rk) && same_structure(lv, rv),
+        );
+        Ok(())
+    }
+    fn end(self) -> Result<Shape, Error> {
+        Ok(Shape::Map {
+            len: self.len,
+            entries: self.entries,
+        })
+    }
+}
+
+struct Fields {
+    container: Container,
+    fields: Vec<(String, Option<Shape>)>,
+}
+
+impl Fields {
+    fn new(container: Container, len: usize) -> Self {
+        Self {
+            container,
+            fields: Vec::with_capacity(len),
+        }
+    }
+    fn end(self) -> Shape {
+        let Self { container, fields } = self;
+        match container {
+            Container::Struct(name) => Shape::Struct {
+                name: name.into(),
+                fields,
+            },
+            Container::Variant(variant) => Shape::StructVariant { variant, fields },
+        }
+    }
+}
+
+macro_rules! fields {
+    ($($trait:ident),* $(,)?) => {
+        $(
+            impl serde::ser::$trait for Fields {
+                type Ok = Shape;
+                type Error = Error;
+                fn serialize_field<T: ?Sized + Serialize>(
+                    &mut self,
+                    key: &'static str,
+                    value: &T,
+                ) -> Result<(), Error> {
+                    self.fields.push((key.into(), Some(child(value))));
+                    Ok(())
+                }
+                fn skip_field(&mut self, key: &'static str) -> Result<(), Error> {
+                    self.fields.push((key.into(), None));
+                    Ok(())
+                }
+                fn end(self) -> Result<Shape, Error> {
+                    Ok(Fields::end(self))
+                }
+            }
+        )*
+    };
+}
+
+fields!(SerializeStruct, SerializeStructVariant);
@@ -0,0 +1,515 @@
+use crate::{Save, Shape, ShapeVariant, Variant};
+use core::fmt;
+use serde::de::{
+    self, value::MapAccessDeserializer, DeserializeSeed, Deserializer, EnumAccess,
+    IntoDeserializer, MapAccess, SeqAccess, Unexpected, VariantAccess, Visitor,
+};
+use serde::Deserialize as _;
+use std::sync::{Mutex, PoisonError};
+
+impl<'s> Save<'s> {
+    /// Deserialize a fully-named tree from a format like JSON,
+    /// which only yields anonymous maps and sequences,
+    /// using a [`Shape`] with the struct, field and variant names.
+    ///
+    /// ```
+    /// # use serde_save::{save, Deserializer, Save, Shape};
+    /// #[derive(serde::Serialize)]
+    /// struct Order {
+    ///     id: u8,
+    ///     status: Status,
+    /// }
+    /// #[derive(serde::Serialize)]
+    /// enum Status {
+    ///     Pending,
+    ///     Shipped,
+    /// }
+    ///
+    /// let shape = Shape::of(&save(Order { id: 1, status: Status::Pending }).unwrap());
+    ///
+    /// // e.g `{"id": 2, "status": "Pending"}`
+    /// let anonymous = Save::<std::convert::Infallible>::Map(vec![
+    ///     (Save::string("id"), Save::U64(2)),
+    ///     (Save::string("status"), Save::string("Pending")),
+    /// ]);
+    /// let named = Save::deserialize_with_shape(&shape, Deserializer::new(&anonymous)).unwrap();
+    /// assert_eq!(named, save(Order { id: 2, status: Status::Pending }).unwrap());
+    /// ```
+    ///
+    /// Numbers are converted to the type in the shape, if they fit.
+    /// Enum variants are matched by name or index,
+    /// so only variants which appear in `shape` can be deserialized.
+    /// Struct fields missing from the input are recorded as
+    /// [skipped](serde::ser::SerializeStruct::skip_field),
+    /// and unknown fields are an error.
+    ///
+    /// The elements of sequences and maps are deserialized without guidance
+    /// if they had more than one shape,
+    /// unless they were all variants of the same enum.
+    ///
+    /// Since serde requires `'static` names,
+    /// the deserializer is only passed placeholder names,
+    /// with the correct number of fields and variants.
+    pub fn deserialize_with_shape<'de, D: Deserializer<'de>>(
+        shape: &'s Shape,
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        Guide::of(&[shape]).deserialize(deserializer)
+    }
+}
+
+/// `len` placeholder names, see [`Save::deserialize_with_shape`].
+fn blanks(len: usize) -> &'static [&'static str] {
+    /// Grown by doubling, so at most twice the longest list is ever leaked.
+    static BLANKS: Mutex<&[&str]> = Mutex::new(&[""; 256]);
+    let mut blanks = BLANKS.lock().unwrap_or_else(PoisonError::into_inner);
+    if blanks.len() < len {
+        *blanks = Vec::leak(vec![""; len.next_power_of_two()])
+    }
+    &blanks[..len]
+}
+
+fn variant(shape: &Shape) -> Option<&ShapeVariant> {
+    match shape {
+        Shape::UnitVariant(it)
+        | Shape::NewTypeVariant { variant: it, .. }
+        | Shape::TupleVariant { variant: it, .. }
+        | Shape::StructVariant { variant: it, .. } => Some(it),
+        _ => None,
+    }
+}
+
+fn borrow(variant: &ShapeVariant) -> Variant<'_> {
+    Variant {
+        name: &variant.name,
+        variant_index: variant.variant_index,
+        variant: &variant.variant,
+    }
+}
+
+/// How to deserialize a node, given the shapes it may have.
+#[derive(Clone, Copy)]
+enum Guide<'g, 's> {
+    Any,
+    One(&'s Shape),
+    /// Variants of the same enum.
+    Enum(&'g [&'s Shape]),
+}
+
+impl<'g, 's> Guide<'g, 's> {
+    fn of(shapes: &'g [&'s Shape]) -> Self {
+        match shapes {
+            [Shape::Error] => Guide::Any,
+            [one] if variant(one).is_none() => Guide::One(one),
+            [first, rest @ ..] => match variant(first) {
+                Some(first)
+                    if rest
+                        .iter()
+                        .all(|it| variant(it).map(|it| &it.name) == Some(&first.name)) =>
+                {
+                    Guide::Enum(shapes)
+                }
+                _ => Guide::Any,
+            },
+            [] => Guide::Any,
+        }
+    }
+}
+
+impl<'de, 's> DeserializeSeed<'de> for Guide<'_, 's> {
+    type Value = Save<'s>;
+
+    fn deserialize<D: Deserializer<'de>>(self, d: D) -> Result<Self::Value, D::Error> {
+        let shape = match self {
+            Guide::Any => return Save::deserialize(d),
+            Guide::Enum(shapes) => {
+                return d.deserialize_enum("", blanks(shapes.len()), Enum(shapes))
+            }
+            Guide::One(it) => it,
+        };
+        match shape {
+            Shape::Bool => d.deserialize_bool(Leaf(shape)),
+            Shape::I8 => d.deserialize_i8(Leaf(shape)),
+            Shape::I16 => d.deserialize_i16(Leaf(shape)),
+            Shape::I32 => d.deserialize_i32(Leaf(shape)),
+            Shape::I64 => d.deserialize_i64(Leaf(shape)),
+            Shape::I128 => d.deserialize_i128(Leaf(shape)),
+            Shape::U8 => d.deserialize_u8(Leaf(shape)),
+            Shape::U16 => d.deserialize_u16(Leaf(shape)),
+            Shape::U32 => d.deserialize_u32(Leaf(shape)),
+            Shape::U64 => d.deserialize_u64(Leaf(shape)),
+            Shape::U128 => d.deserialize_u128(Leaf(shape)),
+            Shape::F32 => d.deserialize_f32(Leaf(shape)),
+            Shape::F64 => d.deserialize_f64(Leaf(shape)),
+            Shape::Char => d.deserialize_char(Leaf(shape)),
+            Shape::String => d.deserialize_string(Leaf(shape)),
+            Shape::ByteArray => d.deserialize_byte_buf(Leaf(shape)),
+            Shape::Option(it) => d.deserialize_option(Optional(it.as_deref())),
+            Shape::Unit => d.deserialize_unit(Leaf(shape)),
+            Shape::UnitStruct(_) => d.deserialize_unit_struct("", Leaf(shape)),
+            Shape::NewTypeStruct { name, value } => {
+                d.deserialize_newtype_struct("", NewType { name, value })
+            }
+            Shape::Seq { items, .. } => d.deserialize_seq(Elements(items)),
+            Shape::Map { entries, .. } => d.deserialize_map(Entries(entries)),
+            Shape::Tuple(values) => d
+                .deserialize_tuple(values.len(), Positional(values))
+                .map(Save::Tuple),
+            Shape::TupleStruct { name, values } => d
+                .deserialize_tuple_struct("", values.len(), Positional(values))
+                .map(|values| Save::TupleStruct { name, values }),
+            Shape::Struct { name, fields } => d
+                .deserialize_struct("", blanks(fields.len()), Fields(fields))
+                .map(|fields| Save::Struct { name, fields }),
+            Shape::UnitVariant(_)
+            | Shape::NewTypeVariant { .. }
+            | Shape::TupleVariant { .. }
+            | Shape::StructVariant { .. }
+            | Shape::Error => unreachable!("handled by `Guide::of`"),
+        }
+    }
+}
+
+/// Deserialize a leaf, converting it to the kind in the shape.
+struct Leaf<'s>(&'s Shape);
+
+macro_rules! integer {
+    ($self:ident, $v:ident, $unexpected:expr; $($kind:ident($ty:ty)),* $(,)?) => {
+        match $self.0 {
+            $(
+                Shape::$kind => <$ty>::try_from($v)
+                    .map(Save::$kind)
+                    .map_err(|_| de::Error::invalid_value($unexpected, &$self)),
+            )*
+            Shape::F32 => Ok(Save::F32($v as f32)),
+            Shape::F64 => Ok(Save::F64($v as f64)),
+            _ => Err(de::Error::invalid_type($unexpected, &$self)),
+        }
+    };
+}
+
+impl<'de, 's> Visitor<'de> for Leaf<'s> {
+    type Value = Save<'s>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a value of shape `{}`", self.0)
+    }
+
+    fn visit_bool<E: de::Error>(self, v: bool) -> Result<Self::Value, E> {
+        match self.0 {
+            Shape::Bool => Ok(Save::Bool(v)),
+            _ => Err(de::Error::invalid_type(Unexpected::Bool(v), &self)),
+        }
+    }
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+        self.visit_i128(v.into())
+    }
+    fn visit_i128<E: de::Error>(self, v: i128) -> Result<Self::Value, E> {
+        let unexpected = Unexpected::Signed(v.try_into().unwrap_or(i64::MIN));
+        integer!(self, v, unexpected;
+            I8(i8), I16(i16), I32(i32), I64(i64), I128(i128),
+            U8(u8), U16(u16), U32(u32), U64(u64), U128(u128),
+        )
+    }
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+        self.visit_u128(v.into())
+    }
+    fn visit_u128<E: de::Error>(self, v: u128) -> Result<Self::Value, E> {
+        let unexpected = Unexpected::Unsigned(v.try_into().unwrap_or(u64::MAX));
+        integer!(self, v, unexpected;
+            I8(i8), I16(i16), I32(i32), I64(i64), I128(i128),
+            U8(u8), U16(u16), U32(u32), U64(u64), U128(u128),
+        )
+    }
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
+        match self.0 {
+            Shape::F32 => Ok(Save::F32(v as f32)),
+            Shape::F64 => Ok(Save::F64(v)),
+            _ => Err(de::Error::invalid_type(Unexpected::Float(v), &self)),
+        }
+    }
+    fn visit_char<E: de::Error>(self, v: char) -> Result<Self::Value, E> {
+        match self.0 {
+            Shape::Char => Ok(Save::Char(v)),
+            Shape::String => Ok(Save::String(v.into())),
+            _ => Err(de::Error::invalid_type(Unexpected::Char(v), &self)),
+        }
+    }
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        let mut chars = v.chars();
+        match (self.0, chars.next(), chars.next()) {
+            (Shape::String, ..) => Ok(Save::String(v.into())),
+            (Shape::Char, Some(c), None) => Ok(Save::Char(c)),
+            _ => Err(de::Error::invalid_type(Unexpected::Str(v), &self)),
+        }
+    }
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        match self.0 {
+            Shape::ByteArray => Ok(Save::ByteArray(v.into())),
+            _ => Err(de::Error::invalid_type(Unexpected::Bytes(v), &self)),
+        }
+    }
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        match self.0 {
+            Shape::ByteArray => {
+                let mut bytes = Vec::new();
+                while let Some(it) = seq.next_element()? {
+                    bytes.push(it)
+                }
+                Ok(Save::ByteArray(bytes))
+            }
+            _ => Err(de::Error::invalid_type(Unexpected::Seq, &self)),
+        }
+    }
+    fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+        match self.0 {
+            Shape::Unit => Ok(Save::Unit),
+            Shape::UnitStruct(name) => Ok(Save::UnitStruct(name)),
+            _ => Err(de::Error::invalid_type(Unexpected::Unit, &self)),
+        }
+    }
+}
+
+/// The contents of a [`Shape::Option`], if it was [`Some`].
+struct Optional<'s>(Option<&'s Shape>);
+
+impl<'de, 's> Visitor<'de> for Optional<'s> {
+    type Value = Save<'s>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an option")
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(Save::Option(None))
+    }
+    fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(Save::Option(None))
+    }
+    fn visit_some<D: Deserializer<'de>>(self, d: D) -> Result<Self::Value, D::Error> {
+        let it = match self.0 {
+            Some(shape) => Guide::of(&[shape]).deserialize(d)?,
+            None => Save::deserialize(d)?,
+        };
+        Ok(Save::Option(Some(Box::new(it))))
+    }
+}
+
+struct NewType<'s> {
+    name: &'s str,
+    value: &'s Shape,
+}
+
+impl<'de, 's> Visitor<'de> for NewType<'s> {
+    type Value = Save<'s>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a newtype struct `{}`", self.name)
+    }
+
+    fn visit_newtype_struct<D: Deserializer<'de>>(self, d: D) -> Result<Self::Value, D::Error> {
+        Ok(Save::NewTypeStruct {
+            name: self.name,
+            value: Box::new(Guide::of(&[self.value]).deserialize(d)?),
+        })
+    }
+}
+
+/// The distinct shapes of a sequence's elements.
+struct Elements<'s>(&'s [Shape]);
+
+impl<'de, 's> Visitor<'de> for Elements<'s> {
+    type Value = Save<'s>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a sequence")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let shapes = self.0.iter().collect::<Vec<_>>();
+        let guide = Guide::of(&shapes);
+        let mut elements = Vec::with_capacity(seq.size_hint().unwrap_or_default());
+        while let Some(it) = seq.next_element_seed(guide)? {
+            elements.push(it)
+        }
+        Ok(Save::Seq(elements))
+    }
+}
+
+/// The distinct shapes of a map's entries.
+struct Entries<'s>(&'s [(Shape, Shape)]);
+
+impl<'de, 's> Visitor<'de> for Entries<'s> {
+    type Value = Save<'s>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a map")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let (keys, values): (Vec<_>, Vec<_>) = self.0.iter().map(|(k, v)| (k, v)).unzip();
+        let (keys, values) = (Guide::of(&keys), Guide::of(&values));
+        let mut entries = Vec::with_capacity(map.size_hint().unwrap_or_default());
+        while let Some(it) = map.next_entry_seed(keys, values)? {
+            entries.push(it)
+        }
+        Ok(Save::Map(entries))
+    }
+}
+
+/// The shapes of each element of a tuple.
+struct Positional<'s>(&'s [Shape]);
+
+impl<'de, 's> Visitor<'de> for Positional<'s> {
+    type Value = Vec<Save<'s>>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a tuple of length {}", self.0.len())
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut values = Vec::with_capacity(self.0.len());
+        for (ix, shape) in self.0.iter().enumerate() {
+            match seq.next_element_seed(Guide::of(&[shape]))? {
+                Some(it) => values.push(it),
+                None => return Err(de::Error::invalid_length(ix, &self)),
+            }
+        }
+        Ok(values)
+    }
+}
+
+/// The fields of a struct, by name.
+struct Fields<'s>(&'s [(String, Option<Shape>)]);
+
+impl<'de, 's> Visitor<'de> for Fields<'s> {
+    type Value = Vec<(&'s str, Option<Save<'s>>)>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a struct")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut found = vec![None; self.0.len()];
+        while let Some(key) = map.next_key::<String>()? {
+            let Some(ix) = self.0.iter().position(|(name, _)| *name == key) else {
+                return Err(de::Error::custom(format_args!("unknown field `{}`", key)));
+            };
+            if found[ix].is_some() {
+                return Err(de::Error::custom(format_args!("duplicate field `{}`", key)));
+            }
+            found[ix] = Some(match &self.0[ix].1 {
+                Some(shape) => map.next_value_seed(Guide::of(&[shape]))?,
+                None => map.next_value::<Save<'s>>()?,
+            });
+        }
+        Ok(self
+            .0
+            .iter()
+            .zip(found)
+            .map(|((name, _), it)| (name.as_str(), it))
+            .collect())
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut fields = Vec::with_capacity(self.0.len());
+        for (ix, (name, shape)) in self.0.iter().enumerate() {
+            let it = match shape {
+                Some(shape) => match seq.next_element_seed(Guide::of(&[shape]))? {
+                    Some(it) => Some(it),
+                    None => return Err(de::Error::invalid_length(ix, &self)),
+                },
+                None => None,
+            };
+            fields.push((name.as_str(), it))
+        }
+        Ok(fields)
+    }
+}
+
+/// Variants of the same enum.
+struct Enum<'g, 's>(&'g [&'s Shape]);
+
+impl<'de, 's> Visitor<'de> for Enum<'_, 's> {
+    type Value = Save<'s>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0.first().and_then(|it| variant(it)) {
+            Some(it) => write!(f, "an enum `{}`", it.name),
+            None => f.write_str("an enum"),
+        }
+    }
+
+    fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<Self::Value, A::Error> {
+        let (shape, access) = data.variant_seed(Identifier(self.0))?;
+        match shape {
+            Shape::UnitVariant(variant) => {
+                access.unit_variant()?;
+                Ok(Save::UnitVariant(borrow(variant)))
+            }
+            Shape::NewTypeVariant { variant, value } => Ok(Save::NewTypeVariant {
+                variant: borrow(variant),
+                value: Box::new(access.newtype_variant_seed(Guide::of(&[value]))?),
+            }),
+            Shape::TupleVariant { variant, values } => Ok(Save::TupleVariant {
+                variant: borrow(variant),
+                values: access.tuple_variant(values.len(), Positional(values))?,
+            }),
+            Shape::StructVariant { variant, fields } => Ok(Save::StructVariant {
+                variant: borrow(variant),
+                fields: access.struct_variant(blanks(fields.len()), Fields(fields))?,
+            }),
+            _ => unreachable!("only variants are guided as enums"),
+        }
+    }
+
+    /// For self-describing formats which represent unit variants as strings.
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        self.visit_enum(v.into_deserializer())
+    }
+
+    /// For self-describing formats which represent variants as single-entry maps.
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+        self.visit_enum(MapAccessDeserializer::new(map))
+    }
+}
+
+/// Choose a variant by name or index.
+struct Identifier<'g, 's>(&'g [&'s Shape]);
+
+impl<'de, 's> DeserializeSeed<'de> for Identifier<'_, 's> {
+    type Value = &'s Shape;
+
+    fn deserialize<D: Deserializer<'de>>(self, d: D) -> Result<Self::Value, D::Error> {
+        d.deserialize_identifier(self)
+    }
+}
+
+impl<'de, 's> Visitor<'de> for Identifier<'_, 's> {
+    type Value = &'s Shape;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a variant name or index")
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+        self.0
+            .iter()
+            .find(|it| variant(it).is_some_and(|it| u64::from(it.variant_index) == v))
+            .copied()
+            .ok_or_else(|| de::Error::invalid_value(Unexpected::Unsigned(v), &self))
+    }
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        self.0
+            .iter()
+            .find(|it| variant(it).is_some_and(|it| it.variant == v))
+            .copied()
+            .ok_or_else(|| de::Error::custom(format_args!("unknown variant `{}`", v)))
+    }
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        match core::str::from_utf8(v) {
+            Ok(it) => self.visit_str(it),
+            Err(_) => Err(de::Error::invalid_value(Unexpected::Bytes(v), &self)),
+        }
+    }
+}
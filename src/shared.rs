@@ -0,0 +1,71 @@
+use crate::Save;
+use core::{convert::Infallible, fmt, ops::Deref};
+use std::sync::Arc;
+
+/// A cheaply cloneable, thread-safe handle to a [`Save`].
+///
+/// Cloning only increments a reference count,
+/// so captures can be fanned out to e.g logging and metrics threads
+/// without deep copies.
+///
+/// ```
+/// # use serde_save::{save, SharedSave};
+/// let shared = SharedSave::new(save(vec![1, 2, 3]).unwrap());
+/// let handles = (0..2)
+///     .map(|_| {
+///         let shared = shared.clone();
+///         std::thread::spawn(move || shared.elements().len())
+///     })
+///     .collect::<Vec<_>>();
+/// for handle in handles {
+///     assert_eq!(handle.join().unwrap(), 3);
+/// }
+/// ```
+#[derive(Debug, PartialEq, PartialOrd)]
+pub struct SharedSave<E = Infallible>(Arc<Save<'static, E>>);
+
+impl<E> SharedSave<E> {
+    /// Share `save`.
+    pub fn new(save: Save<'static, E>) -> Self {
+        Self(Arc::new(save))
+    }
+    /// Return the tree, cloning it only if there are other handles to it.
+    pub fn into_inner(self) -> Save<'static, E>
+    where
+        E: Clone,
+    {
+        Arc::try_unwrap(self.0).unwrap_or_else(|it| (*it).clone())
+    }
+}
+
+impl<E> Clone for SharedSave<E> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<E> Deref for SharedSave<E> {
+    type Target = Save<'static, E>;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<E> AsRef<Save<'static, E>> for SharedSave<E> {
+    fn as_ref(&self) -> &Save<'static, E> {
+        &self.0
+    }
+}
+
+impl<E> From<Save<'static, E>> for SharedSave<E> {
+    fn from(it: Save<'static, E>) -> Self {
+        Self::new(it)
+    }
+}
+
+/// Forwards to the shared [`Save`].
+impl<E: fmt::Display> serde::Serialize for SharedSave<E> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
@@ -0,0 +1,125 @@
+//! Deterministic renditions of a [`Save`], for golden files and snapshot testing.
+//!
+//! Map entries are sorted by [`Save::cmp_canonical`], floats are normalized
+//! so that `-0.0` and every `NaN` render identically,
+//! and every non-empty container is spread over multiple lines,
+//! so that changes show up as small, reviewable diffs.
+//!
+//! ```
+//! # use serde_save::{save, snapshot};
+//! # use std::collections::HashMap;
+//! #[derive(serde::Serialize)]
+//! struct Request {
+//!     id: u64,
+//!     headers: HashMap<&'static str, &'static str>,
+//! }
+//!
+//! let request = Request {
+//!     id: 4,
+//!     headers: HashMap::from([("b", "2"), ("a", "1")]),
+//! };
+//! let saved = save(&request).unwrap();
+//! let snapshot = snapshot::Snapshot::new(&saved).redact("id");
+//! assert_eq!(
+//!     snapshot.to_string(),
+//!     r#"Request {
+//!     id: <redacted>,
+//!     headers: {
+//!         "a": "1",
+//!         "b": "2",
+//!     },
+//! }"#
+//! );
+//! ```
+
+use crate::{pretty, Path, Save};
+use core::fmt;
+
+/// Render `save` using the default [`Snapshot`] settings.
+///
+/// ```
+/// # use serde_save::{save, snapshot};
+/// assert_eq!(snapshot::render(&save((-0.0, 1u8)).unwrap()), "(\n    f64(0.0),\n    u8(1),\n)");
+/// ```
+pub fn render<E>(save: &Save<'_, E>) -> String
+where
+    E: fmt::Display + Ord,
+{
+    Snapshot::new(save).to_string()
+}
+
+/// A configurable, deterministic rendition of a [`Save`].
+///
+/// Display this to get the snapshot text.
+pub struct Snapshot<'s, 'a, E> {
+    save: &'s Save<'a, E>,
+    indent: usize,
+    #[allow(clippy::type_complexity)]
+    redact: Vec<Box<dyn Fn(&Path<'a>, &Save<'a, E>) -> bool + 's>>,
+}
+
+impl<'s, 'a, E> Snapshot<'s, 'a, E> {
+    /// Snapshot `save`, indenting by four spaces.
+    pub fn new(save: &'s Save<'a, E>) -> Self {
+        Self {
+            save,
+            indent: 4,
+            redact: Vec::new(),
+        }
+    }
+    /// Set the number of spaces per level of indentation.
+    pub fn indent(mut self, indent: usize) -> Self {
+        self.indent = indent;
+        self
+    }
+    /// Replace the node at `path` with `<redacted>`,
+    /// for values which change between runs, like timestamps.
+    ///
+    /// See [`Path::parse`] for the syntax.
+    ///
+    /// # Panics
+    /// - If `path` is invalid.
+    #[track_caller]
+    pub fn redact(self, path: &str) -> Self {
+        let path = match Path::parse(path) {
+            Ok(it) => it.into_owned(),
+            Err(e) => panic!("invalid path {:?}: {}", path, e),
+        };
+        self.redact_with(move |it, _| *it == path)
+    }
+    /// Replace every node for which `f` returns `true` with `<redacted>`.
+    ///
+    /// ```
+    /// # use serde_save::{save, snapshot::Snapshot, Save};
+    /// let tokens = save(["secret", "hunter2"]).unwrap();
+    /// let snapshot = Snapshot::new(&tokens).redact_with(|_, it| matches!(it, Save::String(_)));
+    /// assert_eq!(snapshot.to_string(), "(\n    <redacted>,\n    <redacted>,\n)");
+    /// ```
+    pub fn redact_with(mut self, f: impl Fn(&Path<'a>, &Save<'a, E>) -> bool + 's) -> Self {
+        self.redact.push(Box::new(f));
+        self
+    }
+}
+
+impl<E> fmt::Display for Snapshot<'_, '_, E>
+where
+    E: fmt::Display + Ord,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let options = pretty::Options {
+            indent: self.indent,
+            multiline: true,
+            sort_maps: Some(Save::cmp_canonical),
+            normalize_floats: true,
+            redact: &self.redact,
+            ..pretty::Options::default()
+        };
+        fmt::Display::fmt(
+            &pretty::Render {
+                options: &options,
+                save: self.save,
+            },
+            f,
+        )
+    }
+}
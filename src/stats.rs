@@ -0,0 +1,103 @@
+use crate::{canonical::KINDS, diff::DisplayPath, Path, Save};
+use core::fmt;
+
+/// The number of leaves kept in [`PayloadStats::largest`].
+const LARGEST: usize = 10;
+
+/// Where the bytes in a tree are, see [`Save::payload_stats`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PayloadStats<'a> {
+    /// The total length of every [`Save::String`], in bytes.
+    pub string_bytes: usize,
+    /// The total length of every [`Save::ByteArray`].
+    pub byte_array_bytes: usize,
+    /// The largest strings and byte arrays, with their length, largest first.
+    ///
+    /// At most 10 leaves are kept.
+    pub largest: Vec<(Path<'a>, usize)>,
+    /// The number of nodes of each kind which occur in the tree,
+    /// in the order of [`Save`]'s variants.
+    ///
+    /// Kinds are named as in the [`Display`](fmt::Display) of a [`Shape`](crate::Shape).
+    pub kinds: Vec<(&'static str, usize)>,
+}
+
+impl fmt::Display for PayloadStats<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "strings: {} bytes", self.string_bytes)?;
+        writeln!(f, "byte arrays: {} bytes", self.byte_array_bytes)?;
+        if !self.largest.is_empty() {
+            writeln!(f, "largest:")?;
+            for (path, len) in &self.largest {
+                writeln!(f, "  {}: {} bytes", DisplayPath(path), len)?;
+            }
+        }
+        writeln!(f, "kinds:")?;
+        for (kind, count) in &self.kinds {
+            writeln!(f, "  {}: {}", kind, count)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a, E> Save<'a, E> {
+    /// Summarise the size of this tree, to find what makes it large.
+    ///
+    /// ```
+    /// # use serde_save::{save, Path};
+    /// #[derive(serde::Serialize)]
+    /// struct Upload {
+    ///     name: String,
+    ///     #[serde(with = "serde_bytes_like")]
+    ///     body: Vec<u8>,
+    /// }
+    /// # mod serde_bytes_like {
+    /// #     pub fn serialize<S: serde::Serializer>(it: &[u8], s: S) -> Result<S::Ok, S::Error> {
+    /// #         s.serialize_bytes(it)
+    /// #     }
+    /// # }
+    ///
+    /// let saved = save(Upload { name: "cat.png".into(), body: vec![0; 4096] }).unwrap();
+    /// let stats = saved.payload_stats();
+    /// assert_eq!(stats.string_bytes, 7);
+    /// assert_eq!(stats.byte_array_bytes, 4096);
+    /// assert_eq!(stats.largest[0], (Path::parse("body").unwrap(), 4096));
+    /// assert_eq!(stats.kinds, [("string", 1), ("bytes", 1), ("struct", 1)]);
+    /// ```
+    pub fn payload_stats(&self) -> PayloadStats<'a> {
+        let mut string_bytes = 0;
+        let mut byte_array_bytes = 0;
+        let mut largest = Vec::<(Path<'a>, usize)>::new();
+        let mut counts = [0; KINDS.len()];
+        self.walk(|path, node| {
+            counts[usize::from(node.kind_index())] += 1;
+            let len = match node {
+                Save::String(it) => {
+                    string_bytes += it.len();
+                    it.len()
+                }
+                Save::ByteArray(it) => {
+                    byte_array_bytes += it.len();
+                    it.len()
+                }
+                _ => return,
+            };
+            // Keep the earliest of equally large leaves.
+            let ix = largest.partition_point(|(_, it)| *it >= len);
+            if ix < LARGEST {
+                largest.insert(ix, (path.clone(), len));
+                largest.truncate(LARGEST);
+            }
+        });
+        PayloadStats {
+            string_bytes,
+            byte_array_bytes,
+            largest,
+            kinds: KINDS
+                .into_iter()
+                .zip(counts)
+                .filter(|(_, count)| *count != 0)
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,280 @@
+use crate::{Save, Variant};
+use core::{convert::Infallible, fmt, hash::Hash};
+use std::collections::HashMap;
+
+/// A handle to a tree in a [`SaveStore`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SaveId(usize);
+
+impl fmt::Display for SaveId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
+
+/// A content-addressed store of trees, where identical subtrees are only stored once.
+///
+/// This is intended for keeping many periodic snapshots of mostly-unchanged state.
+///
+/// ```
+/// # use serde_save::{save, SaveStore};
+/// let mut store = SaveStore::new();
+/// let monday = store.insert(save(("config", vec![1, 2, 3], 10)).unwrap());
+/// let tuesday = store.insert(save(("config", vec![1, 2, 3], 11)).unwrap());
+/// assert_ne!(monday, tuesday);
+///
+/// // Only the changed leaf and the roots are new.
+/// assert_eq!(store.node_count(), 9);
+///
+/// assert_eq!(store.get(monday), Some(save(("config", vec![1, 2, 3], 10)).unwrap()));
+/// assert_eq!(store.insert(save(("config", vec![1, 2, 3], 10)).unwrap()), monday);
+/// ```
+///
+/// Nodes are deduplicated by their contents, and the ids of their children,
+/// so the store forms a DAG.
+/// Floats are compared by their bits, so e.g `NaN`s are also deduplicated.
+#[derive(Debug, Clone)]
+pub struct SaveStore<E = Infallible> {
+    nodes: Vec<Node<E>>,
+    ids: HashMap<Node<E>, SaveId>,
+}
+
+impl<E> Default for SaveStore<E> {
+    fn default() -> Self {
+        Self {
+            nodes: Vec::new(),
+            ids: HashMap::new(),
+        }
+    }
+}
+
+impl<E> SaveStore<E> {
+    /// An empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// The number of distinct nodes in the store.
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+    /// Add `save` to the store, returning its id.
+    ///
+    /// Inserting an identical tree returns the same id.
+    pub fn insert(&mut self, save: Save<'static, E>) -> SaveId
+    where
+        E: Clone + Hash + Eq,
+    {
+        let node = match save {
+            Save::Bool(it) => Node::Bool(it),
+            Save::I8(it) => Node::I8(it),
+            Save::I16(it) => Node::I16(it),
+            Save::I32(it) => Node::I32(it),
+            Save::I64(it) => Node::I64(it),
+            Save::I128(it) => Node::I128(it),
+            Save::U8(it) => Node::U8(it),
+            Save::U16(it) => Node::U16(it),
+            Save::U32(it) => Node::U32(it),
+            Save::U64(it) => Node::U64(it),
+            Save::U128(it) => Node::U128(it),
+            Save::F32(it) => Node::F32(it.to_bits()),
+            Save::F64(it) => Node::F64(it.to_bits()),
+            Save::Char(it) => Node::Char(it),
+            Save::String(it) => Node::String(it),
+            Save::ByteArray(it) => Node::ByteArray(it),
+            Save::Option(it) => Node::Option(it.map(|it| self.insert(*it))),
+            Save::Unit => Node::Unit,
+            Save::UnitStruct(it) => Node::UnitStruct(it),
+            Save::UnitVariant(it) => Node::UnitVariant(it),
+            Save::NewTypeStruct { name, value } => Node::NewTypeStruct {
+                name,
+                value: self.insert(*value),
+            },
+            Save::NewTypeVariant { variant, value } => Node::NewTypeVariant {
+                variant,
+                value: self.insert(*value),
+            },
+            Save::Seq(it) => Node::Seq(self.insert_all(it)),
+            Save::Map(it) => Node::Map(
+                it.into_iter()
+                    .map(|(k, v)| (self.insert(k), self.insert(v)))
+                    .collect(),
+            ),
+            Save::Tuple(it) => Node::Tuple(self.insert_all(it)),
+            Save::TupleStruct { name, values } => Node::TupleStruct {
+                name,
+                values: self.insert_all(values),
+            },
+            Save::TupleVariant { variant, values } => Node::TupleVariant {
+                variant,
+                values: self.insert_all(values),
+            },
+            Save::Struct { name, fields } => Node::Struct {
+                name,
+                fields: self.insert_fields(fields),
+            },
+            Save::StructVariant { variant, fields } => Node::StructVariant {
+                variant,
+                fields: self.insert_fields(fields),
+            },
+            Save::Error(it) => Node::Error(it),
+        };
+        if let Some(id) = self.ids.get(&node) {
+            return *id;
+        }
+        let id = SaveId(self.nodes.len());
+        self.nodes.push(node.clone());
+        self.ids.insert(node, id);
+        id
+    }
+    /// [`Self::insert`] each of `it`.
+    fn insert_all(&mut self, it: Vec<Save<'static, E>>) -> Vec<SaveId>
+    where
+        E: Clone + Hash + Eq,
+    {
+        it.into_iter().map(|it| self.insert(it)).collect()
+    }
+    /// [`Self::insert`] each field that wasn't skipped.
+    #[allow(clippy::type_complexity)]
+    fn insert_fields(
+        &mut self,
+        fields: Vec<(&'static str, Option<Save<'static, E>>)>,
+    ) -> Vec<(&'static str, Option<SaveId>)>
+    where
+        E: Clone + Hash + Eq,
+    {
+        fields
+            .into_iter()
+            .map(|(k, v)| (k, v.map(|v| self.insert(v))))
+            .collect()
+    }
+    /// Rebuild the tree with the given `id`,
+    /// or return [`None`] if it is not from this store.
+    pub fn get(&self, id: SaveId) -> Option<Save<'static, E>>
+    where
+        E: Clone,
+    {
+        let all = |ids: &[SaveId]| ids.iter().map(|it| self.get(*it)).collect::<Option<_>>();
+        let fields = |fields: &[(&'static str, Option<SaveId>)]| {
+            fields
+                .iter()
+                .map(|(k, v)| match v {
+                    Some(v) => Some((*k, Some(self.get(*v)?))),
+                    None => Some((*k, None)),
+                })
+                .collect::<Option<_>>()
+        };
+        Some(match self.nodes.get(id.0)? {
+            Node::Bool(it) => Save::Bool(*it),
+            Node::I8(it) => Save::I8(*it),
+            Node::I16(it) => Save::I16(*it),
+            Node::I32(it) => Save::I32(*it),
+            Node::I64(it) => Save::I64(*it),
+            Node::I128(it) => Save::I128(*it),
+            Node::U8(it) => Save::U8(*it),
+            Node::U16(it) => Save::U16(*it),
+            Node::U32(it) => Save::U32(*it),
+            Node::U64(it) => Save::U64(*it),
+            Node::U128(it) => Save::U128(*it),
+            Node::F32(it) => Save::F32(f32::from_bits(*it)),
+            Node::F64(it) => Save::F64(f64::from_bits(*it)),
+            Node::Char(it) => Save::Char(*it),
+            Node::String(it) => Save::String(it.clone()),
+            Node::ByteArray(it) => Save::ByteArray(it.clone()),
+            Node::Option(None) => Save::Option(None),
+            Node::Option(Some(it)) => Save::Option(Some(Box::new(self.get(*it)?))),
+            Node::Unit => Save::Unit,
+            Node::UnitStruct(it) => Save::UnitStruct(it),
+            Node::UnitVariant(it) => Save::UnitVariant(*it),
+            Node::NewTypeStruct { name, value } => Save::NewTypeStruct {
+                name,
+                value: Box::new(self.get(*value)?),
+            },
+            Node::NewTypeVariant { variant, value } => Save::NewTypeVariant {
+                variant: *variant,
+                value: Box::new(self.get(*value)?),
+            },
+            Node::Seq(it) => Save::Seq(all(it)?),
+            Node::Map(it) => Save::Map(
+                it.iter()
+                    .map(|(k, v)| Some((self.get(*k)?, self.get(*v)?)))
+                    .collect::<Option<_>>()?,
+            ),
+            Node::Tuple(it) => Save::Tuple(all(it)?),
+            Node::TupleStruct { name, values } => Save::TupleStruct {
+                name,
+                values: all(values)?,
+            },
+            Node::TupleVariant { variant, values } => Save::TupleVariant {
+                variant: *variant,
+                values: all(values)?,
+            },
+            Node::Struct { name, fields: it } => Save::Struct {
+                name,
+                fields: fields(it)?,
+            },
+            Node::StructVariant {
+                variant,
+                fields: it,
+            } => Save::StructVariant {
+                variant: *variant,
+                fields: fields(it)?,
+            },
+            Node::Error(it) => Save::Error(it.clone()),
+        })
+    }
+}
+
+/// A [`Save`] node, with children replaced by their ids.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Node<E> {
+    Bool(bool),
+    I8(i8),
+    I16(i16),
+    I32(i32),
+    I64(i64),
+    I128(i128),
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    U128(u128),
+    /// [`f32::to_bits`].
+    F32(u32),
+    /// [`f64::to_bits`].
+    F64(u64),
+    Char(char),
+    String(String),
+    ByteArray(Vec<u8>),
+    Option(Option<SaveId>),
+    Unit,
+    UnitStruct(&'static str),
+    UnitVariant(Variant<'static>),
+    NewTypeStruct {
+        name: &'static str,
+        value: SaveId,
+    },
+    NewTypeVariant {
+        variant: Variant<'static>,
+        value: SaveId,
+    },
+    Seq(Vec<SaveId>),
+    Map(Vec<(SaveId, SaveId)>),
+    Tuple(Vec<SaveId>),
+    TupleStruct {
+        name: &'static str,
+        values: Vec<SaveId>,
+    },
+    TupleVariant {
+        variant: Variant<'static>,
+        values: Vec<SaveId>,
+    },
+    Struct {
+        name: &'static str,
+        fields: Vec<(&'static str, Option<SaveId>)>,
+    },
+    StructVariant {
+        variant: Variant<'static>,
+        fields: Vec<(&'static str, Option<SaveId>)>,
+    },
+    Error(E),
+}
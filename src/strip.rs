@@ -0,0 +1,156 @@
+use crate::{Path, Save, Segment};
+use std::borrow::Cow;
+
+impl<'a, E> Save<'a, E> {
+    /// Remove all [`Save::Error`]s from this tree, returning the remaining tree
+    /// and the removed errors, along with their [`Path`]s in the original tree.
+    ///
+    /// - Erroneous struct fields are marked as [skip](serde::ser::SerializeStruct::skip_field)ped.
+    /// - Erroneous elements and map entries are removed.
+    /// - Errors anywhere else are replaced with [`Save::Option(None)`](Save::Option).
+    ///
+    /// ```
+    /// # use serde_save::{Path, Save};
+    /// let save = Save::strukt(
+    ///     "Report",
+    ///     [
+    ///         ("ok", Save::Seq(vec![Save::U8(1), Save::error("bad element")])),
+    ///         ("bad", Save::error("bad field")),
+    ///     ],
+    /// );
+    /// let (stripped, errors) = save.strip_errors();
+    /// assert_eq!(
+    ///     stripped,
+    ///     Save::Struct {
+    ///         name: "Report",
+    ///         fields: vec![
+    ///             ("ok", Some(Save::Seq(vec![Save::U8(1)]))),
+    ///             ("bad", None),
+    ///         ]
+    ///     }
+    /// );
+    /// assert_eq!(
+    ///     errors
+    ///         .iter()
+    ///         .map(|(path, e)| format!("{}: {}", path, e))
+    ///         .collect::<Vec<_>>(),
+    ///     ["ok[1]: bad element", "bad: bad field"]
+    /// );
+    /// ```
+    pub fn strip_errors(self) -> (Save<'a>, Vec<(Path<'a>, E)>) {
+        let mut errors = Vec::new();
+        let save = strip(&mut Path::new(), self, &mut errors).unwrap_or(Save::Option(None));
+        (save, errors)
+    }
+}
+
+/// Returns [`None`] if `save` is an error.
+fn strip<'a, E>(
+    path: &mut Path<'a>,
+    save: Save<'a, E>,
+    errors: &mut Vec<(Path<'a>, E)>,
+) -> Option<Save<'a>> {
+    let replaced = |path: &mut Path<'a>, it: Box<Save<'a, E>>, errors: &mut _| {
+        Box::new(strip(path, *it, errors).unwrap_or(Save::Option(None)))
+    };
+    Some(match save {
+        Save::Bool(it) => Save::Bool(it),
+        Save::I8(it) => Save::I8(it),
+        Save::I16(it) => Save::I16(it),
+        Save::I32(it) => Save::I32(it),
+        Save::I64(it) => Save::I64(it),
+        Save::I128(it) => Save::I128(it),
+        Save::U8(it) => Save::U8(it),
+        Save::U16(it) => Save::U16(it),
+        Save::U32(it) => Save::U32(it),
+        Save::U64(it) => Save::U64(it),
+        Save::U128(it) => Save::U128(it),
+        Save::F32(it) => Save::F32(it),
+        Save::F64(it) => Save::F64(it),
+        Save::Char(it) => Save::Char(it),
+        Save::String(it) => Save::String(it),
+        Save::ByteArray(it) => Save::ByteArray(it),
+        Save::Option(it) => Save::Option(it.and_then(|it| strip(path, *it, errors).map(Box::new))),
+        Save::Unit => Save::Unit,
+        Save::UnitStruct(it) => Save::UnitStruct(it),
+        Save::UnitVariant(it) => Save::UnitVariant(it),
+        Save::NewTypeStruct { name, value } => Save::NewTypeStruct {
+            name,
+            value: replaced(path, value, errors),
+        },
+        Save::NewTypeVariant { variant, value } => Save::NewTypeVariant {
+            variant,
+            value: replaced(path, value, errors),
+        },
+        Save::Seq(it) => Save::Seq(elements(path, it, errors)),
+        Save::Map(it) => Save::Map(
+            it.into_iter()
+                .enumerate()
+                .filter_map(|(ix, (k, v))| {
+                    path.push(Segment::Key(ix));
+                    let k = strip(path, k, errors);
+                    path.pop();
+                    path.push(Segment::Index(ix));
+                    let v = strip(path, v, errors);
+                    path.pop();
+                    Some((k?, v?))
+                })
+                .collect(),
+        ),
+        Save::Tuple(it) => Save::Tuple(elements(path, it, errors)),
+        Save::TupleStruct { name, values } => Save::TupleStruct {
+            name,
+            values: elements(path, values, errors),
+        },
+        Save::TupleVariant { variant, values } => Save::TupleVariant {
+            variant,
+            values: elements(path, values, errors),
+        },
+        Save::Struct { name, fields } => Save::Struct {
+            name,
+            fields: self::fields(path, fields, errors),
+        },
+        Save::StructVariant { variant, fields } => Save::StructVariant {
+            variant,
+            fields: self::fields(path, fields, errors),
+        },
+        Save::Error(e) => {
+            errors.push((path.clone(), e));
+            return None;
+        }
+    })
+}
+
+fn elements<'a, E>(
+    path: &mut Path<'a>,
+    elements: Vec<Save<'a, E>>,
+    errors: &mut Vec<(Path<'a>, E)>,
+) -> Vec<Save<'a>> {
+    elements
+        .into_iter()
+        .enumerate()
+        .filter_map(|(ix, it)| {
+            path.push(Segment::Index(ix));
+            let it = strip(path, it, errors);
+            path.pop();
+            it
+        })
+        .collect()
+}
+
+#[allow(clippy::type_complexity)]
+fn fields<'a, E>(
+    path: &mut Path<'a>,
+    fields: Vec<(&'a str, Option<Save<'a, E>>)>,
+    errors: &mut Vec<(Path<'a>, E)>,
+) -> Vec<(&'a str, Option<Save<'a>>)> {
+    fields
+        .into_iter()
+        .map(|(name, it)| {
+            path.push(Segment::Field(Cow::Borrowed(name)));
+            let it = it.and_then(|it| strip(path, it, errors));
+            path.pop();
+            (name, it)
+        })
+        .collect()
+}
@@ -0,0 +1,172 @@
+use crate::save;
+use core::fmt;
+use serde::Serialize;
+
+/// Assert that `value` survives a round trip through a data format.
+///
+/// `round_trip` should serialize its argument with the format under test, and
+/// deserialize the result.
+/// Both `value` and the returned value are [`save`]d, and compared.
+///
+/// ```
+/// # use serde::{Deserialize, Serialize};
+/// # use serde_save::assert_round_trip;
+/// #[derive(Serialize, Deserialize, Clone)]
+/// struct Point {
+///     x: i32,
+///     y: i32,
+/// }
+///
+/// assert_round_trip(&Point { x: 1, y: 2 }, |it| Ok::<_, std::convert::Infallible>(it.clone()));
+/// ```
+///
+/// # Panics
+/// - If `value` or the returned value fail to [`save`].
+/// - If `round_trip` fails.
+/// - If the trees differ, with a [`Diff`](crate::Diff) in the message.
+///
+/// ```should_panic
+/// # use serde_save::assert_round_trip;
+/// // the format under test loses precision
+/// assert_round_trip(&1.5f64, |it| Ok::<_, std::convert::Infallible>(it.round()));
+/// ```
+#[track_caller]
+pub fn assert_round_trip<T, F, E>(value: &T, round_trip: F)
+where
+    T: Serialize,
+    F: FnOnce(&T) -> Result<T, E>,
+    E: fmt::Display,
+{
+    let before = match save(value) {
+        Ok(it) => it,
+        Err(e) => panic!("couldn't save the original value: {}", e),
+    };
+    let returned = match round_trip(value) {
+        Ok(it) => it,
+        Err(e) => panic!("round trip failed: {}", e),
+    };
+    let after = match save(&returned) {
+        Ok(it) => it,
+        Err(e) => panic!("couldn't save the round-tripped value: {}", e),
+    };
+    let diff = before.diff(&after);
+    if !diff.is_empty() {
+        panic!("value changed after a round trip:\n{}", diff)
+    }
+}
+
+/// Assert that serializing a value produces an expected [`Save`](crate::Save),
+/// printing a [`Diff`](crate::Diff) on failure.
+///
+/// [Protocol errors](crate::Serializer::check_for_protocol_errors) are checked
+/// by default.
+/// Trailing `key = value` arguments call the corresponding builder method on
+/// [`Config`](crate::Config) to configure the capture.
+///
+/// ```
+/// # use serde::Serialize;
+/// # use serde_save::{assert_ser_eq, Save};
+/// # use std::net::{IpAddr, Ipv4Addr};
+/// #[derive(Serialize)]
+/// struct Point {
+///     x: i32,
+///     y: i32,
+/// }
+/// assert_ser_eq!(Point { x: 1, y: 2 }, Save::strukt("Point", [("x", 1), ("y", 2)]));
+///
+/// let localhost = IpAddr::V4(Ipv4Addr::LOCALHOST);
+/// assert_ser_eq!(localhost, Save::string("127.0.0.1"));
+/// assert_ser_eq!(
+///     localhost,
+///     Save::NewTypeVariant {
+///         variant: serde_save::Variant { name: "IpAddr", variant_index: 0, variant: "V4" },
+///         value: Box::new(Save::Tuple(vec![127u8.into(), 0u8.into(), 0u8.into(), 1u8.into()])),
+///     },
+///     human_readable = false,
+///     check_for_protocol_errors = false,
+/// );
+/// ```
+#[macro_export]
+macro_rules! assert_ser_eq {
+    ($value:expr, $expected:expr $(, $option:ident = $arg:expr)* $(,)?) => {
+        $crate::__private::assert_ser_eq(
+            &$value,
+            $expected,
+            $crate::Config::new()
+                .check_for_protocol_errors(true)
+                $(.$option($arg))*,
+        )
+    };
+}
+
+#[doc(hidden)]
+#[track_caller]
+pub fn assert_ser_eq<T: Serialize + ?Sized>(
+    value: &T,
+    expected: impl Into<crate::Save<'static>>,
+    config: crate::Config,
+) {
+    let actual = match value.serialize(crate::Serializer::from_config(config)) {
+        Ok(it) => it,
+        Err(e) => panic!("serialization failed: {}", e),
+    };
+    let diff = expected.into().diff(&actual);
+    if !diff.is_empty() {
+        panic!("serialized value differs from expected:\n{}", diff)
+    }
+}
+
+/// Assert that forwarding a tree to a data format produces the expected output,
+/// for golden tests of a format's wire encoding.
+///
+/// The tree may be a [`Save`](crate::Save), or a [`Forward`](crate::Forward)
+/// to configure how it is forwarded.
+/// The format is a function which serializes its argument,
+/// like `serde_json::to_string` or `serde_json::to_vec`,
+/// and its output is compared to the expected value.
+///
+/// ```
+/// # use serde_save::{assert_forwards_to, save, BytesRepr, Save};
+/// let tree = Save::<std::convert::Infallible>::bytes([1, 2]);
+/// assert_forwards_to!(tree, save => Save::bytes([1, 2]));
+/// assert_forwards_to!(
+///     tree.forward().bytes(BytesRepr::Base64),
+///     save => Save::string("AQI="),
+/// );
+/// ```
+///
+/// # Panics
+/// - If the format fails.
+/// - If the output differs from the expected value.
+///
+/// ```should_panic
+/// # use serde_save::{assert_forwards_to, save, Save};
+/// let tree = Save::<std::convert::Infallible>::U8(1);
+/// assert_forwards_to!(tree, save => Save::U8(2));
+/// ```
+#[macro_export]
+macro_rules! assert_forwards_to {
+    ($save:expr, $format:expr => $expected:expr $(,)?) => {
+        $crate::__private::assert_forwards_to(&$save, $format, $expected)
+    };
+}
+
+#[doc(hidden)]
+#[track_caller]
+pub fn assert_forwards_to<'s, T, F, O, E, X>(save: &'s T, format: F, expected: X)
+where
+    T: Serialize + ?Sized,
+    F: FnOnce(&'s T) -> Result<O, E>,
+    E: fmt::Display,
+    O: PartialEq<X> + fmt::Debug,
+    X: fmt::Debug,
+{
+    match format(save) {
+        Ok(actual) if actual == expected => {}
+        Ok(actual) => panic!(
+            "forwarded output differs from expected:\n  expected: {:?}\n    actual: {:?}",
+            expected, actual
+        ),
+        Err(e) => panic!("forwarding failed: {}", e),
+    }
+}
@@ -0,0 +1,315 @@
+use crate::{
+    lexer::{LexError, Lexer},
+    Error, Save, Variant,
+};
+use core::fmt;
+use serde::ser::Error as _;
+
+/// An error returned from [`Save::parse`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseSaveError {
+    offset: usize,
+    msg: &'static str,
+}
+
+impl fmt::Display for ParseSaveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid tree at offset {}: {}", self.offset, self.msg)
+    }
+}
+
+impl std::error::Error for ParseSaveError {}
+
+impl From<LexError> for ParseSaveError {
+    fn from(LexError { offset, msg }: LexError) -> Self {
+        Self { offset, msg }
+    }
+}
+
+impl<'s> Save<'s, Error> {
+    /// Parse a tree from the syntax of [`Save::pretty`],
+    /// so expected trees can live in fixture files.
+    ///
+    /// ```
+    /// # use serde_save::{save_errors, Save};
+    /// #[derive(serde::Serialize)]
+    /// struct MyStruct {
+    ///     a: u8,
+    ///     b: (&'static str, Option<char>),
+    /// }
+    ///
+    /// let parsed = Save::parse(r#"MyStruct { a: u8(1), b: ("x", Some(char('y'))) }"#).unwrap();
+    /// assert_eq!(parsed, save_errors(MyStruct { a: 1, b: ("x", Some('y')) }));
+    ///
+    /// assert_eq!(
+    ///     Save::parse(r#"[true, err("boom")]"#).unwrap(),
+    ///     Save::Seq(vec![Save::Bool(true), Save::error("boom")]),
+    /// );
+    /// assert_eq!(
+    ///     Save::parse("[u8(256)]").unwrap_err().to_string(),
+    ///     "invalid tree at offset 4: expected a number",
+    /// );
+    /// ```
+    ///
+    /// Whitespace is insignificant, and lines starting with `#` are ignored.
+    ///
+    /// Since [`Variant::variant_index`] is only rendered by
+    /// [`Pretty::variant_indices`](crate::Pretty::variant_indices),
+    /// it defaults to `0` if there is no `@index` after the variant's name.
+    /// An empty tuple is rendered as `()`, so is parsed as a [`Save::Unit`].
+    /// Elided values, and multiline hexdumps of byte arrays, cannot be parsed.
+    pub fn parse(s: &'s str) -> Result<Self, ParseSaveError> {
+        let mut parser = Lexer::new(s);
+        let save = parser.tree()?;
+        parser.finish()?;
+        Ok(save)
+    }
+}
+
+type Tree<'s> = Save<'s, Error>;
+
+impl<'s> Lexer<'s> {
+    fn tree(&mut self) -> Result<Tree<'s>, ParseSaveError> {
+        self.skip_whitespace();
+        let start = self.offset;
+        let rest = self.rest();
+        if self.eat("\"") {
+            return Ok(Save::String(self.quoted('"')?));
+        }
+        if rest.starts_with('[') {
+            return Ok(Save::Seq(self.list(("[", "]"), Self::tree)?));
+        }
+        if rest.starts_with('(') {
+            return Ok(match self.list(("(", ")"), Self::tree)? {
+                it if it.is_empty() => Save::Unit,
+                it => Save::Tuple(it),
+            });
+        }
+        if rest.starts_with('{') {
+            return Ok(Save::Map(self.list(("{", "}"), |this| {
+                let k = this.tree()?;
+                this.expect(":", "expected `:`")?;
+                Ok::<_, ParseSaveError>((k, this.tree()?))
+            })?));
+        }
+        let word = self.word();
+        let call = self.rest().trim_start().starts_with('(');
+        Ok(match word {
+            "true" => Save::Bool(true),
+            "false" => Save::Bool(false),
+            "None" => Save::Option(None),
+            "Some" if call => Save::Option(Some(Box::new(self.wrapped_tree()?))),
+            "i8" if call => Save::I8(self.literal()?),
+            "i16" if call => Save::I16(self.literal()?),
+            "i32" if call => Save::I32(self.literal()?),
+            "i64" if call => Save::I64(self.literal()?),
+            "i128" if call => Save::I128(self.literal()?),
+            "u8" if call => Save::U8(self.literal()?),
+            "u16" if call => Save::U16(self.literal()?),
+            "u32" if call => Save::U32(self.literal()?),
+            "u64" if call => Save::U64(self.literal()?),
+            "u128" if call => Save::U128(self.literal()?),
+            "f32" if call => Save::F32(self.literal()?),
+            "f64" if call => Save::F64(self.literal()?),
+            "char" if call => {
+                self.expect("(", "expected `(`")?;
+                self.expect("'", "expected a char")?;
+                let it = self.quoted('\'')?;
+                let mut chars = it.chars();
+                let (Some(c), None) = (chars.next(), chars.next()) else {
+                    return Err(self.fail("expected a single char").into());
+                };
+                self.expect(")", "expected `)`")?;
+                Save::Char(c)
+            }
+            "err" if call => {
+                self.expect("(", "expected `(`")?;
+                let msg = self.string()?;
+                self.expect(")", "expected `)`")?;
+                Save::Error(Error::custom(msg))
+            }
+            "b" if self.rest().starts_with('"') => {
+                self.offset += 1;
+                Save::ByteArray(self.byte_string()?)
+            }
+            "hex" if call => Save::ByteArray(self.encoded_bytes(hex)?),
+            "base64" if call => Save::ByteArray(self.encoded_bytes(base64)?),
+            "" => {
+                self.offset = start;
+                return Err(self.fail("expected a value").into());
+            }
+            name => {
+                let variant = match self.eat("::") {
+                    true => Some(self.tree_variant(name)?),
+                    false => None,
+                };
+                let rest = self.rest().trim_start();
+                match (variant, rest.chars().next()) {
+                    (None, Some('(')) => match self.delimited(("(", ")"), Self::tree)? {
+                        (mut it, false) if it.len() == 1 => Save::NewTypeStruct {
+                            name,
+                            value: Box::new(it.remove(0)),
+                        },
+                        (values, _) => Save::TupleStruct { name, values },
+                    },
+                    (Some(variant), Some('(')) => match self.delimited(("(", ")"), Self::tree)? {
+                        (mut it, false) if it.len() == 1 => Save::NewTypeVariant {
+                            variant,
+                            value: Box::new(it.remove(0)),
+                        },
+                        (values, _) => Save::TupleVariant { variant, values },
+                    },
+                    (None, Some('{')) => Save::Struct {
+                        name,
+                        fields: self.tree_fields()?,
+                    },
+                    (Some(variant), Some('{')) => Save::StructVariant {
+                        variant,
+                        fields: self.tree_fields()?,
+                    },
+                    (None, _) => Save::UnitStruct(name),
+                    (Some(variant), _) => Save::UnitVariant(variant),
+                }
+            }
+        })
+    }
+    fn wrapped_tree(&mut self) -> Result<Tree<'s>, ParseSaveError> {
+        self.expect("(", "expected `(`")?;
+        let it = self.tree()?;
+        self.expect(")", "expected `)`")?;
+        Ok(it)
+    }
+    /// Parse a parenthesized number, like `(1)` or `(-inf)`.
+    fn literal<T: core::str::FromStr>(&mut self) -> Result<T, ParseSaveError> {
+        self.expect("(", "expected `(`")?;
+        self.skip_whitespace();
+        let start = self.offset;
+        let rest = self.rest();
+        let end = rest.find(')').unwrap_or(rest.len());
+        let it = rest[..end].trim_end().parse().map_err(|_| ParseSaveError {
+            offset: start,
+            msg: "expected a number",
+        })?;
+        self.offset += end;
+        self.expect(")", "expected `)`")?;
+        Ok(it)
+    }
+    fn tree_variant(&mut self, name: &'s str) -> Result<Variant<'s>, ParseSaveError> {
+        let variant = self.word();
+        if variant.is_empty() {
+            return Err(self.fail("expected a variant").into());
+        }
+        let variant_index = match self.eat("@") {
+            true => self.number()?,
+            false => 0,
+        };
+        Ok(Variant {
+            name,
+            variant_index,
+            variant,
+        })
+    }
+    #[allow(clippy::type_complexity)]
+    fn tree_fields(&mut self) -> Result<Vec<(&'s str, Option<Tree<'s>>)>, ParseSaveError> {
+        self.list(("{", "}"), |this| {
+            let name = this.word();
+            if name.is_empty() {
+                return Err(this.fail("expected a field name").into());
+            }
+            this.expect(":", "expected `:`")?;
+            match this.eat("<skipped>") {
+                true => Ok((name, None)),
+                false => Ok((name, Some(this.tree()?))),
+            }
+        })
+    }
+    /// Parse the rest of a byte string, as written by [`<[u8]>::escape_ascii`].
+    fn byte_string(&mut self) -> Result<Vec<u8>, ParseSaveError> {
+        let start = self.offset;
+        let mut out = Vec::new();
+        let mut bytes = self.rest().bytes().enumerate();
+        let fail = |offset, msg| ParseSaveError {
+            offset: start + offset,
+            msg,
+        };
+        loop {
+            match bytes.next() {
+                None => return Err(fail(self.rest().len(), "unterminated byte string")),
+                Some((ix, b'"')) => {
+                    self.offset = start + ix + 1;
+                    return Ok(out);
+                }
+                Some((ix, b'\\')) => match bytes.next() {
+                    Some((_, b'n')) => out.push(b'\n'),
+                    Some((_, b'r')) => out.push(b'\r'),
+                    Some((_, b't')) => out.push(b'\t'),
+                    Some((_, b'0')) => out.push(b'\0'),
+                    Some((_, b @ (b'\\' | b'"' | b'\''))) => out.push(b),
+                    Some((_, b'x')) => {
+                        let byte = self
+                            .rest()
+                            .get(ix + 2..ix + 4)
+                            .and_then(|it| u8::from_str_radix(it, 16).ok())
+                            .ok_or_else(|| fail(ix, "invalid hex escape"))?;
+                        out.push(byte);
+                        bytes.next();
+                        bytes.next();
+                    }
+                    _ => return Err(fail(ix, "invalid escape")),
+                },
+                Some((_, b)) => out.push(b),
+            }
+        }
+    }
+    /// Parse parenthesized bytes, in an encoding understood by `decode`.
+    fn encoded_bytes(
+        &mut self,
+        decode: fn(&str) -> Option<Vec<u8>>,
+    ) -> Result<Vec<u8>, ParseSaveError> {
+        self.expect("(", "expected `(`")?;
+        let start = self.offset;
+        let rest = self.rest();
+        let end = rest.find(')').unwrap_or(rest.len());
+        let it = decode(&rest[..end]).ok_or(ParseSaveError {
+            offset: start,
+            msg: "invalid encoded bytes",
+        })?;
+        self.offset += end;
+        self.expect(")", "expected `)`")?;
+        Ok(it)
+    }
+}
+
+/// Decode space-separated hexadecimal bytes, like `68 69`.
+fn hex(s: &str) -> Option<Vec<u8>> {
+    s.split_whitespace()
+        .map(|it| match it.len() {
+            2 => u8::from_str_radix(it, 16).ok(),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Decode standard, padded base64.
+fn base64(s: &str) -> Option<Vec<u8>> {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let s = s.trim().as_bytes();
+    if !s.len().is_multiple_of(4) {
+        return None;
+    }
+    let mut out = Vec::new();
+    for chunk in s.chunks(4) {
+        let padding = chunk.iter().rev().take_while(|it| **it == b'=').count();
+        if padding > 2 {
+            return None;
+        }
+        let mut acc = 0u32;
+        for it in &chunk[..4 - padding] {
+            let digit = ALPHABET.iter().position(|c| c == it)?;
+            acc = acc << 6 | digit as u32;
+        }
+        acc <<= 6 * padding;
+        out.extend(&acc.to_be_bytes()[1..4 - padding]);
+    }
+    Some(out)
+}
@@ -0,0 +1,113 @@
+use crate::{
+    imp::{ErrorDiscipline, Persist, ShortCircuit},
+    Diff, DiffOptions, Error, Save, Serializer,
+};
+use core::fmt;
+
+/// Capture a value repeatedly, returning only what changed since the last capture.
+///
+/// This is intended for logging state changes in long-running services,
+/// without emitting the full tree on every tick.
+///
+/// ```
+/// # use serde_save::SaveTracker;
+/// #[derive(serde::Serialize)]
+/// struct State {
+///     connections: u32,
+///     leader: bool,
+/// }
+///
+/// let mut tracker = SaveTracker::with_defaults();
+///
+/// // The first capture is reported as added at the root.
+/// let diff = tracker.track(State { connections: 0, leader: false }).unwrap();
+/// assert_eq!(diff.len(), 1);
+///
+/// assert!(tracker.track(State { connections: 0, leader: false }).unwrap().is_empty());
+///
+/// let diff = tracker.track(State { connections: 3, leader: false }).unwrap();
+/// assert_eq!(diff.to_string(), "~ connections: U32(0) -> U32(3)\n");
+/// ```
+pub struct SaveTracker<D: ErrorDiscipline = ShortCircuit> {
+    serializer: Serializer<D>,
+    options: DiffOptions,
+    previous: Option<Save<'static, D::SaveError>>,
+}
+
+impl<D: ErrorDiscipline> SaveTracker<D> {
+    /// Create a tracker which captures values with `serializer`.
+    pub fn new(serializer: Serializer<D>) -> Self {
+        Self {
+            serializer,
+            options: DiffOptions::new(),
+            previous: None,
+        }
+    }
+    /// Compare successive captures with `options`, see [`Save::diff_with`].
+    pub fn diff_options(mut self, options: DiffOptions) -> Self {
+        self.options = options;
+        self
+    }
+    /// Capture `t`, returning the changes since the previous capture.
+    ///
+    /// The first capture is returned as a single [`Change::Added`](crate::Change::Added)
+    /// at the root.
+    /// Failed captures are not stored, so the next capture is compared against
+    /// the last successful one.
+    pub fn track<T: serde::Serialize>(&mut self, t: T) -> Result<Diff<'static, D::SaveError>, Error>
+    where
+        D::SaveError: Clone + PartialEq,
+    {
+        let save = self.serializer.save(t)?;
+        let diff = match &self.previous {
+            Some(previous) => previous.diff_with(&save, self.options),
+            None => Diff::added(save.clone()),
+        };
+        self.previous = Some(save);
+        Ok(diff)
+    }
+    /// The most recent successful capture, if any.
+    pub fn previous(&self) -> Option<&Save<'static, D::SaveError>> {
+        self.previous.as_ref()
+    }
+    /// Forget the previous capture,
+    /// so the next capture is reported as added at the root.
+    pub fn reset(&mut self) -> Option<Save<'static, D::SaveError>> {
+        self.previous.take()
+    }
+    /// The serializer shared by every capture.
+    pub fn serializer(&self) -> &Serializer<D> {
+        &self.serializer
+    }
+}
+
+impl SaveTracker<ShortCircuit> {
+    /// A tracker which captures with [`Serializer::new`].
+    pub fn with_defaults() -> Self {
+        Self::new(Serializer::new())
+    }
+}
+
+impl SaveTracker<Persist> {
+    /// A tracker which persists errors in-tree, like [`save_errors`](crate::save_errors).
+    pub fn with_errors() -> Self {
+        Self::new(
+            Serializer::new()
+                .check_for_protocol_errors(true)
+                .save_errors(),
+        )
+    }
+}
+
+impl<D: ErrorDiscipline> fmt::Debug for SaveTracker<D>
+where
+    D::SaveError: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SaveTracker")
+            .field("config", &self.serializer.config())
+            .field("options", &self.options)
+            .field("previous", &self.previous)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,360 @@
+use crate::{Error, ProtocolDetail};
+use serde::Serialize;
+use std::collections::BTreeSet;
+
+/// Check `t` for [protocol errors](crate::Serializer::check_for_protocol_errors),
+/// without building a tree.
+///
+/// Values are discarded as they are serialized, so this is a cheap way to lint
+/// [`Serialize`] implementations in tests and benchmarks.
+///
+/// Unlike the [`Serializer`](crate::Serializer), serialization continues past
+/// errors, and every error is returned, including errors returned by the
+/// implementations themselves.
+///
+/// ```
+/// # use serde::{ser::SerializeSeq as _, Serialize, Serializer};
+/// struct Liar;
+///
+/// impl Serialize for Liar {
+///     fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+///         let mut seq = serializer.serialize_seq(Some(2))?;
+///         seq.serialize_element(&1)?;
+///         seq.end()
+///     }
+/// }
+///
+/// assert_eq!(serde_save::validate(&vec![1, 2]), Ok(()));
+/// let errors = serde_save::validate(&(Liar, Liar)).unwrap_err();
+/// assert_eq!(errors.len(), 2);
+/// assert_eq!(
+///     errors[0].to_string(),
+///     "protocol error: expected a sequence of length 2, got 1"
+/// );
+/// ```
+pub fn validate<T: Serialize + ?Sized>(t: &T) -> Result<(), Vec<Error>> {
+    let mut errors = Vec::new();
+    if let Err(e) = t.serialize(Validator {
+        errors: &mut errors,
+    }) {
+        errors.push(e)
+    }
+    match errors.is_empty() {
+        true => Ok(()),
+        false => Err(errors),
+    }
+}
+
+struct Validator<'v> {
+    errors: &'v mut Vec<Error>,
+}
+
+impl Validator<'_> {
+    /// Serialize a child, recording any error.
+    fn child<T: Serialize + ?Sized>(errors: &mut Vec<Error>, value: &T) {
+        if let Err(e) = value.serialize(Validator {
+            errors: &mut *errors,
+        }) {
+            errors.push(e)
+        }
+    }
+}
+
+fn length(errors: &mut Vec<Error>, what: &'static str, expected: usize, actual: usize) {
+    if expected != actual {
+        errors.push(Error::new_protocol(ProtocolDetail::Length {
+            what,
+            expected,
+            actual,
+        }))
+    }
+}
+
+macro_rules! leaves {
+    ($($method:ident($ty:ty));* $(;)?) => {
+        $(
+            fn $method(self, _: $ty) -> Result<Self::Ok, Self::Error> {
+                Ok(())
+            }
+        )*
+    };
+}
+
+impl<'v> serde::Serializer for Validator<'v> {
+    type Ok = ();
+    type Error = Error;
+    type SerializeSeq = Elements<'v>;
+    type SerializeTuple = Elements<'v>;
+    type SerializeTupleStruct = Elements<'v>;
+    type SerializeTupleVariant = Elements<'v>;
+    type SerializeMap = Entries<'v>;
+    type SerializeStruct = Fields<'v>;
+    type SerializeStructVariant = Fields<'v>;
+
+    leaves! {
+        serialize_bool(bool);
+        serialize_i8(i8);
+        serialize_i16(i16);
+        serialize_i32(i32);
+        serialize_i64(i64);
+        serialize_i128(i128);
+        serialize_u8(u8);
+        serialize_u16(u16);
+        serialize_u32(u32);
+        serialize_u64(u64);
+        serialize_u128(u128);
+        serialize_f32(f32);
+        serialize_f64(f64);
+        serialize_char(char);
+        serialize_str(&str);
+        serialize_bytes(&[u8]);
+        serialize_unit_struct(&'static str);
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+        Ok(())
+    }
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<Self::Ok, Self::Error> {
+        value.serialize(self)
+    }
+    fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+        Ok(())
+    }
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+    ) -> Result<Self::Ok, Self::Error> {
+        Ok(())
+    }
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error> {
+        value.serialize(self)
+    }
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error> {
+        value.serialize(self)
+    }
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        Ok(Elements::new(self, "sequence", len))
+    }
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        Ok(Elements::new(self, "tuple", Some(len)))
+    }
+    fn serialize_tuple_struct(
+        self,
+        _: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        Ok(Elements::new(self, "tuple struct", Some(len)))
+    }
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        Ok(Elements::new(self, "tuple variant", Some(len)))
+    }
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        Ok(Entries {
+            errors: self.errors,
+            expected_len: len,
+            keys: 0,
+            values: 0,
+        })
+    }
+    fn serialize_struct(
+        self,
+        _: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, Self::Error> {
+        Ok(Fields::new(self, len))
+    }
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        Ok(Fields::new(self, len))
+    }
+    fn collect_str<T: ?Sized + core::fmt::Display>(self, _: &T) -> Result<Self::Ok, Self::Error> {
+        Ok(())
+    }
+}
+
+struct Elements<'v> {
+    errors: &'v mut Vec<Error>,
+    what: &'static str,
+    expected_len: Option<usize>,
+    actual: usize,
+}
+
+impl<'v> Elements<'v> {
+    fn new(validator: Validator<'v>, what: &'static str, expected_len: Option<usize>) -> Self {
+        Self {
+            errors: validator.errors,
+            what,
+            expected_len,
+            actual: 0,
+        }
+    }
+    fn element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        Validator::child(self.errors, value);
+        self.actual += 1;
+        Ok(())
+    }
+    fn end(self) -> Result<(), Error> {
+        if let Some(expected) = self.expected_len {
+            length(self.errors, self.what, expected, self.actual)
+        }
+        Ok(())
+    }
+}
+
+macro_rules! elements {
+    ($($trait:ident::$method:ident),* $(,)?) => {
+        $(
+            impl serde::ser::$trait for Elements<'_> {
+                type Ok = ();
+                type Error = Error;
+                fn $method<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+                    self.element(value)
+                }
+                fn end(self) -> Result<(), Error> {
+                    Elements::end(self)
+                }
+            }
+        )*
+    };
+}
+
+elements! {
+    SerializeSeq::serialize_element,
+    SerializeTuple::serialize_element,
+    SerializeTupleStruct::serialize_field,
+    SerializeTupleVariant::serialize_field,
+}
+
+struct Entries<'v> {
+    errors: &'v mut Vec<Error>,
+    expected_len: Option<usize>,
+    keys: usize,
+    values: usize,
+}
+
+impl serde::ser::SerializeMap for Entries<'_> {
+    type Ok = ();
+    type Error = Error;
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), Error> {
+        Validator::child(self.errors, key);
+        self.keys += 1;
+        Ok(())
+    }
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        Validator::child(self.errors, value);
+        self.values += 1;
+        Ok(())
+    }
+    fn end(self) -> Result<(), Error> {
+        let Self {
+            errors,
+            expected_len,
+            keys,
+            values,
+        } = self;
+        if keys != values {
+            errors.push(Error::new_protocol(ProtocolDetail::JaggedMap {
+                keys,
+                values,
+            }))
+        }
+        if let Some(expected) = expected_len {
+            length(errors, "map", expected, keys.max(values))
+        }
+        Ok(())
+    }
+}
+
+struct Fields<'v> {
+    errors: &'v mut Vec<Error>,
+    expected_len: usize,
+    names: Vec<&'static str>,
+}
+
+impl<'v> Fields<'v> {
+    fn new(validator: Validator<'v>, expected_len: usize) -> Self {
+        Self {
+            errors: validator.errors,
+            expected_len,
+            names: Vec::with_capacity(expected_len),
+        }
+    }
+    fn field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<(), Error> {
+        Validator::child(self.errors, value);
+        self.names.push(key);
+        Ok(())
+    }
+    fn end(self) -> Result<(), Error> {
+        let Self {
+            errors,
+            expected_len,
+            names,
+        } = self;
+        let mut seen = BTreeSet::new();
+        let mut dups = Vec::new();
+        for name in &names {
+            let new = seen.insert(*name);
+            if !new {
+                dups.push(*name)
+            }
+        }
+        if !dups.is_empty() {
+            errors.push(Error::new_protocol(ProtocolDetail::DuplicateFields {
+                what: "struct",
+                fields: dups,
+            }))
+        }
+        length(errors, "struct", expected_len, names.len());
+        Ok(())
+    }
+}
+
+macro_rules! fields {
+    ($($trait:ident),* $(,)?) => {
+        $(
+            impl serde::ser::$trait for Fields<'_> {
+                type Ok = ();
+                type Error = Error;
+                fn serialize_field<T: ?Sized + Serialize>(
+                    &mut self,
+                    key: &'static str,
+                    value: &T,
+                ) -> Result<(), Error> {
+                    self.field(key, value)
+                }
+                fn skip_field(&mut self, key: &'static str) -> Result<(), Error> {
+                    self.names.push(key);
+                    Ok(())
+                }
+                fn end(self) -> Result<(), Error> {
+                    Fields::end(self)
+                }
+            }
+        )*
+    };
+}
+
+fields!(SerializeStruct, SerializeStructVariant);
@@ -0,0 +1,687 @@
+use crate::{pretty::Base64, Save};
+use core::fmt::{self, Write as _};
+use std::borrow::Cow;
+
+/// An XML rendition of a [`Save`], for interoperating with XML systems.
+///
+/// Display this to get the text, or see [`Xml::events`].
+///
+/// - The root element is named after the struct or enum at the root,
+///   or [`Xml::root`] otherwise.
+/// - Struct fields are child elements, named after the field.
+///   [`None`]s and [skipped](serde::ser::SerializeStruct::skip_field) fields
+///   are omitted.
+/// - Elements of sequences and tuples are `<item>` elements.
+/// - Map entries are `<entry key="..">` elements,
+///   or `<entry><key>..</key><value>..</value></entry>` if the key isn't a scalar.
+/// - Enum variants are child elements named after the variant,
+///   except for unit variants, which are text.
+/// - Byte arrays are base64 text, and [`Save::Error`]s are comments.
+/// - Characters which XML 1.0 forbids, like most ASCII control characters,
+///   are written as U+FFFD REPLACEMENT CHARACTER.
+///
+/// ```
+/// # use serde_save::{save, Save};
+/// #[derive(serde::Serialize)]
+/// struct Order {
+///     id: u32,
+///     status: Status,
+///     lines: Vec<&'static str>,
+///     note: Option<&'static str>,
+/// }
+/// #[derive(serde::Serialize)]
+/// enum Status {
+///     Shipped { carrier: &'static str },
+/// }
+///
+/// let saved = save(Order {
+///     id: 7,
+///     status: Status::Shipped { carrier: "post & co" },
+///     lines: vec!["tea", "cake"],
+///     note: None,
+/// })
+/// .unwrap();
+/// assert_eq!(
+///     saved.xml().to_string(),
+///     concat!(
+///         "<Order><id>7</id>",
+///         "<status><Shipped><carrier>post &amp; co</carrier></Shipped></status>",
+///         "<lines><item>tea</item><item>cake</item></lines>",
+///         "</Order>",
+///     )
+/// );
+/// assert_eq!(
+///     saved.xml().attributes(true).to_string(),
+///     concat!(
+///         r#"<Order id="7">"#,
+///         r#"<status><Shipped carrier="post &amp; co"/></status>"#,
+///         "<lines><item>tea</item><item>cake</item></lines>",
+///         "</Order>",
+///     )
+/// );
+///
+/// let weird = Save::Seq(vec![Save::error("a---b-"), Save::string("bell\u{7}")]);
+/// assert_eq!(
+///     weird.xml().to_string(),
+///     "<value><item><!--error: a- - -b- --></item><item>bell\u{FFFD}</item></value>"
+/// );
+/// ```
+pub struct Xml<'s, 'a, E> {
+    save: &'s Save<'a, E>,
+    attributes: bool,
+    root: &'s str,
+}
+
+/// A single token of an [`Xml`] document, see [`Xml::events`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum XmlEvent<'s> {
+    /// An opening tag.
+    Start {
+        /// The name of the element.
+        name: Cow<'s, str>,
+        /// Attribute names and unescaped values.
+        attributes: Vec<(Cow<'s, str>, String)>,
+    },
+    /// A closing tag.
+    End {
+        /// The name of the element.
+        name: Cow<'s, str>,
+    },
+    /// Unescaped character data.
+    Text(String),
+    /// A comment, used for [`Save::Error`]s.
+    ///
+    /// When [displayed](Xml), runs of `-` are broken up with spaces,
+    /// since comments may not contain `--` or end with `-`.
+    Comment(String),
+}
+
+impl<'a, E> Save<'a, E> {
+    /// Render this tree as XML, see [`Xml`].
+    pub fn xml(&self) -> Xml<'_, 'a, E> {
+        Xml {
+            save: self,
+            attributes: false,
+            root: "value",
+        }
+    }
+}
+
+impl<'s, 'a, E> Xml<'s, 'a, E> {
+    /// Write the scalar fields of structs as attributes, rather than child elements.
+    pub fn attributes(mut self, attributes: bool) -> Self {
+        self.attributes = attributes;
+        self
+    }
+    /// The name of the root element, if the root isn't a struct or enum.
+    ///
+    /// Defaults to `value`.
+    pub fn root(mut self, name: &'s str) -> Self {
+        self.root = name;
+        self
+    }
+}
+
+impl<'s, E: fmt::Display> Xml<'s, '_, E> {
+    /// The document as a list of tokens,
+    /// for feeding to an XML library.
+    ///
+    /// ```
+    /// # use serde_save::{Save, XmlEvent};
+    /// let save = Save::<std::convert::Infallible>::U8(1);
+    /// assert_eq!(
+    ///     save.xml().root("n").events(),
+    ///     [
+    ///         XmlEvent::Start { name: "n".into(), attributes: vec![] },
+    ///         XmlEvent::Text(String::from("1")),
+    ///         XmlEvent::End { name: "n".into() },
+    ///     ]
+    /// );
+    /// ```
+    pub fn events(&self) -> Vec<XmlEvent<'s>> {
+        let name = match self.save {
+            Save::UnitStruct(name)
+            | Save::NewTypeStruct { name, .. }
+            | Save::TupleStruct { name, .. }
+            | Save::Struct { name, .. } => *name,
+            Save::UnitVariant(variant)
+            | Save::NewTypeVariant { variant, .. }
+            | Save::TupleVariant { variant, .. }
+            | Save::StructVariant { variant, .. } => variant.name,
+            _ => self.root,
+        };
+        let mut out = Vec::new();
+        self.element(self::name(name), Vec::new(), self.save, &mut out);
+        out
+    }
+
+    fn element(
+        &self,
+        name: Cow<'s, str>,
+        attributes: Vec<(Cow<'s, str>, String)>,
+        mut node: &'s Save<'_, E>,
+        out: &mut Vec<XmlEvent<'s>>,
+    ) {
+        while let Save::Option(Some(it)) | Save::NewTypeStruct { value: it, .. } = node {
+            node = it
+        }
+        if let Save::Struct { fields, .. } = node {
+            return self.fields(name, attributes, fields, out);
+        }
+        if let Some(text) = scalar(node) {
+            out.push(XmlEvent::Start {
+                name: name.clone(),
+                attributes,
+            });
+            out.push(XmlEvent::Text(text));
+            out.push(XmlEvent::End { name });
+            return;
+        }
+        let child = |it: &'s str| self::name(it);
+        out.push(XmlEvent::Start {
+            name: name.clone(),
+            attributes,
+        });
+        match node {
+            Save::Seq(it) | Save::Tuple(it) | Save::TupleStruct { values: it, .. } => {
+                for it in it {
+                    self.element(Cow::Borrowed("item"), Vec::new(), it, out)
+                }
+            }
+            Save::TupleVariant { variant, values } => {
+                out.push(XmlEvent::Start {
+                    name: child(variant.variant),
+                    attributes: Vec::new(),
+                });
+                for it in values {
+                    self.element(Cow::Borrowed("item"), Vec::new(), it, out)
+                }
+                out.push(XmlEvent::End {
+                    name: child(variant.variant),
+                });
+            }
+            Save::NewTypeVariant { variant, value } => {
+                self.element(child(variant.variant), Vec::new(), value, out)
+            }
+            Save::StructVariant { variant, fields } => {
+                self.fields(child(variant.variant), Vec::new(), fields, out)
+            }
+            Save::Map(it) => {
+                for (k, v) in it {
+                    match scalar(k) {
+                        Some(key) => self.element(
+                            Cow::Borrowed("entry"),
+                            vec![(Cow::Borrowed("key"), key)],
+                            v,
+                            out,
+                        ),
+                        None => {
+                            out.push(XmlEvent::Start {
+                                name: Cow::Borrowed("entry"),
+                                attributes: Vec::new(),
+                            });
+                            self.element(Cow::Borrowed("key"), Vec::new(), k, out);
+                            self.element(Cow::Borrowed("value"), Vec::new(), v, out);
+                            out.push(XmlEvent::End {
+                                name: Cow::Borrowed("entry"),
+                            });
+                        }
+                    }
+                }
+            }
+            Save::Error(e) => out.push(XmlEvent::Comment(format!("error: {}", e))),
+            _ => {}
+        }
+        out.push(XmlEvent::End { name });
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn fields(
+        &self,
+        name: Cow<'s, str>,
+        mut attributes: Vec<(Cow<'s, str>, String)>,
+        fields: &'s [(&str, Option<Save<'_, E>>)],
+        out: &mut Vec<XmlEvent<'s>>,
+    ) {
+        let mut children = Vec::new();
+        for (field, value) in fields {
+            match value {
+                None | Some(Save::Option(None)) => {}
+                Some(value) => match (self.attributes, scalar(value)) {
+                    (true, Some(text)) => attributes.push((self::name(field), text)),
+                    _ => children.push((field, value)),
+                },
+            }
+        }
+        out.push(XmlEvent::Start {
+            name: name.clone(),
+            attributes,
+        });
+        for (field, value) in children {
+            self.element(self::name(field), Vec::new(), value, out)
+        }
+        out.push(XmlEvent::End { name });
+    }
+}
+
+/// The text of a node which can be an attribute.
+fn scalar<E>(save: &Save<'_, E>) -> Option<String> {
+    Some(match save {
+        Save::Bool(it) => it.to_string(),
+        Save::I8(it) => it.to_string(),
+        Save::I16(it) => it.to_string(),
+        Save::I32(it) => it.to_string(),
+        Save::I64(it) => it.to_string(),
+        Save::I128(it) => it.to_string(),
+        Save::U8(it) => it.to_string(),
+        Save::U16(it) => it.to_string(),
+        Save::U32(it) => it.to_string(),
+        Save::U64(it) => it.to_string(),
+        Save::U128(it) => it.to_string(),
+        Save::F32(it) => it.to_string(),
+        Save::F64(it) => it.to_string(),
+        Save::Char(it) => it.to_string(),
+        Save::String(it) => it.clone(),
+        Save::ByteArray(it) => Base64(it).to_string(),
+        Save::UnitVariant(it) => String::from(it.variant),
+        Save::Option(Some(it)) | Save::NewTypeStruct { value: it, .. } => return scalar(it),
+        _ => return None,
+    })
+}
+
+/// Replace characters which aren't allowed in XML names with `_`.
+fn name(s: &str) -> Cow<'_, str> {
+    let valid = |(ix, c): (usize, char)| {
+        c.is_alphabetic()
+            || c == '_'
+            || (ix != 0 && (c.is_alphanumeric() || matches!(c, '-' | '.')))
+    };
+    match !s.is_empty() && s.chars().enumerate().all(valid) {
+        true => Cow::Borrowed(s),
+        false => Cow::Owned(
+            s.chars()
+                .enumerate()
+                .map(|it| if valid(it) { it.1 } else { '_' })
+                .collect::<String>()
+                + if s.is_empty() { "_" } else { "" },
+        ),
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for Xml<'_, '_, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut events = self.events().into_iter().peekable();
+        while let Some(event) = events.next() {
+            match event {
+                XmlEvent::Start { name, attributes } => {
+                    write!(f, "<{}", name)?;
+                    for (k, v) in attributes {
+                        write!(f, r#" {}="{}""#, k, Escape(&v, true))?;
+                    }
+                    match events.peek() {
+                        Some(XmlEvent::End { .. }) => {
+                            events.next();
+                            f.write_str("/>")?
+                        }
+                        _ => f.write_str(">")?,
+                    }
+                }
+                XmlEvent::End { name } => write!(f, "</{}>", name)?,
+                XmlEvent::Text(it) => write!(f, "{}", Escape(&it, false))?,
+                XmlEvent::Comment(it) => {
+                    f.write_str("<!--")?;
+                    let mut dash = false;
+                    for c in it.chars() {
+                        if c == '-' && dash {
+                            f.write_char(' ')?
+                        }
+                        dash = c == '-';
+                        f.write_char(if allowed(c) { c } else { '\u{FFFD}' })?
+                    }
+                    f.write_str(if dash { " -->" } else { "-->" })?
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Whether XML 1.0 allows `c` in a document.
+fn allowed(c: char) -> bool {
+    matches!(c, '\t' | '\n' | '\r' | ' '..='\u{D7FF}' | '\u{E000}'..='\u{FFFD}' | '\u{10000}'..)
+}
+
+/// Escape text for inclusion in XML, in an attribute value if `.1`.
+///
+/// Whitespace which a parser would normalize is written as a reference.
+struct Escape<'s>(&'s str, bool);
+
+impl fmt::Display for Escape<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for c in self.0.chars() {
+            match c {
+                '&' => f.write_str("&amp;")?,
+                '<' => f.write_str("&lt;")?,
+                '>' => f.write_str("&gt;")?,
+                '"' => f.write_str("&quot;")?,
+                '\r' => f.write_str("&#xD;")?,
+                '\t' if self.1 => f.write_str("&#x9;")?,
+                '\n' if self.1 => f.write_str("&#xA;")?,
+                c if !allowed(c) => f.write_char('\u{FFFD}')?,
+                c => f.write_char(c)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An error returned from [`Save::from_xml`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseXmlError {
+    offset: usize,
+    msg: &'static str,
+}
+
+impl fmt::Display for ParseXmlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid XML at offset {}: {}", self.offset, self.msg)
+    }
+}
+
+impl std::error::Error for ParseXmlError {}
+
+impl<'s> Save<'s> {
+    /// Read a tree from an XML document, on a best-effort basis.
+    ///
+    /// Since XML has no types, this is lossy:
+    /// - Elements with only text become [`Save::String`]s,
+    ///   and empty elements become [`Save::Unit`].
+    /// - Elements whose children are all `<item>`s become [`Save::Seq`]s.
+    /// - Elements whose children are all `<entry key="..">`s become [`Save::Map`]s.
+    /// - Any other element becomes a [`Save::Struct`] named after the element,
+    ///   with a field per attribute and child element.
+    ///   Repeated child elements are collected into a [`Save::Seq`],
+    ///   and any text is in a field called `$text`.
+    ///
+    /// Comments and processing instructions are ignored,
+    /// and elements nested more than 128 deep are rejected.
+    ///
+    /// ```
+    /// # use serde_save::Save;
+    /// let xml = r#"<?xml version="1.0"?>
+    /// <Order id="7">
+    ///     <lines><item>tea</item><item>cake</item></lines>
+    ///     <tag>a</tag>
+    ///     <tag>b</tag>
+    ///     <note/>
+    /// </Order>"#;
+    /// assert_eq!(
+    ///     Save::from_xml(xml).unwrap(),
+    ///     Save::strukt("Order", [
+    ///         ("id", Save::string("7")),
+    ///         ("lines", Save::Seq(vec![Save::string("tea"), Save::string("cake")])),
+    ///         ("tag", Save::Seq(vec![Save::string("a"), Save::string("b")])),
+    ///         ("note", Save::Unit),
+    ///     ])
+    /// );
+    ///
+    /// let hostile = "<a>".repeat(1_000_000);
+    /// assert_eq!(
+    ///     Save::from_xml(&hostile).unwrap_err().to_string(),
+    ///     "invalid XML at offset 387: nesting too deep"
+    /// );
+    /// ```
+    pub fn from_xml(xml: &'s str) -> Result<Self, ParseXmlError> {
+        let mut parser = Parser {
+            s: xml,
+            offset: 0,
+            depth: 0,
+        };
+        parser.misc()?;
+        let root = parser.element()?;
+        parser.misc()?;
+        match parser.offset == xml.len() {
+            true => Ok(root.into_save()),
+            false => Err(parser.fail("trailing characters")),
+        }
+    }
+}
+
+struct Element<'s> {
+    name: &'s str,
+    attributes: Vec<(&'s str, String)>,
+    children: Vec<Element<'s>>,
+    text: String,
+}
+
+impl<'s> Element<'s> {
+    fn into_save(self) -> Save<'s> {
+        let Element {
+            name,
+            mut attributes,
+            children,
+            text,
+        } = self;
+        if attributes.is_empty() && children.is_empty() {
+            return match text.is_empty() {
+                true => Save::Unit,
+                false => Save::String(text),
+            };
+        }
+        if attributes.is_empty() && children.iter().all(|it| it.name == "item") {
+            return Save::Seq(children.into_iter().map(Element::into_save).collect());
+        }
+        let entry = |it: &Element| {
+            it.name == "entry" && it.attributes.len() == 1 && it.attributes[0].0 == "key"
+        };
+        if attributes.is_empty() && children.iter().all(entry) {
+            return Save::Map(
+                children
+                    .into_iter()
+                    .map(|mut it| {
+                        let (_, key) = it.attributes.remove(0);
+                        (Save::String(key), it.into_save())
+                    })
+                    .collect(),
+            );
+        }
+        let mut grouped = attributes
+            .drain(..)
+            .map(|(k, v)| (k, vec![Save::String(v)]))
+            .collect::<Vec<_>>();
+        for child in children {
+            match grouped.iter_mut().find(|(it, _)| *it == child.name) {
+                Some((_, it)) => it.push(child.into_save()),
+                None => grouped.push((child.name, vec![child.into_save()])),
+            }
+        }
+        let mut fields = grouped
+            .into_iter()
+            .map(|(name, mut it)| match it.len() {
+                1 => (name, it.pop()),
+                _ => (name, Some(Save::Seq(it))),
+            })
+            .collect::<Vec<_>>();
+        if !text.trim().is_empty() {
+            fields.push(("$text", Some(Save::String(text))))
+        }
+        Save::Struct { name, fields }
+    }
+}
+
+/// The deepest nesting of elements which will be parsed.
+const MAX_DEPTH: usize = 128;
+
+struct Parser<'s> {
+    s: &'s str,
+    offset: usize,
+    /// The number of elements currently being parsed, see [`MAX_DEPTH`].
+    depth: usize,
+}
+
+impl<'s> Parser<'s> {
+    fn fail(&self, msg: &'static str) -> ParseXmlError {
+        ParseXmlError {
+            offset: self.offset,
+            msg,
+        }
+    }
+    fn rest(&self) -> &'s str {
+        &self.s[self.offset..]
+    }
+    fn eat(&mut self, token: &str) -> bool {
+        match self.rest().starts_with(token) {
+            true => {
+                self.offset += token.len();
+                true
+            }
+            false => false,
+        }
+    }
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.offset += rest.len() - rest.trim_start().len();
+    }
+    /// Consume everything up to and including `end`, returning what came before.
+    fn until(&mut self, end: &str, msg: &'static str) -> Result<&'s str, ParseXmlError> {
+        let rest = self.rest();
+        let Some(ix) = rest.find(end) else {
+            return Err(self.fail(msg));
+        };
+        self.offset += ix + end.len();
+        Ok(&rest[..ix])
+    }
+    /// Skip whitespace, comments, processing instructions and doctypes.
+    fn misc(&mut self) -> Result<(), ParseXmlError> {
+        loop {
+            self.skip_whitespace();
+            if self.eat("<?") {
+                self.until("?>", "unterminated processing instruction")?;
+            } else if self.eat("<!--") {
+                self.until("-->", "unterminated comment")?;
+            } else if self.eat("<!DOCTYPE") {
+                self.until(">", "unterminated doctype")?;
+            } else {
+                return Ok(());
+            }
+        }
+    }
+    fn name(&mut self) -> Result<&'s str, ParseXmlError> {
+        let rest = self.rest();
+        let end = rest
+            .find(|it: char| it.is_whitespace() || matches!(it, '/' | '>' | '='))
+            .unwrap_or(rest.len());
+        if end == 0 {
+            return Err(self.fail("expected a name"));
+        }
+        self.offset += end;
+        Ok(&rest[..end])
+    }
+    fn element(&mut self) -> Result<Element<'s>, ParseXmlError> {
+        if !self.eat("<") {
+            return Err(self.fail("expected an element"));
+        }
+        let name = self.name()?;
+        let mut element = Element {
+            name,
+            attributes: Vec::new(),
+            children: Vec::new(),
+            text: String::new(),
+        };
+        loop {
+            self.skip_whitespace();
+            if self.eat("/>") {
+                return Ok(element);
+            }
+            if self.eat(">") {
+                break;
+            }
+            let key = self.name()?;
+            self.skip_whitespace();
+            if !self.eat("=") {
+                return Err(self.fail("expected `=`"));
+            }
+            self.skip_whitespace();
+            let quote = match self.rest().chars().next() {
+                Some(it @ ('"' | '\'')) => it,
+                _ => return Err(self.fail("expected a quoted value")),
+            };
+            self.offset += 1;
+            let start = self.offset;
+            let value = self.until(&quote.to_string(), "unterminated attribute")?;
+            let value = unescape(value).map_err(|msg| ParseXmlError { offset: start, msg })?;
+            element.attributes.push((key, value));
+        }
+        loop {
+            if self.eat("</") {
+                if self.name()? != name {
+                    return Err(self.fail("mismatched closing tag"));
+                }
+                self.skip_whitespace();
+                if !self.eat(">") {
+                    return Err(self.fail("expected `>`"));
+                }
+                if !element.children.is_empty() && element.text.trim().is_empty() {
+                    element.text.clear()
+                }
+                return Ok(element);
+            } else if self.eat("<!--") {
+                self.until("-->", "unterminated comment")?;
+            } else if self.eat("<![CDATA[") {
+                let text = self.until("]]>", "unterminated CDATA")?;
+                element.text.push_str(text)
+            } else if self.eat("<?") {
+                self.until("?>", "unterminated processing instruction")?;
+            } else if self.rest().starts_with('<') {
+                if self.depth == MAX_DEPTH {
+                    return Err(self.fail("nesting too deep"));
+                }
+                self.depth += 1;
+                let child = self.element();
+                self.depth -= 1;
+                element.children.push(child?)
+            } else if self.rest().is_empty() {
+                return Err(self.fail("unterminated element"));
+            } else {
+                let start = self.offset;
+                let rest = self.rest();
+                let end = rest.find('<').unwrap_or(rest.len());
+                self.offset += end;
+                let text =
+                    unescape(&rest[..end]).map_err(|msg| ParseXmlError { offset: start, msg })?;
+                element.text.push_str(&text)
+            }
+        }
+    }
+}
+
+/// Replace entity and character references.
+fn unescape(s: &str) -> Result<String, &'static str> {
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(ix) = rest.find('&') {
+        out.push_str(&rest[..ix]);
+        rest = &rest[ix + 1..];
+        let Some(end) = rest.find(';') else {
+            return Err("unterminated reference");
+        };
+        let c = match &rest[..end] {
+            "amp" => '&',
+            "lt" => '<',
+            "gt" => '>',
+            "quot" => '"',
+            "apos" => '\'',
+            it => it
+                .strip_prefix("#x")
+                .map(|it| u32::from_str_radix(it, 16))
+                .or_else(|| it.strip_prefix('#').map(str::parse))
+                .and_then(Result::ok)
+                .and_then(char::from_u32)
+                .ok_or("invalid reference")?,
+        };
+        out.push(c);
+        rest = &rest[end + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}